@@ -27,7 +27,7 @@ pub struct ChronicleArgs {
 	/// key file for connector wallet
 	#[clap(long)]
 	pub target_keyfile: PathBuf,
-	/// Url for timechain node to connect to.
+	/// Url for timechain node to connect to, a comma separated list of urls enables failover.
 	#[clap(long)]
 	pub timechain_url: String,
 	/// keyfile having an account with funds for timechain.
@@ -309,6 +309,10 @@ impl From<time_primitives::GatewayOp> for GatewayOp {
 				command: Command::UnregisterShard,
 				params: Into::<TssKey>::into(shard_id).abi_encode().into(),
 			},
+			time_primitives::GatewayOp::SetRoute(route) => GatewayOp {
+				command: Command::SetRoute,
+				params: Into::<Route>::into(route).abi_encode().into(),
+			},
 		}
 	}
 }
@@ -19,6 +19,7 @@ fn main() {
 		.method(method("finalized_block", "FinalizedBlock").build())
 		.method(method("block_stream", "BlockStream").server_streaming().build())
 		.method(method("read_events", "ReadEvents").build())
+		.method(method("subscribe_events", "SubscribeEvents").server_streaming().build())
 		.method(method("submit_commands", "SubmitCommands").build())
 		.method(method("deploy_gateway", "DeployGateway").build())
 		.method(method("redeploy_gateway", "RedeployGateway").build())
@@ -36,6 +37,10 @@ fn main() {
 		.method(method("transaction_base_fee", "TransactionBaseFee").build())
 		.method(method("block_gas_limit", "BlockGasLimit").build())
 		.method(method("withdraw_funds", "WithdrawFunds").build())
+		.method(method("networks", "Networks").build())
+		.method(method("create_network", "CreateNetwork").build())
+		.method(method("snapshot_network", "SnapshotNetwork").build())
+		.method(method("advance_blocks", "AdvanceBlocks").build())
 		.build();
 	Builder::new().compile(&[service]);
 }
@@ -0,0 +1,252 @@
+//! Request authentication for the gmp grpc service.
+//!
+//! Every request carries the caller address, a unix timestamp, a random nonce, the network it
+//! targets and an ed25519 signature over those values, the method path and the request body.
+//! The address is the public key derived from the caller mnemonic, so the server can verify that
+//! the caller controls it.
+use crate::proto::Method;
+use anyhow::Result;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use time_primitives::{Address, NetworkId};
+use tonic::metadata::{Ascii, MetadataMap, MetadataValue};
+use tonic::{Request, Status};
+
+const ADDRESS: &str = "address";
+const TIMESTAMP: &str = "timestamp";
+const NONCE: &str = "nonce";
+const NETWORK: &str = "network";
+const SIGNATURE: &str = "signature";
+
+const DOMAIN: &[u8] = b"Analog GMP grpc v1";
+
+fn now() -> u64 {
+	SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs()
+}
+
+fn signing_payload<T: Method>(
+	network: Option<NetworkId>,
+	timestamp: u64,
+	nonce: u64,
+	msg: &T,
+) -> Result<[u8; 32]> {
+	let body = bincode::serialize(msg)?;
+	let mut hasher = blake3::Hasher::new();
+	hasher.update(DOMAIN);
+	hasher.update(&(T::PATH.len() as u32).to_be_bytes());
+	hasher.update(T::PATH.as_bytes());
+	if let Some(network) = network {
+		hasher.update(&network.to_be_bytes());
+	}
+	hasher.update(&timestamp.to_be_bytes());
+	hasher.update(&nonce.to_be_bytes());
+	hasher.update(&body);
+	Ok(*hasher.finalize().as_bytes())
+}
+
+/// Signs outgoing requests with the key derived from the connector mnemonic.
+pub struct RequestSigner {
+	key: SigningKey,
+	address: MetadataValue<Ascii>,
+	network: Option<NetworkId>,
+}
+
+impl RequestSigner {
+	/// Creates a signer for requests targeting `network`, or the server itself if `None`.
+	pub fn new(mnemonic: &str, network: Option<NetworkId>) -> Self {
+		let key = gmp_rust::mnemonic_to_signing_key(mnemonic);
+		let address = gmp_rust::format_address(key.verifying_key().to_bytes());
+		Self {
+			key,
+			address: address.parse().unwrap(),
+			network,
+		}
+	}
+
+	/// Address the server will attribute the signed requests to.
+	pub fn address(&self) -> Address {
+		self.key.verifying_key().to_bytes()
+	}
+
+	/// Wraps a message into a request carrying the authentication metadata.
+	pub fn sign<T: Method>(&self, msg: T) -> Result<Request<T>> {
+		let timestamp = now();
+		let mut nonce = [0; 8];
+		getrandom::getrandom(&mut nonce)?;
+		let nonce = u64::from_be_bytes(nonce);
+		let signature = self.key.sign(&signing_payload(self.network, timestamp, nonce, &msg)?);
+		let mut request = Request::new(msg);
+		let metadata = request.metadata_mut();
+		if let Some(network) = self.network {
+			metadata.insert(NETWORK, network.into());
+		}
+		metadata.insert(ADDRESS, self.address.clone());
+		metadata.insert(TIMESTAMP, timestamp.into());
+		metadata.insert(NONCE, nonce.into());
+		metadata.insert(SIGNATURE, hex::encode(signature.to_bytes()).parse()?);
+		Ok(request)
+	}
+}
+
+/// Nonces seen within the accepted clock skew window.
+#[derive(Default)]
+struct NonceCache {
+	seen: HashSet<(Address, u64)>,
+	expiry: VecDeque<(u64, Address, u64)>,
+}
+
+impl NonceCache {
+	/// Returns `false` if the nonce was already used by the address.
+	fn insert(&mut self, now: u64, expires: u64, address: Address, nonce: u64) -> bool {
+		while let Some((expires, _, _)) = self.expiry.front() {
+			if *expires > now {
+				break;
+			}
+			let (_, address, nonce) = self.expiry.pop_front().unwrap();
+			self.seen.remove(&(address, nonce));
+		}
+		if !self.seen.insert((address, nonce)) {
+			return false;
+		}
+		self.expiry.push_back((expires, address, nonce));
+		true
+	}
+}
+
+/// Verifies the authentication metadata of incoming requests.
+pub struct Authenticator {
+	max_clock_skew: u64,
+	nonces: Mutex<NonceCache>,
+}
+
+impl Authenticator {
+	pub fn new(max_clock_skew: Duration) -> Self {
+		Self {
+			max_clock_skew: max_clock_skew.as_secs(),
+			nonces: Default::default(),
+		}
+	}
+
+	/// Returns the address that signed the request and the network it targets.
+	pub fn authenticate<T: Method>(
+		&self,
+		metadata: &MetadataMap,
+		msg: &T,
+	) -> Result<(Address, Option<NetworkId>), Status> {
+		let address = read_metadata(metadata, ADDRESS)?;
+		let address = gmp_rust::parse_address(address)
+			.map_err(|_| Status::unauthenticated("invalid address"))?;
+		let timestamp: u64 = read_metadata(metadata, TIMESTAMP)?
+			.parse()
+			.map_err(|_| Status::unauthenticated("invalid timestamp"))?;
+		let nonce: u64 = read_metadata(metadata, NONCE)?
+			.parse()
+			.map_err(|_| Status::unauthenticated("invalid nonce"))?;
+		let signature: [u8; 64] = hex::decode(read_metadata(metadata, SIGNATURE)?)
+			.ok()
+			.and_then(|sig| sig.try_into().ok())
+			.ok_or_else(|| Status::unauthenticated("invalid signature"))?;
+		let network = metadata
+			.get(NETWORK)
+			.map(|network| network.to_str().ok()?.parse().ok())
+			.map(|network| network.ok_or_else(|| Status::unauthenticated("invalid network")))
+			.transpose()?;
+		let now = now();
+		if timestamp.abs_diff(now) > self.max_clock_skew {
+			return Err(Status::unauthenticated("timestamp outside of accepted window"));
+		}
+		let key = VerifyingKey::from_bytes(&address)
+			.map_err(|_| Status::unauthenticated("address is not a valid public key"))?;
+		let payload = signing_payload(network, timestamp, nonce, msg)
+			.map_err(|err| Status::invalid_argument(err.to_string()))?;
+		key.verify_strict(&payload, &Signature::from_bytes(&signature))
+			.map_err(|_| Status::unauthenticated("invalid signature"))?;
+		let expires = timestamp + self.max_clock_skew;
+		if !self.nonces.lock().unwrap().insert(now, expires, address, nonce) {
+			return Err(Status::unauthenticated("nonce already used"));
+		}
+		Ok((address, network))
+	}
+}
+
+fn read_metadata<'a>(metadata: &'a MetadataMap, key: &str) -> Result<&'a str, Status> {
+	metadata
+		.get(key)
+		.and_then(|value| value.to_str().ok())
+		.ok_or_else(|| Status::unauthenticated(format!("no {key} provided")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::proto::{BlockGasLimitRequest, FinalizedBlockRequest, TransferRequest};
+
+	fn transfer() -> TransferRequest {
+		TransferRequest { address: [1; 32], amount: 42 }
+	}
+
+	#[test]
+	fn authenticates_signed_request() -> Result<()> {
+		let signer = RequestSigner::new("alice", Some(3));
+		let auth = Authenticator::new(Duration::from_secs(30));
+		let request = signer.sign(transfer())?;
+		let (address, network) = auth.authenticate(request.metadata(), request.get_ref())?;
+		assert_eq!(address, signer.address());
+		assert_eq!(network, Some(3));
+		assert_eq!(address, gmp_rust::mnemonic_to_address("alice".into()));
+		Ok(())
+	}
+
+	#[test]
+	fn rejects_replayed_request() -> Result<()> {
+		let signer = RequestSigner::new("alice", Some(3));
+		let auth = Authenticator::new(Duration::from_secs(30));
+		let request = signer.sign(transfer())?;
+		auth.authenticate(request.metadata(), request.get_ref())?;
+		assert!(auth.authenticate(request.metadata(), request.get_ref()).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn rejects_tampered_request() -> Result<()> {
+		let signer = RequestSigner::new("alice", Some(3));
+		let auth = Authenticator::new(Duration::from_secs(30));
+		let request = signer.sign(transfer())?;
+		let mut msg = transfer();
+		msg.amount = 1_000_000;
+		assert!(auth.authenticate(request.metadata(), &msg).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn rejects_network_change() -> Result<()> {
+		let signer = RequestSigner::new("alice", Some(3));
+		let auth = Authenticator::new(Duration::from_secs(30));
+		let mut request = signer.sign(transfer())?;
+		request.metadata_mut().insert(NETWORK, 4u16.into());
+		assert!(auth.authenticate(request.metadata(), request.get_ref()).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn rejects_impersonation() -> Result<()> {
+		let signer = RequestSigner::new("mallory", Some(3));
+		let auth = Authenticator::new(Duration::from_secs(30));
+		let mut request = signer.sign(transfer())?;
+		let alice = gmp_rust::format_address(gmp_rust::mnemonic_to_address("alice".into()));
+		request.metadata_mut().insert(ADDRESS, alice.parse()?);
+		assert!(auth.authenticate(request.metadata(), request.get_ref()).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn rejects_other_method() -> Result<()> {
+		let signer = RequestSigner::new("alice", Some(3));
+		let auth = Authenticator::new(Duration::from_secs(30));
+		let request = signer.sign(FinalizedBlockRequest {})?;
+		assert!(auth.authenticate(request.metadata(), &BlockGasLimitRequest {}).is_err());
+		Ok(())
+	}
+}
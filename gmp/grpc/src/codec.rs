@@ -1,41 +1,83 @@
+//! Message codec of the gmp grpc service.
+//!
+//! Tonic always uses `application/grpc` as content type, so the message format is negotiated
+//! in-band instead. Bincode messages are prefixed with a format byte, while messages starting
+//! with `{` are decoded as json for compatibility with older clients. The server replies in
+//! the format of the request.
 use bytes::{Buf, BufMut};
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
 use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
 use tonic::Status;
 
+const BINCODE: u8 = 0;
+const JSON: u8 = b'{';
+
 #[derive(Debug)]
-pub struct BincodeEncoder<T>(PhantomData<T>);
+pub struct BincodeEncoder<T> {
+	format: Arc<AtomicU8>,
+	_marker: PhantomData<T>,
+}
 
 impl<T: serde::Serialize> Encoder for BincodeEncoder<T> {
 	type Item = T;
 	type Error = Status;
 
 	fn encode(&mut self, item: Self::Item, buf: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
-		serde_json::to_writer(buf.writer(), &item).map_err(|e| Status::internal(e.to_string()))
+		if self.format.load(Ordering::Relaxed) == JSON {
+			return serde_json::to_writer(buf.writer(), &item)
+				.map_err(|e| Status::internal(e.to_string()));
+		}
+		buf.put_u8(BINCODE);
+		bincode::serialize_into(buf.writer(), &item).map_err(|e| Status::internal(e.to_string()))
 	}
 }
 
 #[derive(Debug)]
-pub struct BincodeDecoder<U>(PhantomData<U>);
+pub struct BincodeDecoder<U> {
+	format: Arc<AtomicU8>,
+	_marker: PhantomData<U>,
+}
 
 impl<U: serde::de::DeserializeOwned> Decoder for BincodeDecoder<U> {
 	type Item = U;
 	type Error = Status;
 
 	fn decode(&mut self, buf: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
-		let item: Self::Item =
-			serde_json::from_reader(buf.reader()).map_err(|e| Status::internal(e.to_string()))?;
+		if !buf.has_remaining() {
+			return Err(Status::internal("empty message"));
+		}
+		let format = buf.chunk()[0];
+		let item: Self::Item = match format {
+			BINCODE => {
+				buf.advance(1);
+				bincode::deserialize_from(buf.reader())
+					.map_err(|e| Status::internal(e.to_string()))?
+			},
+			JSON => serde_json::from_reader(buf.reader())
+				.map_err(|e| Status::internal(e.to_string()))?,
+			_ => return Err(Status::internal("unknown message format")),
+		};
+		self.format.store(format, Ordering::Relaxed);
 		Ok(Some(item))
 	}
 }
 
-/// A [`Codec`] that implements `application/grpc+json` via the serde library.
+/// A [`Codec`] that implements `application/grpc` using bincode, falling back to json
+/// for peers that send json.
 #[derive(Debug, Clone)]
-pub struct BincodeCodec<T, U>(PhantomData<(T, U)>);
+pub struct BincodeCodec<T, U> {
+	format: Arc<AtomicU8>,
+	_marker: PhantomData<(T, U)>,
+}
 
 impl<T, U> Default for BincodeCodec<T, U> {
 	fn default() -> Self {
-		Self(PhantomData)
+		Self {
+			format: Arc::new(AtomicU8::new(BINCODE)),
+			_marker: PhantomData,
+		}
 	}
 }
 
@@ -50,10 +92,16 @@ where
 	type Decoder = BincodeDecoder<U>;
 
 	fn encoder(&mut self) -> Self::Encoder {
-		BincodeEncoder(PhantomData)
+		BincodeEncoder {
+			format: self.format.clone(),
+			_marker: PhantomData,
+		}
 	}
 
 	fn decoder(&mut self) -> Self::Decoder {
-		BincodeDecoder(PhantomData)
+		BincodeDecoder {
+			format: self.format.clone(),
+			_marker: PhantomData,
+		}
 	}
 }
@@ -1,22 +1,24 @@
 use anyhow::Result;
 use futures::{Stream, StreamExt};
+use std::collections::{BTreeMap, HashMap};
 use std::ops::Range;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 use time_primitives::{
 	Address, BatchId, ConnectorParams, Gateway, GatewayMessage, GmpEvent, GmpMessage, IChain,
 	IConnector, IConnectorAdmin, IConnectorBuilder, MessageId, NetworkId, Route, TssPublicKey,
 	TssSignature,
 };
-use tokio::sync::Mutex;
-use tonic::metadata::{Ascii, MetadataValue};
-use tonic::service::interceptor::{InterceptedService, Interceptor};
-use tonic::transport::{Channel, ClientTlsConfig};
-use tonic::{Request, Status};
+use tokio::sync::{watch, Mutex};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Identity};
 
+pub mod auth;
 mod codec;
 pub mod proto;
 
+use auth::RequestSigner;
+
 mod gmp {
 	include!(concat!(env!("OUT_DIR"), "/gmp.Gmp.rs"));
 }
@@ -24,33 +26,175 @@ mod gmp {
 use gmp::gmp_client::GmpClient;
 pub use gmp::gmp_server::{Gmp, GmpServer};
 
-#[derive(Clone)]
-struct AddressInterceptor {
-	address: MetadataValue<Ascii>,
+type GmpClientT = GmpClient<Channel>;
+
+/// How long `read_events` waits for a subscription to catch up before falling back
+/// to a `ReadEvents` request.
+const SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Builds the client tls config.
+///
+/// Servers with a private certificate authority are supported by setting
+/// `GMP_GRPC_TLS_CA`, and mTLS by setting `GMP_GRPC_TLS_CERT` and `GMP_GRPC_TLS_KEY`
+/// to pem encoded files.
+fn tls_config() -> Result<ClientTlsConfig> {
+	let mut config = ClientTlsConfig::new().with_native_roots();
+	if let Ok(ca) = std::env::var("GMP_GRPC_TLS_CA") {
+		config = config.ca_certificate(Certificate::from_pem(std::fs::read(ca)?));
+	}
+	if let (Ok(cert), Ok(key)) =
+		(std::env::var("GMP_GRPC_TLS_CERT"), std::env::var("GMP_GRPC_TLS_KEY"))
+	{
+		let identity = Identity::from_pem(std::fs::read(cert)?, std::fs::read(key)?);
+		config = config.identity(identity);
+	}
+	Ok(config)
 }
 
-impl AddressInterceptor {
-	fn new(address: Address) -> Self {
-		Self {
-			address: gmp_rust::format_address(address).parse().unwrap(),
+/// Events pushed by a `SubscribeEvents` stream.
+struct EventCache {
+	/// First block that is still cached.
+	start: u64,
+	/// Events of all blocks in `start..end` are cached.
+	end: u64,
+	events: BTreeMap<u64, Vec<GmpEvent>>,
+}
+
+impl EventCache {
+	fn read(&mut self, blocks: Range<u64>) -> Option<Vec<GmpEvent>> {
+		if blocks.start < self.start || blocks.end > self.end {
+			return None;
 		}
+		let events = self
+			.events
+			.range(blocks.clone())
+			.flat_map(|(_, events)| events.iter().cloned())
+			.collect();
+		// events are read in order, so blocks before the requested range are not needed anymore
+		self.events = self.events.split_off(&blocks.start);
+		self.start = blocks.start;
+		Some(events)
 	}
 }
 
-impl Interceptor for AddressInterceptor {
-	fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
-		request.metadata_mut().insert("address", self.address.clone());
-		Ok(request)
-	}
+#[derive(Clone)]
+struct EventSubscription {
+	cache: Arc<std::sync::Mutex<EventCache>>,
+	end: watch::Receiver<u64>,
 }
 
-type GmpClientT = GmpClient<InterceptedService<Channel, AddressInterceptor>>;
+impl EventSubscription {
+	fn is_closed(&self) -> bool {
+		self.end.has_changed().is_err()
+	}
+
+	async fn read(&mut self, blocks: Range<u64>) -> Option<Vec<GmpEvent>> {
+		let end = blocks.end;
+		tokio::time::timeout(SUBSCRIPTION_TIMEOUT, self.end.wait_for(|current| *current >= end))
+			.await
+			.ok()?
+			.ok()?;
+		self.cache.lock().unwrap().read(blocks)
+	}
+}
 
 #[derive(Clone)]
 pub struct Connector {
 	network: NetworkId,
-	address: Address,
+	signer: Arc<RequestSigner>,
 	client: Arc<Mutex<GmpClientT>>,
+	subscriptions: Arc<Mutex<HashMap<Gateway, EventSubscription>>>,
+}
+
+impl Connector {
+	/// Subscribes to the gateway events starting at `from_block`.
+	async fn subscribe_events(
+		&self,
+		gateway: Gateway,
+		from_block: u64,
+	) -> Result<EventSubscription> {
+		let request = self.signer.sign(proto::SubscribeEventsRequest { gateway, from_block })?;
+		let mut stream = self.client.lock().await.subscribe_events(request).await?.into_inner();
+		let cache = Arc::new(std::sync::Mutex::new(EventCache {
+			start: from_block,
+			end: from_block,
+			events: Default::default(),
+		}));
+		let (tx, end) = watch::channel(from_block);
+		let weak = Arc::downgrade(&cache);
+		tokio::spawn(async move {
+			while let Some(Ok(msg)) = stream.next().await {
+				let Some(cache) = weak.upgrade() else {
+					break;
+				};
+				let mut cache = cache.lock().unwrap();
+				if !msg.events.is_empty() {
+					cache.events.insert(msg.block, msg.events);
+				}
+				cache.end = msg.block + 1;
+				tx.send_replace(cache.end);
+			}
+		});
+		Ok(EventSubscription { cache, end })
+	}
+
+	/// Lists the networks hosted by the server.
+	pub async fn networks(&self) -> Result<Vec<NetworkId>> {
+		let request = self.signer.sign(proto::NetworksRequest {})?;
+		let response = self.client.lock().await.networks(request).await?.into_inner();
+		Ok(response.networks)
+	}
+
+	/// Creates a network on the server, optionally restoring it from a snapshot.
+	pub async fn create_network(
+		&self,
+		network: NetworkId,
+		snapshot: Option<String>,
+		manual_clock: bool,
+	) -> Result<()> {
+		let request = self.signer.sign(proto::CreateNetworkRequest {
+			network,
+			snapshot,
+			manual_clock,
+		})?;
+		self.client.lock().await.create_network(request).await?;
+		Ok(())
+	}
+
+	/// Stores a snapshot of a network on the server.
+	pub async fn snapshot_network(&self, network: NetworkId, snapshot: String) -> Result<()> {
+		let request = self.signer.sign(proto::SnapshotNetworkRequest { network, snapshot })?;
+		self.client.lock().await.snapshot_network(request).await?;
+		Ok(())
+	}
+
+	/// Opens the stream of finalized block indexes.
+	async fn open_block_stream(&self) -> Result<tonic::Streaming<proto::BlockStreamResponse>> {
+		let request = self.signer.sign(proto::BlockStreamRequest {})?;
+		Ok(self.client.lock().await.block_stream(request).await?.into_inner())
+	}
+
+	/// Reads the events from the gateway subscription, creating it if necessary.
+	///
+	/// Returns `None` when the subscription doesn't cover the requested blocks.
+	async fn read_subscribed_events(
+		&self,
+		gateway: Gateway,
+		blocks: Range<u64>,
+	) -> Result<Option<Vec<GmpEvent>>> {
+		let mut subscription = {
+			let mut subscriptions = self.subscriptions.lock().await;
+			match subscriptions.get(&gateway) {
+				Some(subscription) if !subscription.is_closed() => subscription.clone(),
+				_ => {
+					let subscription = self.subscribe_events(gateway, blocks.start).await?;
+					subscriptions.insert(gateway, subscription.clone());
+					subscription
+				},
+			}
+		};
+		Ok(subscription.read(blocks).await)
+	}
 }
 
 #[tonic::async_trait]
@@ -60,18 +204,18 @@ impl IConnectorBuilder for Connector {
 	where
 		Self: Sized,
 	{
-		let address = gmp_rust::mnemonic_to_address(params.mnemonic);
+		let signer = RequestSigner::new(&params.mnemonic, Some(params.network_id));
 		let channel = if params.url.starts_with("https") {
-			let tls_config = ClientTlsConfig::new().with_native_roots();
-			Channel::from_shared(params.url)?.tls_config(tls_config)?.connect().await?
+			Channel::from_shared(params.url)?.tls_config(tls_config()?)?.connect().await?
 		} else {
 			Channel::from_shared(params.url)?.connect().await?
 		};
-		let client = GmpClient::with_interceptor(channel, AddressInterceptor::new(address));
+		let client = GmpClient::new(channel);
 		Ok(Self {
 			network: params.network_id,
-			address,
+			signer: Arc::new(signer),
 			client: Arc::new(Mutex::new(client)),
+			subscriptions: Default::default(),
 		})
 	}
 }
@@ -95,45 +239,40 @@ impl IChain for Connector {
 	}
 	/// Human readable connector account identifier.
 	fn address(&self) -> Address {
-		self.address
+		self.signer.address()
 	}
 	/// Uses a faucet to fund the account when possible.
 	async fn faucet(&self, balance: u128) -> Result<()> {
-		let request = Request::new(proto::FaucetRequest { balance });
+		let request = self.signer.sign(proto::FaucetRequest { balance })?;
 		self.client.lock().await.faucet(request).await?;
 		Ok(())
 	}
 	/// Transfers an amount to an account.
 	async fn transfer(&self, address: Address, amount: u128) -> Result<()> {
-		let request = Request::new(proto::TransferRequest { address, amount });
+		let request = self.signer.sign(proto::TransferRequest { address, amount })?;
 		self.client.lock().await.transfer(request).await?;
 		Ok(())
 	}
 	/// Queries the account balance.
 	async fn balance(&self, address: Address) -> Result<u128> {
-		let request = Request::new(proto::BalanceRequest { address });
+		let request = self.signer.sign(proto::BalanceRequest { address })?;
 		let response = self.client.lock().await.balance(request).await?;
 		Ok(response.get_ref().balance)
 	}
 	async fn finalized_block(&self) -> Result<u64> {
-		let request = Request::new(proto::FinalizedBlockRequest {});
+		let request = self.signer.sign(proto::FinalizedBlockRequest {})?;
 		let response = self.client.lock().await.finalized_block(request).await?;
 		Ok(response.get_ref().finalized_block)
 	}
 	/// Stream of finalized block indexes.
 	fn block_stream(&self) -> Pin<Box<dyn Stream<Item = u64> + Send>> {
-		let request = Request::new(proto::BlockStreamRequest {});
-		futures::executor::block_on(async move {
-			self.client
-				.lock()
-				.await
-				.block_stream(request)
-				.await
-				.unwrap()
-				.into_inner()
-				.filter_map(|res| async { res.ok().map(|msg| msg.block) })
-				.boxed()
-		})
+		match futures::executor::block_on(self.open_block_stream()) {
+			Ok(stream) => stream.filter_map(|res| async { res.ok().map(|msg| msg.block) }).boxed(),
+			Err(err) => {
+				tracing::error!("failed to open block stream: {err:?}");
+				futures::stream::empty().boxed()
+			},
+		}
 	}
 }
 
@@ -141,11 +280,16 @@ impl IChain for Connector {
 impl IConnector for Connector {
 	/// Reads gmp messages from the target chain.
 	async fn read_events(&self, gateway: Gateway, blocks: Range<u64>) -> Result<Vec<GmpEvent>> {
-		let request = Request::new(proto::ReadEventsRequest {
+		match self.read_subscribed_events(gateway, blocks.clone()).await {
+			Ok(Some(events)) => return Ok(events),
+			Ok(None) => {},
+			Err(err) => tracing::warn!("failed to subscribe to gateway events: {err:?}"),
+		}
+		let request = self.signer.sign(proto::ReadEventsRequest {
 			gateway,
 			start_block: blocks.start,
 			end_block: blocks.end,
-		});
+		})?;
 		let response = self.client.lock().await.read_events(request).await?;
 		Ok(response.into_inner().events)
 	}
@@ -158,13 +302,16 @@ impl IConnector for Connector {
 		signer: TssPublicKey,
 		sig: TssSignature,
 	) -> Result<(), String> {
-		let request = Request::new(proto::SubmitCommandsRequest {
-			gateway,
-			batch,
-			msg,
-			signer,
-			sig,
-		});
+		let request = self
+			.signer
+			.sign(proto::SubmitCommandsRequest {
+				gateway,
+				batch,
+				msg,
+				signer,
+				sig,
+			})
+			.map_err(|err| err.to_string())?;
 		self.client
 			.lock()
 			.await
@@ -184,10 +331,10 @@ impl IConnectorAdmin for Connector {
 		proxy: &[u8],
 		gateway: &[u8],
 	) -> Result<(Address, u64)> {
-		let request = Request::new(proto::DeployGatewayRequest {
+		let request = self.signer.sign(proto::DeployGatewayRequest {
 			proxy: proxy.to_vec(),
 			gateway: gateway.to_vec(),
-		});
+		})?;
 		let response = self.client.lock().await.deploy_gateway(request).await?.into_inner();
 		Ok((response.address, response.block))
 	}
@@ -198,28 +345,28 @@ impl IConnectorAdmin for Connector {
 		proxy: Address,
 		gateway: &[u8],
 	) -> Result<()> {
-		let request = Request::new(proto::RedeployGatewayRequest {
+		let request = self.signer.sign(proto::RedeployGatewayRequest {
 			proxy,
 			gateway: gateway.to_vec(),
-		});
+		})?;
 		self.client.lock().await.redeploy_gateway(request).await?;
 		Ok(())
 	}
 	/// Returns the gateway admin.
 	async fn admin(&self, gateway: Address) -> Result<Address> {
-		let request = Request::new(proto::AdminRequest { gateway });
+		let request = self.signer.sign(proto::AdminRequest { gateway })?;
 		let response = self.client.lock().await.admin(request).await?.into_inner();
 		Ok(response.address)
 	}
 	/// Sets the gateway admin.
 	async fn set_admin(&self, gateway: Address, admin: Address) -> Result<()> {
-		let request = Request::new(proto::SetAdminRequest { gateway, admin });
+		let request = self.signer.sign(proto::SetAdminRequest { gateway, admin })?;
 		self.client.lock().await.set_admin(request).await?;
 		Ok(())
 	}
 	/// Returns the registered shard keys.
 	async fn shards(&self, gateway: Address) -> Result<Vec<TssPublicKey>> {
-		let request = Request::new(proto::ShardsRequest { gateway });
+		let request = self.signer.sign(proto::ShardsRequest { gateway })?;
 		let response = self.client.lock().await.shards(request).await?.into_inner();
 		Ok(unsafe {
 			std::mem::transmute::<Vec<serde_big_array::Array<u8, 33>>, Vec<TssPublicKey>>(
@@ -230,28 +377,28 @@ impl IConnectorAdmin for Connector {
 	/// Sets the registered shard keys. Overwrites any other keys.
 	async fn set_shards(&self, gateway: Address, keys: &[TssPublicKey]) -> Result<()> {
 		let shards = keys.iter().copied().map(serde_big_array::Array).collect();
-		let request = Request::new(proto::SetShardsRequest { gateway, shards });
+		let request = self.signer.sign(proto::SetShardsRequest { gateway, shards })?;
 		self.client.lock().await.set_shards(request).await?;
 		Ok(())
 	}
 	/// Returns the gateway routing table.
 	async fn routes(&self, gateway: Address) -> Result<Vec<Route>> {
-		let request = Request::new(proto::RoutesRequest { gateway });
+		let request = self.signer.sign(proto::RoutesRequest { gateway })?;
 		let response = self.client.lock().await.routes(request).await?.into_inner();
 		Ok(response.routes)
 	}
 	/// Updates an entry in the gateway routing table.
 	async fn set_route(&self, gateway: Address, route: Route) -> Result<()> {
-		let request = Request::new(proto::SetRouteRequest { gateway, route });
+		let request = self.signer.sign(proto::SetRouteRequest { gateway, route })?;
 		self.client.lock().await.set_route(request).await?;
 		Ok(())
 	}
 	/// Deploys a test contract.
 	async fn deploy_test(&self, gateway: Address, tester: &[u8]) -> Result<(Address, u64)> {
-		let request = Request::new(proto::DeployTestRequest {
+		let request = self.signer.sign(proto::DeployTestRequest {
 			gateway,
 			tester: tester.to_vec(),
-		});
+		})?;
 		let response = self.client.lock().await.deploy_test(request).await?.into_inner();
 		Ok((response.address, response.block))
 	}
@@ -263,12 +410,12 @@ impl IConnectorAdmin for Connector {
 		src: Address,
 		payload: Vec<u8>,
 	) -> Result<u128> {
-		let request = Request::new(proto::EstimateMessageGasLimitRequest {
+		let request = self.signer.sign(proto::EstimateMessageGasLimitRequest {
 			contract,
 			src_network,
 			src,
 			payload,
-		});
+		})?;
 		let response =
 			self.client.lock().await.estimate_message_gas_limit(request).await?.into_inner();
 		Ok(response.gas_limit)
@@ -281,12 +428,12 @@ impl IConnectorAdmin for Connector {
 		gas_limit: u128,
 		payload: Vec<u8>,
 	) -> Result<u128> {
-		let request = Request::new(proto::EstimateMessageCostRequest {
+		let request = self.signer.sign(proto::EstimateMessageCostRequest {
 			gateway,
 			dest_network,
 			gas_limit,
 			payload,
-		});
+		})?;
 		let response = self.client.lock().await.estimate_message_cost(request).await?.into_inner();
 		Ok(response.cost)
 	}
@@ -300,14 +447,14 @@ impl IConnectorAdmin for Connector {
 		gas_cost: u128,
 		payload: Vec<u8>,
 	) -> Result<MessageId> {
-		let request = Request::new(proto::SendMessageRequest {
+		let request = self.signer.sign(proto::SendMessageRequest {
 			src,
 			dest_network,
 			dest,
 			gas_limit,
 			gas_cost,
 			payload,
-		});
+		})?;
 		let response = self.client.lock().await.send_message(request).await?.into_inner();
 		Ok(response.message_id)
 	}
@@ -317,32 +464,39 @@ impl IConnectorAdmin for Connector {
 		contract: Address,
 		blocks: Range<u64>,
 	) -> Result<Vec<GmpMessage>> {
-		let request = Request::new(proto::RecvMessagesRequest {
+		let request = self.signer.sign(proto::RecvMessagesRequest {
 			contract,
 			start_block: blocks.start,
 			end_block: blocks.end,
-		});
+		})?;
 		let response = self.client.lock().await.recv_messages(request).await?.into_inner();
 		Ok(response.messages)
 	}
 	/// Calculate transaction base fee for a chain.
 	async fn transaction_base_fee(&self) -> Result<u128> {
-		let request = Request::new(proto::TransactionBaseFeeRequest {});
+		let request = self.signer.sign(proto::TransactionBaseFeeRequest {})?;
 		let response = self.client.lock().await.transaction_base_fee(request).await?.into_inner();
 		Ok(response.base_fee)
 	}
 
 	/// Returns gas limit of latest block.
 	async fn block_gas_limit(&self) -> Result<u64> {
-		let request = Request::new(proto::BlockGasLimitRequest {});
+		let request = self.signer.sign(proto::BlockGasLimitRequest {})?;
 		let response = self.client.lock().await.block_gas_limit(request).await?.into_inner();
 		Ok(response.gas_limit)
 	}
 
 	/// Withdraw gateway funds.
 	async fn withdraw_funds(&self, gateway: Address, amount: u128, address: Address) -> Result<()> {
-		let request = Request::new(proto::WithdrawFundsRequest { gateway, amount, address });
+		let request = self.signer.sign(proto::WithdrawFundsRequest { gateway, amount, address })?;
 		self.client.lock().await.withdraw_funds(request).await?.into_inner();
 		Ok(())
 	}
+
+	/// Advances a chain with a manual clock.
+	async fn advance_blocks(&self, blocks: u64) -> Result<u64> {
+		let request = self.signer.sign(proto::AdvanceBlocksRequest { blocks })?;
+		let response = self.client.lock().await.advance_blocks(request).await?.into_inner();
+		Ok(response.block)
+	}
 }
@@ -1,50 +1,156 @@
 use anyhow::Result;
 use clap::Parser;
 use futures::{Stream, StreamExt};
-use gmp_grpc::{proto, Gmp, GmpServer};
+use gmp_grpc::auth::Authenticator;
+use gmp_grpc::proto::{self, Method};
+use gmp_grpc::{Gmp, GmpServer};
 use gmp_rust::Connector;
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet};
 use std::net::SocketAddr;
+use std::num::NonZeroU32;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
+use std::sync::RwLock;
+use std::time::Duration;
 use time_primitives::{
-	ConnectorParams, IChain, IConnector, IConnectorAdmin, IConnectorBuilder, NetworkId,
+	Address, ConnectorParams, IChain, IConnector, IConnectorAdmin, IConnectorBuilder, NetworkId,
 	TssPublicKey,
 };
-use tonic::transport::Server;
+use tonic::transport::{Certificate, Identity, Server, ServerTlsConfig};
 use tonic::{Request, Response, Status};
 use tracing_subscriber::filter::EnvFilter;
 
 type GmpResult<T> = Result<Response<T>, Status>;
 
+/// Directory inside the db directory that holds the network snapshots.
+const SNAPSHOTS: &str = "snapshots";
+
+fn network_db(dir: &Path, network: NetworkId) -> PathBuf {
+	dir.join(format!("{network}.redb"))
+}
+
+fn snapshot_db(dir: &Path, snapshot: &str) -> Result<PathBuf, Status> {
+	if snapshot.is_empty()
+		|| !snapshot.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+	{
+		return Err(Status::invalid_argument("invalid snapshot name"));
+	}
+	Ok(dir.join(SNAPSHOTS).join(format!("{snapshot}.redb")))
+}
+
+/// Opens a network, a manual clock is only selected when the network is created.
+async fn open_network(dir: &Path, network: NetworkId, manual_clock: bool) -> Result<Connector> {
+	let mut url = network_db(dir, network).to_str().unwrap().to_string();
+	if manual_clock {
+		url.push_str("?clock=manual");
+	}
+	Connector::new(ConnectorParams {
+		network_id: network,
+		blockchain: "rust".into(),
+		network: network.to_string(),
+		url,
+		mnemonic: String::new(),
+		cctp_sender: None,
+		cctp_attestation: None,
+	})
+	.await
+}
+
 pub struct ConnectorWrapper {
-	connector: Connector,
+	dir: PathBuf,
+	connectors: RwLock<BTreeMap<NetworkId, Connector>>,
+	admins: BTreeSet<Address>,
+	auth: Authenticator,
+	rate_limiter: Option<DefaultKeyedRateLimiter<Address>>,
 }
 
 impl ConnectorWrapper {
-	pub async fn new(network: NetworkId, db: &Path) -> Result<Self> {
-		let connector = Connector::new(ConnectorParams {
-			network_id: network,
-			blockchain: "rust".into(),
-			network: network.to_string(),
-			url: db.to_str().unwrap().to_string(),
-			mnemonic: String::new(),
-			cctp_sender: None,
-			cctp_attestation: None,
+	/// Hosts `networks` and all networks previously created in `dir`.
+	pub async fn new(
+		dir: PathBuf,
+		networks: &[NetworkId],
+		manual_clock: bool,
+		admins: BTreeSet<Address>,
+		auth: Authenticator,
+		rate_limit: Option<NonZeroU32>,
+	) -> Result<Self> {
+		std::fs::create_dir_all(dir.join(SNAPSHOTS))?;
+		let new_networks: BTreeSet<NetworkId> = networks
+			.iter()
+			.copied()
+			.filter(|network| !network_db(&dir, *network).exists())
+			.collect();
+		let mut networks: BTreeSet<NetworkId> = networks.iter().copied().collect();
+		for entry in std::fs::read_dir(&dir)? {
+			let path = entry?.path();
+			if path.extension() != Some("redb".as_ref()) {
+				continue;
+			}
+			if let Some(network) = path.file_stem().and_then(|s| s.to_str()?.parse().ok()) {
+				networks.insert(network);
+			}
+		}
+		let mut connectors = BTreeMap::new();
+		for network in networks {
+			tracing::info!("hosting network {network}");
+			let manual_clock = manual_clock && new_networks.contains(&network);
+			connectors.insert(network, open_network(&dir, network, manual_clock).await?);
+		}
+		let rate_limiter = rate_limit.map(|limit| RateLimiter::keyed(Quota::per_second(limit)));
+		Ok(Self {
+			dir,
+			connectors: RwLock::new(connectors),
+			admins,
+			auth,
+			rate_limiter,
 		})
-		.await?;
-		Ok(Self { connector })
 	}
 
-	fn connector<T>(&self, req: Request<T>) -> Result<(Connector, T), Status> {
-		let Some(addr) = req.metadata().get("address") else {
-			return Err(Status::unauthenticated("No address provided"));
-		};
-		let Ok(addr) = gmp_rust::parse_address(addr.to_str().unwrap()) else {
-			return Err(Status::unauthenticated("No valid address provided"));
+	fn authenticate<T: Method>(
+		&self,
+		req: Request<T>,
+	) -> Result<(Address, Option<NetworkId>, T), Status> {
+		let (metadata, _, msg) = req.into_parts();
+		let (addr, network) = self.auth.authenticate(&metadata, &msg)?;
+		if let Some(rate_limiter) = &self.rate_limiter {
+			if rate_limiter.check_key(&addr).is_err() {
+				return Err(Status::resource_exhausted("rate limit exceeded"));
+			}
+		}
+		Ok((addr, network, msg))
+	}
+
+	fn network_connector(&self, network: Option<NetworkId>) -> Result<Connector, Status> {
+		let connectors = self.connectors.read().unwrap();
+		let connector = match network {
+			Some(network) => connectors
+				.get(&network)
+				.ok_or_else(|| Status::not_found(format!("network {network} is not hosted")))?,
+			None if connectors.len() == 1 => connectors.values().next().unwrap(),
+			None => return Err(Status::invalid_argument("no network provided")),
 		};
-		let connector = self.connector.with_address(addr);
-		let msg = req.into_inner();
-		Ok((connector, msg))
+		Ok(connector.clone())
+	}
+
+	fn connector<T: Method>(&self, req: Request<T>) -> Result<(Connector, T), Status> {
+		let (addr, network, msg) = self.authenticate(req)?;
+		let connector = self.network_connector(network)?;
+		Ok((connector.with_address(addr), msg))
+	}
+
+	fn ensure_admin(&self, addr: Address) -> Result<(), Status> {
+		if !self.admins.contains(&addr) {
+			return Err(Status::permission_denied("not a server admin"));
+		}
+		Ok(())
+	}
+
+	fn admin_request<T: Method>(&self, req: Request<T>) -> Result<T, Status> {
+		let (addr, _, msg) = self.authenticate(req)?;
+		self.ensure_admin(addr)?;
+		Ok(msg)
 	}
 }
 
@@ -122,6 +228,32 @@ impl Gmp for ConnectorWrapper {
 		Ok(Response::new(proto::ReadEventsResponse { events }))
 	}
 
+	type SubscribeEventsStream = Pin<
+		Box<dyn Stream<Item = Result<proto::SubscribeEventsResponse, Status>> + Send + 'static>,
+	>;
+
+	async fn subscribe_events(
+		&self,
+		request: Request<proto::SubscribeEventsRequest>,
+	) -> GmpResult<Self::SubscribeEventsStream> {
+		let (connector, msg) = self.connector(request)?;
+		let stream = async_stream::try_stream! {
+			let mut next = msg.from_block;
+			let mut blocks = connector.block_stream();
+			while let Some(finalized) = blocks.next().await {
+				for block in next..finalized {
+					let events = connector
+						.read_events(msg.gateway, block..block + 1)
+						.await
+						.map_err(|err| Status::unknown(err.to_string()))?;
+					yield proto::SubscribeEventsResponse { block, events };
+				}
+				next = next.max(finalized);
+			}
+		};
+		Ok(Response::new(stream.boxed()))
+	}
+
 	async fn submit_commands(
 		&self,
 		request: Request<proto::SubmitCommandsRequest>,
@@ -335,16 +467,119 @@ impl Gmp for ConnectorWrapper {
 			.map_err(|err| Status::unknown(err.to_string()))?;
 		Ok(Response::new(proto::WithdrawFundsResponse {}))
 	}
+
+	async fn networks(
+		&self,
+		request: Request<proto::NetworksRequest>,
+	) -> GmpResult<proto::NetworksResponse> {
+		self.authenticate(request)?;
+		let networks = self.connectors.read().unwrap().keys().copied().collect();
+		Ok(Response::new(proto::NetworksResponse { networks }))
+	}
+
+	async fn create_network(
+		&self,
+		request: Request<proto::CreateNetworkRequest>,
+	) -> GmpResult<proto::CreateNetworkResponse> {
+		let msg = self.admin_request(request)?;
+		let exists = || Status::already_exists(format!("network {} already exists", msg.network));
+		if self.connectors.read().unwrap().contains_key(&msg.network) {
+			return Err(exists());
+		}
+		if let Some(snapshot) = &msg.snapshot {
+			let snapshot = snapshot_db(&self.dir, snapshot)?;
+			if !snapshot.exists() {
+				return Err(Status::not_found("snapshot not found"));
+			}
+			std::fs::copy(snapshot, network_db(&self.dir, msg.network))
+				.map_err(|err| Status::unknown(err.to_string()))?;
+		}
+		let connector = open_network(&self.dir, msg.network, msg.manual_clock)
+			.await
+			.map_err(|err| Status::unknown(err.to_string()))?;
+		match self.connectors.write().unwrap().entry(msg.network) {
+			Entry::Occupied(_) => return Err(exists()),
+			Entry::Vacant(entry) => entry.insert(connector),
+		};
+		tracing::info!("created network {}", msg.network);
+		Ok(Response::new(proto::CreateNetworkResponse {}))
+	}
+
+	async fn snapshot_network(
+		&self,
+		request: Request<proto::SnapshotNetworkRequest>,
+	) -> GmpResult<proto::SnapshotNetworkResponse> {
+		let msg = self.admin_request(request)?;
+		let connector =
+			self.connectors.read().unwrap().get(&msg.network).cloned().ok_or_else(|| {
+				Status::not_found(format!("network {} is not hosted", msg.network))
+			})?;
+		let snapshot = snapshot_db(&self.dir, &msg.snapshot)?;
+		connector.snapshot(&snapshot).map_err(|err| Status::unknown(err.to_string()))?;
+		tracing::info!("stored snapshot {} of network {}", msg.snapshot, msg.network);
+		Ok(Response::new(proto::SnapshotNetworkResponse {}))
+	}
+
+	async fn advance_blocks(
+		&self,
+		request: Request<proto::AdvanceBlocksRequest>,
+	) -> GmpResult<proto::AdvanceBlocksResponse> {
+		let (addr, network, msg) = self.authenticate(request)?;
+		self.ensure_admin(addr)?;
+		let block = self
+			.network_connector(network)?
+			.advance_blocks(msg.blocks)
+			.await
+			.map_err(|err| Status::failed_precondition(err.to_string()))?;
+		Ok(Response::new(proto::AdvanceBlocksResponse { block }))
+	}
 }
 
 #[derive(Parser)]
 struct Args {
+	/// Networks to host. Networks created at runtime are restored from the db directory.
+	#[arg(long = "network-id")]
+	network_ids: Vec<NetworkId>,
+	/// Create new networks with a manual clock that only advances through `AdvanceBlocks`.
 	#[arg(long)]
-	network_id: NetworkId,
+	manual_clock: bool,
 	#[arg(long)]
 	port: u16,
+	/// Directory containing a redb file per network.
 	#[arg(long)]
 	db: PathBuf,
+	/// Addresses allowed to create and snapshot networks.
+	#[arg(long = "admin", value_parser = gmp_rust::parse_address)]
+	admins: Vec<Address>,
+	/// Maximum difference in seconds between a request timestamp and the server clock.
+	#[arg(long, default_value_t = 30)]
+	max_clock_skew: u64,
+	/// Maximum number of requests per second per address.
+	#[arg(long)]
+	rate_limit: Option<NonZeroU32>,
+	/// Pem encoded server certificate, enables tls.
+	#[arg(long, requires = "tls_key")]
+	tls_cert: Option<PathBuf>,
+	/// Pem encoded server private key.
+	#[arg(long, requires = "tls_cert")]
+	tls_key: Option<PathBuf>,
+	/// Pem encoded certificate authority used to verify client certificates, enables mTLS.
+	#[arg(long, requires = "tls_cert")]
+	tls_client_ca: Option<PathBuf>,
+}
+
+impl Args {
+	fn tls_config(&self) -> Result<Option<ServerTlsConfig>> {
+		let (Some(cert), Some(key)) = (&self.tls_cert, &self.tls_key) else {
+			return Ok(None);
+		};
+		let identity = Identity::from_pem(std::fs::read(cert)?, std::fs::read(key)?);
+		let mut config = ServerTlsConfig::new().identity(identity);
+		if let Some(ca) = &self.tls_client_ca {
+			config = config.client_ca_root(Certificate::from_pem(std::fs::read(ca)?));
+		}
+		Ok(Some(config))
+	}
 }
 
 async fn shutdown_signal() {
@@ -374,9 +609,23 @@ async fn main() -> Result<()> {
 	let filter = EnvFilter::from_default_env();
 	tracing_subscriber::fmt().with_env_filter(filter).init();
 	let args = Args::parse();
-	let server = ConnectorWrapper::new(args.network_id, &args.db).await?;
+	let auth = Authenticator::new(Duration::from_secs(args.max_clock_skew));
+	let admins = args.admins.iter().copied().collect();
+	let server = ConnectorWrapper::new(
+		args.db.clone(),
+		&args.network_ids,
+		args.manual_clock,
+		admins,
+		auth,
+		args.rate_limit,
+	)
+	.await?;
 	let svc = GmpServer::new(server);
-	Server::builder()
+	let mut builder = Server::builder();
+	if let Some(tls_config) = args.tls_config()? {
+		builder = builder.tls_config(tls_config)?;
+	}
+	builder
 		.add_service(svc)
 		.serve_with_shutdown(SocketAddr::new([0, 0, 0, 0].into(), args.port), shutdown_signal())
 		.await?;
@@ -60,6 +60,18 @@ pub struct ReadEventsResponse {
 	pub events: Vec<GmpEvent>,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct SubscribeEventsRequest {
+	pub gateway: Gateway,
+	pub from_block: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SubscribeEventsResponse {
+	pub block: u64,
+	pub events: Vec<GmpEvent>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SubmitCommandsRequest {
 	pub gateway: Gateway,
@@ -242,3 +254,89 @@ pub struct WithdrawFundsRequest {
 
 #[derive(Serialize, Deserialize)]
 pub struct WithdrawFundsResponse {}
+
+#[derive(Serialize, Deserialize)]
+pub struct NetworksRequest {}
+
+#[derive(Serialize, Deserialize)]
+pub struct NetworksResponse {
+	pub networks: Vec<NetworkId>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateNetworkRequest {
+	pub network: NetworkId,
+	pub snapshot: Option<String>,
+	pub manual_clock: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateNetworkResponse {}
+
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotNetworkRequest {
+	pub network: NetworkId,
+	pub snapshot: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotNetworkResponse {}
+
+#[derive(Serialize, Deserialize)]
+pub struct AdvanceBlocksRequest {
+	pub blocks: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AdvanceBlocksResponse {
+	pub block: u64,
+}
+
+/// Request of a grpc method.
+///
+/// The path of the method is part of the signed payload, so a signed request can't be replayed
+/// against another method with a request that encodes the same way.
+pub trait Method: Serialize {
+	const PATH: &'static str;
+}
+
+macro_rules! methods {
+	($($route:ident => $request:ident,)*) => {
+		$(
+			impl Method for $request {
+				const PATH: &'static str = concat!("/gmp.Gmp/", stringify!($route));
+			}
+		)*
+	};
+}
+
+methods! {
+	Faucet => FaucetRequest,
+	Transfer => TransferRequest,
+	Balance => BalanceRequest,
+	FinalizedBlock => FinalizedBlockRequest,
+	BlockStream => BlockStreamRequest,
+	ReadEvents => ReadEventsRequest,
+	SubscribeEvents => SubscribeEventsRequest,
+	SubmitCommands => SubmitCommandsRequest,
+	DeployGateway => DeployGatewayRequest,
+	RedeployGateway => RedeployGatewayRequest,
+	Admin => AdminRequest,
+	SetAdmin => SetAdminRequest,
+	Shards => ShardsRequest,
+	SetShards => SetShardsRequest,
+	Routes => RoutesRequest,
+	SetRoute => SetRouteRequest,
+	DeployTest => DeployTestRequest,
+	EstimateMessageGasLimit => EstimateMessageGasLimitRequest,
+	EstimateMessageCost => EstimateMessageCostRequest,
+	SendMessage => SendMessageRequest,
+	RecvMessages => RecvMessagesRequest,
+	TransactionBaseFee => TransactionBaseFeeRequest,
+	BlockGasLimit => BlockGasLimitRequest,
+	WithdrawFunds => WithdrawFundsRequest,
+	Networks => NetworksRequest,
+	CreateNetwork => CreateNetworkRequest,
+	SnapshotNetwork => SnapshotNetworkRequest,
+	AdvanceBlocks => AdvanceBlocksRequest,
+}
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use ed25519_dalek::SigningKey;
 use futures::{Stream, StreamExt};
 use redb::{
 	Database, Key, MultimapTableDefinition, ReadableTable, TableDefinition, TypeName, Value,
@@ -10,7 +11,7 @@ use std::cmp::Ordering;
 use std::collections::BTreeSet;
 use std::fmt::Debug;
 use std::ops::Range;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -20,11 +21,13 @@ use time_primitives::{
 	IChain, IConnector, IConnectorAdmin, IConnectorBuilder, MessageId, NetworkId, Route,
 	TssPublicKey, TssSignature,
 };
+use tokio::sync::watch;
 
 const BLOCK_TIME: u64 = 1;
 const FINALIZATION_TIME: u64 = 2;
 
 const BLOCKS: TableDefinition<u64, u64> = TableDefinition::new("blocks");
+const CLOCK: TableDefinition<(), u64> = TableDefinition::new("clock");
 const BALANCE: TableDefinition<Address, u128> = TableDefinition::new("balance");
 const ADMIN: TableDefinition<Address, Address> = TableDefinition::new("admin");
 const NONCE: TableDefinition<(Address, Address), u64> = TableDefinition::new("nonce");
@@ -37,12 +40,61 @@ const ROUTES: TableDefinition<(Address, NetworkId), Bincode<Route>> =
 const GATEWAY: TableDefinition<Address, Address> = TableDefinition::new("gateway");
 const TESTERS: MultimapTableDefinition<Address, Address> = MultimapTableDefinition::new("testers");
 
+/// Url suffix selecting a manual clock when creating a chain.
+const MANUAL_CLOCK: &str = "?clock=manual";
+
+/// Source of the current block of the chain.
+#[derive(Clone)]
+enum Clock {
+	/// A block is produced every `BLOCK_TIME` seconds since genesis.
+	WallClock(SystemTime),
+	/// Blocks are only produced by `advance_blocks`, the current block is stored in `CLOCK`.
+	Manual(Arc<watch::Sender<u64>>),
+}
+
+impl Clock {
+	fn block(&self) -> u64 {
+		match self {
+			Self::WallClock(genesis) => {
+				let elapsed = SystemTime::now().duration_since(*genesis).unwrap();
+				elapsed.as_secs() / BLOCK_TIME
+			},
+			Self::Manual(block) => *block.borrow(),
+		}
+	}
+
+	fn stream(&self) -> Pin<Box<dyn Stream<Item = u64> + Send>> {
+		match self {
+			Self::WallClock(genesis) => {
+				let genesis = *genesis;
+				futures::stream::repeat(0)
+					.then(move |_| async move {
+						tokio::time::sleep(Duration::from_secs(FINALIZATION_TIME)).await;
+						Self::WallClock(genesis).block()
+					})
+					.boxed()
+			},
+			Self::Manual(block) => {
+				futures::stream::unfold((block.subscribe(), true), |(mut rx, first)| async move {
+					if !first {
+						rx.changed().await.ok()?;
+					}
+					let block = *rx.borrow_and_update();
+					Some((block, (rx, false)))
+				})
+				.boxed()
+			},
+		}
+	}
+}
+
 #[derive(Clone)]
 pub struct Connector {
 	network_id: NetworkId,
 	address: Address,
 	db: Arc<Database>,
-	genesis: SystemTime,
+	path: PathBuf,
+	clock: Clock,
 	_tmpfile: Option<Arc<NamedTempFile>>,
 }
 
@@ -57,6 +109,14 @@ impl Connector {
 		clone
 	}
 
+	/// Copies a consistent snapshot of the chain database to `path`.
+	pub fn snapshot(&self, path: &Path) -> Result<()> {
+		// holding a write transaction prevents commits while copying
+		let _tx = self.db.begin_write()?;
+		std::fs::copy(&self.path, path)?;
+		Ok(())
+	}
+
 	fn ensure_admin(&self, tx: &WriteTransaction, gateway: Address) -> Result<()> {
 		let t = tx.open_table(ADMIN)?;
 		let admin = read_admin(&t, gateway)?;
@@ -85,8 +145,14 @@ impl Connector {
 	}
 }
 
+/// Derives the ed25519 key used to authenticate requests on behalf of an account.
+pub fn mnemonic_to_signing_key(mnemonic: &str) -> SigningKey {
+	SigningKey::from_bytes(blake3::hash(mnemonic.as_bytes()).as_bytes())
+}
+
+/// Accounts are identified by the public key of their signing key.
 pub fn mnemonic_to_address(mnemonic: String) -> Address {
-	*blake3::hash(mnemonic.as_bytes()).as_bytes()
+	mnemonic_to_signing_key(&mnemonic).verifying_key().to_bytes()
 }
 
 pub fn format_address(address: Address) -> String {
@@ -103,11 +169,6 @@ pub fn currency() -> (u32, &'static str) {
 	(3, "TT")
 }
 
-fn block(genesis: SystemTime) -> u64 {
-	let elapsed = SystemTime::now().duration_since(genesis).unwrap();
-	elapsed.as_secs() / BLOCK_TIME
-}
-
 fn read_balance<T: ReadableTable<Address, u128>>(table: &T, addr: Address) -> Result<u128> {
 	Ok(if let Some(value) = table.get(addr)? { value.value() } else { 0 })
 }
@@ -127,14 +188,18 @@ impl IConnectorBuilder for Connector {
 			anyhow::bail!("unsupported blockchain");
 		}
 		let address = mnemonic_to_address(params.mnemonic);
-		let (tmpfile, path) = if params.url == "tempfile" {
+		let (url, manual_clock) = match params.url.strip_suffix(MANUAL_CLOCK) {
+			Some(url) => (url, true),
+			None => (params.url.as_str(), false),
+		};
+		let (tmpfile, path) = if url == "tempfile" {
 			let file = NamedTempFile::new()?;
 			let path = file.path().to_owned();
 			(Some(Arc::new(file)), path)
 		} else {
-			(None, Path::new(&params.url).to_owned())
+			(None, Path::new(url).to_owned())
 		};
-		let db = Database::create(path)?;
+		let db = Database::create(&path)?;
 		let tx = db.begin_write()?;
 		let genesis = {
 			let mut blocks = tx.open_table(BLOCKS)?;
@@ -147,6 +212,21 @@ impl IConnectorBuilder for Connector {
 				genesis
 			}
 		};
+		// once a chain uses a manual clock it keeps using it, starting at the
+		// block it was switched at
+		let clock = {
+			let mut clock = tx.open_table(CLOCK)?;
+			let block = clock.get(())?.map(|b| b.value());
+			match block {
+				Some(block) => Clock::Manual(Arc::new(watch::Sender::new(block))),
+				None if manual_clock => {
+					let block = Clock::WallClock(genesis).block();
+					clock.insert((), block)?;
+					Clock::Manual(Arc::new(watch::Sender::new(block)))
+				},
+				None => Clock::WallClock(genesis),
+			}
+		};
 		tx.open_table(BALANCE)?;
 		tx.open_table(ADMIN)?;
 		tx.open_table(ROUTES)?;
@@ -160,7 +240,8 @@ impl IConnectorBuilder for Connector {
 			network_id: params.network_id,
 			address,
 			db: Arc::new(db),
-			genesis,
+			path,
+			clock,
 			_tmpfile: tmpfile,
 		})
 	}
@@ -220,18 +301,12 @@ impl IChain for Connector {
 	}
 
 	async fn finalized_block(&self) -> Result<u64> {
-		Ok(block(self.genesis))
+		Ok(self.clock.block())
 	}
 
 	/// Stream of finalized block indexes.
 	fn block_stream(&self) -> Pin<Box<dyn Stream<Item = u64> + Send>> {
-		let genesis = self.genesis;
-		futures::stream::repeat(0)
-			.then(move |_| async move {
-				tokio::time::sleep(Duration::from_secs(FINALIZATION_TIME)).await;
-				block(genesis)
-			})
-			.boxed()
+		self.clock.stream()
 	}
 }
 
@@ -270,7 +345,8 @@ impl IConnector for Connector {
 			{
 				let mut events = tx.open_multimap_table(EVENTS)?;
 				let mut shards = tx.open_multimap_table(SHARDS)?;
-				let block = block(self.genesis);
+				let mut routes = tx.open_table(ROUTES)?;
+				let block = self.clock.block();
 				for op in &msg.ops {
 					match op {
 						GatewayOp::RegisterShard(key) => {
@@ -291,6 +367,9 @@ impl IConnector for Connector {
 								GmpEvent::MessageExecuted(msg.message_id()),
 							)?;
 						},
+						GatewayOp::SetRoute(route) => {
+							routes.insert((gateway, route.network_id), route)?;
+						},
 					}
 				}
 				events.insert(
@@ -315,7 +394,7 @@ impl IConnectorAdmin for Connector {
 	) -> Result<(Address, u64)> {
 		let mut gateway = [0; 32];
 		getrandom::getrandom(&mut gateway).unwrap();
-		let block = block(self.genesis);
+		let block = self.clock.block();
 		let tx = self.db.begin_write()?;
 		{
 			let mut t = tx.open_table(ADMIN)?;
@@ -368,7 +447,7 @@ impl IConnectorAdmin for Connector {
 			self.ensure_admin(&tx, gateway)?;
 			let mut events = tx.open_multimap_table(EVENTS)?;
 			let mut shards = tx.open_multimap_table(SHARDS)?;
-			let block = block(self.genesis);
+			let block = self.clock.block();
 			let values = shards.remove_all(gateway)?;
 			let keys: BTreeSet<_> = keys.iter().copied().collect();
 			let mut old_keys = BTreeSet::new();
@@ -435,7 +514,7 @@ impl IConnectorAdmin for Connector {
 	async fn deploy_test(&self, gateway: Address, _path: &[u8]) -> Result<(Address, u64)> {
 		let mut tester = [0; 32];
 		getrandom::getrandom(&mut tester).unwrap();
-		let block = block(self.genesis);
+		let block = self.clock.block();
 		let tx = self.db.begin_write()?;
 		{
 			let mut t = tx.open_table(GATEWAY)?;
@@ -501,7 +580,7 @@ impl IConnectorAdmin for Connector {
 
 			// insert gateway event
 			let mut t = tx.open_multimap_table(EVENTS)?;
-			let block = block(self.genesis);
+			let block = self.clock.block();
 			t.insert((gateway, block), GmpEvent::MessageReceived(msg))?;
 			id
 		};
@@ -542,6 +621,23 @@ impl IConnectorAdmin for Connector {
 		tx.commit()?;
 		Ok(())
 	}
+
+	/// Advances a chain with a manual clock.
+	async fn advance_blocks(&self, blocks: u64) -> Result<u64> {
+		let Clock::Manual(sender) = &self.clock else {
+			anyhow::bail!("chain uses a wall clock");
+		};
+		let tx = self.db.begin_write()?;
+		let block = {
+			let mut t = tx.open_table(CLOCK)?;
+			let block = t.get(())?.context("missing clock")?.value() + blocks;
+			t.insert((), block)?;
+			block
+		};
+		tx.commit()?;
+		sender.send_replace(block);
+		Ok(block)
+	}
 }
 
 #[derive(Debug)]
@@ -600,11 +696,15 @@ mod tests {
 	use time_primitives::MockTssSigner;
 
 	async fn connector(network: NetworkId, mnemonic: u8) -> Result<Connector> {
+		connector_with_url(network, mnemonic, "tempfile").await
+	}
+
+	async fn connector_with_url(network: NetworkId, mnemonic: u8, url: &str) -> Result<Connector> {
 		Connector::new(ConnectorParams {
 			network_id: network,
 			blockchain: "rust".to_string(),
 			network: network.to_string(),
-			url: "tempfile".to_string(),
+			url: url.to_string(),
 			mnemonic: mnemonic.to_string(),
 			cctp_sender: None,
 			cctp_attestation: None,
@@ -662,4 +762,31 @@ mod tests {
 		assert_eq!(msgs, vec![msg]);
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn manual_clock() -> Result<()> {
+		let chain = connector_with_url(0, 0, "tempfile?clock=manual").await?;
+		let shard = MockTssSigner::new(0);
+		let mut blocks = chain.block_stream();
+		assert_eq!(chain.finalized_block().await?, 0);
+		assert_eq!(blocks.next().await, Some(0));
+		let (gateway, _) = chain.deploy_gateway("".as_ref(), "".as_ref(), "".as_ref()).await?;
+		assert_eq!(chain.advance_blocks(5).await?, 5);
+		assert_eq!(blocks.next().await, Some(5));
+		chain.set_shards(gateway, &[shard.public_key()]).await?;
+		assert_eq!(chain.finalized_block().await?, 5);
+		assert_eq!(chain.read_events(gateway, 0..5).await?, vec![]);
+		assert_eq!(
+			chain.read_events(gateway, 5..6).await?,
+			vec![GmpEvent::ShardRegistered(shard.public_key())]
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn wall_clock_cannot_be_advanced() -> Result<()> {
+		let chain = connector(0, 0).await?;
+		assert!(chain.advance_blocks(1).await.is_err());
+		Ok(())
+	}
 }
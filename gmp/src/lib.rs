@@ -9,6 +9,7 @@ pub enum Backend {
 	Evm,
 	Grpc,
 	Rust,
+	Svm,
 }
 
 impl std::str::FromStr for Backend {
@@ -19,6 +20,7 @@ impl std::str::FromStr for Backend {
 			"evm" => Self::Evm,
 			"grpc" => Self::Grpc,
 			"rust" => Self::Rust,
+			"svm" => Self::Svm,
 			_ => anyhow::bail!("unsupported backend"),
 		})
 	}
@@ -30,6 +32,7 @@ impl Backend {
 			Self::Evm => Arc::new(gmp_evm::Connector::new(params.clone()).await?),
 			Self::Grpc => Arc::new(gmp_grpc::Connector::new(params.clone()).await?),
 			Self::Rust => Arc::new(gmp_rust::Connector::new(params.clone()).await?),
+			Self::Svm => Arc::new(gmp_svm::Connector::new(params.clone()).await?),
 		})
 	}
 
@@ -41,6 +44,7 @@ impl Backend {
 			Self::Evm => Arc::new(gmp_evm::Connector::new(params.clone()).await?),
 			Self::Grpc => Arc::new(gmp_grpc::Connector::new(params.clone()).await?),
 			Self::Rust => Arc::new(gmp_rust::Connector::new(params.clone()).await?),
+			Self::Svm => Arc::new(gmp_svm::Connector::new(params.clone()).await?),
 		})
 	}
 }
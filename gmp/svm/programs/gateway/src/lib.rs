@@ -0,0 +1,304 @@
+//! Gateway program of the svm gmp connector.
+//!
+//! Every gateway is a state account owned by this program. Batches signed by one of its
+//! shards are executed, messages to other networks are charged according to the route to
+//! the destination network and emitted as [`GmpEvent::MessageReceived`].
+use gmp_svm_interface::{
+	schnorr, Address, BatchId, GatewayInstruction, GatewayMessage, GatewayOp, GatewayState,
+	GmpEvent, GmpMessage, NetworkId, TesterInstruction, TssPublicKey, TssSignature, AUTHORITY_SEED,
+};
+use scale_codec::{Decode, Encode};
+use solana_program::account_info::{next_account_info, AccountInfo};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::log::sol_log_data;
+use solana_program::program::{invoke, invoke_signed};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_instruction;
+use solana_program::sysvar::Sysvar;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GatewayError {
+	/// The state account was initialized already.
+	AlreadyInitialized,
+	/// The admin didn't sign the instruction.
+	NotAdmin,
+	/// The signer of the batch isn't a registered shard.
+	UnknownShard,
+	/// The signature of the batch is invalid.
+	InvalidSignature,
+	/// The batch was executed already.
+	BatchExecuted,
+	/// The gateway has no route to the destination network.
+	NoRoute,
+	/// The gas cost doesn't cover the cost of the route.
+	InsufficientGasCost,
+	/// The state doesn't fit into the state account.
+	StateTooLarge,
+	/// The withdrawal exceeds the balance above the rent exemption.
+	InsufficientFunds,
+	/// An account doesn't match the expected one.
+	InvalidAccount,
+}
+
+impl From<GatewayError> for ProgramError {
+	fn from(err: GatewayError) -> Self {
+		ProgramError::Custom(err as u32)
+	}
+}
+
+pub fn process_instruction(
+	program_id: &Pubkey,
+	accounts: &[AccountInfo],
+	data: &[u8],
+) -> ProgramResult {
+	let ix = GatewayInstruction::decode(&mut &data[..])
+		.map_err(|_| ProgramError::InvalidInstructionData)?;
+	match ix {
+		GatewayInstruction::Initialize { network } => initialize(program_id, accounts, network),
+		GatewayInstruction::Execute { batch, msg, signer, sig } => {
+			execute(program_id, accounts, batch, msg, signer, sig)
+		},
+		GatewayInstruction::SetAdmin { admin } => {
+			admin_call(program_id, accounts, |state| state.admin = admin)
+		},
+		GatewayInstruction::SetShards { shards } => {
+			admin_call(program_id, accounts, |state| state.shards = shards)
+		},
+		GatewayInstruction::SetRoute { route } => admin_call(program_id, accounts, |state| {
+			state.routes.retain(|r| r.network_id != route.network_id);
+			state.routes.push(route);
+		}),
+		GatewayInstruction::Withdraw { amount } => withdraw(program_id, accounts, amount),
+		GatewayInstruction::SendMessage {
+			dest_network,
+			dest,
+			gas_limit,
+			gas_cost,
+			payload,
+		} => send_message(program_id, accounts, dest_network, dest, gas_limit, gas_cost, payload),
+	}
+}
+
+fn load(program_id: &Pubkey, account: &AccountInfo) -> Result<GatewayState, ProgramError> {
+	if account.owner != program_id {
+		return Err(ProgramError::IncorrectProgramId);
+	}
+	GatewayState::decode(&mut &account.try_borrow_data()?[..])
+		.map_err(|_| ProgramError::InvalidAccountData)
+}
+
+fn store(account: &AccountInfo, state: &GatewayState) -> ProgramResult {
+	let bytes = state.encode();
+	let mut data = account.try_borrow_mut_data()?;
+	if bytes.len() > data.len() {
+		return Err(GatewayError::StateTooLarge.into());
+	}
+	data[..bytes.len()].copy_from_slice(&bytes);
+	Ok(())
+}
+
+fn emit(event: GmpEvent) {
+	sol_log_data(&[&event.encode()]);
+}
+
+/// Accounts: `[state (w, s), admin (s)]`.
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], network: NetworkId) -> ProgramResult {
+	let accounts = &mut accounts.iter();
+	let state_info = next_account_info(accounts)?;
+	let admin = next_account_info(accounts)?;
+	if !state_info.is_signer || !admin.is_signer {
+		return Err(ProgramError::MissingRequiredSignature);
+	}
+	let state = load(program_id, state_info)?;
+	if state.admin != Address::default() {
+		return Err(GatewayError::AlreadyInitialized.into());
+	}
+	let state = GatewayState {
+		admin: admin.key.to_bytes(),
+		network,
+		..Default::default()
+	};
+	store(state_info, &state)
+}
+
+/// Accounts: `[state (w), admin (s)]`.
+fn admin_call(
+	program_id: &Pubkey,
+	accounts: &[AccountInfo],
+	f: impl FnOnce(&mut GatewayState),
+) -> ProgramResult {
+	let accounts = &mut accounts.iter();
+	let state_info = next_account_info(accounts)?;
+	let admin = next_account_info(accounts)?;
+	let mut state = load(program_id, state_info)?;
+	if !admin.is_signer || admin.key.to_bytes() != state.admin {
+		return Err(GatewayError::NotAdmin.into());
+	}
+	f(&mut state);
+	store(state_info, &state)
+}
+
+/// Accounts: `[state (w), admin (s), recipient (w)]`.
+fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+	let accounts = &mut accounts.iter();
+	let state_info = next_account_info(accounts)?;
+	let admin = next_account_info(accounts)?;
+	let recipient = next_account_info(accounts)?;
+	let state = load(program_id, state_info)?;
+	if !admin.is_signer || admin.key.to_bytes() != state.admin {
+		return Err(GatewayError::NotAdmin.into());
+	}
+	let rent = Rent::get()?.minimum_balance(state_info.data_len());
+	if state_info.lamports().saturating_sub(rent) < amount {
+		return Err(GatewayError::InsufficientFunds.into());
+	}
+	**state_info.try_borrow_mut_lamports()? -= amount;
+	**recipient.try_borrow_mut_lamports()? += amount;
+	Ok(())
+}
+
+/// Accounts: `[state (w), payer (w, s), authority]` followed by the destination account
+/// and program of every `SendMessage` op, destinations which don't exist are skipped by the
+/// connector and their messages are dropped.
+fn execute(
+	program_id: &Pubkey,
+	accounts: &[AccountInfo],
+	batch: BatchId,
+	msg: GatewayMessage,
+	signer: TssPublicKey,
+	sig: TssSignature,
+) -> ProgramResult {
+	let accounts = &mut accounts.iter().peekable();
+	let state_info = next_account_info(accounts)?;
+	let _payer = next_account_info(accounts)?;
+	let authority = next_account_info(accounts)?;
+	let mut state = load(program_id, state_info)?;
+	if !state.shards.contains(&signer) {
+		return Err(GatewayError::UnknownShard.into());
+	}
+	let hash = msg.signing_hash(state.network, &state_info.key.to_bytes(), batch);
+	if !schnorr::verify(&signer, &hash, &sig) {
+		return Err(GatewayError::InvalidSignature.into());
+	}
+	if !state.record_batch(batch) {
+		return Err(GatewayError::BatchExecuted.into());
+	}
+	let (authority_key, bump) =
+		Pubkey::find_program_address(&[AUTHORITY_SEED, state_info.key.as_ref()], program_id);
+	if *authority.key != authority_key {
+		return Err(GatewayError::InvalidAccount.into());
+	}
+	// the batch is recorded before any destination is invoked
+	store(state_info, &state)?;
+	for op in msg.ops {
+		match op {
+			GatewayOp::SendMessage(msg) => {
+				let id = msg.message_id();
+				match accounts.next_if(|account| account.key.to_bytes() == msg.dest) {
+					Some(dest) => {
+						let program = next_account_info(accounts)?;
+						if msg.dest_network == state.network {
+							deliver(state_info, authority, bump, dest, program, msg)?;
+						}
+					},
+					None => solana_program::msg!("dropping message to a missing destination"),
+				}
+				emit(GmpEvent::MessageExecuted(id));
+			},
+			GatewayOp::RegisterShard(key) => {
+				if !state.shards.contains(&key) {
+					state.shards.push(key);
+				}
+				emit(GmpEvent::ShardRegistered(key));
+			},
+			GatewayOp::UnregisterShard(key) => {
+				state.shards.retain(|shard| *shard != key);
+				emit(GmpEvent::ShardUnregistered(key));
+			},
+			GatewayOp::SetRoute(route) => {
+				state.routes.retain(|r| r.network_id != route.network_id);
+				state.routes.push(route);
+			},
+		}
+	}
+	emit(GmpEvent::BatchExecuted { batch_id: batch, tx_hash: None });
+	store(state_info, &state)
+}
+
+/// Invokes `OnMessage` of the destination program, signed by the gateway authority.
+fn deliver<'a>(
+	state_info: &AccountInfo<'a>,
+	authority: &AccountInfo<'a>,
+	bump: u8,
+	dest: &AccountInfo<'a>,
+	program: &AccountInfo<'a>,
+	msg: GmpMessage,
+) -> ProgramResult {
+	let ix = Instruction {
+		program_id: *program.key,
+		accounts: vec![
+			AccountMeta::new(*dest.key, false),
+			AccountMeta::new_readonly(*state_info.key, false),
+			AccountMeta::new_readonly(*authority.key, true),
+		],
+		data: TesterInstruction::OnMessage(msg).encode(),
+	};
+	invoke_signed(
+		&ix,
+		&[dest.clone(), state_info.clone(), authority.clone(), program.clone()],
+		&[&[AUTHORITY_SEED, state_info.key.as_ref(), &[bump]]],
+	)
+}
+
+/// Accounts: `[state (w), sender (s), payer (w, s), system program]`.
+fn send_message(
+	program_id: &Pubkey,
+	accounts: &[AccountInfo],
+	dest_network: NetworkId,
+	dest: Address,
+	gas_limit: u128,
+	gas_cost: u128,
+	payload: Vec<u8>,
+) -> ProgramResult {
+	let accounts = &mut accounts.iter();
+	let state_info = next_account_info(accounts)?;
+	let sender = next_account_info(accounts)?;
+	let payer = next_account_info(accounts)?;
+	let system_program = next_account_info(accounts)?;
+	if !sender.is_signer || !payer.is_signer {
+		return Err(ProgramError::MissingRequiredSignature);
+	}
+	let mut state = load(program_id, state_info)?;
+	let route = state
+		.routes
+		.iter()
+		.find(|route| route.network_id == dest_network)
+		.ok_or(GatewayError::NoRoute)?;
+	if gas_cost < route.message_cost(gas_limit, payload.len()) {
+		return Err(GatewayError::InsufficientGasCost.into());
+	}
+	let lamports = u64::try_from(gas_cost).map_err(|_| ProgramError::ArithmeticOverflow)?;
+	invoke(
+		&system_instruction::transfer(payer.key, state_info.key, lamports),
+		&[payer.clone(), state_info.clone(), system_program.clone()],
+	)?;
+	let msg = GmpMessage {
+		src_network: state.network,
+		dest_network,
+		src: sender.key.to_bytes(),
+		dest,
+		nonce: state.nonce,
+		gas_limit,
+		gas_cost,
+		bytes: payload,
+	};
+	state.nonce += 1;
+	emit(GmpEvent::MessageReceived(msg));
+	store(state_info, &state)
+}
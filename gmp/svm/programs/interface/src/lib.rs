@@ -0,0 +1,264 @@
+//! Types and encodings shared by the gateway and tester programs.
+//!
+//! Mirrors `time_primitives` and `gmp_svm::program`, the scale encoding of every type and
+//! the hashes signed by the shards have to stay identical to the ones of the connector.
+use scale_codec::{Decode, Encode};
+use solana_program::keccak::hashv;
+
+pub mod schnorr;
+
+pub type NetworkId = u16;
+pub type Address = [u8; 32];
+pub type BatchId = u64;
+pub type MessageId = [u8; 32];
+pub type TssPublicKey = [u8; 33];
+pub type TssSignature = [u8; 64];
+
+/// Seed of the gateway account signing the delivery of messages.
+pub const AUTHORITY_SEED: &[u8] = b"authority";
+/// Seed of the tester account sending messages, it is the source of the messages.
+pub const SENDER_SEED: &[u8] = b"sender";
+/// Number of executed batches a gateway remembers to reject replays.
+pub const MAX_EXECUTED_BATCHES: usize = 256;
+
+const GMP_VERSION: &[u8] = b"Analog GMP v2";
+
+#[derive(Clone, Debug, Default, Encode, Decode, PartialEq, Eq)]
+pub struct GmpMessage {
+	pub src_network: NetworkId,
+	pub dest_network: NetworkId,
+	pub src: Address,
+	pub dest: Address,
+	pub nonce: u64,
+	pub gas_limit: u128,
+	pub gas_cost: u128,
+	pub bytes: Vec<u8>,
+}
+
+impl GmpMessage {
+	pub fn message_id(&self) -> MessageId {
+		// the gas cost isn't part of the id as it depends on the route
+		let mut hdr = [0u8; 224];
+		hdr[32..64].copy_from_slice(&self.src);
+		hdr[94..96].copy_from_slice(&self.src_network.to_be_bytes());
+		hdr[96..128].copy_from_slice(&self.dest);
+		hdr[158..160].copy_from_slice(&self.dest_network.to_be_bytes());
+		hdr[176..192].copy_from_slice(&self.gas_limit.to_be_bytes());
+		hdr[216..224].copy_from_slice(&self.nonce.to_be_bytes());
+		hashv(&[&hdr, &hashv(&[&self.bytes]).0]).0
+	}
+}
+
+#[derive(Clone, Debug, Default, Encode, Decode, PartialEq, Eq)]
+pub struct RouteState {
+	pub network_id: NetworkId,
+	pub gateway: Address,
+	pub relative_gas_price: (u128, u128),
+	pub gas_limit: u64,
+	pub base_fee: u128,
+}
+
+impl RouteState {
+	/// Cost in lamports of delivering a message.
+	pub fn message_cost(&self, gas_limit: u128, payload_len: usize) -> u128 {
+		let (num, den) = self.relative_gas_price;
+		let gas = gas_limit.saturating_add((payload_len as u128).saturating_mul(16));
+		self.base_fee.saturating_add(gas.saturating_mul(num).div_ceil(den.max(1)))
+	}
+
+	fn hash(&self) -> [u8; 32] {
+		let mut op_bytes = [0u8; 192];
+		op_bytes[30..32].copy_from_slice(&self.network_id.to_be_bytes());
+		op_bytes[56..64].copy_from_slice(&self.gas_limit.to_be_bytes());
+		op_bytes[80..96].copy_from_slice(&self.base_fee.to_be_bytes());
+		op_bytes[96..128].copy_from_slice(&self.gateway);
+		op_bytes[144..160].copy_from_slice(&self.relative_gas_price.0.to_be_bytes());
+		op_bytes[176..192].copy_from_slice(&self.relative_gas_price.1.to_be_bytes());
+		hashv(&[&op_bytes]).0
+	}
+}
+
+#[derive(Clone, Debug, Encode, Decode, PartialEq, Eq)]
+pub enum GatewayOp {
+	SendMessage(GmpMessage),
+	RegisterShard(TssPublicKey),
+	UnregisterShard(TssPublicKey),
+	SetRoute(RouteState),
+}
+
+impl GatewayOp {
+	fn code(&self) -> u8 {
+		match self {
+			Self::SendMessage(_) => 1,
+			Self::RegisterShard(_) => 2,
+			Self::UnregisterShard(_) => 3,
+			Self::SetRoute(_) => 4,
+		}
+	}
+
+	fn hash(&self) -> [u8; 32] {
+		match self {
+			Self::SendMessage(msg) => msg.message_id(),
+			Self::RegisterShard(key) | Self::UnregisterShard(key) => {
+				let mut op_bytes = [0u8; 64];
+				op_bytes[31..].copy_from_slice(key);
+				hashv(&[&op_bytes]).0
+			},
+			Self::SetRoute(route) => route.hash(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Encode, Decode, PartialEq, Eq)]
+pub struct GatewayMessage {
+	pub ops: Vec<GatewayOp>,
+}
+
+impl GatewayMessage {
+	/// Hash of the batch, the same as the one of the evm gateway.
+	pub fn hash(&self, batch: BatchId) -> [u8; 32] {
+		let mut root = [0u8; 32];
+		for op in &self.ops {
+			let mut code = [0u8; 32];
+			code[31] = op.code();
+			root = hashv(&[&root, &code, &op.hash()]).0;
+		}
+		let mut batch_id = [0u8; 32];
+		batch_id[24..].copy_from_slice(&batch.to_be_bytes());
+		// the version is encoded as a zero word
+		hashv(&[&[0u8; 32], &batch_id, &root]).0
+	}
+
+	/// Hash of the batch for the gateway `gateway` of `network` the shards sign.
+	pub fn signing_hash(&self, network: NetworkId, gateway: &Address, batch: BatchId) -> [u8; 32] {
+		hashv(&[GMP_VERSION, &network.to_be_bytes(), gateway, &self.hash(batch)]).0
+	}
+}
+
+#[derive(Clone, Debug, Encode, Decode, PartialEq, Eq)]
+pub enum GmpEvent {
+	ShardRegistered(TssPublicKey),
+	ShardUnregistered(TssPublicKey),
+	MessageReceived(GmpMessage),
+	MessageExecuted(MessageId),
+	BatchExecuted { batch_id: BatchId, tx_hash: Option<[u8; 32]> },
+}
+
+/// Data of the account identifying a gateway.
+#[derive(Clone, Debug, Default, Encode, Decode, PartialEq, Eq)]
+pub struct GatewayState {
+	pub admin: Address,
+	pub network: NetworkId,
+	pub shards: Vec<TssPublicKey>,
+	pub routes: Vec<RouteState>,
+	pub nonce: u64,
+	/// The last executed batches in ascending order.
+	pub executed_batches: Vec<BatchId>,
+}
+
+impl GatewayState {
+	/// Records the execution of `batch`, returns false if it was executed already or is
+	/// older than the batches the gateway remembers.
+	pub fn record_batch(&mut self, batch: BatchId) -> bool {
+		let Err(index) = self.executed_batches.binary_search(&batch) else {
+			return false;
+		};
+		if index == 0 && self.executed_batches.len() >= MAX_EXECUTED_BATCHES {
+			return false;
+		}
+		self.executed_batches.insert(index, batch);
+		if self.executed_batches.len() > MAX_EXECUTED_BATCHES {
+			self.executed_batches.remove(0);
+		}
+		true
+	}
+}
+
+/// Data of the account identifying a tester.
+#[derive(Clone, Debug, Default, Encode, Decode, PartialEq, Eq)]
+pub struct TesterState {
+	pub gateway: Address,
+}
+
+/// Instructions of the gateway program, see `gmp_svm::program` for the accounts.
+#[derive(Clone, Debug, Encode, Decode)]
+pub enum GatewayInstruction {
+	Initialize {
+		network: NetworkId,
+	},
+	Execute {
+		batch: BatchId,
+		msg: GatewayMessage,
+		signer: TssPublicKey,
+		sig: TssSignature,
+	},
+	SetAdmin {
+		admin: Address,
+	},
+	SetShards {
+		shards: Vec<TssPublicKey>,
+	},
+	SetRoute {
+		route: RouteState,
+	},
+	Withdraw {
+		amount: u64,
+	},
+	SendMessage {
+		dest_network: NetworkId,
+		dest: Address,
+		gas_limit: u128,
+		gas_cost: u128,
+		payload: Vec<u8>,
+	},
+}
+
+/// Instructions of the tester program, see `gmp_svm::program` for the accounts.
+///
+/// Programs receiving messages from the gateway implement `OnMessage` with the same
+/// encoding.
+#[derive(Clone, Debug, Encode, Decode)]
+pub enum TesterInstruction {
+	Initialize {
+		gateway: Address,
+	},
+	SendMessage {
+		dest_network: NetworkId,
+		dest: Address,
+		gas_limit: u128,
+		gas_cost: u128,
+		payload: Vec<u8>,
+	},
+	OnMessage(GmpMessage),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rejects_replayed_batches() {
+		let mut state = GatewayState::default();
+		assert!(state.record_batch(5));
+		assert!(state.record_batch(3));
+		assert!(!state.record_batch(5));
+		for batch in 6..6 + MAX_EXECUTED_BATCHES as u64 {
+			assert!(state.record_batch(batch));
+		}
+		assert_eq!(state.executed_batches.len(), MAX_EXECUTED_BATCHES);
+		// batches older than the remembered ones can't be told apart from replays
+		assert!(!state.record_batch(4));
+		assert!(!state.record_batch(6 + MAX_EXECUTED_BATCHES as u64 - 1));
+		assert!(state.record_batch(6 + MAX_EXECUTED_BATCHES as u64));
+	}
+
+	#[test]
+	fn message_cost() {
+		let route = RouteState {
+			relative_gas_price: (3, 2),
+			base_fee: 10,
+			..Default::default()
+		};
+		assert_eq!(route.message_cost(100, 1), 10 + (116 * 3).div_ceil(2));
+	}
+}
@@ -0,0 +1,155 @@
+//! Verification of the schnorr signatures of the shards.
+//!
+//! Uses the same `ecrecover` construction as the evm gateway: for a public key with x
+//! coordinate `px` and a signature `(e, s)` the point `R = s * G - e * P` is recovered from
+//! `ecrecover(-s * px, parity, px, -e * px)` and the signature is valid if
+//! `e == keccak256(address(R) || parity || px || message)`.
+use crate::{TssPublicKey, TssSignature};
+use solana_program::keccak::hashv;
+use solana_program::secp256k1_recover::secp256k1_recover;
+
+/// 256 bit integer as little endian limbs.
+type U256 = [u64; 4];
+
+/// Order of the secp256k1 group.
+const Q: U256 = [0xBFD25E8CD0364141, 0xBAAEDCE6AF48A03B, 0xFFFFFFFFFFFFFFFE, 0xFFFFFFFFFFFFFFFF];
+
+fn from_be(bytes: &[u8]) -> U256 {
+	let mut limbs = [0; 4];
+	for (i, limb) in limbs.iter_mut().enumerate() {
+		let start = 24 - i * 8;
+		*limb = u64::from_be_bytes(bytes[start..start + 8].try_into().unwrap());
+	}
+	limbs
+}
+
+fn to_be(limbs: &U256) -> [u8; 32] {
+	let mut bytes = [0; 32];
+	for (i, limb) in limbs.iter().enumerate() {
+		let start = 24 - i * 8;
+		bytes[start..start + 8].copy_from_slice(&limb.to_be_bytes());
+	}
+	bytes
+}
+
+fn geq(a: &U256, b: &U256) -> bool {
+	for i in (0..4).rev() {
+		if a[i] != b[i] {
+			return a[i] > b[i];
+		}
+	}
+	true
+}
+
+fn sub(a: &U256, b: &U256) -> U256 {
+	let mut out = [0; 4];
+	let mut borrow = false;
+	for i in 0..4 {
+		let (diff, b1) = a[i].overflowing_sub(b[i]);
+		let (diff, b2) = diff.overflowing_sub(borrow as u64);
+		out[i] = diff;
+		borrow = b1 || b2;
+	}
+	out
+}
+
+/// `a + b mod Q` for `a, b < Q`.
+fn add_mod(a: &U256, b: &U256) -> U256 {
+	let mut out = [0; 4];
+	let mut carry = false;
+	for i in 0..4 {
+		let (sum, c1) = a[i].overflowing_add(b[i]);
+		let (sum, c2) = sum.overflowing_add(carry as u64);
+		out[i] = sum;
+		carry = c1 || c2;
+	}
+	if carry || geq(&out, &Q) {
+		sub(&out, &Q)
+	} else {
+		out
+	}
+}
+
+/// `a * b mod Q` by double and add.
+fn mul_mod(a: &U256, b: &U256) -> U256 {
+	let mut a = *a;
+	while geq(&a, &Q) {
+		a = sub(&a, &Q);
+	}
+	let mut out = [0; 4];
+	for i in (0..256).rev() {
+		out = add_mod(&out, &out);
+		if (b[i / 64] >> (i % 64)) & 1 == 1 {
+			out = add_mod(&out, &a);
+		}
+	}
+	out
+}
+
+/// `-a mod Q` for `a < Q`.
+fn neg_mod(a: &U256) -> U256 {
+	if *a == [0; 4] {
+		*a
+	} else {
+		sub(&Q, a)
+	}
+}
+
+/// Verifies the signature of the shard with the public key `key` over the 32 byte `message`.
+pub fn verify(key: &TssPublicKey, message: &[u8; 32], sig: &TssSignature) -> bool {
+	let parity = match key[0] {
+		2 => 0,
+		3 => 1,
+		_ => return false,
+	};
+	let px = from_be(&key[1..]);
+	let e = from_be(&sig[..32]);
+	let s = from_be(&sig[32..]);
+	let sp = neg_mod(&mul_mod(&s, &px));
+	let ep = neg_mod(&mul_mod(&e, &px));
+	if sp == [0; 4] {
+		return false;
+	}
+	let mut rs = [0; 64];
+	rs[..32].copy_from_slice(&key[1..]);
+	rs[32..].copy_from_slice(&to_be(&ep));
+	let Ok(r) = secp256k1_recover(&to_be(&sp), parity, &rs) else {
+		return false;
+	};
+	let address = &hashv(&[&r.to_bytes()]).0[12..];
+	let challenge = hashv(&[address, &[27 + parity], &key[1..], message]).0;
+	challenge[..] == sig[..32]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn encodes_big_endian() {
+		let mut bytes = [0; 32];
+		bytes[31] = 1;
+		bytes[0] = 2;
+		let limbs = from_be(&bytes);
+		assert_eq!(limbs, [1, 0, 0, 2 << 56]);
+		assert_eq!(to_be(&limbs), bytes);
+	}
+
+	#[test]
+	fn modular_arithmetic() {
+		let minus_one = sub(&Q, &[1, 0, 0, 0]);
+		assert_eq!(add_mod(&minus_one, &[2, 0, 0, 0]), [1, 0, 0, 0]);
+		assert_eq!(neg_mod(&[1, 0, 0, 0]), minus_one);
+		// (-1) * (-1) = 1
+		assert_eq!(mul_mod(&minus_one, &minus_one), [1, 0, 0, 0]);
+		assert_eq!(mul_mod(&[3, 0, 0, 0], &[1 << 63, 0, 0, 0]), [1 << 63, 1, 0, 0]);
+		// operands above the order are reduced
+		assert_eq!(mul_mod(&Q, &[5, 0, 0, 0]), [0; 4]);
+	}
+
+	#[test]
+	fn rejects_invalid_keys() {
+		assert!(!verify(&[4; 33], &[0; 32], &[1; 64]));
+		assert!(!verify(&[2; 33], &[0; 32], &[0; 64]));
+	}
+}
@@ -0,0 +1,155 @@
+//! Tester program of the svm gmp connector.
+//!
+//! Every tester is a state account owned by this program and bound to a gateway. Messages
+//! are sent through the gateway with the sender account of the tester as source, received
+//! messages are logged for the connector to read.
+use gmp_svm_interface::{
+	Address, GatewayInstruction, GmpMessage, TesterInstruction, TesterState, AUTHORITY_SEED,
+	SENDER_SEED,
+};
+use scale_codec::{Decode, Encode};
+use solana_program::account_info::{next_account_info, AccountInfo};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::log::sol_log_data;
+use solana_program::program::invoke_signed;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_program;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TesterError {
+	/// The state account was initialized already.
+	AlreadyInitialized,
+	/// The gateway isn't the one of the tester.
+	WrongGateway,
+	/// The message wasn't delivered by the gateway.
+	NotGateway,
+	/// An account doesn't match the expected one.
+	InvalidAccount,
+}
+
+impl From<TesterError> for ProgramError {
+	fn from(err: TesterError) -> Self {
+		ProgramError::Custom(err as u32)
+	}
+}
+
+pub fn process_instruction(
+	program_id: &Pubkey,
+	accounts: &[AccountInfo],
+	data: &[u8],
+) -> ProgramResult {
+	let ix = TesterInstruction::decode(&mut &data[..])
+		.map_err(|_| ProgramError::InvalidInstructionData)?;
+	match ix {
+		TesterInstruction::Initialize { gateway } => initialize(program_id, accounts, gateway),
+		TesterInstruction::SendMessage {
+			dest_network,
+			dest,
+			gas_limit,
+			gas_cost,
+			payload,
+		} => {
+			let ix = GatewayInstruction::SendMessage {
+				dest_network,
+				dest,
+				gas_limit,
+				gas_cost,
+				payload,
+			};
+			send_message(program_id, accounts, ix)
+		},
+		TesterInstruction::OnMessage(msg) => on_message(program_id, accounts, msg),
+	}
+}
+
+fn load(program_id: &Pubkey, account: &AccountInfo) -> Result<TesterState, ProgramError> {
+	if account.owner != program_id {
+		return Err(ProgramError::IncorrectProgramId);
+	}
+	TesterState::decode(&mut &account.try_borrow_data()?[..])
+		.map_err(|_| ProgramError::InvalidAccountData)
+}
+
+/// Accounts: `[state (w, s)]`.
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], gateway: Address) -> ProgramResult {
+	let accounts = &mut accounts.iter();
+	let state_info = next_account_info(accounts)?;
+	if !state_info.is_signer {
+		return Err(ProgramError::MissingRequiredSignature);
+	}
+	let state = load(program_id, state_info)?;
+	if state.gateway != Address::default() {
+		return Err(TesterError::AlreadyInitialized.into());
+	}
+	let bytes = TesterState { gateway }.encode();
+	state_info.try_borrow_mut_data()?[..bytes.len()].copy_from_slice(&bytes);
+	Ok(())
+}
+
+/// Accounts: `[state, payer (w, s), gateway state (w), gateway program, sender, system
+/// program]`.
+fn send_message(
+	program_id: &Pubkey,
+	accounts: &[AccountInfo],
+	ix: GatewayInstruction,
+) -> ProgramResult {
+	let accounts = &mut accounts.iter();
+	let state_info = next_account_info(accounts)?;
+	let payer = next_account_info(accounts)?;
+	let gateway = next_account_info(accounts)?;
+	let gateway_program = next_account_info(accounts)?;
+	let sender = next_account_info(accounts)?;
+	let system = next_account_info(accounts)?;
+	let state = load(program_id, state_info)?;
+	if gateway.key.to_bytes() != state.gateway {
+		return Err(TesterError::WrongGateway.into());
+	}
+	if gateway.owner != gateway_program.key || !system_program::check_id(system.key) {
+		return Err(TesterError::InvalidAccount.into());
+	}
+	let (sender_key, bump) =
+		Pubkey::find_program_address(&[SENDER_SEED, state_info.key.as_ref()], program_id);
+	if *sender.key != sender_key {
+		return Err(TesterError::InvalidAccount.into());
+	}
+	let ix = Instruction {
+		program_id: *gateway_program.key,
+		accounts: vec![
+			AccountMeta::new(*gateway.key, false),
+			AccountMeta::new_readonly(sender_key, true),
+			AccountMeta::new(*payer.key, true),
+			AccountMeta::new_readonly(*system.key, false),
+		],
+		data: ix.encode(),
+	};
+	invoke_signed(
+		&ix,
+		&[gateway.clone(), sender.clone(), payer.clone(), system.clone(), gateway_program.clone()],
+		&[&[SENDER_SEED, state_info.key.as_ref(), &[bump]]],
+	)
+}
+
+/// Accounts: `[state (w), gateway state, gateway authority (s)]`.
+fn on_message(program_id: &Pubkey, accounts: &[AccountInfo], msg: GmpMessage) -> ProgramResult {
+	let accounts = &mut accounts.iter();
+	let state_info = next_account_info(accounts)?;
+	let gateway = next_account_info(accounts)?;
+	let authority = next_account_info(accounts)?;
+	let state = load(program_id, state_info)?;
+	if gateway.key.to_bytes() != state.gateway {
+		return Err(TesterError::WrongGateway.into());
+	}
+	// only the gateway program can sign for its authority
+	let (authority_key, _) =
+		Pubkey::find_program_address(&[AUTHORITY_SEED, gateway.key.as_ref()], gateway.owner);
+	if !authority.is_signer || *authority.key != authority_key {
+		return Err(TesterError::NotGateway.into());
+	}
+	sol_log_data(&[&msg.encode()]);
+	Ok(())
+}
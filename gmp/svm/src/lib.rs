@@ -0,0 +1,660 @@
+//! Gmp connector for Solana and other SVM chains.
+//!
+//! The connector drives a gateway and a tester program speaking the interface in [`program`].
+//! The programs are built from `programs` and deployed to the target chain separately, the
+//! local environment preloads them into its validators.
+use anyhow::{Context, Result};
+use ed25519_dalek::SigningKey;
+use futures::Stream;
+use scale_codec::{Decode, Encode};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use time_primitives::{
+	Address, BatchId, ConnectorParams, GatewayMessage, GatewayOp, GmpEvent, GmpMessage, IChain,
+	IConnector, IConnectorAdmin, IConnectorBuilder, MessageId, NetworkId, Route, TssPublicKey,
+	TssSignature,
+};
+
+use crate::program::{
+	GatewayInstruction, GatewayState, RouteState, TesterInstruction, TesterState, AUTHORITY_SEED,
+	GATEWAY_STATE_SIZE, SENDER_SEED, TESTER_STATE_SIZE,
+};
+use crate::rpc::RpcClient;
+use crate::tx::{AccountMeta, Instruction, SYSTEM_PROGRAM};
+
+pub mod program;
+mod rpc;
+mod tx;
+
+/// Fee charged per transaction signature.
+const LAMPORTS_PER_SIGNATURE: u128 = 5000;
+/// Maximum number of compute units consumed by the transactions of a block.
+const BLOCK_COMPUTE_UNIT_LIMIT: u64 = 48_000_000;
+/// Compute units available to an instruction unless more are requested.
+const DEFAULT_COMPUTE_UNITS: u128 = 200_000;
+/// Compute units of the ops of a batch other than messages.
+const OP_COMPUTE_UNITS: u128 = 40_000;
+const SLOT_TIME: Duration = Duration::from_millis(400);
+
+#[derive(Clone)]
+pub struct Connector {
+	network_id: NetworkId,
+	keypair: Arc<SigningKey>,
+	rpc: RpcClient,
+	// accounts never change their owner, so the program of a state account can be cached
+	programs: Arc<Mutex<HashMap<Address, Address>>>,
+}
+
+impl Connector {
+	/// Signs the instructions with the connector keypair and `signers` and submits them.
+	///
+	/// Returns the transaction signature and the slot it was confirmed in.
+	async fn send(
+		&self,
+		instructions: &[Instruction],
+		signers: &[&SigningKey],
+	) -> Result<(String, u64)> {
+		let blockhash = self.rpc.latest_blockhash().await?;
+		let keys: Vec<&SigningKey> =
+			[&*self.keypair].into_iter().chain(signers.iter().copied()).collect();
+		let (tx, signature) = tx::sign_transaction(&keys, instructions, blockhash)?;
+		let slot = self.rpc.send_transaction(&tx).await?;
+		Ok((bs58::encode(signature).into_string(), slot))
+	}
+
+	/// Returns the program owning `account`.
+	async fn program(&self, account: Address) -> Result<Address> {
+		if let Some(program) = self.programs.lock().unwrap().get(&account) {
+			return Ok(*program);
+		}
+		let info = self.rpc.account_info(account).await?.context("account does not exist")?;
+		let program = info.owner()?;
+		self.programs.lock().unwrap().insert(account, program);
+		Ok(program)
+	}
+
+	async fn state<T: Decode>(&self, account: Address) -> Result<T> {
+		let info = self.rpc.account_info(account).await?.context("account does not exist")?;
+		Ok(T::decode(&mut &info.data()?[..])?)
+	}
+
+	/// Creates a state account owned by `program` and initializes it.
+	async fn create_state(
+		&self,
+		program: Address,
+		space: u64,
+		init: Vec<u8>,
+	) -> Result<(Address, u64)> {
+		let mut secret = [0; 32];
+		getrandom::getrandom(&mut secret)?;
+		let state = SigningKey::from_bytes(&secret);
+		let address = state.verifying_key().to_bytes();
+		let lamports = self.rpc.minimum_balance(space).await?;
+		let instructions = [
+			Instruction::create_account(self.address(), address, lamports, space, program),
+			Instruction {
+				program_id: program,
+				accounts: vec![
+					AccountMeta::new(address, true),
+					AccountMeta::readonly(self.address(), true),
+				],
+				data: init,
+			},
+		];
+		let (_, slot) = self.send(&instructions, &[&state]).await?;
+		self.programs.lock().unwrap().insert(address, program);
+		tracing::info!(
+			"program {} state created at {}",
+			format_address(program),
+			format_address(address)
+		);
+		Ok((address, slot))
+	}
+
+	/// Submits an admin instruction to the gateway.
+	async fn gateway_admin_call(
+		&self,
+		gateway: Address,
+		ix: GatewayInstruction,
+		accounts: Vec<AccountMeta>,
+	) -> Result<()> {
+		let program_id = self.program(gateway).await?;
+		let mut metas =
+			vec![AccountMeta::new(gateway, false), AccountMeta::readonly(self.address(), true)];
+		metas.extend(accounts);
+		let ix = Instruction {
+			program_id,
+			accounts: metas,
+			data: ix.encode(),
+		};
+		self.send(&[ix], &[]).await?;
+		Ok(())
+	}
+
+	/// Decodes the data logged by the program owning `account` in the transactions touching
+	/// `account` within `blocks`.
+	async fn program_logs<T: Decode>(
+		&self,
+		account: Address,
+		blocks: Range<u64>,
+	) -> Result<Vec<T>> {
+		let program = self.program(account).await?;
+		let mut items = vec![];
+		for signature in self.rpc.signatures(account, blocks).await? {
+			let logs = self.rpc.transaction_logs(&signature.signature).await?;
+			for data in rpc::program_data(&logs, program) {
+				match T::decode(&mut &data[..]) {
+					Ok(item) => items.push(item),
+					Err(err) => {
+						tracing::warn!("failed to decode log of tx {}: {err}", signature.signature)
+					},
+				}
+			}
+		}
+		Ok(items)
+	}
+}
+
+/// Derives the keypair the same way `solana-keygen recover` does for a bip39 mnemonic
+/// without a derivation path.
+pub fn mnemonic_to_keypair(mnemonic: &str) -> Result<SigningKey> {
+	let mnemonic = bip39::Mnemonic::parse(mnemonic)?;
+	let seed = mnemonic.to_seed("");
+	Ok(SigningKey::from_bytes(seed[..32].try_into().unwrap()))
+}
+
+/// Reads the program id from a program keypair file as written by `cargo build-sbf`.
+pub fn program_id(keypair: &[u8]) -> Result<Address> {
+	let keypair: Vec<u8> = serde_json::from_slice(keypair).context("invalid program keypair")?;
+	let keypair: [u8; 64] =
+		keypair.try_into().map_err(|_| anyhow::anyhow!("invalid program keypair"))?;
+	Ok(keypair[32..].try_into().unwrap())
+}
+
+pub fn format_address(address: Address) -> String {
+	rpc::encode(address)
+}
+
+pub fn parse_address(address: &str) -> Result<Address> {
+	rpc::decode(address)
+}
+
+pub fn currency() -> (u32, &'static str) {
+	(9, "SOL")
+}
+
+fn lamports(amount: u128) -> Result<u64> {
+	amount
+		.try_into()
+		.map_err(|_| anyhow::anyhow!("amount exceeds u64::MAX lamports"))
+}
+
+#[async_trait::async_trait]
+impl IConnectorBuilder for Connector {
+	/// Creates a new connector.
+	async fn new(params: ConnectorParams) -> Result<Self>
+	where
+		Self: Sized,
+	{
+		if params.blockchain != "solana" {
+			anyhow::bail!("unsupported blockchain");
+		}
+		let keypair = mnemonic_to_keypair(&params.mnemonic)?;
+		Ok(Self {
+			network_id: params.network_id,
+			keypair: Arc::new(keypair),
+			rpc: RpcClient::new(params.url),
+			programs: Default::default(),
+		})
+	}
+}
+
+#[async_trait::async_trait]
+impl IChain for Connector {
+	/// Formats an address into a string.
+	fn format_address(&self, address: Address) -> String {
+		format_address(address)
+	}
+
+	/// Parses an address from a string.
+	fn parse_address(&self, address: &str) -> Result<Address> {
+		parse_address(address)
+	}
+
+	/// Network identifier.
+	fn network_id(&self) -> NetworkId {
+		self.network_id
+	}
+
+	/// Human readable connector account identifier.
+	fn address(&self) -> Address {
+		self.keypair.verifying_key().to_bytes()
+	}
+
+	fn currency(&self) -> (u32, &str) {
+		currency()
+	}
+
+	/// Requests an airdrop, only available on test validators and devnets.
+	async fn faucet(&self, balance: u128) -> Result<()> {
+		self.rpc.request_airdrop(self.address(), lamports(balance)?).await?;
+		Ok(())
+	}
+
+	/// Transfers an amount to an account.
+	async fn transfer(&self, address: Address, amount: u128) -> Result<()> {
+		let ix = Instruction::transfer(self.address(), address, lamports(amount)?);
+		self.send(&[ix], &[]).await?;
+		Ok(())
+	}
+
+	/// Queries the account balance.
+	async fn balance(&self, address: Address) -> Result<u128> {
+		Ok(self.rpc.balance(address).await? as _)
+	}
+
+	async fn finalized_block(&self) -> Result<u64> {
+		self.rpc.finalized_slot().await
+	}
+
+	/// Stream of finalized slots.
+	fn block_stream(&self) -> Pin<Box<dyn Stream<Item = u64> + Send + 'static>> {
+		let rpc = self.rpc.clone();
+		Box::pin(futures::stream::unfold((rpc, 0), |(rpc, last)| async move {
+			loop {
+				match rpc.finalized_slot().await {
+					Ok(slot) if slot > last => return Some((slot, (rpc, slot))),
+					Ok(_) => {},
+					Err(err) => tracing::error!("failed to fetch finalized slot: {err:?}"),
+				}
+				tokio::time::sleep(SLOT_TIME).await;
+			}
+		}))
+	}
+}
+
+#[async_trait::async_trait]
+impl IConnector for Connector {
+	/// Reads the events logged by the gateway program from the target chain.
+	async fn read_events(&self, gateway: Address, blocks: Range<u64>) -> Result<Vec<GmpEvent>> {
+		self.program_logs(gateway, blocks).await
+	}
+
+	/// Submits a gmp message to the target chain.
+	async fn submit_commands(
+		&self,
+		gateway: Address,
+		batch: BatchId,
+		msg: GatewayMessage,
+		signer: TssPublicKey,
+		sig: TssSignature,
+	) -> Result<(), String> {
+		(async {
+			let program_id = self.program(gateway).await?;
+			let authority = tx::find_program_address(&[AUTHORITY_SEED, &gateway], program_id);
+			let mut accounts = vec![
+				AccountMeta::new(gateway, false),
+				AccountMeta::new(self.address(), true),
+				AccountMeta::readonly(authority, false),
+			];
+			// verifying the signature and the ops other than messages
+			let mut units = DEFAULT_COMPUTE_UNITS;
+			// the gateway invokes the destination programs, so they need to be passed in
+			for op in &msg.ops {
+				units = units.saturating_add(match op {
+					GatewayOp::SendMessage(msg) => msg.gas_limit,
+					_ => OP_COMPUTE_UNITS,
+				});
+				let GatewayOp::SendMessage(msg) = op else {
+					continue;
+				};
+				match self.program(msg.dest).await {
+					Ok(program) => {
+						accounts.push(AccountMeta::new(msg.dest, false));
+						accounts.push(AccountMeta::readonly(program, false));
+					},
+					Err(err) => tracing::warn!(
+						"skipping accounts of message {}: {err}",
+						hex::encode(msg.message_id())
+					),
+				}
+			}
+			let ix = GatewayInstruction::Execute { batch, msg, signer, sig };
+			let ix = Instruction {
+				program_id,
+				accounts,
+				data: ix.encode(),
+			};
+			let units = Instruction::set_compute_unit_limit(units.try_into().unwrap_or(u32::MAX));
+			self.send(&[units, ix], &[]).await?;
+			Ok::<_, anyhow::Error>(())
+		})
+		.await
+		.map_err(|err| err.to_string())
+	}
+}
+
+#[async_trait::async_trait]
+impl IConnectorAdmin for Connector {
+	/// Creates a gateway state account. The gateway program keypair is passed as `gateway`,
+	/// the program itself is expected to be deployed already. There is no separate proxy, so
+	/// `additional_params` and `proxy` are ignored.
+	async fn deploy_gateway(
+		&self,
+		_additional_params: &[u8],
+		_proxy: &[u8],
+		gateway: &[u8],
+	) -> Result<(Address, u64)> {
+		let program = program_id(gateway)?;
+		let init = GatewayInstruction::Initialize { network: self.network_id };
+		self.create_state(program, GATEWAY_STATE_SIZE, init.encode()).await
+	}
+
+	async fn redeploy_gateway(
+		&self,
+		_additional_params: &[u8],
+		_proxy: Address,
+		_gateway: &[u8],
+	) -> Result<()> {
+		anyhow::bail!("svm programs are upgraded in place using `solana program deploy`");
+	}
+
+	async fn admin(&self, gateway: Address) -> Result<Address> {
+		let state: GatewayState = self.state(gateway).await?;
+		Ok(state.admin)
+	}
+
+	async fn set_admin(&self, gateway: Address, admin: Address) -> Result<()> {
+		self.gateway_admin_call(gateway, GatewayInstruction::SetAdmin { admin }, vec![])
+			.await
+	}
+
+	async fn shards(&self, gateway: Address) -> Result<Vec<TssPublicKey>> {
+		let state: GatewayState = self.state(gateway).await?;
+		Ok(state.shards)
+	}
+
+	async fn set_shards(&self, gateway: Address, keys: &[TssPublicKey]) -> Result<()> {
+		let ix = GatewayInstruction::SetShards { shards: keys.to_vec() };
+		self.gateway_admin_call(gateway, ix, vec![]).await
+	}
+
+	async fn routes(&self, gateway: Address) -> Result<Vec<Route>> {
+		let state: GatewayState = self.state(gateway).await?;
+		Ok(state.routes.into_iter().map(Into::into).collect())
+	}
+
+	async fn set_route(&self, gateway: Address, new_route: Route) -> Result<()> {
+		let state: GatewayState = self.state(gateway).await?;
+		let new_route = RouteState::from(new_route);
+		let mut route = state
+			.routes
+			.into_iter()
+			.find(|route| route.network_id == new_route.network_id)
+			.unwrap_or(new_route.clone());
+		if new_route.gateway != [0; 32] {
+			route.gateway = new_route.gateway;
+		}
+		if new_route.relative_gas_price != (0, 0) {
+			route.relative_gas_price = new_route.relative_gas_price;
+		}
+		if new_route.gas_limit != 0 {
+			route.gas_limit = new_route.gas_limit;
+		}
+		if new_route.base_fee != 0 {
+			route.base_fee = new_route.base_fee;
+		}
+		self.gateway_admin_call(gateway, GatewayInstruction::SetRoute { route }, vec![])
+			.await
+	}
+
+	/// Creates a tester state account. The tester program keypair is passed as `tester`.
+	async fn deploy_test(&self, gateway: Address, tester: &[u8]) -> Result<(Address, u64)> {
+		let program = program_id(tester)?;
+		let init = TesterInstruction::Initialize { gateway };
+		self.create_state(program, TESTER_STATE_SIZE, init.encode()).await
+	}
+
+	async fn estimate_message_gas_limit(
+		&self,
+		_contract: Address,
+		_src_network: NetworkId,
+		_src: Address,
+		_payload: Vec<u8>,
+	) -> Result<u128> {
+		Ok(DEFAULT_COMPUTE_UNITS)
+	}
+
+	async fn estimate_message_cost(
+		&self,
+		gateway: Address,
+		dest_network: NetworkId,
+		gas_limit: u128,
+		payload: Vec<u8>,
+	) -> Result<u128> {
+		let state: GatewayState = self.state(gateway).await?;
+		let route = state
+			.routes
+			.iter()
+			.find(|route| route.network_id == dest_network)
+			.with_context(|| format!("no route to network {dest_network}"))?;
+		Ok(route.message_cost(gas_limit, payload.len()))
+	}
+
+	async fn send_message(
+		&self,
+		src: Address,
+		dest_network: NetworkId,
+		dest: Address,
+		gas_limit: u128,
+		gas_cost: u128,
+		payload: Vec<u8>,
+	) -> Result<MessageId> {
+		let tester: TesterState = self.state(src).await?;
+		let program_id = self.program(src).await?;
+		let gateway_program = self.program(tester.gateway).await?;
+		let sender = tx::find_program_address(&[SENDER_SEED, &src], program_id);
+		let ix = TesterInstruction::SendMessage {
+			dest_network,
+			dest,
+			gas_limit,
+			gas_cost,
+			payload,
+		};
+		let ix = Instruction {
+			program_id,
+			accounts: vec![
+				AccountMeta::readonly(src, false),
+				AccountMeta::new(self.address(), true),
+				AccountMeta::new(tester.gateway, false),
+				AccountMeta::readonly(gateway_program, false),
+				AccountMeta::readonly(sender, false),
+				AccountMeta::readonly(SYSTEM_PROGRAM, false),
+			],
+			data: ix.encode(),
+		};
+		let (signature, _) = self.send(&[ix], &[]).await?;
+		let logs = self.rpc.transaction_logs(&signature).await?;
+		for data in rpc::program_data(&logs, gateway_program) {
+			if let Ok(GmpEvent::MessageReceived(msg)) = GmpEvent::decode(&mut &data[..]) {
+				return Ok(msg.message_id());
+			}
+		}
+		anyhow::bail!("tx {signature} did not emit a message");
+	}
+
+	/// Reads the messages logged by the tester program.
+	async fn recv_messages(
+		&self,
+		contract: Address,
+		blocks: Range<u64>,
+	) -> Result<Vec<GmpMessage>> {
+		self.program_logs(contract, blocks).await
+	}
+
+	/// Base fee of a transaction with a single signature.
+	async fn transaction_base_fee(&self) -> Result<u128> {
+		Ok(LAMPORTS_PER_SIGNATURE)
+	}
+
+	/// Compute unit limit of a block.
+	async fn block_gas_limit(&self) -> Result<u64> {
+		Ok(BLOCK_COMPUTE_UNIT_LIMIT)
+	}
+
+	async fn withdraw_funds(&self, gateway: Address, amount: u128, address: Address) -> Result<()> {
+		let ix = GatewayInstruction::Withdraw { amount: lamports(amount)? };
+		self.gateway_admin_call(gateway, ix, vec![AccountMeta::new(address, false)])
+			.await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+	/// Requires a `solana-test-validator` listening on the default port.
+	async fn connector() -> Result<Connector> {
+		Connector::new(ConnectorParams {
+			network_id: 4,
+			blockchain: "solana".into(),
+			network: "localnet".into(),
+			url: "http://127.0.0.1:8899".into(),
+			mnemonic: MNEMONIC.into(),
+			cctp_sender: None,
+			cctp_attestation: None,
+		})
+		.await
+	}
+
+	#[test]
+	fn parses_program_keypair() -> Result<()> {
+		let key = SigningKey::from_bytes(&[7; 32]);
+		let keypair = serde_json::to_vec(&key.to_keypair_bytes().to_vec())?;
+		assert_eq!(program_id(&keypair)?, key.verifying_key().to_bytes());
+		Ok(())
+	}
+
+	#[test]
+	fn route_message_cost() {
+		let route = RouteState {
+			relative_gas_price: (3, 2),
+			base_fee: 10,
+			..Default::default()
+		};
+		assert_eq!(route.message_cost(100, 1), 10 + (116 * 3).div_ceil(2));
+	}
+
+	#[tokio::test]
+	#[ignore]
+	async fn faucet_and_transfer() -> Result<()> {
+		let chain = connector().await?;
+		chain.faucet(10_000_000_000).await?;
+		let dest = [9; 32];
+		let balance = chain.balance(dest).await?;
+		chain.transfer(dest, 1_000_000_000).await?;
+		assert_eq!(chain.balance(dest).await?, balance + 1_000_000_000);
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[ignore]
+	async fn block_stream() -> Result<()> {
+		use futures::StreamExt;
+		let chain = connector().await?;
+		let mut stream = chain.block_stream();
+		let first = stream.next().await.unwrap();
+		let second = stream.next().await.unwrap();
+		assert!(second > first);
+		Ok(())
+	}
+
+	/// Polls `f` until it returns a value, transactions are only read once finalized.
+	async fn eventually<T, F, Fut>(f: F) -> Result<T>
+	where
+		F: Fn() -> Fut,
+		Fut: std::future::Future<Output = Result<Option<T>>>,
+	{
+		for _ in 0..150 {
+			if let Some(value) = f().await? {
+				return Ok(value);
+			}
+			tokio::time::sleep(SLOT_TIME).await;
+		}
+		anyhow::bail!("timed out");
+	}
+
+	/// Requires the programs in `programs` to be preloaded, see `programs/README.md`.
+	#[tokio::test]
+	#[ignore]
+	async fn send_and_execute_message() -> Result<()> {
+		use time_primitives::MockTssSigner;
+		const GATEWAY: &[u8] = include_bytes!(concat!(
+			env!("CARGO_MANIFEST_DIR"),
+			"/../../config/envs/local/svm/gateway-keypair.json"
+		));
+		const TESTER: &[u8] = include_bytes!(concat!(
+			env!("CARGO_MANIFEST_DIR"),
+			"/../../config/envs/local/svm/tester-keypair.json"
+		));
+		let chain = connector().await?;
+		let network = chain.network_id();
+		let shard = MockTssSigner::new(0);
+		chain.faucet(10_000_000_000).await?;
+		let (gateway, block) = chain.deploy_gateway(&[], &[], GATEWAY).await?;
+		chain.set_shards(gateway, &[shard.public_key()]).await?;
+		assert_eq!(chain.shards(gateway).await?, vec![shard.public_key()]);
+		let route = Route {
+			network_id: network,
+			gateway,
+			relative_gas_price: (1, 1),
+			gas_limit: 1_000_000,
+			base_fee: 0,
+		};
+		chain.set_route(gateway, route).await?;
+		let (src, _) = chain.deploy_test(gateway, TESTER).await?;
+		let (dest, _) = chain.deploy_test(gateway, TESTER).await?;
+		let payload = vec![1, 2, 3];
+		let gas_limit = 100_000;
+		let gas_cost = chain
+			.estimate_message_cost(gateway, network, gas_limit, payload.clone())
+			.await?;
+		let id = chain.send_message(src, network, dest, gas_limit, gas_cost, payload).await?;
+		let msg = eventually(|| async {
+			let events = chain.read_events(gateway, block..u64::MAX).await?;
+			Ok(events.into_iter().find_map(|event| match event {
+				GmpEvent::MessageReceived(msg) => Some(msg),
+				_ => None,
+			}))
+		})
+		.await?;
+		assert_eq!(msg.message_id(), id);
+		let batch = 0;
+		let cmds = GatewayMessage::new(vec![GatewayOp::SendMessage(msg.clone())]);
+		let sig = shard.sign_gateway_message(network, gateway, batch, &cmds);
+		chain
+			.submit_commands(gateway, batch, cmds.clone(), shard.public_key(), sig)
+			.await
+			.map_err(anyhow::Error::msg)?;
+		// replayed batches are rejected
+		assert!(chain
+			.submit_commands(gateway, batch, cmds, shard.public_key(), sig)
+			.await
+			.is_err());
+		let msgs = eventually(|| async {
+			let msgs = chain.recv_messages(dest, block..u64::MAX).await?;
+			Ok((!msgs.is_empty()).then_some(msgs))
+		})
+		.await?;
+		assert_eq!(msgs, vec![msg]);
+		let events = chain.read_events(gateway, block..u64::MAX).await?;
+		assert!(events.contains(&GmpEvent::MessageExecuted(id)));
+		assert!(events.contains(&GmpEvent::BatchExecuted { batch_id: batch, tx_hash: None }));
+		Ok(())
+	}
+}
@@ -0,0 +1,130 @@
+//! Interface of the gateway and tester programs.
+//!
+//! The programs implementing it live in `programs`, which mirrors these types.
+//!
+//! Instructions, account state and events are scale encoded. Events are emitted with
+//! `sol_log_data` and show up as `Program data: <base64>` lines in the transaction logs.
+use scale_codec::{Decode, Encode};
+use time_primitives::{
+	Address, BatchId, GatewayMessage, GmpMessage, NetworkId, Route, TssPublicKey, TssSignature,
+};
+
+/// Size of the gateway state account.
+pub const GATEWAY_STATE_SIZE: u64 = 10 * 1024;
+/// Size of the tester state account.
+pub const TESTER_STATE_SIZE: u64 = 128;
+/// Seed of the gateway account signing the delivery of messages.
+pub const AUTHORITY_SEED: &[u8] = b"authority";
+/// Seed of the tester account sending messages, it is the source of the messages.
+pub const SENDER_SEED: &[u8] = b"sender";
+
+#[derive(Clone, Debug, Default, Encode, Decode, PartialEq, Eq)]
+pub struct RouteState {
+	pub network_id: NetworkId,
+	pub gateway: Address,
+	pub relative_gas_price: (u128, u128),
+	pub gas_limit: u64,
+	pub base_fee: u128,
+}
+
+impl From<Route> for RouteState {
+	fn from(route: Route) -> Self {
+		Self {
+			network_id: route.network_id,
+			gateway: route.gateway,
+			relative_gas_price: route.relative_gas_price,
+			gas_limit: route.gas_limit,
+			base_fee: route.base_fee,
+		}
+	}
+}
+
+impl From<RouteState> for Route {
+	fn from(route: RouteState) -> Self {
+		Self {
+			network_id: route.network_id,
+			gateway: route.gateway,
+			relative_gas_price: route.relative_gas_price,
+			gas_limit: route.gas_limit,
+			base_fee: route.base_fee,
+		}
+	}
+}
+
+impl RouteState {
+	/// Cost in lamports of delivering a message, the gateway program charges the same amount.
+	pub fn message_cost(&self, gas_limit: u128, payload_len: usize) -> u128 {
+		Route::from(self.clone()).message_cost(gas_limit, payload_len)
+	}
+}
+
+/// Data of the account identifying a gateway.
+#[derive(Clone, Debug, Default, Encode, Decode, PartialEq, Eq)]
+pub struct GatewayState {
+	pub admin: Address,
+	pub network: NetworkId,
+	pub shards: Vec<TssPublicKey>,
+	pub routes: Vec<RouteState>,
+	pub nonce: u64,
+	/// The last executed batches in ascending order, older batches are rejected.
+	pub executed_batches: Vec<BatchId>,
+}
+
+/// Data of the account identifying a tester.
+#[derive(Clone, Debug, Default, Encode, Decode, PartialEq, Eq)]
+pub struct TesterState {
+	pub gateway: Address,
+}
+
+#[derive(Clone, Debug, Encode, Decode)]
+pub enum GatewayInstruction {
+	/// Accounts: `[state (w, s), admin (s)]`.
+	Initialize { network: NetworkId },
+	/// Verifies the schnorr signature of the shard and executes the batch. Messages are
+	/// delivered by invoking `OnMessage` of the destination program signed by the authority,
+	/// the account derived from [`AUTHORITY_SEED`] and the state.
+	///
+	/// Accounts: `[state (w), payer (w, s), authority]` followed by the destination account
+	/// and program of every `SendMessage` op.
+	Execute { batch: BatchId, msg: GatewayMessage, signer: TssPublicKey, sig: TssSignature },
+	/// Accounts: `[state (w), admin (s)]`.
+	SetAdmin { admin: Address },
+	/// Accounts: `[state (w), admin (s)]`.
+	SetShards { shards: Vec<TssPublicKey> },
+	/// Accounts: `[state (w), admin (s)]`.
+	SetRoute { route: RouteState },
+	/// Accounts: `[state (w), admin (s), recipient (w)]`.
+	Withdraw { amount: u64 },
+	/// Charges `gas_cost` from the payer and emits the message with the sender as source.
+	///
+	/// Accounts: `[state (w), sender (s), payer (w, s), system program]`.
+	SendMessage {
+		dest_network: NetworkId,
+		dest: Address,
+		gas_limit: u128,
+		gas_cost: u128,
+		payload: Vec<u8>,
+	},
+}
+
+#[derive(Clone, Debug, Encode, Decode)]
+pub enum TesterInstruction {
+	/// Accounts: `[state (w, s)]`.
+	Initialize { gateway: Address },
+	/// Pays `gas_cost` to the gateway and submits the message from the sender, the account
+	/// derived from [`SENDER_SEED`] and the state.
+	///
+	/// Accounts: `[state, payer (w, s), gateway state (w), gateway program, sender, system
+	/// program]`.
+	SendMessage {
+		dest_network: NetworkId,
+		dest: Address,
+		gas_limit: u128,
+		gas_cost: u128,
+		payload: Vec<u8>,
+	},
+	/// Invoked by the gateway, logs the message.
+	///
+	/// Accounts: `[state (w), gateway state, gateway authority (s)]`.
+	OnMessage(GmpMessage),
+}
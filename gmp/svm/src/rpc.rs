@@ -0,0 +1,344 @@
+//! Json rpc client for the subset of the solana api used by the connector.
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use time_primitives::Address;
+
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+const SIGNATURES_LIMIT: usize = 1000;
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+	result: Option<T>,
+	error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+	code: i64,
+	message: String,
+}
+
+#[derive(Deserialize)]
+struct WithContext<T> {
+	value: T,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LatestBlockhash {
+	blockhash: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignatureStatus {
+	slot: u64,
+	err: Option<Value>,
+	confirmation_status: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AccountInfo {
+	pub lamports: u64,
+	pub owner: String,
+	pub data: (String, String),
+}
+
+#[derive(Clone, Deserialize)]
+pub struct SignatureInfo {
+	pub signature: String,
+	pub slot: u64,
+	pub err: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionMeta {
+	log_messages: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct Transaction {
+	meta: Option<TransactionMeta>,
+}
+
+/// Position of the last scan of the signatures of an address.
+struct Cursor {
+	/// The newest signature fetched.
+	until: Option<String>,
+	/// The fetched signatures in slots starting at `start`, oldest first.
+	start: u64,
+	signatures: Vec<SignatureInfo>,
+}
+
+#[derive(Clone)]
+pub struct RpcClient {
+	client: reqwest::Client,
+	url: String,
+	cursors: Arc<Mutex<HashMap<Address, Cursor>>>,
+}
+
+impl RpcClient {
+	pub fn new(url: String) -> Self {
+		Self {
+			client: reqwest::Client::new(),
+			url,
+			cursors: Default::default(),
+		}
+	}
+
+	async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+		let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+		let response: RpcResponse<T> =
+			self.client.post(&self.url).json(&request).send().await?.json().await?;
+		if let Some(error) = response.error {
+			anyhow::bail!("{method} failed with {}: {}", error.code, error.message);
+		}
+		response.result.with_context(|| format!("{method} returned no result"))
+	}
+
+	pub async fn balance(&self, address: Address) -> Result<u64> {
+		let balance: WithContext<u64> = self
+			.call("getBalance", json!([encode(address), { "commitment": "confirmed" }]))
+			.await?;
+		Ok(balance.value)
+	}
+
+	pub async fn finalized_slot(&self) -> Result<u64> {
+		self.call("getSlot", json!([{ "commitment": "finalized" }])).await
+	}
+
+	pub async fn latest_blockhash(&self) -> Result<[u8; 32]> {
+		let blockhash: WithContext<LatestBlockhash> =
+			self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }])).await?;
+		decode(&blockhash.value.blockhash)
+	}
+
+	pub async fn minimum_balance(&self, space: u64) -> Result<u64> {
+		self.call("getMinimumBalanceForRentExemption", json!([space])).await
+	}
+
+	pub async fn account_info(&self, address: Address) -> Result<Option<AccountInfo>> {
+		let info: WithContext<Option<AccountInfo>> = self
+			.call(
+				"getAccountInfo",
+				json!([encode(address), { "encoding": "base64", "commitment": "confirmed" }]),
+			)
+			.await?;
+		Ok(info.value)
+	}
+
+	pub async fn request_airdrop(&self, address: Address, lamports: u64) -> Result<u64> {
+		let signature: String =
+			self.call("requestAirdrop", json!([encode(address), lamports])).await?;
+		self.confirm(&signature).await
+	}
+
+	/// Submits a signed transaction and returns the slot it was confirmed in.
+	pub async fn send_transaction(&self, tx: &[u8]) -> Result<u64> {
+		let tx = base64::engine::general_purpose::STANDARD.encode(tx);
+		let signature: String = self
+			.call(
+				"sendTransaction",
+				json!([tx, { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+			)
+			.await?;
+		self.confirm(&signature).await
+	}
+
+	async fn confirm(&self, signature: &str) -> Result<u64> {
+		let start = tokio::time::Instant::now();
+		loop {
+			let statuses: WithContext<Vec<Option<SignatureStatus>>> =
+				self.call("getSignatureStatuses", json!([[signature]])).await?;
+			if let Some(Some(status)) = statuses.value.into_iter().next() {
+				if let Some(err) = status.err {
+					anyhow::bail!("tx {signature} failed: {err}");
+				}
+				if matches!(status.confirmation_status.as_deref(), Some("confirmed" | "finalized"))
+				{
+					tracing::info!("tx {signature} confirmed in slot {}", status.slot);
+					return Ok(status.slot);
+				}
+			}
+			if start.elapsed() > CONFIRMATION_TIMEOUT {
+				anyhow::bail!("tx {signature} timed out");
+			}
+			tokio::time::sleep(Duration::from_millis(400)).await;
+		}
+	}
+
+	/// Returns the successful finalized transactions touching `address` in `slots`, oldest
+	/// first.
+	///
+	/// Ranges are usually read in ascending order, so the signatures of an address are paged
+	/// from the newest one fetched by the previous call. Ranges starting before the previous
+	/// one are scanned from the newest signature.
+	pub async fn signatures(
+		&self,
+		address: Address,
+		slots: Range<u64>,
+	) -> Result<Vec<SignatureInfo>> {
+		let cursor = self.cursors.lock().unwrap().remove(&address);
+		let mut cursor = match cursor {
+			Some(mut cursor) if cursor.start <= slots.start => {
+				let mut signatures =
+					self.fetch_signatures(address, cursor.until.clone(), cursor.start).await?;
+				if let Some(newest) = signatures.first() {
+					cursor.until = Some(newest.signature.clone());
+				}
+				signatures.reverse();
+				cursor.signatures.extend(signatures);
+				cursor
+			},
+			_ => {
+				let mut signatures = self.fetch_signatures(address, None, slots.start).await?;
+				signatures.reverse();
+				Cursor {
+					until: signatures.last().map(|s| s.signature.clone()),
+					start: slots.start,
+					signatures,
+				}
+			},
+		};
+		let signatures =
+			cursor.signatures.iter().filter(|s| slots.contains(&s.slot)).cloned().collect();
+		// the next range starts where this one ends
+		cursor.start = cursor.start.max(slots.end);
+		let start = cursor.start;
+		cursor.signatures.retain(|s| s.slot >= start);
+		self.cursors.lock().unwrap().insert(address, cursor);
+		Ok(signatures)
+	}
+
+	/// Fetches the successful finalized transactions touching `address` newer than `until`
+	/// and at least in slot `start`, newest first.
+	async fn fetch_signatures(
+		&self,
+		address: Address,
+		until: Option<String>,
+		start: u64,
+	) -> Result<Vec<SignatureInfo>> {
+		let mut signatures = vec![];
+		let mut before: Option<String> = None;
+		loop {
+			let mut config = json!({ "limit": SIGNATURES_LIMIT, "commitment": "finalized" });
+			if let Some(before) = before {
+				config["before"] = before.into();
+			}
+			if let Some(until) = &until {
+				config["until"] = until.clone().into();
+			}
+			let page: Vec<SignatureInfo> =
+				self.call("getSignaturesForAddress", json!([encode(address), config])).await?;
+			let done = page.len() < SIGNATURES_LIMIT
+				|| page.last().map(|s| s.slot < start).unwrap_or(true);
+			before = page.last().map(|s| s.signature.clone());
+			signatures.extend(page.into_iter().filter(|s| s.err.is_none() && s.slot >= start));
+			if done {
+				break;
+			}
+		}
+		Ok(signatures)
+	}
+
+	pub async fn transaction_logs(&self, signature: &str) -> Result<Vec<String>> {
+		let tx: Transaction = self
+			.call(
+				"getTransaction",
+				json!([signature, {
+					"encoding": "json",
+					"commitment": "confirmed",
+					"maxSupportedTransactionVersion": 0,
+				}]),
+			)
+			.await?;
+		Ok(tx.meta.and_then(|meta| meta.log_messages).unwrap_or_default())
+	}
+}
+
+pub fn encode(address: Address) -> String {
+	bs58::encode(address).into_string()
+}
+
+pub fn decode(address: &str) -> Result<Address> {
+	let address = bs58::decode(address).into_vec().context("invalid address")?;
+	address.try_into().map_err(|_| anyhow::anyhow!("invalid address"))
+}
+
+impl AccountInfo {
+	pub fn data(&self) -> Result<Vec<u8>> {
+		Ok(base64::engine::general_purpose::STANDARD.decode(&self.data.0)?)
+	}
+
+	pub fn owner(&self) -> Result<Address> {
+		decode(&self.owner)
+	}
+}
+
+/// Returns the data logged by `program` in the transaction logs.
+///
+/// Logs of programs invoked through cpi are attributed to the invoked program.
+pub fn program_data(logs: &[String], program: Address) -> Vec<Vec<u8>> {
+	let program = encode(program);
+	let mut stack: Vec<&str> = vec![];
+	let mut data = vec![];
+	for log in logs {
+		let Some(log) = log.strip_prefix("Program ") else {
+			continue;
+		};
+		if let Some(payload) = log.strip_prefix("data: ") {
+			if stack.last() != Some(&program.as_str()) {
+				continue;
+			}
+			for chunk in payload.split(' ') {
+				if let Ok(chunk) = base64::engine::general_purpose::STANDARD.decode(chunk) {
+					data.push(chunk);
+				}
+			}
+			continue;
+		}
+		let mut words = log.split(' ');
+		let (Some(id), Some(status)) = (words.next(), words.next()) else {
+			continue;
+		};
+		match status {
+			"invoke" => stack.push(id),
+			"success" | "failed:" => {
+				stack.pop();
+			},
+			_ => {},
+		}
+	}
+	data
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn attributes_program_data() {
+		let gateway = [1; 32];
+		let tester = [2; 32];
+		let logs: Vec<String> = [
+			format!("Program {} invoke [1]", encode(gateway)),
+			"Program data: AQ==".into(),
+			format!("Program {} invoke [2]", encode(tester)),
+			"Program data: Ag==".into(),
+			format!("Program {} success", encode(tester)),
+			"Program data: Aw== BA==".into(),
+			format!("Program {} success", encode(gateway)),
+		]
+		.into();
+		assert_eq!(program_data(&logs, gateway), vec![vec![1], vec![3], vec![4]]);
+		assert_eq!(program_data(&logs, tester), vec![vec![2]]);
+	}
+}
@@ -0,0 +1,251 @@
+//! Minimal encoder for legacy solana transactions.
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use ed25519_dalek::{Signer, SigningKey};
+use sha2::{Digest, Sha256};
+use time_primitives::Address;
+
+/// The system program id.
+pub const SYSTEM_PROGRAM: Address = [0; 32];
+
+/// The compute budget program id, `ComputeBudget111111111111111111111111111111`.
+pub const COMPUTE_BUDGET_PROGRAM: Address = [
+	3, 6, 70, 111, 229, 33, 23, 50, 255, 236, 173, 186, 114, 195, 155, 231, 188, 140, 229, 187,
+	197, 247, 18, 107, 44, 67, 155, 58, 64, 0, 0, 0,
+];
+
+/// Maximum number of compute units a transaction can request.
+pub const MAX_COMPUTE_UNITS: u32 = 1_400_000;
+
+/// Solana limits the size of a serialized transaction to the IPv6 MTU minus headers.
+pub const MAX_TX_SIZE: usize = 1232;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountMeta {
+	pub pubkey: Address,
+	pub is_signer: bool,
+	pub is_writable: bool,
+}
+
+impl AccountMeta {
+	pub fn new(pubkey: Address, is_signer: bool) -> Self {
+		Self {
+			pubkey,
+			is_signer,
+			is_writable: true,
+		}
+	}
+
+	pub fn readonly(pubkey: Address, is_signer: bool) -> Self {
+		Self {
+			pubkey,
+			is_signer,
+			is_writable: false,
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct Instruction {
+	pub program_id: Address,
+	pub accounts: Vec<AccountMeta>,
+	pub data: Vec<u8>,
+}
+
+impl Instruction {
+	/// Creates `account` with `space` bytes owned by `owner`.
+	pub fn create_account(
+		payer: Address,
+		account: Address,
+		lamports: u64,
+		space: u64,
+		owner: Address,
+	) -> Self {
+		let mut data = Vec::with_capacity(52);
+		data.extend(0u32.to_le_bytes());
+		data.extend(lamports.to_le_bytes());
+		data.extend(space.to_le_bytes());
+		data.extend(owner);
+		Self {
+			program_id: SYSTEM_PROGRAM,
+			accounts: vec![AccountMeta::new(payer, true), AccountMeta::new(account, true)],
+			data,
+		}
+	}
+
+	/// Transfers `lamports` from `from` to `to`.
+	pub fn transfer(from: Address, to: Address, lamports: u64) -> Self {
+		let mut data = Vec::with_capacity(12);
+		data.extend(2u32.to_le_bytes());
+		data.extend(lamports.to_le_bytes());
+		Self {
+			program_id: SYSTEM_PROGRAM,
+			accounts: vec![AccountMeta::new(from, true), AccountMeta::new(to, false)],
+			data,
+		}
+	}
+
+	/// Requests `units` compute units for the transaction.
+	pub fn set_compute_unit_limit(units: u32) -> Self {
+		let mut data = Vec::with_capacity(5);
+		data.push(2);
+		data.extend(units.min(MAX_COMPUTE_UNITS).to_le_bytes());
+		Self {
+			program_id: COMPUTE_BUDGET_PROGRAM,
+			accounts: vec![],
+			data,
+		}
+	}
+}
+
+/// Finds the program derived address of `program` for `seeds` the same way
+/// `Pubkey::find_program_address` does: the first address off the ed25519 curve, trying
+/// bump seeds from 255 down.
+pub fn find_program_address(seeds: &[&[u8]], program: Address) -> Address {
+	for bump in (0..=u8::MAX).rev() {
+		let mut hasher = Sha256::new();
+		for seed in seeds {
+			hasher.update(seed);
+		}
+		hasher.update([bump]);
+		hasher.update(program);
+		hasher.update(b"ProgramDerivedAddress");
+		let address: Address = hasher.finalize().into();
+		if CompressedEdwardsY(address).decompress().is_none() {
+			return address;
+		}
+	}
+	unreachable!("no program derived address found");
+}
+
+fn encode_len(buf: &mut Vec<u8>, mut len: usize) {
+	loop {
+		let byte = (len & 0x7f) as u8;
+		len >>= 7;
+		if len == 0 {
+			buf.push(byte);
+			return;
+		}
+		buf.push(byte | 0x80);
+	}
+}
+
+/// Compiles the instructions into a message paid for by `payer`.
+///
+/// Returns the serialized message and the keys that need to sign it.
+pub fn compile_message(
+	payer: Address,
+	instructions: &[Instruction],
+	recent_blockhash: [u8; 32],
+) -> (Vec<u8>, Vec<Address>) {
+	let mut accounts: Vec<AccountMeta> = vec![AccountMeta::new(payer, true)];
+	let metas = instructions.iter().flat_map(|ix| {
+		ix.accounts.iter().cloned().chain([AccountMeta::readonly(ix.program_id, false)])
+	});
+	for meta in metas {
+		if let Some(account) = accounts.iter_mut().find(|a| a.pubkey == meta.pubkey) {
+			account.is_signer |= meta.is_signer;
+			account.is_writable |= meta.is_writable;
+		} else {
+			accounts.push(meta);
+		}
+	}
+	// the payer stays first, the sort is stable
+	accounts[1..].sort_by_key(|a| (!a.is_signer, !a.is_writable));
+	let signers: Vec<Address> = accounts.iter().filter(|a| a.is_signer).map(|a| a.pubkey).collect();
+	let readonly_signed = accounts.iter().filter(|a| a.is_signer && !a.is_writable).count();
+	let readonly_unsigned = accounts.iter().filter(|a| !a.is_signer && !a.is_writable).count();
+	let index = |pubkey: &Address| accounts.iter().position(|a| a.pubkey == *pubkey).unwrap() as u8;
+
+	let mut msg = vec![signers.len() as u8, readonly_signed as u8, readonly_unsigned as u8];
+	encode_len(&mut msg, accounts.len());
+	for account in &accounts {
+		msg.extend(account.pubkey);
+	}
+	msg.extend(recent_blockhash);
+	encode_len(&mut msg, instructions.len());
+	for ix in instructions {
+		msg.push(index(&ix.program_id));
+		encode_len(&mut msg, ix.accounts.len());
+		for account in &ix.accounts {
+			msg.push(index(&account.pubkey));
+		}
+		encode_len(&mut msg, ix.data.len());
+		msg.extend(&ix.data);
+	}
+	(msg, signers)
+}
+
+/// Builds and signs a transaction. Every required signer must be in `keys`.
+pub fn sign_transaction(
+	keys: &[&SigningKey],
+	instructions: &[Instruction],
+	recent_blockhash: [u8; 32],
+) -> anyhow::Result<(Vec<u8>, [u8; 64])> {
+	let payer = keys.first().ok_or_else(|| anyhow::anyhow!("no payer"))?;
+	let (msg, signers) =
+		compile_message(payer.verifying_key().to_bytes(), instructions, recent_blockhash);
+	let mut tx = vec![];
+	encode_len(&mut tx, signers.len());
+	for signer in &signers {
+		let key =
+			keys.iter()
+				.find(|key| key.verifying_key().to_bytes() == *signer)
+				.ok_or_else(|| {
+					anyhow::anyhow!("missing signer {}", bs58::encode(signer).into_string())
+				})?;
+		tx.extend(key.sign(&msg).to_bytes());
+	}
+	tx.extend(msg);
+	if tx.len() > MAX_TX_SIZE {
+		anyhow::bail!("transaction of {} bytes exceeds the size limit", tx.len());
+	}
+	// the first signature identifies the transaction
+	let signature = tx[1..65].try_into().unwrap();
+	Ok((tx, signature))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn compact_len() {
+		for (len, expected) in
+			[(0, &[0x00][..]), (0x7f, &[0x7f]), (0x80, &[0x80, 0x01]), (0x3fff, &[0xff, 0x7f])]
+		{
+			let mut buf = vec![];
+			encode_len(&mut buf, len);
+			assert_eq!(buf, expected);
+		}
+	}
+
+	#[test]
+	fn derives_addresses_off_curve() {
+		let program = [7; 32];
+		let address = find_program_address(&[b"authority", &[1; 32]], program);
+		assert!(CompressedEdwardsY(address).decompress().is_none());
+		assert_eq!(address, find_program_address(&[b"authority", &[1; 32]], program));
+		assert_ne!(address, find_program_address(&[b"sender", &[1; 32]], program));
+	}
+
+	#[test]
+	fn orders_accounts() {
+		let payer = [1; 32];
+		let account = [2; 32];
+		let to = [3; 32];
+		let (msg, signers) = compile_message(
+			payer,
+			&[
+				Instruction::transfer(payer, to, 1),
+				Instruction::create_account(payer, account, 1, 0, [4; 32]),
+			],
+			[5; 32],
+		);
+		assert_eq!(signers, vec![payer, account]);
+		// two signers, no readonly signers, the system program is readonly
+		assert_eq!(msg[..4], [2, 0, 1, 4]);
+		let keys: Vec<&[u8]> = msg[4..4 + 4 * 32].chunks(32).collect();
+		assert_eq!(keys, vec![&payer[..], &account[..], &to[..], &SYSTEM_PROGRAM[..]]);
+		assert_eq!(msg[4 + 4 * 32..4 + 5 * 32], [5; 32]);
+	}
+}
@@ -29,6 +29,23 @@ use sp_keystore::KeystorePtr;
 
 use time_primitives::{AccountId, Balance, Block, BlockHash, BlockNumber, Nonce};
 
+#[cfg(feature = "testnet")]
+mod dmail;
+
+/// Runtime apis only provided by the testnet runtime.
+#[cfg(feature = "testnet")]
+pub trait TestnetRuntimeApi: time_primitives::DmailApi<Block> {}
+
+#[cfg(feature = "testnet")]
+impl<T: time_primitives::DmailApi<Block>> TestnetRuntimeApi for T {}
+
+/// Runtime apis only provided by the testnet runtime.
+#[cfg(not(feature = "testnet"))]
+pub trait TestnetRuntimeApi {}
+
+#[cfg(not(feature = "testnet"))]
+impl<T> TestnetRuntimeApi for T {}
+
 /// Extra dependencies for BABE.
 pub struct BabeDeps {
 	/// A handle to the BABE worker for issuing requests.
@@ -97,6 +114,7 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
+	C::Api: TestnetRuntimeApi,
 	P: TransactionPool + 'static,
 	SC: SelectChain<Block> + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
@@ -150,6 +168,11 @@ where
 	)?;
 
 	io.merge(StateMigration::new(client.clone(), backend, deny_unsafe).into_rpc())?;
+	#[cfg(feature = "testnet")]
+	{
+		use dmail::{Dmail, DmailApiServer};
+		io.merge(Dmail::new(client.clone()).into_rpc())?;
+	}
 	io.merge(Dev::new(client, deny_unsafe).into_rpc())?;
 
 	Ok(io)
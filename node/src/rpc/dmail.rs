@@ -0,0 +1,76 @@
+//! RPC methods to page through dmail inboxes.
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc, types::ErrorObjectOwned};
+use std::sync::Arc;
+
+use polkadot_sdk::*;
+
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+
+use time_primitives::{
+	AccountId, Block, BlockHash, DmailApi as DmailRuntimeApi, DmailInboxMessage,
+};
+
+/// Maximum number of messages returned per page.
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Dmail RPC methods.
+#[rpc(server)]
+pub trait DmailApi {
+	/// Returns up to `limit` messages of the inbox of `account` starting at
+	/// `offset`, oldest first.
+	#[method(name = "dmail_inbox")]
+	fn inbox(
+		&self,
+		account: AccountId,
+		offset: u32,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<DmailInboxMessage>>;
+
+	/// Returns the number of messages in the inbox of `account`.
+	#[method(name = "dmail_inboxLen")]
+	fn inbox_len(&self, account: AccountId, at: Option<BlockHash>) -> RpcResult<u32>;
+}
+
+/// Implements the [`DmailApiServer`] using the dmail runtime api.
+pub struct Dmail<C> {
+	client: Arc<C>,
+}
+
+impl<C> Dmail<C> {
+	/// Creates a new instance of the dmail RPC.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client }
+	}
+}
+
+impl<C> DmailApiServer for Dmail<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: DmailRuntimeApi<Block>,
+{
+	fn inbox(
+		&self,
+		account: AccountId,
+		offset: u32,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<DmailInboxMessage>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client
+			.runtime_api()
+			.get_inbox(at, account, offset, limit.min(MAX_PAGE_SIZE))
+			.map_err(runtime_error)
+	}
+
+	fn inbox_len(&self, account: AccountId, at: Option<BlockHash>) -> RpcResult<u32> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client.runtime_api().get_inbox_len(at, account).map_err(runtime_error)
+	}
+}
+
+fn runtime_error(err: sp_api::ApiError) -> ErrorObjectOwned {
+	ErrorObjectOwned::owned(1, "Unable to query dmail inbox", Some(err.to_string()))
+}
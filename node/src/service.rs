@@ -110,6 +110,7 @@ where
 		+ pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>
 		+ sp_block_builder::BlockBuilder<Block>
 		+ sp_consensus_babe::BabeApi<Block>
+		+ crate::rpc::TestnetRuntimeApi
 		+ sp_consensus_grandpa::GrandpaApi<Block>
 		+ sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
 {
@@ -372,6 +373,7 @@ where
 		+ sp_authority_discovery::AuthorityDiscoveryApi<Block>
 		+ sp_block_builder::BlockBuilder<Block>
 		+ sp_consensus_babe::BabeApi<Block>
+		+ crate::rpc::TestnetRuntimeApi
 		+ sp_consensus_grandpa::GrandpaApi<Block>
 		+ sp_offchain::OffchainWorkerApi<Block>
 		+ sp_session::SessionKeys<Block>
@@ -656,6 +658,7 @@ where
 		+ sp_authority_discovery::AuthorityDiscoveryApi<Block>
 		+ sp_block_builder::BlockBuilder<Block>
 		+ sp_consensus_babe::BabeApi<Block>
+		+ crate::rpc::TestnetRuntimeApi
 		+ sp_consensus_grandpa::GrandpaApi<Block>
 		+ sp_offchain::OffchainWorkerApi<Block>
 		+ sp_session::SessionKeys<Block>,
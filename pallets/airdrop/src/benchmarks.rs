@@ -1,20 +1,24 @@
 //! Benchmarks suite for airdrop pallet
 
-use crate::{Call, Claims, Config, Pallet, RawSignature};
+use crate::{
+	BalanceOf, Call, Claims, Config, MerkleProof, Pallet, RawSignature, RoundClaims, Rounds,
+	VestingOf,
+};
 
 use scale_codec::{Decode, Encode};
 
 use polkadot_sdk::*;
 
 use frame_benchmarking::{account, benchmarks};
-use frame_support::traits::UnfilteredDispatchable;
+use frame_support::traits::{Currency, Get, UnfilteredDispatchable};
 use frame_system::RawOrigin;
 use sp_keyring::AccountKeyring::Charlie as SrClaimer;
 use sp_keyring::Ed25519Keyring::Ferdie as EdClaimer;
 
 use sp_runtime::{traits::ValidateUnsigned, DispatchResult};
+use sp_std::vec::Vec;
 
-use time_primitives::AccountId;
+use time_primitives::{merkle_leaf, merkle_root_from_proof, AccountId, MAX_MERKLE_DEPTH};
 
 const SEED: u32 = 0;
 
@@ -22,6 +26,12 @@ const MAX_CLAIMS: u32 = 200_000;
 const VALUE: u32 = 100_000;
 const VESTING: (u32, u32, u32) = (10_000u32, 1_000u32, 10u32);
 
+/// Funds the pot reserving the totals of the benchmarked rounds.
+fn fund_round_pot<T: Config>() {
+	let balance = <T::Currency as Currency<T::AccountId>>::minimum_balance() + (VALUE * 10).into();
+	<T::Currency as Currency<T::AccountId>>::make_free_balance_be(&T::RoundPot::get(), balance);
+}
+
 fn create_claim<T: Config>(account: &AccountId) -> DispatchResult {
 	Pallet::<T>::mint(
 		RawOrigin::Root.into(),
@@ -131,6 +141,64 @@ benchmarks! {
 		assert_eq!(Claims::<T>::get(to), Some(VALUE.into()));
 	}
 
+	// Benchmark `create_round`.
+	create_round {
+		fund_round_pot::<T>();
+		let merkle_root = [1; 32];
+	}: _(RawOrigin::Root, merkle_root, VALUE.into(), 10u32.into())
+	verify {
+		assert!(Rounds::<T>::get(0).is_some());
+	}
+
+	// Benchmark `claim_merkle` including `validate_unsigned` logic.
+	claim_merkle {
+		let n in 0 .. MAX_MERKLE_DEPTH;
+
+		let source: AccountId = SrClaimer.into();
+		let amount: BalanceOf<T> = VALUE.into();
+		let vesting: Option<VestingOf<T>> = Some((VESTING.0.into(), VESTING.1.into(), VESTING.2.into()));
+		let proof: MerkleProof = (0..n).map(|i| [i as u8; 32]).collect::<Vec<_>>().try_into().unwrap();
+		let merkle_root = merkle_root_from_proof(merkle_leaf(&(&source, amount, vesting)), &proof);
+		fund_round_pot::<T>();
+		Pallet::<T>::create_round(RawOrigin::Root.into(), merkle_root, amount, 10u32.into())?;
+
+		let target: T::AccountId = account("target", 0, SEED);
+		let txsource = sp_runtime::transaction_validity::TransactionSource::External;
+		let call_enc = Call::<T>::claim_merkle {
+			round: 0,
+			source: source.clone(),
+			amount,
+			vesting,
+			merkle_proof: proof,
+			proof: SCHNORR_PROOF,
+			target,
+		}.encode();
+	}: {
+		let call = <Call<T> as Decode>::decode(&mut &*call_enc)
+			.expect("call is encoded above, encoding must be correct");
+		super::Pallet::<T>::validate_unsigned(txsource, &call).map_err(|e| -> &'static str { e.into() })?;
+		call.dispatch_bypass_filter(RawOrigin::None.into())?;
+	}
+	verify {
+		assert!(RoundClaims::<T>::contains_key(0, source));
+	}
+
+	// Benchmark `expire_round` returning the remainder to the pot and removing `n` claims.
+	expire_round {
+		let n in 1 .. T::MaxRoundClaimsRemoved::get();
+		fund_round_pot::<T>();
+		Pallet::<T>::create_round(RawOrigin::Root.into(), [1; 32], VALUE.into(), 10u32.into())?;
+		for i in 0..n {
+			RoundClaims::<T>::insert(0, account::<AccountId>("claimer", i, SEED), ());
+		}
+		frame_system::Pallet::<T>::set_block_number(10u32.into());
+		let caller: T::AccountId = account("caller", 0, SEED);
+	}: _(RawOrigin::Signed(caller), 0)
+	verify {
+		assert!(Rounds::<T>::get(0).is_none());
+		assert!(RoundClaims::<T>::iter_prefix(0).next().is_none());
+	}
+
 	impl_benchmark_test_suite!(
 		Pallet,
 		crate::mock::new_test_ext(),
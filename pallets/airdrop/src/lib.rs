@@ -1,6 +1,12 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::manual_inspect)]
 //! Pallet to process airdrop claims
+//!
+//! Besides claims stored per account, airdrops can be distributed in rounds.
+//! A round only stores the merkle root of its claims, users prove their claim
+//! with a merkle proof and the usual signature. The total of a round is
+//! reserved from the round pot when it is created and claims are paid out of
+//! that reserve. Once a round expires the unclaimed remainder is unreserved.
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarks;
@@ -15,12 +21,13 @@ use polkadot_sdk::*;
 
 use frame_support::{
 	ensure,
-	traits::{Currency, Get, VestingSchedule},
+	traits::{BalanceStatus, ConstU32, Currency, Get, NamedReservableCurrency, VestingSchedule},
 	weights::Weight,
-	DefaultNoBound,
+	BoundedVec, DefaultNoBound,
 };
 use frame_system::pallet_prelude::BlockNumberFor;
-use scale_codec::Encode;
+use scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
 use sp_core::{
 	ed25519::{Public as EdwardsPublic, Signature as EdwardsSignature},
 	sr25519::{Public as SchnorrPublic, Signature as SchnorrSignature},
@@ -28,9 +35,10 @@ use sp_core::{
 use sp_runtime::{
 	traits::{CheckedSub, Saturating, Verify, Zero},
 	transaction_validity::{InvalidTransaction, TransactionValidity, ValidTransaction},
-	AccountId32,
+	AccountId32, RuntimeDebug,
 };
 use sp_std::{vec, vec::Vec};
+use time_primitives::{merkle_leaf, verify_merkle_proof, MerkleHash, MAX_MERKLE_DEPTH};
 
 type CurrencyOf<T> = <<T as Config>::VestingSchedule as VestingSchedule<
 	<T as frame_system::Config>::AccountId,
@@ -39,10 +47,36 @@ type BalanceOf<T> = <CurrencyOf<T> as Currency<<T as frame_system::Config>::Acco
 
 pub type RawSignature = [u8; 64];
 
+/// Identifier of the reserves funding airdrop rounds.
+pub const RESERVE_ID: [u8; 8] = *b"airdrop/";
+
+pub type RoundId = u32;
+
+pub type MerkleProof = BoundedVec<MerkleHash, ConstU32<MAX_MERKLE_DEPTH>>;
+
+/// Vesting schedule of a claim, see [`Vesting`].
+pub type VestingOf<T> = (BalanceOf<T>, BalanceOf<T>, BlockNumberFor<T>);
+
+/// Airdrop round distributing the claims committed to by `merkle_root`.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Round<Balance, BlockNumber> {
+	/// Root of the merkle tree of `(source, amount, vesting)` claims.
+	pub merkle_root: MerkleHash,
+	/// Sum of all claims of the round.
+	pub total: Balance,
+	/// Sum of the claims paid out so far.
+	pub claimed: Balance,
+	/// Block from which the round can no longer be claimed.
+	pub expires_at: BlockNumber,
+}
+
 pub trait WeightInfo {
 	fn claim_raw() -> Weight;
 	fn mint() -> Weight;
 	fn transfer() -> Weight;
+	fn create_round() -> Weight;
+	fn claim_merkle(n: u32) -> Weight;
+	fn expire_round(n: u32) -> Weight;
 }
 
 pub struct TestWeightInfo;
@@ -56,6 +90,15 @@ impl WeightInfo for TestWeightInfo {
 	fn transfer() -> Weight {
 		Weight::zero()
 	}
+	fn create_round() -> Weight {
+		Weight::zero()
+	}
+	fn claim_merkle(_: u32) -> Weight {
+		Weight::zero()
+	}
+	fn expire_round(_: u32) -> Weight {
+		Weight::zero()
+	}
 }
 
 #[polkadot_sdk::frame_support::pallet]
@@ -81,6 +124,19 @@ pub mod pallet {
 		type RawPrefix: Get<&'static [u8]>;
 		/// Additional safety check to avoid, minting claims below existential balance.
 		type MinimumBalance: Get<BalanceOf<Self>>;
+		/// Currency holding the reserved funds of airdrop rounds.
+		type Currency: NamedReservableCurrency<
+			Self::AccountId,
+			Balance = BalanceOf<Self>,
+			ReserveIdentifier = [u8; 8],
+		>;
+		/// Account funding airdrop rounds, the total of a round is reserved
+		/// from it until the round expires.
+		type RoundPot: Get<Self::AccountId>;
+		/// Maximum number of paid out claims of an expired round removed per call
+		/// of [`Pallet::expire_round`].
+		#[pallet::constant]
+		type MaxRoundClaimsRemoved: Get<u32>;
 		/// Weight information of the pallet
 		type WeightInfo: WeightInfo;
 	}
@@ -94,6 +150,24 @@ pub mod pallet {
 		Moved { from: AccountId32, to: AccountId32 },
 		/// Someone claimed their airdrop.
 		Claimed { source: AccountId32, target: T::AccountId, amount: BalanceOf<T> },
+		/// A new airdrop round was created.
+		RoundCreated {
+			round: RoundId,
+			merkle_root: MerkleHash,
+			total: BalanceOf<T>,
+			expires_at: BlockNumberFor<T>,
+		},
+		/// Someone claimed their airdrop of a round.
+		RoundClaimed {
+			round: RoundId,
+			source: AccountId32,
+			target: T::AccountId,
+			amount: BalanceOf<T>,
+		},
+		/// A round expired and its unclaimed remainder was returned to the pot.
+		RoundExpired { round: RoundId, unclaimed: BalanceOf<T> },
+		/// All paid out claims of an expired round were removed.
+		RoundCleared { round: RoundId },
 	}
 
 	#[derive(PartialEq)]
@@ -111,6 +185,20 @@ pub mod pallet {
 		VestingNotPossible,
 		/// The account already has a claim associated with it
 		AlreadyHasClaim,
+		/// The round doesn't exist.
+		UnknownRound,
+		/// The round has expired.
+		RoundExpired,
+		/// The round has not expired yet.
+		RoundNotExpired,
+		/// The expiry of a round must lie in the future.
+		InvalidExpiry,
+		/// The claim of the round was already paid out.
+		AlreadyClaimed,
+		/// Provided merkle proof doesn't match the root of the round.
+		InvalidMerkleProof,
+		/// Claims exceed the total of the round.
+		RoundExhausted,
 	}
 
 	/// List of all unclaimed airdrops and their amounts
@@ -129,6 +217,24 @@ pub mod pallet {
 	pub type Vesting<T: Config> =
 		StorageMap<_, Identity, AccountId32, (BalanceOf<T>, BalanceOf<T>, BlockNumberFor<T>)>;
 
+	/// Id of the next airdrop round.
+	#[pallet::storage]
+	pub type NextRoundId<T: Config> = StorageValue<_, RoundId, ValueQuery>;
+
+	/// Airdrop rounds which have not expired yet.
+	#[pallet::storage]
+	pub type Rounds<T: Config> =
+		StorageMap<_, Twox64Concat, RoundId, Round<BalanceOf<T>, BlockNumberFor<T>>>;
+
+	/// Claims of a round which were paid out.
+	#[pallet::storage]
+	pub type RoundClaims<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, RoundId, Identity, AccountId32, ()>;
+
+	/// Cursors of expired rounds whose paid out claims are still being removed.
+	#[pallet::storage]
+	pub type RoundClaimsCursor<T: Config> = StorageMap<_, Twox64Concat, RoundId, Vec<u8>>;
+
 	#[pallet::genesis_config]
 	#[derive(DefaultNoBound)]
 	pub struct GenesisConfig<T: Config> {
@@ -250,6 +356,165 @@ pub mod pallet {
 
 			Ok(Self::move_airdrop(from, to)?)
 		}
+
+		/// Create a new airdrop round.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		///
+		/// Reserves `total` from the round pot, fails if the pot can't fund it.
+		///
+		/// Parameters:
+		/// - `merkle_root`: The root of the merkle tree of all claims of the round.
+		/// - `total`: The sum of all claims of the round.
+		/// - `expires_at`: The block from which claims are no longer accepted.
+		///
+		/// <weight>
+		/// The weight of this call is invariant over the input parameters.
+		///
+		/// Total Complexity: O(1)
+		/// </weight>
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::create_round())]
+		pub fn create_round(
+			origin: OriginFor<T>,
+			merkle_root: MerkleHash,
+			total: BalanceOf<T>,
+			expires_at: BlockNumberFor<T>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(
+				expires_at > frame_system::Pallet::<T>::block_number(),
+				Error::<T>::InvalidExpiry
+			);
+			T::Currency::reserve_named(&RESERVE_ID, &T::RoundPot::get(), total)?;
+
+			let round = NextRoundId::<T>::get();
+			NextRoundId::<T>::put(round.saturating_add(1));
+			Rounds::<T>::insert(
+				round,
+				Round {
+					merkle_root,
+					total,
+					claimed: Zero::zero(),
+					expires_at,
+				},
+			);
+
+			Self::deposit_event(Event::<T>::RoundCreated {
+				round,
+				merkle_root,
+				total,
+				expires_at,
+			});
+
+			Ok(())
+		}
+
+		/// Make a claim to collect your airdrop of a round.
+		///
+		/// The dispatch origin for this call must be _None_.
+		///
+		/// Unsigned Validation:
+		/// A call to claim is deemed valid if the signature matches the
+		/// message described in [`Pallet::claim_raw`] and the merkle proof
+		/// proves `(source, amount, vesting)` to be part of the round.
+		///
+		/// Parameters:
+		/// - `round`: The round to claim from.
+		/// - `source`: The wallet used to register for the air-drop.
+		/// - `amount`: The number of tokens to claim.
+		/// - `vesting`: The optional vesting schedule of the claim.
+		/// - `merkle_proof`: The siblings on the path from the claim to the root.
+		/// - `proof`: The signature of the raw signed message.
+		/// - `target`: The destination account to payout the claim.
+		///
+		/// <weight>
+		/// The weight of this call is linear in the length of the merkle proof.
+		/// Weight includes logic to validate unsigned `claim` call.
+		///
+		/// Total Complexity: O(P) where P is the length of the merkle proof
+		/// </weight>
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::claim_merkle(merkle_proof.len() as u32))]
+		#[allow(clippy::too_many_arguments)]
+		pub fn claim_merkle(
+			origin: OriginFor<T>,
+			round: RoundId,
+			source: AccountId32,
+			amount: BalanceOf<T>,
+			vesting: Option<VestingOf<T>>,
+			merkle_proof: MerkleProof,
+			proof: RawSignature,
+			target: T::AccountId,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			Self::verify_proof(&source, &proof, &target)?;
+			let mut info =
+				Self::verify_round_claim(round, &source, amount, vesting, &merkle_proof)?;
+			info.claimed = info.claimed.saturating_add(amount);
+			ensure!(info.claimed <= info.total, Error::<T>::RoundExhausted);
+
+			Self::payout_reserved(&target, amount, vesting)?;
+			Rounds::<T>::insert(round, info);
+			RoundClaims::<T>::insert(round, &source, ());
+
+			Self::deposit_event(Event::<T>::RoundClaimed { round, source, target, amount });
+
+			Ok(())
+		}
+
+		/// Expire an airdrop round, returning its unclaimed remainder to the pot.
+		///
+		/// The dispatch origin for this call must be _Signed_, anyone can expire
+		/// a round once its expiry block has been reached.
+		///
+		/// Each call removes at most `MaxRoundClaimsRemoved` paid out claims of
+		/// the round, the call is repeated until [`Event::RoundCleared`] is
+		/// emitted.
+		///
+		/// Parameters:
+		/// - `round`: The round to expire.
+		///
+		/// <weight>
+		/// The weight of this call is linear in `MaxRoundClaimsRemoved`.
+		///
+		/// Total Complexity: O(C) where C is `MaxRoundClaimsRemoved`
+		/// </weight>
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::expire_round(T::MaxRoundClaimsRemoved::get()))]
+		pub fn expire_round(origin: OriginFor<T>, round: RoundId) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let cursor = RoundClaimsCursor::<T>::take(round);
+			if cursor.is_none() {
+				let info = Rounds::<T>::get(round).ok_or(Error::<T>::UnknownRound)?;
+				ensure!(
+					frame_system::Pallet::<T>::block_number() >= info.expires_at,
+					Error::<T>::RoundNotExpired
+				);
+
+				let unclaimed = info.total.saturating_sub(info.claimed);
+				if !unclaimed.is_zero() {
+					T::Currency::unreserve_named(&RESERVE_ID, &T::RoundPot::get(), unclaimed);
+				}
+				Rounds::<T>::remove(round);
+
+				Self::deposit_event(Event::<T>::RoundExpired { round, unclaimed });
+			}
+
+			let result = RoundClaims::<T>::clear_prefix(
+				round,
+				T::MaxRoundClaimsRemoved::get(),
+				cursor.as_deref(),
+			);
+			match result.maybe_cursor {
+				Some(cursor) => RoundClaimsCursor::<T>::insert(round, cursor),
+				None => Self::deposit_event(Event::<T>::RoundCleared { round }),
+			}
+
+			Ok(())
+		}
 	}
 
 	/// Ensure that only valid unsigned extrinsics are handled by our nodes
@@ -277,6 +542,33 @@ pub mod pallet {
 				});
 			}
 
+			if let Call::claim_merkle {
+				round,
+				source,
+				amount,
+				vesting,
+				merkle_proof,
+				proof,
+				target,
+			} = call
+			{
+				// Check if provided proof is valid
+				Self::verify_proof(source, proof, target)
+					.map_err(|_| InvalidTransaction::BadProof)?;
+
+				// Check if provided user has a valid claim in the round
+				Self::verify_round_claim(*round, source, *amount, *vesting, merkle_proof)
+					.map_err(|_| InvalidTransaction::BadSigner)?;
+
+				return Ok(ValidTransaction {
+					priority: PRIORITY,
+					requires: vec![],
+					provides: vec![("airdrop", round, source).encode()],
+					longevity: TransactionLongevity::MAX,
+					propagate: true,
+				});
+			}
+
 			// All other calls are not unsigned
 			Err(InvalidTransaction::Call.into())
 		}
@@ -402,28 +694,8 @@ impl<T: Config> Pallet<T> {
 		let amount = Claims::<T>::get(&source).ok_or(Error::<T>::HasNoClaim)?;
 		let new_total = Total::<T>::get().checked_sub(&amount).ok_or(Error::<T>::PotUnderflow)?;
 
-		// Ensure the account has not other vesting schedules associate with it
-		let vesting = Vesting::<T>::get(&source);
-		ensure!(
-			vesting.is_none()
-				|| T::VestingSchedule::can_add_vesting_schedule(
-					&target,
-					vesting.expect("Vesting is some; qed").0,
-					vesting.expect("Vesting is some; qed").1,
-					vesting.expect("Vesting is some; qed").2,
-				)
-				.is_ok(),
-			Error::<T>::VestingNotPossible
-		);
-
-		// First deposit the balance to ensure that the account exists.
-		let _ = CurrencyOf::<T>::deposit_creating(&target, amount);
-
-		// Then apply any associated vesting schedule
-		if let Some(vs) = vesting {
-			T::VestingSchedule::add_vesting_schedule(&target, vs.0, vs.1, vs.2)
-				.expect("Adding vesting schedule was checked above; qed");
-		}
+		// Pay out the claim and its vesting schedule
+		Self::payout(&target, amount, Vesting::<T>::get(&source))?;
 
 		// Update total and remove claim
 		Total::<T>::put(new_total);
@@ -435,4 +707,91 @@ impl<T: Config> Pallet<T> {
 
 		Ok(())
 	}
+	/// Internal verification function that ensures the claim is part of an unexpired round
+	fn verify_round_claim(
+		round: RoundId,
+		source: &AccountId32,
+		amount: BalanceOf<T>,
+		vesting: Option<VestingOf<T>>,
+		merkle_proof: &[MerkleHash],
+	) -> Result<Round<BalanceOf<T>, BlockNumberFor<T>>, Error<T>> {
+		let info = Rounds::<T>::get(round).ok_or(Error::<T>::UnknownRound)?;
+		ensure!(
+			frame_system::Pallet::<T>::block_number() < info.expires_at,
+			Error::<T>::RoundExpired
+		);
+		ensure!(!RoundClaims::<T>::contains_key(round, source), Error::<T>::AlreadyClaimed);
+		ensure!(amount >= T::MinimumBalance::get(), Error::<T>::BalanceTooSmall);
+
+		let leaf = merkle_leaf(&(source, amount, vesting));
+		ensure!(
+			verify_merkle_proof(&info.merkle_root, leaf, merkle_proof),
+			Error::<T>::InvalidMerkleProof
+		);
+
+		Ok(info)
+	}
+
+	/// Internal function depositing a claim and applying its vesting schedule
+	fn payout(
+		target: &T::AccountId,
+		amount: BalanceOf<T>,
+		vesting: Option<VestingOf<T>>,
+	) -> sp_runtime::DispatchResult {
+		Self::ensure_vesting_possible(target, vesting)?;
+
+		// First deposit the balance to ensure that the account exists.
+		let _ = CurrencyOf::<T>::deposit_creating(target, amount);
+
+		// Then apply any associated vesting schedule
+		Self::add_vesting(target, vesting);
+
+		Ok(())
+	}
+
+	/// Internal function paying a round claim out of the reserve of the pot
+	fn payout_reserved(
+		target: &T::AccountId,
+		amount: BalanceOf<T>,
+		vesting: Option<VestingOf<T>>,
+	) -> sp_runtime::DispatchResult {
+		Self::ensure_vesting_possible(target, vesting)?;
+
+		// First move the balance to ensure that the account exists.
+		let missing = T::Currency::repatriate_reserved_named(
+			&RESERVE_ID,
+			&T::RoundPot::get(),
+			target,
+			amount,
+			BalanceStatus::Free,
+		)?;
+		ensure!(missing.is_zero(), Error::<T>::PotUnderflow);
+
+		// Then apply any associated vesting schedule
+		Self::add_vesting(target, vesting);
+
+		Ok(())
+	}
+
+	/// Ensure the account has not other vesting schedules associate with it
+	fn ensure_vesting_possible(
+		target: &T::AccountId,
+		vesting: Option<VestingOf<T>>,
+	) -> sp_runtime::DispatchResult {
+		if let Some(vs) = vesting {
+			ensure!(
+				T::VestingSchedule::can_add_vesting_schedule(target, vs.0, vs.1, vs.2).is_ok(),
+				Error::<T>::VestingNotPossible
+			);
+		}
+		Ok(())
+	}
+
+	/// Apply the vesting schedule checked by [`Self::ensure_vesting_possible`]
+	fn add_vesting(target: &T::AccountId, vesting: Option<VestingOf<T>>) {
+		if let Some(vs) = vesting {
+			T::VestingSchedule::add_vesting_schedule(target, vs.0, vs.1, vs.2)
+				.expect("Adding vesting schedule was checked above; qed");
+		}
+	}
 }
@@ -6,7 +6,7 @@ use super::*;
 //use scale_codec::Encode;
 
 use frame_support::{derive_impl, ord_parameter_types, parameter_types, traits::WithdrawReasons};
-use sp_core::ConstU64;
+use sp_core::{ConstU32, ConstU64};
 
 use sp_runtime::{
 	traits::{Identity, IdentityLookup},
@@ -47,6 +47,8 @@ impl frame_system::Config for Test {
 #[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
 impl pallet_balances::Config for Test {
 	type AccountStore = System;
+	type ReserveIdentifier = [u8; 8];
+	type MaxReserves = frame_support::traits::ConstU32<1>;
 }
 
 parameter_types! {
@@ -69,6 +71,7 @@ impl pallet_vesting::Config for Test {
 parameter_types! {
 	// Currently needs to match the prefix to be benchmarked with as we can not sign in wasm.
 	pub RawPrefix: &'static [u8] = b"Airdrop TANLOG to the Testnet account: ";
+	pub RoundPot: AccountId = AccountId::new([42; 32]);
 }
 
 ord_parameter_types! {
@@ -80,6 +83,9 @@ impl Config for Test {
 	type VestingSchedule = Vesting;
 	type RawPrefix = RawPrefix;
 	type MinimumBalance = ConstU64<500>;
+	type Currency = Balances;
+	type RoundPot = RoundPot;
+	type MaxRoundClaimsRemoved = ConstU32<2>;
 	type WeightInfo = TestWeightInfo;
 }
 
@@ -87,10 +93,11 @@ impl Config for Test {
 // our desired mockup.
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
-	// We use default for brevity, but you can configure as desired if needed.
-	pallet_balances::GenesisConfig::<Test>::default()
-		.assimilate_storage(&mut t)
-		.unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(RoundPot::get(), ROUND_POT)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
 	pallet_airdrop::GenesisConfig::<Test> {
 		claims: vec![
 			(Alice.into(), 1000),
@@ -105,6 +112,9 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	t.into()
 }
 
+/// Initial balance of the round pot.
+pub const ROUND_POT: u64 = 10_000;
+
 pub fn total_claims() -> u64 {
 	1000 + 2000 + 3000 + 4000
 }
@@ -6,13 +6,14 @@ use crate::Call as AirdropCall;
 use super::*;
 use mock::{
 	new_test_ext, total_claims, Airdrop, Alice, Balances, Bob, Charlie, Dave, Eve, Ferdie,
-	RuntimeOrigin, Test, Vesting,
+	RoundPot, RuntimeOrigin, System, Test, Vesting, ROUND_POT,
 };
 
 use scale_codec::Encode;
 
 use frame_support::{assert_err, assert_noop, assert_ok, traits::ExistenceRequirement};
 use sp_runtime::{transaction_validity::TransactionLongevity, TokenError};
+use time_primitives::MerkleTree;
 
 #[test]
 fn basic_setup_works() {
@@ -566,3 +567,360 @@ fn validate_unsigned_works() {
 		);
 	});
 }
+
+type RoundClaim = (AccountId32, u64, Option<(u64, u64, u64)>);
+
+fn round_claims() -> Vec<RoundClaim> {
+	vec![
+		(Alice.into(), 1000, Some((800, 10, 1))),
+		(Dave.into(), 2000, None),
+		(Charlie.into(), 3000, None),
+	]
+}
+
+fn create_round(claims: &[RoundClaim], expires_at: u64) -> MerkleTree {
+	let tree = MerkleTree::new(claims.iter().map(merkle_leaf).collect());
+	let total = claims.iter().map(|(_, amount, _)| amount).sum();
+	assert_ok!(Airdrop::create_round(RuntimeOrigin::root(), tree.root(), total, expires_at));
+	tree
+}
+
+fn merkle_proof(tree: &MerkleTree, index: usize) -> MerkleProof {
+	tree.proof(index).unwrap().try_into().unwrap()
+}
+
+#[test]
+fn create_round_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			Airdrop::create_round(RuntimeOrigin::signed(Alice.into()), [0; 32], 1000, 10),
+			sp_runtime::traits::BadOrigin,
+		);
+		assert_noop!(
+			Airdrop::create_round(RuntimeOrigin::root(), [0; 32], 1000, 1),
+			Error::<Test>::InvalidExpiry,
+		);
+		let tree = create_round(&round_claims(), 10);
+		assert_eq!(
+			pallet_airdrop::Rounds::<Test>::get(0),
+			Some(Round {
+				merkle_root: tree.root(),
+				total: 6000,
+				claimed: 0,
+				expires_at: 10
+			})
+		);
+		assert_eq!(pallet_airdrop::NextRoundId::<Test>::get(), 1);
+		assert_eq!(Balances::reserved_balance_named(&RESERVE_ID, &RoundPot::get()), 6000);
+		assert_eq!(Balances::free_balance(&RoundPot::get()), ROUND_POT - 6000);
+	});
+}
+
+#[test]
+fn create_round_requires_funded_pot() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			Airdrop::create_round(RuntimeOrigin::root(), [0; 32], ROUND_POT + 1, 10),
+			pallet_balances::Error::<Test>::InsufficientBalance,
+		);
+		create_round(&round_claims(), 10);
+		assert_noop!(
+			Airdrop::create_round(RuntimeOrigin::root(), [0; 32], ROUND_POT - 5000, 10),
+			pallet_balances::Error::<Test>::InsufficientBalance,
+		);
+	});
+}
+
+#[test]
+fn claim_merkle_works() {
+	new_test_ext().execute_with(|| {
+		let claims = round_claims();
+		let tree = create_round(&claims, 10);
+
+		assert_ok!(Airdrop::claim_merkle(
+			RuntimeOrigin::none(),
+			0,
+			Alice.into(),
+			1000,
+			Some((800, 10, 1)),
+			merkle_proof(&tree, 0),
+			Alice.sign(&Airdrop::to_message(&Alice.into())[..]).0,
+			Alice.into(),
+		));
+		assert_eq!(Balances::free_balance(&Alice.into()), 1000);
+		assert_eq!(Vesting::vesting_balance(&Alice.into()), Some(800));
+
+		assert_ok!(Airdrop::claim_merkle(
+			RuntimeOrigin::none(),
+			0,
+			Dave.into(),
+			2000,
+			None,
+			merkle_proof(&tree, 1),
+			Dave.sign(&Airdrop::to_message(&Ferdie.into())[..]).0,
+			Ferdie.into(),
+		));
+		assert_eq!(Balances::free_balance(&Ferdie.into()), 2000);
+		assert_eq!(Vesting::vesting_balance(&Ferdie.into()), None);
+
+		assert_eq!(pallet_airdrop::Rounds::<Test>::get(0).unwrap().claimed, 3000);
+		// Claims are paid out of the reserve of the pot
+		assert_eq!(Balances::reserved_balance_named(&RESERVE_ID, &RoundPot::get()), 3000);
+		assert_eq!(Balances::total_balance(&RoundPot::get()), ROUND_POT - 3000);
+		assert_eq!(Balances::total_issuance(), ROUND_POT);
+		// Legacy claims are untouched
+		assert_eq!(pallet_airdrop::Total::<Test>::get(), total_claims());
+
+		assert_noop!(
+			Airdrop::claim_merkle(
+				RuntimeOrigin::none(),
+				0,
+				Alice.into(),
+				1000,
+				Some((800, 10, 1)),
+				merkle_proof(&tree, 0),
+				Alice.sign(&Airdrop::to_message(&Alice.into())[..]).0,
+				Alice.into(),
+			),
+			Error::<Test>::AlreadyClaimed,
+		);
+	});
+}
+
+#[test]
+fn claim_merkle_invalid_proof_fails() {
+	new_test_ext().execute_with(|| {
+		let tree = create_round(&round_claims(), 10);
+
+		// Claim more than committed to
+		assert_noop!(
+			Airdrop::claim_merkle(
+				RuntimeOrigin::none(),
+				0,
+				Dave.into(),
+				3000,
+				None,
+				merkle_proof(&tree, 1),
+				Dave.sign(&Airdrop::to_message(&Dave.into())[..]).0,
+				Dave.into(),
+			),
+			Error::<Test>::InvalidMerkleProof,
+		);
+		// Claim without vesting schedule
+		assert_noop!(
+			Airdrop::claim_merkle(
+				RuntimeOrigin::none(),
+				0,
+				Alice.into(),
+				1000,
+				None,
+				merkle_proof(&tree, 0),
+				Alice.sign(&Airdrop::to_message(&Alice.into())[..]).0,
+				Alice.into(),
+			),
+			Error::<Test>::InvalidMerkleProof,
+		);
+		// Claim of someone else
+		assert_noop!(
+			Airdrop::claim_merkle(
+				RuntimeOrigin::none(),
+				0,
+				Bob.into(),
+				2000,
+				None,
+				merkle_proof(&tree, 1),
+				Bob.sign(&Airdrop::to_message(&Bob.into())[..]).0,
+				Bob.into(),
+			),
+			Error::<Test>::InvalidMerkleProof,
+		);
+		// Claim signed by someone else
+		assert_noop!(
+			Airdrop::claim_merkle(
+				RuntimeOrigin::none(),
+				0,
+				Dave.into(),
+				2000,
+				None,
+				merkle_proof(&tree, 1),
+				Eve.sign(&Airdrop::to_message(&Dave.into())[..]).0,
+				Dave.into(),
+			),
+			Error::<Test>::InvalidSignature,
+		);
+		// Claim of an unknown round
+		assert_noop!(
+			Airdrop::claim_merkle(
+				RuntimeOrigin::none(),
+				1,
+				Dave.into(),
+				2000,
+				None,
+				merkle_proof(&tree, 1),
+				Dave.sign(&Airdrop::to_message(&Dave.into())[..]).0,
+				Dave.into(),
+			),
+			Error::<Test>::UnknownRound,
+		);
+	});
+}
+
+#[test]
+fn expire_round_works() {
+	new_test_ext().execute_with(|| {
+		let tree = create_round(&round_claims(), 10);
+		assert_ok!(Airdrop::claim_merkle(
+			RuntimeOrigin::none(),
+			0,
+			Dave.into(),
+			2000,
+			None,
+			merkle_proof(&tree, 1),
+			Dave.sign(&Airdrop::to_message(&Dave.into())[..]).0,
+			Dave.into(),
+		));
+		assert_noop!(
+			Airdrop::expire_round(RuntimeOrigin::signed(Bob.into()), 0),
+			Error::<Test>::RoundNotExpired,
+		);
+
+		System::set_block_number(10);
+		assert_noop!(
+			Airdrop::claim_merkle(
+				RuntimeOrigin::none(),
+				0,
+				Charlie.into(),
+				3000,
+				None,
+				merkle_proof(&tree, 2),
+				Charlie.sign(&Airdrop::to_message(&Charlie.into())[..]).0,
+				Charlie.into(),
+			),
+			Error::<Test>::RoundExpired,
+		);
+		assert_ok!(Airdrop::expire_round(RuntimeOrigin::signed(Bob.into()), 0));
+		assert_eq!(Balances::free_balance(&RoundPot::get()), ROUND_POT - 2000);
+		assert_eq!(Balances::reserved_balance_named(&RESERVE_ID, &RoundPot::get()), 0);
+		assert_eq!(pallet_airdrop::Rounds::<Test>::get(0), None);
+		System::assert_has_event(Event::<Test>::RoundExpired { round: 0, unclaimed: 4000 }.into());
+		System::assert_last_event(Event::<Test>::RoundCleared { round: 0 }.into());
+
+		assert_noop!(
+			Airdrop::expire_round(RuntimeOrigin::signed(Bob.into()), 0),
+			Error::<Test>::UnknownRound,
+		);
+	});
+}
+
+#[test]
+fn expire_round_removes_claims() {
+	new_test_ext().execute_with(|| {
+		let tree = create_round(&round_claims(), 10);
+		assert_ok!(Airdrop::claim_merkle(
+			RuntimeOrigin::none(),
+			0,
+			Alice.into(),
+			1000,
+			Some((800, 10, 1)),
+			merkle_proof(&tree, 0),
+			Alice.sign(&Airdrop::to_message(&Alice.into())[..]).0,
+			Alice.into(),
+		));
+		assert_ok!(Airdrop::claim_merkle(
+			RuntimeOrigin::none(),
+			0,
+			Dave.into(),
+			2000,
+			None,
+			merkle_proof(&tree, 1),
+			Dave.sign(&Airdrop::to_message(&Dave.into())[..]).0,
+			Dave.into(),
+		));
+		assert_ok!(Airdrop::claim_merkle(
+			RuntimeOrigin::none(),
+			0,
+			Charlie.into(),
+			3000,
+			None,
+			merkle_proof(&tree, 2),
+			Charlie.sign(&Airdrop::to_message(&Charlie.into())[..]).0,
+			Charlie.into(),
+		));
+		assert_eq!(pallet_airdrop::RoundClaims::<Test>::iter_prefix(0).count(), 3);
+
+		System::set_block_number(10);
+		// at most `MaxRoundClaimsRemoved` claims are removed per call
+		assert_ok!(Airdrop::expire_round(RuntimeOrigin::signed(Bob.into()), 0));
+		assert_eq!(pallet_airdrop::RoundClaims::<Test>::iter_prefix(0).count(), 1);
+		assert!(pallet_airdrop::RoundClaimsCursor::<Test>::get(0).is_some());
+		System::assert_last_event(Event::<Test>::RoundExpired { round: 0, unclaimed: 0 }.into());
+
+		assert_ok!(Airdrop::expire_round(RuntimeOrigin::signed(Bob.into()), 0));
+		System::assert_last_event(Event::<Test>::RoundCleared { round: 0 }.into());
+		assert_noop!(
+			Airdrop::expire_round(RuntimeOrigin::signed(Bob.into()), 0),
+			Error::<Test>::UnknownRound,
+		);
+
+		// expiry leaves no state of the round behind
+		assert_eq!(pallet_airdrop::Rounds::<Test>::get(0), None);
+		assert_eq!(pallet_airdrop::RoundClaims::<Test>::iter_prefix(0).count(), 0);
+		assert_eq!(pallet_airdrop::RoundClaimsCursor::<Test>::get(0), None);
+	});
+}
+
+#[test]
+fn validate_unsigned_merkle_works() {
+	use sp_runtime::traits::ValidateUnsigned;
+	let source = sp_runtime::transaction_validity::TransactionSource::External;
+
+	new_test_ext().execute_with(|| {
+		let tree = create_round(&round_claims(), 10);
+		let call = |proof| AirdropCall::claim_merkle {
+			round: 0,
+			source: Dave.into(),
+			amount: 2000,
+			vesting: None,
+			merkle_proof: merkle_proof(&tree, 1),
+			proof,
+			target: Dave.into(),
+		};
+		// Allow transaction with a valid signature and merkle proof
+		assert_eq!(
+			Pallet::<Test>::validate_unsigned(
+				source,
+				&call(Dave.sign(&Airdrop::to_message(&Dave.into())[..]).0)
+			),
+			Ok(ValidTransaction {
+				priority: 100,
+				requires: vec![],
+				provides: vec![("airdrop", 0u32, AccountId32::from(Dave)).encode()],
+				longevity: TransactionLongevity::MAX,
+				propagate: true,
+			})
+		);
+		// Fail transaction with an invalid signature
+		assert_eq!(
+			Pallet::<Test>::validate_unsigned(source, &call([0u8; 64])),
+			InvalidTransaction::BadProof.into(),
+		);
+		// Fail transaction with an invalid merkle proof
+		assert_eq!(
+			Pallet::<Test>::validate_unsigned(
+				source,
+				&AirdropCall::claim_merkle {
+					round: 0,
+					source: Dave.into(),
+					amount: 2000,
+					vesting: None,
+					merkle_proof: merkle_proof(&tree, 0),
+					proof: Dave.sign(&Airdrop::to_message(&Dave.into())[..]).0,
+					target: Dave.into(),
+				}
+			),
+			InvalidTransaction::BadSigner.into(),
+		);
+	});
+}
@@ -1,12 +1,55 @@
 use super::*;
 use crate::Pallet;
 
+use frame_support::traits::{Currency, Get};
 use frame_system::RawOrigin;
-use polkadot_sdk::frame_benchmarking::benchmarks;
-use polkadot_sdk::{frame_system, sp_runtime, sp_std};
+use polkadot_sdk::frame_benchmarking::{account, benchmarks};
+use polkadot_sdk::{frame_support, frame_system, sp_runtime, sp_std};
 use sp_runtime::BoundedVec;
 use sp_std::vec;
-use time_primitives::{DmailPath, DmailTo, DMAIL_PATH_LEN, DMAIL_TO_LEN};
+use sp_std::vec::Vec;
+use time_primitives::{
+	AccountId, DmailEnvelope, DmailMessageId, DmailPath, DmailTo, DMAIL_PATH_LEN,
+	DMAIL_PAYLOAD_LEN, DMAIL_TO_LEN,
+};
+
+fn funded<T: Config>(name: &'static str, index: u32) -> AccountId {
+	let account: AccountId = account(name, index, 0);
+	T::Currency::make_free_balance_be(&account, T::MessageDeposit::get() * 1_000_000);
+	account
+}
+
+fn path() -> DmailPath {
+	DmailPath(BoundedVec::truncate_from(vec![0u8; DMAIL_PATH_LEN as usize]))
+}
+
+fn envelope() -> DmailEnvelope {
+	DmailEnvelope {
+		ephemeral_key: [1; 32],
+		nonce: [2; 24],
+		ciphertext: BoundedVec::truncate_from(vec![3u8; DMAIL_PAYLOAD_LEN as usize]),
+	}
+}
+
+fn send<T: Config>(sender: &AccountId, recipient: &AccountId) -> DmailMessageId {
+	let id = NextMessageId::<T>::get();
+	Pallet::<T>::send_message(
+		RawOrigin::Signed(sender.clone()).into(),
+		recipient.clone(),
+		path(),
+		Some(envelope()),
+	)
+	.unwrap();
+	id
+}
+
+/// Sends `n` messages with a deposit, spread over as many senders as the
+/// sender quota requires.
+fn fill_inbox<T: Config>(recipient: &AccountId, n: u32) -> Vec<DmailMessageId> {
+	(0..n)
+		.map(|i| send::<T>(&funded::<T>("sender", i / T::MaxMessagesPerSender::get()), recipient))
+		.collect()
+}
 
 benchmarks! {
 	send_email {
@@ -15,5 +58,51 @@ benchmarks! {
 	}: _(RawOrigin::Signed([0u8; 32].into()), to, path)
 	verify {}
 
+	send_message {
+		// worst case evicts the only message with a deposit, which is the newest
+		let sender = funded::<T>("allowed", 0);
+		let recipient = funded::<T>("recipient", 0);
+		Pallet::<T>::set_allowed_sender(RawOrigin::Signed(recipient.clone()).into(), sender.clone(), true)?;
+		for _ in 1 .. T::MaxInboxSize::get() {
+			send::<T>(&sender, &recipient);
+		}
+		fill_inbox::<T>(&recipient, 1);
+	}: _(RawOrigin::Signed(sender), recipient.clone(), path(), Some(envelope()))
+	verify {
+		assert_eq!(Pallet::<T>::get_inbox_len(recipient), T::MaxInboxSize::get());
+	}
+
+	mark_read {
+		let n in 1 .. T::MaxInboxSize::get();
+		let recipient = funded::<T>("recipient", 0);
+		let ids = fill_inbox::<T>(&recipient, n);
+	}: _(RawOrigin::Signed(recipient), BoundedVec::truncate_from(ids))
+	verify {}
+
+	delete_messages {
+		let n in 1 .. T::MaxInboxSize::get();
+		let recipient = funded::<T>("recipient", 0);
+		let ids = fill_inbox::<T>(&recipient, n);
+	}: _(RawOrigin::Signed(recipient.clone()), BoundedVec::truncate_from(ids))
+	verify {
+		assert_eq!(Pallet::<T>::get_inbox_len(recipient), 0);
+	}
+
+	set_allowed_sender {
+		let recipient = funded::<T>("recipient", 0);
+	}: _(RawOrigin::Signed(recipient), [1u8; 32].into(), true)
+	verify {}
+
+	prune_inbox {
+		let n in 1 .. T::MaxInboxSize::get();
+		let recipient = funded::<T>("recipient", 0);
+		fill_inbox::<T>(&recipient, n);
+		let now = frame_system::Pallet::<T>::block_number();
+		frame_system::Pallet::<T>::set_block_number(now + T::MessageLifetime::get().into());
+	}: _(RawOrigin::Signed(recipient.clone()), recipient.clone())
+	verify {
+		assert_eq!(Pallet::<T>::get_inbox_len(recipient), 0);
+	}
+
 	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -1,5 +1,19 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::manual_inspect)]
+//! # Dmail Pallet
+//!
+//! Delivers messages to per-recipient inboxes.
+//!
+//! Messages carry a path and optionally an envelope encrypted to the x25519
+//! key of the recipient. To deter spam senders reserve a `MessageDeposit`
+//! per message under the [`RESERVE_ID`] named reserve, which is refunded once
+//! the recipient marks the message as read or once it expires and is pruned.
+//! Deleting an unread message slashes the deposit. Recipients can allow
+//! senders to message them without a deposit.
+//!
+//! A sender can have at most `MaxMessagesPerSender` messages with a deposit
+//! in an inbox. When an inbox is full, messages from allowed senders evict
+//! the oldest message with a deposit, refunding its deposit.
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
@@ -11,20 +25,54 @@ pub use pallet::*;
 
 #[polkadot_sdk::frame_support::pallet]
 pub mod pallet {
-	use polkadot_sdk::{frame_support, frame_system};
+	use polkadot_sdk::{frame_support, frame_system, sp_runtime};
 
 	use frame_support::pallet_prelude::*;
+	use frame_support::traits::NamedReservableCurrency;
 	use frame_system::pallet_prelude::*;
-	use time_primitives::{AccountId, DmailMessage, DmailPath, DmailTo};
+	use scale_info::prelude::vec::Vec;
+	use sp_runtime::traits::{Saturating, UniqueSaturatedInto, Zero};
+	use time_primitives::{
+		AccountId, Balance, BlockNumber, DmailEnvelope, DmailInboxMessage, DmailMessage,
+		DmailMessageId, DmailPath, DmailTo,
+	};
+
+	/// Identifier of the named reserve holding message deposits.
+	pub const RESERVE_ID: [u8; 8] = *b"dmail/md";
 
 	pub trait WeightInfo {
 		fn send_email() -> Weight;
+		fn send_message() -> Weight;
+		fn mark_read(n: u32) -> Weight;
+		fn delete_messages(n: u32) -> Weight;
+		fn set_allowed_sender() -> Weight;
+		fn prune_inbox(n: u32) -> Weight;
 	}
 
 	impl WeightInfo for () {
 		fn send_email() -> Weight {
 			Weight::default()
 		}
+
+		fn send_message() -> Weight {
+			Weight::default()
+		}
+
+		fn mark_read(_: u32) -> Weight {
+			Weight::default()
+		}
+
+		fn delete_messages(_: u32) -> Weight {
+			Weight::default()
+		}
+
+		fn set_allowed_sender() -> Weight {
+			Weight::default()
+		}
+
+		fn prune_inbox(_: u32) -> Weight {
+			Weight::default()
+		}
 	}
 
 	#[pallet::pallet]
@@ -39,12 +87,117 @@ pub mod pallet {
 		type RuntimeEvent: From<Event<Self>>
 			+ IsType<<Self as polkadot_sdk::frame_system::Config>::RuntimeEvent>;
 		type WeightInfo: WeightInfo;
+		/// Currency used for message deposits.
+		type Currency: NamedReservableCurrency<
+			Self::AccountId,
+			Balance = Balance,
+			ReserveIdentifier = [u8; 8],
+		>;
+		/// Deposit reserved per message from senders which aren't allowed by
+		/// the recipient.
+		#[pallet::constant]
+		type MessageDeposit: Get<Balance>;
+		/// Maximum number of messages in an inbox.
+		#[pallet::constant]
+		type MaxInboxSize: Get<u32>;
+		/// Maximum number of messages with a deposit from a sender in an
+		/// inbox.
+		#[pallet::constant]
+		type MaxMessagesPerSender: Get<u32>;
+		/// Number of blocks after which a message can be pruned by anyone.
+		#[pallet::constant]
+		type MessageLifetime: Get<BlockNumber>;
 	}
 
+	/// Id of the next message.
+	#[pallet::storage]
+	pub type NextMessageId<T: Config> = StorageValue<_, DmailMessageId, ValueQuery>;
+
+	/// Messages by id.
+	#[pallet::storage]
+	pub type Messages<T: Config> =
+		StorageMap<_, Blake2_128Concat, DmailMessageId, DmailInboxMessage, OptionQuery>;
+
+	/// Message ids of an inbox, oldest first.
+	#[pallet::storage]
+	pub type Inbox<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AccountId,
+		BoundedVec<DmailMessageId, T::MaxInboxSize>,
+		ValueQuery,
+	>;
+
+	/// Senders allowed by a recipient to message them without a deposit.
+	#[pallet::storage]
+	pub type AllowedSenders<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, AccountId, Blake2_128Concat, AccountId, ()>;
+
+	/// Number of messages with a deposit from a sender in the inbox of a
+	/// recipient.
+	#[pallet::storage]
+	pub type SenderMessages<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		AccountId,
+		Blake2_128Concat,
+		AccountId,
+		u32,
+		ValueQuery,
+	>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		Message(DmailMessage),
+		/// A message was delivered to the inbox of `recipient`.
+		MessageSent {
+			id: DmailMessageId,
+			sender: AccountId,
+			recipient: AccountId,
+			encrypted: bool,
+		},
+		/// Read receipt of a message.
+		MessageRead {
+			id: DmailMessageId,
+			sender: AccountId,
+			recipient: AccountId,
+		},
+		/// A message was deleted, `slashed` is the deposit of an unread message.
+		MessageDeleted {
+			id: DmailMessageId,
+			recipient: AccountId,
+			slashed: Balance,
+		},
+		/// A message was evicted from a full inbox by a message from an
+		/// allowed sender, its deposit was refunded.
+		MessageEvicted {
+			id: DmailMessageId,
+			sender: AccountId,
+			recipient: AccountId,
+		},
+		/// Expired messages were pruned from the inbox of `recipient`.
+		InboxPruned {
+			recipient: AccountId,
+			count: u32,
+		},
+		AllowedSenderSet {
+			recipient: AccountId,
+			sender: AccountId,
+			allowed: bool,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The inbox of the recipient is full.
+		InboxFull,
+		/// The message doesn't exist.
+		UnknownMessage,
+		/// The message isn't addressed to the caller.
+		NotRecipient,
+		/// The sender has too many messages with a deposit in the inbox.
+		TooManyMessages,
 	}
 
 	#[pallet::call]
@@ -57,5 +210,234 @@ pub mod pallet {
 			Self::deposit_event(Event::Message(message));
 			Ok(())
 		}
+
+		/// Delivers a message to the inbox of `recipient`, reserving the
+		/// message deposit unless the sender is allowed by the recipient.
+		///
+		/// If the inbox is full, a message from an allowed sender evicts the
+		/// oldest message with a deposit.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::send_message())]
+		pub fn send_message(
+			origin: OriginFor<T>,
+			recipient: AccountId,
+			path: DmailPath,
+			envelope: Option<DmailEnvelope>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let allowed =
+				sender == recipient || AllowedSenders::<T>::contains_key(&recipient, &sender);
+			let deposit = if allowed {
+				Zero::zero()
+			} else {
+				ensure!(
+					SenderMessages::<T>::get(&recipient, &sender) < T::MaxMessagesPerSender::get(),
+					Error::<T>::TooManyMessages
+				);
+				T::MessageDeposit::get()
+			};
+			if Self::get_inbox_len(recipient.clone()) >= T::MaxInboxSize::get() {
+				ensure!(allowed, Error::<T>::InboxFull);
+				Self::evict_message(&recipient)?;
+			}
+			let id = NextMessageId::<T>::get();
+			Inbox::<T>::try_mutate(&recipient, |inbox| inbox.try_push(id))
+				.map_err(|_| Error::<T>::InboxFull)?;
+			if !deposit.is_zero() {
+				T::Currency::reserve_named(&RESERVE_ID, &sender, deposit)?;
+				SenderMessages::<T>::mutate(&recipient, &sender, |count| {
+					*count = count.saturating_add(1)
+				});
+			}
+			let encrypted = envelope.is_some();
+			Messages::<T>::insert(
+				id,
+				DmailInboxMessage {
+					id,
+					sender: sender.clone(),
+					recipient: recipient.clone(),
+					path,
+					envelope,
+					sent_at: Self::block_number(),
+					deposit,
+					read: false,
+				},
+			);
+			NextMessageId::<T>::put(id.saturating_add(1));
+			Self::deposit_event(Event::MessageSent {
+				id,
+				sender,
+				recipient,
+				encrypted,
+			});
+			Ok(())
+		}
+
+		/// Marks messages as read, refunding their deposits to the senders.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::mark_read(ids.len() as u32))]
+		pub fn mark_read(
+			origin: OriginFor<T>,
+			ids: BoundedVec<DmailMessageId, T::MaxInboxSize>,
+		) -> DispatchResult {
+			let recipient = ensure_signed(origin)?;
+			for id in ids {
+				let mut message = Self::message_of(&recipient, id)?;
+				if message.read {
+					continue;
+				}
+				Self::refund_deposit(&message);
+				message.read = true;
+				message.deposit = Zero::zero();
+				Self::deposit_event(Event::MessageRead {
+					id,
+					sender: message.sender.clone(),
+					recipient: recipient.clone(),
+				});
+				Messages::<T>::insert(id, message);
+			}
+			Ok(())
+		}
+
+		/// Deletes messages from the inbox of the caller. The deposits of
+		/// unread messages are slashed.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::delete_messages(ids.len() as u32))]
+		pub fn delete_messages(
+			origin: OriginFor<T>,
+			ids: BoundedVec<DmailMessageId, T::MaxInboxSize>,
+		) -> DispatchResult {
+			let recipient = ensure_signed(origin)?;
+			for id in ids {
+				let message = Self::message_of(&recipient, id)?;
+				let (_, remaining) = T::Currency::slash_reserved_named(
+					&RESERVE_ID,
+					&message.sender,
+					message.deposit,
+				);
+				if !message.deposit.is_zero() {
+					Self::message_released(&recipient, &message.sender);
+				}
+				Self::remove_message(&recipient, id);
+				Self::deposit_event(Event::MessageDeleted {
+					id,
+					recipient: recipient.clone(),
+					slashed: message.deposit.saturating_sub(remaining),
+				});
+			}
+			Ok(())
+		}
+
+		/// Allows or disallows `sender` to message the caller without a deposit.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::set_allowed_sender())]
+		pub fn set_allowed_sender(
+			origin: OriginFor<T>,
+			sender: AccountId,
+			allowed: bool,
+		) -> DispatchResult {
+			let recipient = ensure_signed(origin)?;
+			if allowed {
+				AllowedSenders::<T>::insert(&recipient, &sender, ());
+			} else {
+				AllowedSenders::<T>::remove(&recipient, &sender);
+			}
+			Self::deposit_event(Event::AllowedSenderSet { recipient, sender, allowed });
+			Ok(())
+		}
+
+		/// Prunes the expired messages of `recipient`, refunding the deposits
+		/// of unread messages. Callable by anyone.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::prune_inbox(T::MaxInboxSize::get()))]
+		pub fn prune_inbox(origin: OriginFor<T>, recipient: AccountId) -> DispatchResult {
+			ensure_signed(origin)?;
+			let now = Self::block_number();
+			let mut count = 0;
+			Inbox::<T>::mutate(&recipient, |inbox| {
+				inbox.retain(|id| {
+					let Some(message) = Messages::<T>::get(id) else {
+						return false;
+					};
+					if now < message.sent_at.saturating_add(T::MessageLifetime::get()) {
+						return true;
+					}
+					Self::refund_deposit(&message);
+					Messages::<T>::remove(id);
+					count += 1;
+					false
+				});
+			});
+			Self::deposit_event(Event::InboxPruned { recipient, count });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn block_number() -> BlockNumber {
+			frame_system::Pallet::<T>::block_number().unique_saturated_into()
+		}
+
+		fn message_of(
+			recipient: &AccountId,
+			id: DmailMessageId,
+		) -> Result<DmailInboxMessage, DispatchError> {
+			let message = Messages::<T>::get(id).ok_or(Error::<T>::UnknownMessage)?;
+			ensure!(&message.recipient == recipient, Error::<T>::NotRecipient);
+			Ok(message)
+		}
+
+		fn remove_message(recipient: &AccountId, id: DmailMessageId) {
+			Messages::<T>::remove(id);
+			Inbox::<T>::mutate(recipient, |inbox| inbox.retain(|i| *i != id));
+		}
+
+		/// Refunds the deposit of a message to its sender.
+		fn refund_deposit(message: &DmailInboxMessage) {
+			if message.deposit.is_zero() {
+				return;
+			}
+			T::Currency::unreserve_named(&RESERVE_ID, &message.sender, message.deposit);
+			Self::message_released(&message.recipient, &message.sender);
+		}
+
+		fn message_released(recipient: &AccountId, sender: &AccountId) {
+			SenderMessages::<T>::mutate_exists(recipient, sender, |count| {
+				*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0);
+			});
+		}
+
+		/// Evicts the oldest message with a deposit from the inbox of
+		/// `recipient`, refunding its deposit.
+		fn evict_message(recipient: &AccountId) -> DispatchResult {
+			let message = Inbox::<T>::get(recipient)
+				.into_iter()
+				.filter_map(Messages::<T>::get)
+				.find(|message| !message.deposit.is_zero())
+				.ok_or(Error::<T>::InboxFull)?;
+			Self::refund_deposit(&message);
+			Self::remove_message(recipient, message.id);
+			Self::deposit_event(Event::MessageEvicted {
+				id: message.id,
+				sender: message.sender,
+				recipient: recipient.clone(),
+			});
+			Ok(())
+		}
+
+		/// Returns up to `limit` messages of the inbox of `account` starting
+		/// at `offset`, oldest first.
+		pub fn get_inbox(account: AccountId, offset: u32, limit: u32) -> Vec<DmailInboxMessage> {
+			Inbox::<T>::get(account)
+				.into_iter()
+				.skip(offset as usize)
+				.take(limit as usize)
+				.filter_map(Messages::<T>::get)
+				.collect()
+		}
+
+		pub fn get_inbox_len(account: AccountId) -> u32 {
+			Inbox::<T>::decode_len(account).unwrap_or_default() as u32
+		}
 	}
 }
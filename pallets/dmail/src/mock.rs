@@ -2,7 +2,7 @@ use crate::{self as pallet_dmail};
 
 use polkadot_sdk::frame_support::derive_impl;
 use polkadot_sdk::sp_core;
-use polkadot_sdk::sp_core::{ConstU128, ConstU64};
+use polkadot_sdk::sp_core::{ConstU128, ConstU32, ConstU64};
 use polkadot_sdk::sp_runtime::{traits::IdentityLookup, BuildStorage};
 use polkadot_sdk::sp_runtime::{
 	traits::{IdentifyAccount, Verify},
@@ -41,6 +41,8 @@ impl pallet_balances::Config for Test {
 	type Balance = u128;
 	type RuntimeEvent = RuntimeEvent;
 	type ExistentialDeposit = ConstU128<1>;
+	type ReserveIdentifier = [u8; 8];
+	type MaxReserves = ConstU32<2>;
 	type AccountStore = System;
 	type WeightInfo = pallet_balances::weights::SubstrateWeight<Test>;
 }
@@ -48,6 +50,11 @@ impl pallet_balances::Config for Test {
 impl pallet_dmail::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
+	type Currency = Balances;
+	type MessageDeposit = ConstU128<10>;
+	type MaxInboxSize = ConstU32<3>;
+	type MaxMessagesPerSender = ConstU32<2>;
+	type MessageLifetime = ConstU32<10>;
 }
 
 fn acc_pub(acc_num: u8) -> sp_core::sr25519::Public {
@@ -57,7 +64,11 @@ fn acc_pub(acc_num: u8) -> sp_core::sr25519::Public {
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
 	pallet_balances::GenesisConfig::<Test> {
-		balances: vec![(acc_pub(1).into(), 10_000_000_000)],
+		balances: vec![
+			(acc_pub(1).into(), 10_000_000_000),
+			(acc_pub(2).into(), 10_000_000_000),
+			(acc_pub(3).into(), 10_000_000_000),
+		],
 	}
 	.assimilate_storage(&mut storage)
 	.unwrap();
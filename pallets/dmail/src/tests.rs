@@ -1,13 +1,16 @@
 use crate::mock::*;
-use crate::Event;
+use crate::{Error, Event, Messages, NextMessageId, SenderMessages, RESERVE_ID};
 
 use polkadot_sdk::{frame_support, frame_system, sp_runtime};
 
-use frame_support::assert_ok;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{NamedReservableCurrency, ReservableCurrency},
+};
 use frame_system::RawOrigin;
 use scale_codec::Encode;
 use sp_runtime::BoundedVec;
-use time_primitives::{DmailMessage, DmailPath, DmailTo};
+use time_primitives::{DmailEnvelope, DmailMessage, DmailMessageId, DmailPath, DmailTo};
 
 #[test]
 fn test_dmail_event() {
@@ -28,3 +31,242 @@ fn test_dmail_event() {
 		System::assert_last_event(Event::<Test>::Message(event).into());
 	});
 }
+
+const DEPOSIT: u128 = 10;
+
+fn alice() -> AccountId {
+	[1; 32].into()
+}
+
+fn bob() -> AccountId {
+	[2; 32].into()
+}
+
+fn charlie() -> AccountId {
+	[3; 32].into()
+}
+
+fn path() -> DmailPath {
+	DmailPath(BoundedVec::truncate_from(b"//inbox".to_vec()))
+}
+
+fn envelope() -> DmailEnvelope {
+	DmailEnvelope {
+		ephemeral_key: [3; 32],
+		nonce: [4; 24],
+		ciphertext: BoundedVec::truncate_from(vec![5; 32]),
+	}
+}
+
+fn send(sender: AccountId, recipient: AccountId) -> DmailMessageId {
+	let id = NextMessageId::<Test>::get();
+	assert_ok!(Dmail::send_message(
+		RawOrigin::Signed(sender).into(),
+		recipient,
+		path(),
+		Some(envelope()),
+	));
+	id
+}
+
+#[test]
+fn send_message_reserves_deposit() {
+	new_test_ext().execute_with(|| {
+		let id = send(alice(), bob());
+		System::assert_last_event(
+			Event::<Test>::MessageSent {
+				id,
+				sender: alice(),
+				recipient: bob(),
+				encrypted: true,
+			}
+			.into(),
+		);
+		assert_eq!(Balances::reserved_balance(alice()), DEPOSIT);
+		assert_eq!(Dmail::get_inbox_len(bob()), 1);
+		let message = Messages::<Test>::get(id).unwrap();
+		assert_eq!(message.envelope, Some(envelope()));
+		assert_eq!(message.deposit, DEPOSIT);
+		assert!(!message.read);
+	});
+}
+
+#[test]
+fn allowed_sender_pays_no_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Dmail::set_allowed_sender(RawOrigin::Signed(bob()).into(), alice(), true));
+		let id = send(alice(), bob());
+		assert_eq!(Balances::reserved_balance(alice()), 0);
+		assert_eq!(Messages::<Test>::get(id).unwrap().deposit, 0);
+		assert_ok!(Dmail::set_allowed_sender(RawOrigin::Signed(bob()).into(), alice(), false));
+		send(alice(), bob());
+		assert_eq!(Balances::reserved_balance(alice()), DEPOSIT);
+	});
+}
+
+#[test]
+fn mark_read_refunds_deposit() {
+	new_test_ext().execute_with(|| {
+		let id = send(alice(), bob());
+		assert_noop!(
+			Dmail::mark_read(
+				RawOrigin::Signed(alice()).into(),
+				BoundedVec::truncate_from(vec![id])
+			),
+			Error::<Test>::NotRecipient
+		);
+		assert_ok!(Dmail::mark_read(
+			RawOrigin::Signed(bob()).into(),
+			BoundedVec::truncate_from(vec![id])
+		));
+		System::assert_last_event(
+			Event::<Test>::MessageRead {
+				id,
+				sender: alice(),
+				recipient: bob(),
+			}
+			.into(),
+		);
+		assert_eq!(Balances::reserved_balance(alice()), 0);
+		assert!(Messages::<Test>::get(id).unwrap().read);
+	});
+}
+
+#[test]
+fn delete_unread_message_slashes_deposit() {
+	new_test_ext().execute_with(|| {
+		let balance = Balances::free_balance(alice());
+		let id = send(alice(), bob());
+		assert_ok!(Dmail::delete_messages(
+			RawOrigin::Signed(bob()).into(),
+			BoundedVec::truncate_from(vec![id])
+		));
+		System::assert_last_event(
+			Event::<Test>::MessageDeleted {
+				id,
+				recipient: bob(),
+				slashed: DEPOSIT,
+			}
+			.into(),
+		);
+		assert_eq!(Balances::free_balance(alice()), balance - DEPOSIT);
+		assert_eq!(Dmail::get_inbox_len(bob()), 0);
+		assert!(Messages::<Test>::get(id).is_none());
+	});
+}
+
+#[test]
+fn full_inbox_rejects_messages() {
+	new_test_ext().execute_with(|| {
+		send(alice(), bob());
+		send(alice(), bob());
+		send(charlie(), bob());
+		assert_noop!(
+			Dmail::send_message(RawOrigin::Signed(charlie()).into(), bob(), path(), None),
+			Error::<Test>::InboxFull
+		);
+	});
+}
+
+#[test]
+fn sender_quota_is_enforced() {
+	new_test_ext().execute_with(|| {
+		let id = send(alice(), bob());
+		send(alice(), bob());
+		assert_eq!(SenderMessages::<Test>::get(bob(), alice()), 2);
+		assert_noop!(
+			Dmail::send_message(RawOrigin::Signed(alice()).into(), bob(), path(), None),
+			Error::<Test>::TooManyMessages
+		);
+		assert_ok!(Dmail::mark_read(
+			RawOrigin::Signed(bob()).into(),
+			BoundedVec::truncate_from(vec![id])
+		));
+		assert_eq!(SenderMessages::<Test>::get(bob(), alice()), 1);
+		send(alice(), bob());
+		// allowed senders don't count against the quota
+		assert_ok!(Dmail::set_allowed_sender(RawOrigin::Signed(charlie()).into(), alice(), true));
+		for _ in 0..3 {
+			send(alice(), charlie());
+		}
+		assert!(!SenderMessages::<Test>::contains_key(charlie(), alice()));
+	});
+}
+
+#[test]
+fn allowed_sender_evicts_oldest_message_with_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Dmail::set_allowed_sender(RawOrigin::Signed(bob()).into(), charlie(), true));
+		let allowed = send(charlie(), bob());
+		let evicted = send(alice(), bob());
+		let kept = send(alice(), bob());
+		assert_eq!(Balances::reserved_balance(alice()), 2 * DEPOSIT);
+		let id = send(charlie(), bob());
+		System::assert_has_event(
+			Event::<Test>::MessageEvicted {
+				id: evicted,
+				sender: alice(),
+				recipient: bob(),
+			}
+			.into(),
+		);
+		assert_eq!(Balances::reserved_balance(alice()), DEPOSIT);
+		assert_eq!(SenderMessages::<Test>::get(bob(), alice()), 1);
+		assert!(Messages::<Test>::get(evicted).is_none());
+		assert_eq!(
+			Dmail::get_inbox(bob(), 0, 10).into_iter().map(|m| m.id).collect::<Vec<_>>(),
+			vec![allowed, kept, id]
+		);
+	});
+}
+
+#[test]
+fn deposits_use_named_reserve() {
+	new_test_ext().execute_with(|| {
+		// reserved by another pallet
+		assert_ok!(Balances::reserve(&alice(), 100));
+		let id = send(alice(), bob());
+		assert_eq!(Balances::reserved_balance_named(&RESERVE_ID, &alice()), DEPOSIT);
+		assert_ok!(Dmail::delete_messages(
+			RawOrigin::Signed(bob()).into(),
+			BoundedVec::truncate_from(vec![id])
+		));
+		assert_eq!(Balances::reserved_balance_named(&RESERVE_ID, &alice()), 0);
+		assert_eq!(Balances::reserved_balance(alice()), 100);
+		assert!(!SenderMessages::<Test>::contains_key(bob(), alice()));
+	});
+}
+
+#[test]
+fn prune_inbox_removes_expired_messages() {
+	new_test_ext().execute_with(|| {
+		let expired = send(alice(), bob());
+		System::set_block_number(5);
+		let kept = send(alice(), bob());
+		System::set_block_number(11);
+		assert_ok!(Dmail::prune_inbox(RawOrigin::Signed(alice()).into(), bob()));
+		System::assert_last_event(Event::<Test>::InboxPruned { recipient: bob(), count: 1 }.into());
+		assert!(Messages::<Test>::get(expired).is_none());
+		assert_eq!(Balances::reserved_balance(alice()), DEPOSIT);
+		assert_eq!(
+			Dmail::get_inbox(bob(), 0, 10).into_iter().map(|m| m.id).collect::<Vec<_>>(),
+			vec![kept]
+		);
+	});
+}
+
+#[test]
+fn get_inbox_pages_messages() {
+	new_test_ext().execute_with(|| {
+		let ids: Vec<_> = (0..3).map(|_| send(alice(), bob())).collect();
+		let page = |offset, limit| {
+			Dmail::get_inbox(bob(), offset, limit)
+				.into_iter()
+				.map(|m| m.id)
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(page(0, 2), ids[..2]);
+		assert_eq!(page(2, 2), ids[2..]);
+		assert!(page(3, 2).is_empty());
+	});
+}
@@ -195,6 +195,12 @@ pub mod pallet {
 			});
 			T::Shards::member_offline(member, network);
 		}
+		///   Handles the removal of a network.
+		/// # Flow
+		///    1. Removes the [`Unassigned`] members of the network.
+		fn network_removed(network: NetworkId) {
+			Unassigned::<T>::remove(network);
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
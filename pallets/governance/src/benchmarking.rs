@@ -0,0 +1,53 @@
+use super::*;
+use crate::Pallet;
+
+use polkadot_sdk::frame_benchmarking::{benchmarks, BenchmarkError};
+use polkadot_sdk::{frame_support, frame_system, sp_std};
+
+use frame_support::traits::{EnsureOrigin, Get, Hooks};
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_std::{boxed::Box, vec};
+
+fn remark<T: Config>() -> CallOf<T> {
+	frame_system::Call::<T>::remark { remark: vec![] }.into()
+}
+
+fn fill_scheduled<T: Config>(n: u32, eta: BlockNumberFor<T>) {
+	for id in 0..n {
+		Scheduled::<T>::insert(id, ScheduledCall { call: remark::<T>(), eta });
+		Pending::<T>::try_append((id, eta)).unwrap();
+	}
+	NextScheduleId::<T>::put(n);
+}
+
+benchmarks! {
+	schedule {
+		let origin = T::SystemAdmin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		fill_scheduled::<T>(T::MaxScheduled::get() - 1, 1u32.into());
+		let call: CallOf<T> = frame_system::Call::<T>::authorize_upgrade { code_hash: Default::default() }.into();
+	}: _<T::RuntimeOrigin>(origin, Box::new(call))
+	verify {
+		assert_eq!(Pending::<T>::get().len() as u32, T::MaxScheduled::get());
+	}
+
+	veto {
+		let origin = T::VetoOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		fill_scheduled::<T>(T::MaxScheduled::get(), 1u32.into());
+		let id = T::MaxScheduled::get() - 1;
+	}: _<T::RuntimeOrigin>(origin, id)
+	verify {
+		assert!(!Scheduled::<T>::contains_key(id));
+	}
+
+	on_initialize {
+		let n in 0 .. T::MaxScheduled::get();
+		fill_scheduled::<T>(n, 1u32.into());
+	}: {
+		Pallet::<T>::on_initialize(1u32.into());
+	}
+	verify {
+		assert!(Pending::<T>::get().is_empty());
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -7,10 +7,19 @@
 //! Currently only wraps a few important root call to lower the required privilege level
 //! to a custom origin.
 //!
+//! Wrapped and other privileged calls are time-locked: they are scheduled
+//! with a delay depending on the call type, announced by an event and can be
+//! vetoed during the delay. Once the delay passed they are dispatched as root
+//! at the start of a block, unless executions are frozen (e.g. while safe
+//! mode is entered).
+//!
 //! See [`Call`] for a list of wrapped extrinsics.
 
 pub use pallet::*;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
 #[cfg(test)]
 mod mock;
 
@@ -26,65 +35,204 @@ pub mod pallet {
 	use polkadot_sdk::{frame_support, frame_system};
 	use polkadot_sdk::{pallet_staking, sp_runtime};
 
+	use frame_support::dispatch::{GetDispatchInfo, PostDispatchInfo};
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
 
 	// Additional custom imports
-	use frame_system::{RawOrigin, WeightInfo as SystemWeights};
+	use frame_system::RawOrigin;
 
-	use pallet_staking::{ConfigOp, WeightInfo as StakingWeights};
+	use pallet_staking::ConfigOp;
+	use scale_info::prelude::boxed::Box;
+	use sp_runtime::traits::{Dispatchable, Hash, Saturating};
 	use sp_runtime::{Perbill, Percent};
 
 	// Useful coupling shorthands
 	type CurrencyBalanceOf<T> = <T as pallet_staking::Config>::CurrencyBalance;
+	pub type CallOf<T> = <T as Config>::RuntimeCall;
+	pub type ScheduledCallOf<T> = ScheduledCall<CallOf<T>, BlockNumberFor<T>>;
+
+	/// Identifier of a scheduled call.
+	pub type ScheduleId = u32;
+
+	/// Privileged call waiting for its delay to pass.
+	#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct ScheduledCall<Call, BlockNumber> {
+		pub call: Call,
+		/// Block from which the call is dispatched.
+		pub eta: BlockNumber,
+	}
+
+	/// Returns the delay of time-locked calls.
+	pub trait Timelock<Call, BlockNumber> {
+		/// Delay before `call` is dispatched or `None` if the call can't be scheduled.
+		fn delay(call: &Call) -> Option<BlockNumber>;
+	}
+
+	pub trait WeightInfo {
+		fn schedule() -> Weight;
+		fn veto() -> Weight;
+		fn on_initialize(n: u32) -> Weight;
+	}
+
+	impl WeightInfo for () {
+		fn schedule() -> Weight {
+			Weight::default()
+		}
+
+		fn veto() -> Weight {
+			Weight::default()
+		}
+
+		fn on_initialize(_: u32) -> Weight {
+			Weight::default()
+		}
+	}
 
 	#[pallet::pallet]
+	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
 	pub trait Config:
 		polkadot_sdk::frame_system::Config + pallet_balances::Config + pallet_staking::Config
 	{
+		type RuntimeEvent: From<Event<Self>>
+			+ IsType<<Self as polkadot_sdk::frame_system::Config>::RuntimeEvent>;
+		/// Calls which can be scheduled.
+		type RuntimeCall: Parameter
+			+ Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
+			+ GetDispatchInfo
+			+ From<frame_system::Call<Self>>
+			+ From<pallet_staking::Call<Self>>;
+		type WeightInfo: WeightInfo;
 		/// Allowed origin for system calls
 		type SystemAdmin: EnsureOrigin<Self::RuntimeOrigin>;
 		// Allowed origin for staking calls
 		type StakingAdmin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Allowed origin to veto scheduled calls.
+		type VetoOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Delays of the time-locked calls.
+		type Timelock: Timelock<CallOf<Self>, BlockNumberFor<Self>>;
+		/// Returns `true` while the execution of scheduled calls is frozen.
+		type ExecutionFrozen: Get<bool>;
+		/// Maximum number of scheduled calls.
+		#[pallet::constant]
+		type MaxScheduled: Get<u32>;
+		/// Maximum weight of the calls dispatched per block.
+		#[pallet::constant]
+		type MaxCallWeight: Get<Weight>;
+	}
+
+	/// Id of the next scheduled call.
+	#[pallet::storage]
+	pub type NextScheduleId<T: Config> = StorageValue<_, ScheduleId, ValueQuery>;
+
+	/// Scheduled calls by id.
+	#[pallet::storage]
+	pub type Scheduled<T: Config> =
+		StorageMap<_, Twox64Concat, ScheduleId, ScheduledCallOf<T>, OptionQuery>;
+
+	/// Ids and etas of the scheduled calls, in scheduling order.
+	#[pallet::storage]
+	pub type Pending<T: Config> =
+		StorageValue<_, BoundedVec<(ScheduleId, BlockNumberFor<T>), T::MaxScheduled>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A call was scheduled and is dispatched at `eta` unless vetoed.
+		Scheduled { id: ScheduleId, call_hash: T::Hash, eta: BlockNumberFor<T> },
+		/// A scheduled call was vetoed.
+		Vetoed { id: ScheduleId },
+		/// A scheduled call was dispatched.
+		Executed { id: ScheduleId, result: DispatchResult },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The call can't be scheduled.
+		NotTimelocked,
+		/// Too many calls are scheduled.
+		TooManyScheduled,
+		/// The call exceeds the maximum call weight.
+		CallTooHeavy,
+		/// The scheduled call doesn't exist.
+		UnknownSchedule,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			if T::ExecutionFrozen::get() {
+				return T::WeightInfo::on_initialize(0);
+			}
+			let mut pending = Pending::<T>::get();
+			let mut weight = T::WeightInfo::on_initialize(pending.len() as u32);
+			let mut budget = T::MaxCallWeight::get();
+			let mut executed = 0;
+			pending.retain(|(id, eta)| {
+				if *eta > now {
+					return true;
+				}
+				let Some(scheduled) = Scheduled::<T>::get(*id) else {
+					return false;
+				};
+				let info = scheduled.call.get_dispatch_info();
+				if executed > 0 && !info.weight.all_lte(budget) {
+					return true;
+				}
+				budget = budget.saturating_sub(info.weight);
+				executed += 1;
+				Scheduled::<T>::remove(*id);
+				let result = scheduled.call.dispatch(RawOrigin::Root.into());
+				weight.saturating_accrue(frame_support::dispatch::extract_actual_weight(
+					&result, &info,
+				));
+				Self::deposit_event(Event::Executed {
+					id: *id,
+					result: result.map(|_| ()).map_err(|e| e.error),
+				});
+				false
+			});
+			if executed > 0 {
+				Pending::<T>::put(pending);
+			}
+			weight
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		// Wrapper around system pallet calls
 		#[pallet::call_index(0)]
-		#[pallet::weight(<T as frame_system::Config>::SystemWeightInfo::authorize_upgrade())]
+		#[pallet::weight(<T as Config>::WeightInfo::schedule())]
 		pub fn authorize_upgrade(origin: OriginFor<T>, code_hash: T::Hash) -> DispatchResult {
 			T::SystemAdmin::ensure_origin(origin)?;
-			frame_system::Pallet::<T>::authorize_upgrade(RawOrigin::Root.into(), code_hash)
+			Self::schedule_call(frame_system::Call::authorize_upgrade { code_hash }.into())
 		}
 
 		// Wrapper around staking pallet calls
 		#[pallet::call_index(1)]
-		#[pallet::weight(<T as pallet_staking::Config>::WeightInfo::force_new_era())]
+		#[pallet::weight(<T as Config>::WeightInfo::schedule())]
 		pub fn force_new_era(origin: OriginFor<T>) -> DispatchResult {
 			T::StakingAdmin::ensure_origin(origin)?;
-			pallet_staking::Pallet::<T>::force_new_era(RawOrigin::Root.into())
+			Self::schedule_call(pallet_staking::Call::force_new_era {}.into())
 		}
 
 		#[pallet::call_index(2)]
-		#[pallet::weight(<T as pallet_staking::Config>::WeightInfo::set_validator_count())]
+		#[pallet::weight(<T as Config>::WeightInfo::schedule())]
 		pub fn set_validator_count(
 			origin: OriginFor<T>,
 			#[pallet::compact] new: u32,
 		) -> DispatchResult {
 			T::StakingAdmin::ensure_origin(origin)?;
-			pallet_staking::Pallet::<T>::set_validator_count(RawOrigin::Root.into(), new)
+			Self::schedule_call(pallet_staking::Call::set_validator_count { new }.into())
 		}
 
 		#[allow(clippy::too_many_arguments)]
 		#[pallet::call_index(3)]
-		#[pallet::weight(
-			<T as pallet_staking::Config>::WeightInfo::set_staking_configs_all_set()
-				.max(<T as pallet_staking::Config>::WeightInfo::set_staking_configs_all_remove())
-		)]
+		#[pallet::weight(<T as Config>::WeightInfo::schedule())]
 		pub fn set_staking_configs(
 			origin: OriginFor<T>,
 			min_nominator_bond: ConfigOp<CurrencyBalanceOf<T>>,
@@ -96,16 +244,57 @@ pub mod pallet {
 			max_staked_rewards: ConfigOp<Percent>,
 		) -> DispatchResult {
 			T::StakingAdmin::ensure_origin(origin)?;
-			pallet_staking::Pallet::<T>::set_staking_configs(
-				RawOrigin::Root.into(),
-				min_nominator_bond,
-				min_validator_bond,
-				max_nominator_count,
-				max_validator_count,
-				chill_threshold,
-				min_commission,
-				max_staked_rewards,
+			Self::schedule_call(
+				pallet_staking::Call::set_staking_configs {
+					min_nominator_bond,
+					min_validator_bond,
+					max_nominator_count,
+					max_validator_count,
+					chill_threshold,
+					min_commission,
+					max_staked_rewards,
+				}
+				.into(),
 			)
 		}
+
+		/// Schedules any time-locked call, e.g. privileged calls of the
+		/// chronicle pallets.
+		#[pallet::call_index(4)]
+		#[pallet::weight(<T as Config>::WeightInfo::schedule())]
+		pub fn schedule(origin: OriginFor<T>, call: Box<CallOf<T>>) -> DispatchResult {
+			T::SystemAdmin::ensure_origin(origin)?;
+			Self::schedule_call(*call)
+		}
+
+		/// Vetoes a scheduled call before it is dispatched.
+		#[pallet::call_index(5)]
+		#[pallet::weight(<T as Config>::WeightInfo::veto())]
+		pub fn veto(origin: OriginFor<T>, id: ScheduleId) -> DispatchResult {
+			T::VetoOrigin::ensure_origin(origin)?;
+			ensure!(Scheduled::<T>::contains_key(id), Error::<T>::UnknownSchedule);
+			Scheduled::<T>::remove(id);
+			Pending::<T>::mutate(|pending| pending.retain(|(i, _)| *i != id));
+			Self::deposit_event(Event::Vetoed { id });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn schedule_call(call: CallOf<T>) -> DispatchResult {
+			let delay = T::Timelock::delay(&call).ok_or(Error::<T>::NotTimelocked)?;
+			ensure!(
+				call.get_dispatch_info().weight.all_lte(T::MaxCallWeight::get()),
+				Error::<T>::CallTooHeavy
+			);
+			let id = NextScheduleId::<T>::get();
+			let eta = frame_system::Pallet::<T>::block_number().saturating_add(delay);
+			Pending::<T>::try_append((id, eta)).map_err(|_| Error::<T>::TooManyScheduled)?;
+			let call_hash = T::Hashing::hash_of(&call);
+			Scheduled::<T>::insert(id, ScheduledCall { call, eta });
+			NextScheduleId::<T>::put(id.saturating_add(1));
+			Self::deposit_event(Event::Scheduled { id, call_hash, eta });
+			Ok(())
+		}
 	}
 }
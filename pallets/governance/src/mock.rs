@@ -2,7 +2,10 @@ use crate as pallet_governance;
 
 use polkadot_sdk::*;
 
-use frame_support::{construct_runtime, derive_impl, ord_parameter_types, parameter_types};
+use frame_support::{
+	construct_runtime, derive_impl, ord_parameter_types, parameter_types, traits::ConstU32,
+	weights::Weight,
+};
 use frame_system::{EnsureRoot, EnsureSignedBy};
 use sp_runtime::BuildStorage;
 use sp_staking::{EraIndex, SessionIndex};
@@ -26,6 +29,7 @@ ord_parameter_types! {
 	pub const SystemAdmin: AccountId = 1;
 	pub const StakingAdmin: AccountId = 2;
 	pub const Other: AccountId = 3;
+	pub const Vetoer: AccountId = 4;
 }
 
 #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
@@ -107,9 +111,39 @@ impl pallet_staking::Config for Test {
 	type DisablingStrategy = pallet_staking::UpToLimitDisablingStrategy;
 }
 
+pub const UPGRADE_DELAY: u64 = 10;
+pub const STAKING_DELAY: u64 = 5;
+
+pub struct MockTimelock;
+impl pallet_governance::Timelock<RuntimeCall, u64> for MockTimelock {
+	fn delay(call: &RuntimeCall) -> Option<u64> {
+		match call {
+			RuntimeCall::System(frame_system::Call::authorize_upgrade { .. }) => {
+				Some(UPGRADE_DELAY)
+			},
+			RuntimeCall::System(frame_system::Call::remark { .. }) => Some(0),
+			RuntimeCall::Staking(_) => Some(STAKING_DELAY),
+			_ => None,
+		}
+	}
+}
+
+parameter_types! {
+	pub static Frozen: bool = false;
+	pub MaxCallWeight: Weight = Weight::from_parts(u64::MAX / 2, u64::MAX / 2);
+}
+
 impl pallet_governance::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type WeightInfo = ();
 	type SystemAdmin = EnsureSignedBy<SystemAdmin, AccountId>;
 	type StakingAdmin = EnsureSignedBy<StakingAdmin, AccountId>;
+	type VetoOrigin = EnsureSignedBy<Vetoer, AccountId>;
+	type Timelock = MockTimelock;
+	type ExecutionFrozen = Frozen;
+	type MaxScheduled = ConstU32<3>;
+	type MaxCallWeight = MaxCallWeight;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -1,31 +1,53 @@
 use crate::mock::*;
+use crate::{Error, Event, NextScheduleId, Pending, ScheduleId, Scheduled};
 
 use polkadot_sdk::*;
 
 use frame_support::assert_noop;
 use frame_support::assert_ok;
+use frame_support::traits::Hooks;
 use pallet_staking::ConfigOp;
 use sp_runtime::traits::BadOrigin;
 use sp_runtime::{Perbill, Percent};
 
 use time_primitives::H256;
 
+fn run_to_block(n: u64) {
+	for block in System::block_number() + 1..=n {
+		System::set_block_number(block);
+		Governance::on_initialize(block);
+	}
+}
+
+fn last_schedule_id() -> ScheduleId {
+	NextScheduleId::<Test>::get() - 1
+}
+
 #[test]
 fn success_system_admin() {
 	new_test_ext().execute_with(|| {
 		// Go past genesis block so events get deposited
 		System::set_block_number(1);
 
-		// Authorize upgrade and check result
+		// Schedule upgrade authorization
 		let hash = H256::random();
 		assert_ok!(Governance::authorize_upgrade(RuntimeOrigin::signed(SystemAdmin::get()), hash));
-		System::assert_last_event(
+		let id = last_schedule_id();
+		assert_eq!(Scheduled::<Test>::get(id).unwrap().eta, 1 + UPGRADE_DELAY);
+
+		// Check result once the delay passed
+		run_to_block(UPGRADE_DELAY);
+		assert!(Scheduled::<Test>::contains_key(id));
+		run_to_block(1 + UPGRADE_DELAY);
+		System::assert_has_event(
 			frame_system::Event::UpgradeAuthorized {
 				code_hash: hash,
 				check_version: true,
 			}
 			.into(),
 		);
+		System::assert_last_event(Event::<Test>::Executed { id, result: Ok(()) }.into());
+		assert!(Pending::<Test>::get().is_empty());
 	});
 }
 
@@ -34,11 +56,13 @@ fn success_staking_admin() {
 	new_test_ext().execute_with(|| {
 		// Set and check validator count
 		assert_ok!(Governance::set_validator_count(RuntimeOrigin::signed(StakingAdmin::get()), 42));
+		run_to_block(STAKING_DELAY);
 		assert_eq!(Staking::validator_count(), 42);
 	});
 	new_test_ext().execute_with(|| {
 		// Set and check force new
 		assert_ok!(Governance::force_new_era(RuntimeOrigin::signed(StakingAdmin::get())));
+		run_to_block(STAKING_DELAY);
 		assert_eq!(Staking::force_era(), pallet_staking::Forcing::ForceNew);
 	});
 	new_test_ext().execute_with(|| {
@@ -52,7 +76,68 @@ fn success_staking_admin() {
 			ConfigOp::Set(Perbill::one()),
 			ConfigOp::Set(Percent::one()),
 		));
+		run_to_block(STAKING_DELAY);
 		// FIXME: Check state change
+		System::assert_has_event(Event::<Test>::Executed { id: 0, result: Ok(()) }.into());
+	});
+}
+
+#[test]
+fn veto_cancels_scheduled_call() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Governance::set_validator_count(RuntimeOrigin::signed(StakingAdmin::get()), 42));
+		let id = last_schedule_id();
+		assert_noop!(Governance::veto(RuntimeOrigin::signed(Other::get()), id), BadOrigin);
+		assert_ok!(Governance::veto(RuntimeOrigin::signed(Vetoer::get()), id));
+		System::assert_last_event(Event::<Test>::Vetoed { id }.into());
+		assert_noop!(
+			Governance::veto(RuntimeOrigin::signed(Vetoer::get()), id),
+			Error::<Test>::UnknownSchedule
+		);
+		run_to_block(1 + STAKING_DELAY);
+		assert_eq!(Staking::validator_count(), 0);
+		assert!(Pending::<Test>::get().is_empty());
+	});
+}
+
+#[test]
+fn frozen_executions_are_deferred() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Governance::set_validator_count(RuntimeOrigin::signed(StakingAdmin::get()), 42));
+		Frozen::set(true);
+		run_to_block(1 + STAKING_DELAY);
+		assert_eq!(Staking::validator_count(), 0);
+		Frozen::set(false);
+		run_to_block(2 + STAKING_DELAY);
+		assert_eq!(Staking::validator_count(), 42);
+	});
+}
+
+#[test]
+fn schedule_checks_call() {
+	new_test_ext().execute_with(|| {
+		let call: RuntimeCall = frame_system::Call::remark_with_event { remark: vec![] }.into();
+		assert_noop!(
+			Governance::schedule(RuntimeOrigin::signed(SystemAdmin::get()), Box::new(call)),
+			Error::<Test>::NotTimelocked
+		);
+		let call: RuntimeCall = frame_system::Call::remark { remark: vec![] }.into();
+		assert_noop!(
+			Governance::schedule(RuntimeOrigin::signed(Other::get()), Box::new(call.clone())),
+			BadOrigin
+		);
+		for _ in 0..3 {
+			assert_ok!(Governance::schedule(
+				RuntimeOrigin::signed(SystemAdmin::get()),
+				Box::new(call.clone())
+			));
+		}
+		assert_noop!(
+			Governance::schedule(RuntimeOrigin::signed(SystemAdmin::get()), Box::new(call)),
+			Error::<Test>::TooManyScheduled
+		);
 	});
 }
 
@@ -6,7 +6,7 @@ use polkadot_sdk::*;
 use frame_benchmarking::benchmarks;
 use frame_support::traits::{Currency, Get};
 use frame_system::RawOrigin;
-use time_primitives::{traits::IdentifyAccount, AccountId, NetworkId, PublicKey};
+use time_primitives::{traits::IdentifyAccount, AccountId, MembersInterface, NetworkId, PublicKey};
 
 pub const ALICE: [u8; 32] = [1u8; 32];
 pub const ETHEREUM: NetworkId = 1;
@@ -78,5 +78,26 @@ benchmarks! {
 		}
 	}
 
+	network_removed {
+		let n in 1..100;
+		for i in 0..n {
+			let raw = [i as u8; 32];
+			let caller: AccountId = raw.into();
+			pallet_balances::Pallet::<T>::resolve_creating(
+				&caller,
+				pallet_balances::Pallet::<T>::issue(<T as Config>::MinStake::get() * 100),
+			);
+			Pallet::<T>::register_member(RawOrigin::Signed(caller.clone()).into(), ETHEREUM, pk_from_account(raw), caller.clone().into(), <T as Config>::MinStake::get())?;
+			Pallet::<T>::send_heartbeat(RawOrigin::Signed(caller.clone()).into())?;
+		}
+	}: {
+		<Pallet<T> as MembersInterface>::network_removed(ETHEREUM, n);
+	} verify {
+		for i in 0..n {
+			let caller: AccountId = [i as u8; 32].into();
+			assert!(MemberNetwork::<T>::get(&caller).is_none());
+		}
+	}
+
 	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -50,6 +50,7 @@ pub mod pallet {
 		fn send_heartbeat() -> Weight;
 		fn unregister_member() -> Weight;
 		fn timeout_heartbeats(n: u32) -> Weight;
+		fn network_removed(n: u32) -> Weight;
 	}
 
 	impl WeightInfo for () {
@@ -65,6 +66,9 @@ pub mod pallet {
 		fn timeout_heartbeats(_: u32) -> Weight {
 			Weight::default()
 		}
+		fn network_removed(_: u32) -> Weight {
+			Weight::default()
+		}
 	}
 
 	#[pallet::pallet]
@@ -97,6 +101,12 @@ pub mod pallet {
 	pub type MemberNetwork<T: Config> =
 		StorageMap<_, Blake2_128Concat, AccountId, NetworkId, OptionQuery>;
 
+	/// Position in [`MemberNetwork`] of the unregistration of the members of a
+	/// removed network.
+	#[pallet::storage]
+	pub type RemovedNetworkCursor<T: Config> =
+		StorageMap<_, Blake2_128Concat, NetworkId, Vec<u8>, OptionQuery>;
+
 	/// Get PeerId for member
 	#[pallet::storage]
 	pub type MemberPeerId<T: Config> =
@@ -467,5 +477,33 @@ pub mod pallet {
 			MemberPeerId::<T>::remove(member);
 			MemberPublicKey::<T>::remove(member);
 		}
+
+		/// Unregisters and unstakes the members of a removed network.
+		///
+		/// # Flow
+		///   1. Visit at most `limit` entries of [`MemberNetwork`], starting after the
+		///      [`RemovedNetworkCursor`] of the network.
+		///   2. Unregister and unstake the visited members of the network.
+		///   3. Store the cursor if there are entries left to visit.
+		fn network_removed(network: NetworkId, limit: u32) -> (Weight, bool) {
+			let mut iter = match RemovedNetworkCursor::<T>::take(network) {
+				Some(cursor) => MemberNetwork::<T>::iter_from(cursor),
+				None => MemberNetwork::<T>::iter(),
+			};
+			let visited = iter.by_ref().take(limit as usize).collect::<Vec<_>>();
+			let done = visited.len() < limit as usize;
+			if !done {
+				RemovedNetworkCursor::<T>::insert(network, iter.last_raw_key().to_vec());
+			}
+			for (member, _) in visited.iter().filter(|(_, n)| *n == network) {
+				MemberRegistered::<T>::remove(member);
+				MemberOnline::<T>::remove(member);
+				Heartbeat::<T>::remove(member);
+				TimedOut::<T>::mutate(|members| members.retain(|m| m != member));
+				Self::unstake_member(member);
+				Self::deposit_event(Event::UnRegisteredMember(member.clone(), network));
+			}
+			(<T as Config>::WeightInfo::network_removed(visited.len() as u32), done)
+		}
 	}
 }
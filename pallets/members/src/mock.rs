@@ -7,7 +7,7 @@ use frame_support::traits::OnInitialize;
 use sp_core::{ConstU128, ConstU32, ConstU64};
 use sp_runtime::{
 	traits::{IdentifyAccount, IdentityLookup, Verify},
-	BuildStorage, DispatchError, MultiSignature,
+	BuildStorage, DispatchError, MultiSignature, Weight,
 };
 
 use time_primitives::{
@@ -26,6 +26,7 @@ impl ElectionsInterface for MockElections {
 	fn member_online(_: &AccountId, _: NetworkId) {}
 	fn member_offline(_: &AccountId, _: NetworkId) {}
 	fn shard_offline(_network: NetworkId, _members: Vec<AccountId>) {}
+	fn network_removed(_network: NetworkId) {}
 }
 
 pub fn pubkey_from_bytes(bytes: [u8; 32]) -> PublicKey {
@@ -62,6 +63,9 @@ impl ShardsInterface for MockShards {
 	fn tss_public_key(_shard_id: ShardId) -> Option<TssPublicKey> {
 		None
 	}
+	fn network_removed(_network: NetworkId, _limit: u32) -> (Weight, bool) {
+		(Weight::zero(), true)
+	}
 }
 
 frame_support::construct_runtime!(
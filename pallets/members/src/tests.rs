@@ -1,5 +1,8 @@
 use crate::mock::*;
-use crate::{Error, Event, Heartbeat, MemberNetwork, MemberOnline, MemberPeerId, MemberStake};
+use crate::{
+	Error, Event, Heartbeat, MemberNetwork, MemberOnline, MemberPeerId, MemberStake,
+	RemovedNetworkCursor,
+};
 
 use polkadot_sdk::{frame_support, frame_system, sp_runtime};
 
@@ -10,6 +13,7 @@ use sp_runtime::{DispatchError, DispatchResult, ModuleError};
 use time_primitives::{AccountId, MembersInterface, NetworkId};
 
 const A: [u8; 32] = [1u8; 32];
+const B: [u8; 32] = [2u8; 32];
 const C: [u8; 32] = [3u8; 32];
 const ETHEREUM: NetworkId = 0;
 
@@ -162,3 +166,28 @@ fn unregister_member_works() {
 		assert!(Heartbeat::<Test>::get(&a).is_none());
 	});
 }
+
+#[test]
+fn network_removed_unregisters_members_in_steps() {
+	new_test_ext().execute_with(|| {
+		let a: AccountId = A.into();
+		let b: AccountId = B.into();
+		assert_ok!(register_member(a.clone(), A, 5));
+		assert_ok!(Members::register_member(
+			RawOrigin::Signed(b.clone()).into(),
+			ETHEREUM + 1,
+			pubkey_from_bytes(B),
+			B,
+			5,
+		));
+		// visits one of the two members per step
+		assert!(!Members::network_removed(ETHEREUM, 1).1);
+		assert!(!Members::network_removed(ETHEREUM, 1).1);
+		assert!(Members::network_removed(ETHEREUM, 1).1);
+		assert_eq!(MemberNetwork::<Test>::get(&a), None);
+		assert_eq!(Balances::reserved_balance(&a), 0);
+		assert_eq!(MemberNetwork::<Test>::get(&b), Some(ETHEREUM + 1));
+		assert!(Members::is_member_registered(&b));
+		assert!(RemovedNetworkCursor::<Test>::get(ETHEREUM).is_none());
+	});
+}
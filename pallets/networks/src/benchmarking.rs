@@ -8,7 +8,8 @@ use scale_codec::Encode;
 use scale_info::prelude::string::String;
 use sp_runtime::BoundedVec;
 use time_primitives::{
-	ChainName, ChainNetwork, Network, NetworkConfig, NetworkId, CHAIN_NAME_LEN, CHAIN_NET_LEN,
+	ChainName, ChainNetwork, Network, NetworkConfig, NetworkId, Route, CHAIN_NAME_LEN,
+	CHAIN_NET_LEN,
 };
 
 const NETWORK: NetworkId = 42;
@@ -35,6 +36,16 @@ fn mock_network(chain_name: String, chain_network: String) -> Network {
 	}
 }
 
+fn mock_route() -> Route {
+	Route {
+		network_id: NETWORK,
+		gateway: [0; 32],
+		relative_gas_price: (1, 1),
+		gas_limit: 100_000,
+		base_fee: 0,
+	}
+}
+
 benchmarks! {
 	register_network {
 		let a in 1..CHAIN_NAME_LEN;
@@ -57,8 +68,88 @@ benchmarks! {
 
 	remove_network {
 		Pallet::<T>::register_network(RawOrigin::Root.into(), mock_network("Ethereum".into(), "Mainnet".into())).unwrap();
+		Pallet::<T>::drain_network(RawOrigin::Root.into(), NETWORK).unwrap();
+		// the queued ops were refunded
+		NetworkCleanup::<T>::remove(NETWORK);
+	}: _(RawOrigin::Root, NETWORK)
+	verify {}
+
+	pause_network {
+		Pallet::<T>::register_network(RawOrigin::Root.into(), mock_network("Ethereum".into(), "Mainnet".into())).unwrap();
+	}: _(RawOrigin::Root, NETWORK)
+	verify {}
+
+	resume_network {
+		Pallet::<T>::register_network(RawOrigin::Root.into(), mock_network("Ethereum".into(), "Mainnet".into())).unwrap();
+		Pallet::<T>::pause_network(RawOrigin::Root.into(), NETWORK).unwrap();
 	}: _(RawOrigin::Root, NETWORK)
 	verify {}
 
+	drain_network {
+		Pallet::<T>::register_network(RawOrigin::Root.into(), mock_network("Ethereum".into(), "Mainnet".into())).unwrap();
+	}: _(RawOrigin::Root, NETWORK)
+	verify {}
+
+	migrate_gateway {
+		Pallet::<T>::register_network(RawOrigin::Root.into(), mock_network("Ethereum".into(), "Mainnet".into())).unwrap();
+	}: _(RawOrigin::Root, NETWORK, [1; 32], 120)
+	verify {}
+
+	complete_gateway_migrations {
+		let n in 1..T::MaxGatewayMigrationsPerBlock::get();
+		for i in 0..n {
+			let mut network = mock_network("Ethereum".into(), "Mainnet".into());
+			network.id = NETWORK + i as NetworkId;
+			network.chain_network = ChainNetwork(BoundedVec::truncate_from(i.encode()));
+			Pallet::<T>::register_network(RawOrigin::Root.into(), network).unwrap();
+			Pallet::<T>::migrate_gateway(RawOrigin::Root.into(), NETWORK + i as NetworkId, [1; 32], 120).unwrap();
+		}
+	}: {
+		Pallet::<T>::complete_gateway_migrations();
+	}
+	verify {}
+
+	migrate_routes {
+		let n in 1..T::MaxNetworks::get();
+		Pallet::<T>::register_network(RawOrigin::Root.into(), mock_network("Ethereum".into(), "Mainnet".into())).unwrap();
+		for i in 1..n {
+			let mut network = mock_network("Ethereum".into(), "Mainnet".into());
+			network.id = NETWORK + i as NetworkId;
+			network.chain_network = ChainNetwork(BoundedVec::truncate_from(i.encode()));
+			Pallet::<T>::register_network(RawOrigin::Root.into(), network).unwrap();
+			let route = Route { network_id: NETWORK + i as NetworkId, ..mock_route() };
+			Pallet::<T>::set_route(RawOrigin::Root.into(), NETWORK, route).unwrap();
+			Pallet::<T>::set_route(RawOrigin::Root.into(), NETWORK + i as NetworkId, mock_route()).unwrap();
+		}
+	}: {
+		Pallet::<T>::migrate_routes(NETWORK, [1; 32]);
+	}
+	verify {}
+
+	set_route {
+		Pallet::<T>::register_network(RawOrigin::Root.into(), mock_network("Ethereum".into(), "Mainnet".into())).unwrap();
+	}: _(RawOrigin::Root, NETWORK, mock_route())
+	verify {}
+
+	cleanup_network {
+		let n in 1..T::MaxCleanupPerBlock::get();
+		Pallet::<T>::register_network(RawOrigin::Root.into(), mock_network("Ethereum".into(), "Mainnet".into())).unwrap();
+		for i in 1..=n {
+			let mut network = mock_network("Ethereum".into(), "Mainnet".into());
+			network.id = NETWORK + i as NetworkId;
+			network.chain_network = ChainNetwork(BoundedVec::truncate_from(i.encode()));
+			Pallet::<T>::register_network(RawOrigin::Root.into(), network).unwrap();
+			let route = Route { network_id: NETWORK + i as NetworkId, ..mock_route() };
+			Pallet::<T>::set_route(RawOrigin::Root.into(), NETWORK, route).unwrap();
+		}
+		Pallet::<T>::drain_network(RawOrigin::Root.into(), NETWORK).unwrap();
+		NetworkCleanup::<T>::remove(NETWORK);
+		Pallet::<T>::remove_network(RawOrigin::Root.into(), NETWORK).unwrap();
+		NetworkCleanup::<T>::insert(NETWORK, CleanupStep::Routes);
+	}: {
+		Pallet::<T>::cleanup_networks();
+	}
+	verify {}
+
 	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -17,6 +17,22 @@
 //! - Initializes the pallet with predefined network configurations during
 //!   blockchain genesis, ensuring seamless operation from the start.
 //!
+//! - Tracks the lifecycle of each network. An active network can be paused,
+//!   which stops reading gateway events and batching while keeping its state,
+//!   and resumed. Before removal a network is drained, finishing in-flight
+//!   batches and refunding queued messages. Removing a drained network cleans
+//!   up its tasks, shards, unassigned members and registered members. Refunds
+//!   and cleanup run in bounded steps in `on_initialize`.
+//!
+//! - Migrates a network to a new gateway deployment. The old gateway keeps
+//!   being read until its in-flight batches are finished, after which events
+//!   are read from the new gateway starting at its deployment block and the
+//!   online shards are registered on it.
+//!
+//! - Holds the canonical route table between networks. Route changes are sent
+//!   to the gateway of the source network as batched gateway ops and are used
+//!   to estimate the cost of messages.
+//!
 //!
 #![doc = simple_mermaid::mermaid!("../docs/network_flow.mmd")]
 
@@ -37,14 +53,23 @@ pub mod pallet {
 	use frame_system::pallet_prelude::*;
 	use scale_info::prelude::vec::Vec;
 	use time_primitives::{
-		Address, ChainName, ChainNetwork, Network, NetworkConfig, NetworkId, NetworksInterface,
-		TasksInterface,
+		Address, ChainName, ChainNetwork, ElectionsInterface, MembersInterface, Network,
+		NetworkConfig, NetworkId, NetworkStatus as Status, NetworksInterface, Route,
+		ShardsInterface, TasksInterface,
 	};
 
 	pub trait WeightInfo {
 		fn register_network(name: u32, network: u32) -> Weight;
 		fn set_network_config() -> Weight;
 		fn remove_network() -> Weight;
+		fn pause_network() -> Weight;
+		fn resume_network() -> Weight;
+		fn drain_network() -> Weight;
+		fn migrate_gateway() -> Weight;
+		fn complete_gateway_migrations(n: u32) -> Weight;
+		fn migrate_routes(n: u32) -> Weight;
+		fn set_route() -> Weight;
+		fn cleanup_network(n: u32) -> Weight;
 	}
 
 	impl WeightInfo for () {
@@ -59,6 +84,38 @@ pub mod pallet {
 		fn remove_network() -> Weight {
 			Weight::default()
 		}
+
+		fn pause_network() -> Weight {
+			Weight::default()
+		}
+
+		fn resume_network() -> Weight {
+			Weight::default()
+		}
+
+		fn drain_network() -> Weight {
+			Weight::default()
+		}
+
+		fn migrate_gateway() -> Weight {
+			Weight::default()
+		}
+
+		fn complete_gateway_migrations(_: u32) -> Weight {
+			Weight::default()
+		}
+
+		fn migrate_routes(_: u32) -> Weight {
+			Weight::default()
+		}
+
+		fn set_route() -> Weight {
+			Weight::default()
+		}
+
+		fn cleanup_network(_: u32) -> Weight {
+			Weight::default()
+		}
 	}
 
 	#[pallet::pallet]
@@ -70,8 +127,21 @@ pub mod pallet {
 		type RuntimeEvent: From<Event<Self>>
 			+ IsType<<Self as polkadot_sdk::frame_system::Config>::RuntimeEvent>;
 		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Origin allowed to update routes, like the admin or a price oracle.
+		type RouteOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 		type WeightInfo: WeightInfo;
 		type Tasks: TasksInterface;
+		type Shards: ShardsInterface;
+		type Elections: ElectionsInterface;
+		type Members: MembersInterface;
+		/// Maximum number of items of draining and removed networks cleaned up per
+		/// block in `on_initialize`.
+		type MaxCleanupPerBlock: Get<u32>;
+		/// Maximum number of gateway migrations checked per block in `on_initialize`.
+		type MaxGatewayMigrationsPerBlock: Get<u32>;
+		/// Maximum number of registered networks, bounds the routes updated when the
+		/// gateway of a network is migrated.
+		type MaxNetworks: Get<u32>;
 	}
 
 	#[pallet::event]
@@ -81,6 +151,22 @@ pub mod pallet {
 		NetworkRegistered(NetworkId, Address, u64),
 		/// Network config changed.
 		NetworkConfigChanged(NetworkId, NetworkConfig),
+		/// Network paused.
+		NetworkPaused(NetworkId),
+		/// Network resumed.
+		NetworkResumed(NetworkId),
+		/// Network draining.
+		NetworkDraining(NetworkId),
+		/// Network removal started, its state is cleaned up in the next blocks.
+		NetworkRemovalStarted(NetworkId),
+		/// Network removed.
+		NetworkRemoved(NetworkId),
+		/// Gateway migration started, waiting for the old gateway's batches to finish.
+		GatewayMigrationStarted(NetworkId, Address, u64),
+		/// Network gateway migrated.
+		GatewayMigrated(NetworkId, Address, u64),
+		/// Route from a network changed.
+		RouteChanged(NetworkId, Route),
 	}
 
 	#[pallet::error]
@@ -93,6 +179,31 @@ pub mod pallet {
 		ShardSizeAboveMax,
 		/// Network threshold is invalid.
 		ThresholdLargerThanSize,
+		/// Network status doesn't allow the transition.
+		InvalidNetworkStatus,
+		/// Route doesn't point to the destination gateway or has an invalid relative gas price.
+		InvalidRoute,
+		/// Network is already using the gateway.
+		GatewayUnchanged,
+		/// Network still has batches in flight or queued ops to refund.
+		NetworkNotDrained,
+		/// The maximum number of networks is registered.
+		TooManyNetworks,
+	}
+
+	/// Step of the cleanup of a draining or removed network.
+	#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+	pub enum CleanupStep {
+		/// Refund the queued ops of a draining network.
+		RefundOps,
+		/// Take the shards of a removed network offline.
+		Shards,
+		/// Remove the tasks, queues and batches of a removed network.
+		Tasks,
+		/// Unregister the members of a removed network.
+		Members,
+		/// Remove the routes from and to a removed network.
+		Routes,
 	}
 
 	/// Workaround for subxt not supporting iterating over the decoded keys.
@@ -142,6 +253,35 @@ pub mod pallet {
 	pub type NetworkShardThreshold<T: Config> =
 		StorageMap<_, Blake2_128Concat, NetworkId, u16, OptionQuery>;
 
+	/// Map storage for the lifecycle status of networks.
+	#[pallet::storage]
+	pub type NetworkStatus<T: Config> =
+		StorageMap<_, Blake2_128Concat, NetworkId, Status, ValueQuery>;
+
+	/// Map storage for the gateway and deployment block networks are migrating to.
+	#[pallet::storage]
+	pub type NetworkGatewayMigration<T: Config> =
+		StorageMap<_, Blake2_128Concat, NetworkId, (Address, u64), OptionQuery>;
+
+	/// Position in [`NetworkGatewayMigration`] of the next gateway migrations to check.
+	#[pallet::storage]
+	pub type GatewayMigrationCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+	/// Double map storage for the routes from a source to a destination network.
+	#[pallet::storage]
+	pub type Routes<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, NetworkId, Twox64Concat, NetworkId, Route, OptionQuery>;
+
+	/// Map storage for the next cleanup step of draining and removed networks.
+	#[pallet::storage]
+	pub type NetworkCleanup<T: Config> =
+		StorageMap<_, Blake2_128Concat, NetworkId, CleanupStep, OptionQuery>;
+
+	/// Position in [`Networks`] of the removal of the routes to a removed network.
+	#[pallet::storage]
+	pub type RemovedNetworkCursor<T: Config> =
+		StorageMap<_, Blake2_128Concat, NetworkId, Vec<u8>, OptionQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T> {
 		pub networks: Vec<Network>,
@@ -174,10 +314,15 @@ pub mod pallet {
 		///  # Flow
 		///    1. Iterate through existing networks to check if the given `ChainName` and `ChainNetwork` already exist.
 		///    2. If the network exists, return [`Error::<T>::NetworkExists`].
-		///    3. Insert the new network into the [`Networks`] storage map with the current `NetworkId`.
-		///    4. Return the new `NetworkId`.
+		///    3. If `MaxNetworks` networks exist, return [`Error::<T>::TooManyNetworks`].
+		///    4. Insert the new network into the [`Networks`] storage map with the current `NetworkId`.
+		///    5. Return the new `NetworkId`.
 		fn insert_network(network: &Network) -> Result<(), Error<T>> {
 			ensure!(Networks::<T>::get(network.id).is_none(), Error::<T>::NetworkExists);
+			ensure!(
+				(Networks::<T>::iter_keys().count() as u32) < T::MaxNetworks::get(),
+				Error::<T>::TooManyNetworks
+			);
 			Networks::<T>::insert(network.id, network.id);
 			NetworkName::<T>::insert(
 				network.id,
@@ -185,6 +330,7 @@ pub mod pallet {
 			);
 			NetworkGatewayAddress::<T>::insert(network.id, network.gateway);
 			NetworkGatewayBlock::<T>::insert(network.id, network.gateway_block);
+			NetworkStatus::<T>::insert(network.id, Status::Active);
 			T::Tasks::gateway_registered(network.id, network.gateway_block);
 			Self::insert_network_config(network.id, network.config.clone())?;
 			Self::deposit_event(Event::NetworkRegistered(
@@ -217,6 +363,198 @@ pub mod pallet {
 			Self::deposit_event(Event::NetworkConfigChanged(network, config));
 			Ok(())
 		}
+
+		/// Moves a network from one of the `from` statuses to `to` and notifies the tasks pallet.
+		fn transition(network: NetworkId, from: &[Status], to: Status) -> Result<(), Error<T>> {
+			ensure!(Networks::<T>::get(network).is_some(), Error::<T>::NetworkNotFound);
+			ensure!(
+				from.contains(&NetworkStatus::<T>::get(network)),
+				Error::<T>::InvalidNetworkStatus
+			);
+			NetworkStatus::<T>::insert(network, to);
+			T::Tasks::network_status_changed(network, to);
+			Ok(())
+		}
+
+		/// Validates and stores a route and sends it to the gateway of `network`.
+		fn insert_route(network: NetworkId, route: Route) -> Result<(), Error<T>> {
+			ensure!(Networks::<T>::get(network).is_some(), Error::<T>::NetworkNotFound);
+			ensure!(
+				NetworkStatus::<T>::get(network) != Status::Removed
+					&& NetworkStatus::<T>::get(route.network_id) != Status::Removed,
+				Error::<T>::InvalidNetworkStatus
+			);
+			ensure!(
+				NetworkGatewayAddress::<T>::get(route.network_id) == Some(route.gateway),
+				Error::<T>::InvalidRoute
+			);
+			ensure!(route.relative_gas_price.1 != 0, Error::<T>::InvalidRoute);
+			Routes::<T>::insert(network, route.network_id, route.clone());
+			T::Tasks::route_changed(network, route.clone());
+			Self::deposit_event(Event::RouteChanged(network, route));
+			Ok(())
+		}
+
+		/// Switches migrating networks to their new gateway once the old gateway's
+		/// batches are finished.
+		///
+		/// # Flow
+		///   1. Visit at most `MaxGatewayMigrationsPerBlock` entries of the
+		///      [`NetworkGatewayMigration`] storage, starting after the
+		///      [`GatewayMigrationCursor`].
+		///   2. Skip networks which still have batches in flight.
+		///   3. Update the gateway address and block and make the network active again.
+		///   4. Notify the tasks pallet to read from the new gateway and register the shards.
+		///   5. Emit the [`Event::GatewayMigrated`] event.
+		pub(crate) fn complete_gateway_migrations() -> Weight {
+			let limit = T::MaxGatewayMigrationsPerBlock::get() as usize;
+			let mut iter = match GatewayMigrationCursor::<T>::take() {
+				Some(cursor) => NetworkGatewayMigration::<T>::iter_from(cursor),
+				None => NetworkGatewayMigration::<T>::iter(),
+			};
+			let migrations = iter.by_ref().take(limit).collect::<Vec<_>>();
+			if migrations.len() == limit {
+				GatewayMigrationCursor::<T>::put(iter.last_raw_key().to_vec());
+			}
+			let mut weight = T::WeightInfo::complete_gateway_migrations(migrations.len() as u32);
+			for (network, (gateway, block)) in migrations {
+				if T::Tasks::has_pending_batches(network) {
+					continue;
+				}
+				NetworkGatewayMigration::<T>::remove(network);
+				NetworkGatewayAddress::<T>::insert(network, gateway);
+				NetworkGatewayBlock::<T>::insert(network, block);
+				NetworkStatus::<T>::insert(network, Status::Active);
+				T::Tasks::gateway_migrated(network, block);
+				let num_networks = Self::migrate_routes(network, gateway);
+				weight = weight.saturating_add(T::WeightInfo::migrate_routes(num_networks));
+				Self::deposit_event(Event::GatewayMigrated(network, gateway, block));
+			}
+			weight
+		}
+
+		/// Points the routes to a migrated network at its new gateway and sends all
+		/// routes from the network to its new gateway. Returns the number of visited
+		/// networks, which is at most `MaxNetworks`.
+		pub(crate) fn migrate_routes(network: NetworkId, gateway: Address) -> u32 {
+			let mut num_networks = 0;
+			for src in Networks::<T>::iter_keys() {
+				num_networks += 1;
+				Routes::<T>::mutate(src, network, |route| {
+					let Some(route) = route else {
+						return;
+					};
+					route.gateway = gateway;
+					if src != network {
+						T::Tasks::route_changed(src, route.clone());
+					}
+				});
+			}
+			for route in Routes::<T>::iter_prefix_values(network) {
+				T::Tasks::route_changed(network, route);
+			}
+			num_networks
+		}
+
+		/// Runs the next step of the cleanup of a draining or removed network.
+		///
+		/// # Flow
+		///   1. Take the first network from the [`NetworkCleanup`] storage.
+		///   2. Run its [`CleanupStep`], cleaning up at most `MaxCleanupPerBlock` items.
+		///   3. Move on to the next step once all items of the step are cleaned up.
+		///   4. Remove the network config after the last step and emit [`Event::NetworkRemoved`].
+		pub(crate) fn cleanup_networks() -> Weight {
+			let Some((network, step)) = NetworkCleanup::<T>::iter().next() else {
+				return T::DbWeight::get().reads(1);
+			};
+			let limit = T::MaxCleanupPerBlock::get();
+			let mut num_routes = 0;
+			let (weight, done) = match step {
+				CleanupStep::RefundOps => T::Tasks::refund_queued_ops(network, limit),
+				CleanupStep::Shards => T::Shards::network_removed(network, limit),
+				CleanupStep::Tasks => T::Tasks::network_removed(network, limit),
+				CleanupStep::Members => T::Members::network_removed(network, limit),
+				CleanupStep::Routes => {
+					let (removed, done) = Self::remove_routes(network, limit);
+					num_routes = removed;
+					(Weight::zero(), done)
+				},
+			};
+			if done {
+				match step {
+					CleanupStep::RefundOps => NetworkCleanup::<T>::remove(network),
+					CleanupStep::Shards => {
+						// shards going offline return their members to the unassigned
+						// members, so they are removed afterwards
+						T::Elections::network_removed(network);
+						NetworkCleanup::<T>::insert(network, CleanupStep::Tasks);
+					},
+					CleanupStep::Tasks => {
+						NetworkCleanup::<T>::insert(network, CleanupStep::Members)
+					},
+					CleanupStep::Members => {
+						NetworkCleanup::<T>::insert(network, CleanupStep::Routes)
+					},
+					CleanupStep::Routes => {
+						NetworkCleanup::<T>::remove(network);
+						Self::remove_network_config(network);
+					},
+				}
+			}
+			weight.saturating_add(T::WeightInfo::cleanup_network(num_routes))
+		}
+
+		/// Removes at most `limit` routes from a removed network and the routes to it
+		/// from the other networks, starting after the [`RemovedNetworkCursor`] of the
+		/// network. Returns the number of removed routes and whether all routes are
+		/// removed.
+		fn remove_routes(network: NetworkId, limit: u32) -> (u32, bool) {
+			let dests =
+				Routes::<T>::iter_key_prefix(network).take(limit as usize).collect::<Vec<_>>();
+			for dest in &dests {
+				Routes::<T>::remove(network, dest);
+			}
+			let removed = dests.len() as u32;
+			if removed == limit {
+				return (removed, false);
+			}
+			let remaining = (limit - removed) as usize;
+			let mut iter = match RemovedNetworkCursor::<T>::take(network) {
+				Some(cursor) => Networks::<T>::iter_keys_from(cursor),
+				None => Networks::<T>::iter_keys(),
+			};
+			let srcs = iter.by_ref().take(remaining).collect::<Vec<_>>();
+			for src in &srcs {
+				Routes::<T>::remove(src, network);
+			}
+			let done = srcs.len() < remaining;
+			if !done {
+				RemovedNetworkCursor::<T>::insert(network, iter.last_raw_key().to_vec());
+			}
+			(removed.saturating_add(srcs.len() as u32), done)
+		}
+
+		/// Removes the config of a removed network once all of its state is cleaned up.
+		fn remove_network_config(network: NetworkId) {
+			Networks::<T>::remove(network);
+			NetworkName::<T>::remove(network);
+			NetworkGatewayAddress::<T>::remove(network);
+			NetworkGatewayBlock::<T>::remove(network);
+			NetworkBatchSize::<T>::remove(network);
+			NetworkBatchOffset::<T>::remove(network);
+			NetworkBatchGasLimit::<T>::remove(network);
+			NetworkShardTaskLimit::<T>::remove(network);
+			NetworkShardSize::<T>::remove(network);
+			NetworkShardThreshold::<T>::remove(network);
+			Self::deposit_event(Event::NetworkRemoved(network));
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_: BlockNumberFor<T>) -> Weight {
+			Self::complete_gateway_migrations().saturating_add(Self::cleanup_networks())
+		}
 	}
 
 	#[pallet::call]
@@ -230,7 +568,8 @@ pub mod pallet {
 		///    3. Emit the [`Event::NetworkRegistered`] event with the new `NetworkId`.
 		///    4. Return `Ok(())` to indicate success.
 		#[pallet::call_index(0)]
-		#[pallet::weight(T::WeightInfo::register_network(network.chain_name.0.len() as u32, network.chain_network.0.len() as u32))]
+		#[pallet::weight(T::WeightInfo::register_network(network.chain_name.0.len() as u32, network.chain_network.0.len() as u32)
+			.saturating_add(T::DbWeight::get().reads(T::MaxNetworks::get().into())))]
 		pub fn register_network(origin: OriginFor<T>, network: Network) -> DispatchResult {
 			T::AdminOrigin::ensure_origin(origin)?;
 			Self::insert_network(&network)?;
@@ -257,19 +596,114 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Removes a drained network. Its state is cleaned up in bounded steps in
+		/// `on_initialize`.
+		///
+		/// # Flow
+		///   1. Ensure the origin of the transaction is a root user.
+		///   2. Ensure the network has no batches in flight and its queued ops were refunded.
+		///   3. Move the network from [`Status::Draining`] to [`Status::Removed`].
+		///   4. Schedule the cleanup, starting with taking the shards of the network
+		///      offline as they requeue their tasks.
+		///   5. Emit the [`Event::NetworkRemovalStarted`] event.
+		///
+		/// The cleanup removes the tasks, queues and batches, the unassigned and
+		/// registered members and the routes of the network before removing its config
+		/// and emitting [`Event::NetworkRemoved`].
 		#[pallet::call_index(3)]
 		#[pallet::weight(<T as Config>::WeightInfo::remove_network())]
 		pub fn remove_network(origin: OriginFor<T>, network: NetworkId) -> DispatchResult {
 			T::AdminOrigin::ensure_origin(origin)?;
-			Networks::<T>::remove(network);
-			NetworkName::<T>::remove(network);
-			NetworkGatewayAddress::<T>::remove(network);
-			NetworkGatewayBlock::<T>::remove(network);
-			NetworkBatchSize::<T>::remove(network);
-			NetworkBatchGasLimit::<T>::remove(network);
-			NetworkShardTaskLimit::<T>::remove(network);
-			NetworkShardSize::<T>::remove(network);
-			NetworkShardThreshold::<T>::remove(network);
+			ensure!(!T::Tasks::has_pending_batches(network), Error::<T>::NetworkNotDrained);
+			ensure!(
+				NetworkCleanup::<T>::get(network) != Some(CleanupStep::RefundOps),
+				Error::<T>::NetworkNotDrained
+			);
+			Self::transition(network, &[Status::Draining], Status::Removed)?;
+			NetworkCleanup::<T>::insert(network, CleanupStep::Shards);
+			Self::deposit_event(Event::NetworkRemovalStarted(network));
+			Ok(())
+		}
+
+		/// Stops reading gateway events and starting batches for an active network,
+		/// keeping its state.
+		#[pallet::call_index(4)]
+		#[pallet::weight(<T as Config>::WeightInfo::pause_network())]
+		pub fn pause_network(origin: OriginFor<T>, network: NetworkId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Self::transition(network, &[Status::Active], Status::Paused)?;
+			Self::deposit_event(Event::NetworkPaused(network));
+			Ok(())
+		}
+
+		/// Resumes reading gateway events and batching for a paused network.
+		#[pallet::call_index(5)]
+		#[pallet::weight(<T as Config>::WeightInfo::resume_network())]
+		pub fn resume_network(origin: OriginFor<T>, network: NetworkId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Self::transition(network, &[Status::Paused], Status::Active)?;
+			Self::deposit_event(Event::NetworkResumed(network));
+			Ok(())
+		}
+
+		/// Prepares an active or paused network for removal. In-flight batches are
+		/// finished while no new batches are started. Queued messages are refunded in
+		/// bounded steps in `on_initialize`.
+		#[pallet::call_index(6)]
+		#[pallet::weight(<T as Config>::WeightInfo::drain_network())]
+		pub fn drain_network(origin: OriginFor<T>, network: NetworkId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Self::transition(network, &[Status::Active, Status::Paused], Status::Draining)?;
+			NetworkCleanup::<T>::insert(network, CleanupStep::RefundOps);
+			Self::deposit_event(Event::NetworkDraining(network));
+			Ok(())
+		}
+
+		/// Points an active network at a new gateway deployment, keeping its sync
+		/// height and task history.
+		///
+		/// # Flow
+		///   1. Ensure the origin of the transaction is a root user.
+		///   2. Ensure the new gateway differs from the current gateway.
+		///   3. Move the network to [`Status::Migrating`], which stops starting new batches.
+		///   4. Store the new gateway in [`NetworkGatewayMigration`].
+		///   5. Emit the [`Event::GatewayMigrationStarted`] event.
+		///
+		/// The migration is completed in `on_initialize` once the old gateway's
+		/// in-flight batches are finished.
+		#[pallet::call_index(7)]
+		#[pallet::weight(<T as Config>::WeightInfo::migrate_gateway())]
+		pub fn migrate_gateway(
+			origin: OriginFor<T>,
+			network: NetworkId,
+			gateway: Address,
+			gateway_block: u64,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(
+				NetworkGatewayAddress::<T>::get(network) != Some(gateway),
+				Error::<T>::GatewayUnchanged
+			);
+			Self::transition(network, &[Status::Active], Status::Migrating)?;
+			NetworkGatewayMigration::<T>::insert(network, (gateway, gateway_block));
+			Self::deposit_event(Event::GatewayMigrationStarted(network, gateway, gateway_block));
+			Ok(())
+		}
+
+		/// Sets the route from `network` to `route.network_id` and sends it to the
+		/// gateway of `network`.
+		///
+		/// # Flow
+		///   1. Ensure the origin of the transaction is the admin or a price oracle.
+		///   2. Ensure both networks exist and the route points to the destination gateway.
+		///   3. Insert the route into the [`Routes`] storage.
+		///   4. Queue a `GatewayOp::SetRoute` on the source network.
+		///   5. Emit the [`Event::RouteChanged`] event.
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_route())]
+		pub fn set_route(origin: OriginFor<T>, network: NetworkId, route: Route) -> DispatchResult {
+			T::RouteOrigin::ensure_origin(origin)?;
+			Self::insert_route(network, route)?;
 			Ok(())
 		}
 	}
@@ -283,11 +717,31 @@ pub mod pallet {
 		pub fn get_network(network: NetworkId) -> Option<(ChainName, ChainNetwork)> {
 			NetworkName::<T>::get(network)
 		}
+
+		/// Retrieves the routes from a network.
+		pub fn get_routes(network: NetworkId) -> Vec<Route> {
+			Routes::<T>::iter_prefix_values(network).collect()
+		}
+
+		/// Estimates the cost of sending a message from `src` to `dest` in the currency
+		/// of `src`, returns `None` if there is no route.
+		pub fn estimate_message_cost(
+			src: NetworkId,
+			dest: NetworkId,
+			gas_limit: u128,
+			payload_len: u32,
+		) -> Option<u128> {
+			let route = Routes::<T>::get(src, dest)?;
+			Some(route.message_cost(gas_limit, payload_len as usize))
+		}
 	}
 
 	impl<T: Config> NetworksInterface for Pallet<T> {
+		/// Returns the registered networks which aren't being removed.
 		fn get_networks() -> Vec<NetworkId> {
-			NetworkName::<T>::iter().map(|(n, _)| n).collect()
+			NetworkName::<T>::iter_keys()
+				.filter(|network| NetworkStatus::<T>::get(network) != Status::Removed)
+				.collect()
 		}
 
 		fn gateway(network: NetworkId) -> Option<Address> {
@@ -316,5 +770,18 @@ pub mod pallet {
 		fn shard_threshold(network: NetworkId) -> u16 {
 			NetworkShardThreshold::<T>::get(network).unwrap_or(2)
 		}
+
+		fn network_status(network: NetworkId) -> Status {
+			NetworkStatus::<T>::get(network)
+		}
+
+		fn route(src: NetworkId, dest: NetworkId) -> Option<Route> {
+			Routes::<T>::get(src, dest)
+		}
+
+		fn set_route(src: NetworkId, route: Route) -> DispatchResult {
+			Self::insert_route(src, route)?;
+			Ok(())
+		}
 	}
 }
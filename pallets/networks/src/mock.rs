@@ -1,10 +1,33 @@
 use crate::{self as pallet_networks};
+use core::cell::{Cell, RefCell};
 
 use polkadot_sdk::frame_support::derive_impl;
+use polkadot_sdk::sp_core::{sr25519, ConstU32};
 use polkadot_sdk::sp_core::{ConstU128, ConstU64};
-use polkadot_sdk::sp_runtime::{traits::IdentityLookup, BuildStorage};
+use polkadot_sdk::sp_runtime::{
+	traits::IdentityLookup, BuildStorage, DispatchError, DispatchResult, Weight,
+};
 use polkadot_sdk::{frame_support, frame_system, pallet_balances, sp_io};
-use time_primitives::{NetworkId, ShardId, TasksInterface};
+use time_primitives::{
+	AccountId as MemberId, Balance, ElectionsInterface, MembersInterface, NetworkId, NetworkStatus,
+	PeerId, PublicKey, Route, ShardId, ShardsInterface, TasksInterface, TssPublicKey,
+};
+
+thread_local! {
+	pub static PENDING_BATCHES: Cell<bool> = const { Cell::new(false) };
+	pub static ROUTE_CHANGES: RefCell<Vec<(NetworkId, Route)>> = const { RefCell::new(Vec::new()) };
+	pub static CLEANUP_CALLS: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Records a cleanup call, the first call of each step is not done.
+fn cleanup_call(step: &'static str) -> (Weight, bool) {
+	CLEANUP_CALLS.with(|calls| {
+		let mut calls = calls.borrow_mut();
+		let done = calls.contains(&step);
+		calls.push(step);
+		(Weight::zero(), done)
+	})
+}
 
 pub struct MockTasks;
 
@@ -12,6 +35,97 @@ impl TasksInterface for MockTasks {
 	fn shard_online(_shard_id: ShardId, _network: NetworkId) {}
 	fn shard_offline(_shard_id: ShardId, _network: NetworkId) {}
 	fn gateway_registered(_network: NetworkId, _block: u64) {}
+	fn network_status_changed(_network: NetworkId, _status: NetworkStatus) {}
+	fn refund_queued_ops(_network: NetworkId, _limit: u32) -> (Weight, bool) {
+		cleanup_call("refund")
+	}
+	fn network_removed(_network: NetworkId, _limit: u32) -> (Weight, bool) {
+		cleanup_call("tasks")
+	}
+	fn has_pending_batches(_network: NetworkId) -> bool {
+		PENDING_BATCHES.with(|pending| pending.get())
+	}
+	fn gateway_migrated(_network: NetworkId, _block: u64) {}
+	fn route_changed(network: NetworkId, route: Route) {
+		ROUTE_CHANGES.with(|changes| changes.borrow_mut().push((network, route)));
+	}
+}
+
+pub struct MockShards;
+
+impl ShardsInterface for MockShards {
+	fn member_online(_id: &MemberId, _network: NetworkId) {}
+	fn member_offline(_id: &MemberId, _network: NetworkId) {}
+	fn is_shard_online(_shard_id: ShardId) -> bool {
+		false
+	}
+	fn is_shard_member(_account: &MemberId) -> bool {
+		false
+	}
+	fn shard_members(_shard_id: ShardId) -> Vec<MemberId> {
+		vec![]
+	}
+	fn shard_network(_shard_id: ShardId) -> Option<NetworkId> {
+		None
+	}
+	fn create_shard(
+		_network: NetworkId,
+		_members: Vec<MemberId>,
+		_threshold: u16,
+	) -> Result<ShardId, DispatchError> {
+		Ok(0)
+	}
+	fn next_signer(_shard_id: ShardId) -> PublicKey {
+		PublicKey::Sr25519(sr25519::Public::from_raw([0; 32]))
+	}
+	fn tss_public_key(_shard_id: ShardId) -> Option<TssPublicKey> {
+		None
+	}
+	fn network_removed(_network: NetworkId, _limit: u32) -> (Weight, bool) {
+		cleanup_call("shards")
+	}
+}
+
+pub struct MockElections;
+
+impl ElectionsInterface for MockElections {
+	type MaxElectionsPerBlock = ConstU32<10>;
+	fn shard_offline(_network: NetworkId, _members: Vec<MemberId>) {}
+	fn member_online(_id: &MemberId, _network: NetworkId) {}
+	fn member_offline(_id: &MemberId, _network: NetworkId) {}
+	fn network_removed(_network: NetworkId) {
+		CLEANUP_CALLS.with(|calls| calls.borrow_mut().push("elections"));
+	}
+}
+
+pub struct MockMembers;
+
+impl MembersInterface for MockMembers {
+	fn member_stake(_account: &MemberId) -> Balance {
+		0
+	}
+	fn member_peer_id(_account: &MemberId) -> Option<PeerId> {
+		None
+	}
+	fn member_public_key(_account: &MemberId) -> Option<PublicKey> {
+		None
+	}
+	fn is_member_registered(_account: &MemberId) -> bool {
+		false
+	}
+	fn is_member_online(_account: &MemberId) -> bool {
+		false
+	}
+	fn total_stake() -> Balance {
+		0
+	}
+	fn transfer_stake(_from: &MemberId, _to: &MemberId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+	fn unstake_member(_account: &MemberId) {}
+	fn network_removed(_network: NetworkId, _limit: u32) -> (Weight, bool) {
+		cleanup_call("members")
+	}
 }
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -50,8 +164,15 @@ impl pallet_balances::Config for Test {
 impl pallet_networks::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type RouteOrigin = frame_system::EnsureRoot<AccountId>;
 	type WeightInfo = ();
 	type Tasks = MockTasks;
+	type Shards = MockShards;
+	type Elections = MockElections;
+	type Members = MockMembers;
+	type MaxCleanupPerBlock = ConstU32<10>;
+	type MaxGatewayMigrationsPerBlock = ConstU32<10>;
+	type MaxNetworks = ConstU32<64>;
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -1,11 +1,16 @@
 use crate::{self as pallet_networks};
 use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Get, Hooks},
+};
 use frame_system::RawOrigin;
 use polkadot_sdk::{frame_support, frame_system, sp_runtime};
 use scale_codec::Encode;
 use sp_runtime::BoundedVec;
-use time_primitives::{ChainName, ChainNetwork, Network, NetworkConfig};
+use time_primitives::{
+	ChainName, ChainNetwork, Network, NetworkConfig, NetworkStatus, NetworksInterface, Route,
+};
 
 fn mock_network_config() -> NetworkConfig {
 	NetworkConfig {
@@ -29,6 +34,25 @@ fn mock_network() -> Network {
 	}
 }
 
+fn mock_route(gateway: [u8; 32]) -> Route {
+	Route {
+		network_id: 42,
+		gateway,
+		relative_gas_price: (3, 2),
+		gas_limit: 100_000,
+		base_fee: 1_000,
+	}
+}
+
+/// Runs `on_initialize` until no network is cleaned up.
+fn cleanup() {
+	while pallet_networks::NetworkCleanup::<Test>::iter().next().is_some() {
+		let block = System::block_number() + 1;
+		System::set_block_number(block);
+		Networks::on_initialize(block);
+	}
+}
+
 #[test]
 fn test_register_network() {
 	let network = mock_network();
@@ -58,3 +82,235 @@ fn test_duplicate_insertion() {
 		);
 	});
 }
+
+#[test]
+fn test_network_lifecycle() {
+	let network = mock_network();
+	new_test_ext().execute_with(|| {
+		assert_ok!(Networks::register_network(RawOrigin::Root.into(), network.clone()));
+		assert_eq!(pallet_networks::NetworkStatus::<Test>::get(42), NetworkStatus::Active);
+		assert_ok!(Networks::pause_network(RawOrigin::Root.into(), 42));
+		assert_eq!(pallet_networks::NetworkStatus::<Test>::get(42), NetworkStatus::Paused);
+		assert_ok!(Networks::resume_network(RawOrigin::Root.into(), 42));
+		assert_eq!(pallet_networks::NetworkStatus::<Test>::get(42), NetworkStatus::Active);
+		assert_ok!(Networks::drain_network(RawOrigin::Root.into(), 42));
+		assert_eq!(pallet_networks::NetworkStatus::<Test>::get(42), NetworkStatus::Draining);
+		cleanup();
+		assert!(pallet_networks::NetworkCleanup::<Test>::get(42).is_none());
+		assert_ok!(Networks::remove_network(RawOrigin::Root.into(), 42));
+		assert_eq!(pallet_networks::NetworkStatus::<Test>::get(42), NetworkStatus::Removed);
+		System::assert_last_event(pallet_networks::Event::NetworkRemovalStarted(42).into());
+		// the network can't be registered again before its state is cleaned up
+		assert_noop!(
+			Networks::register_network(RawOrigin::Root.into(), network.clone()),
+			<Error<Test>>::NetworkExists
+		);
+		assert!(Networks::get_networks().is_empty());
+		cleanup();
+		assert_eq!(
+			CLEANUP_CALLS.with(|calls| calls.take()),
+			vec![
+				"refund",
+				"refund",
+				"shards",
+				"shards",
+				"elections",
+				"tasks",
+				"tasks",
+				"members",
+				"members"
+			]
+		);
+		assert_eq!(pallet_networks::NetworkStatus::<Test>::get(42), NetworkStatus::Removed);
+		assert_eq!(pallet_networks::Networks::<Test>::get(42), None);
+		assert_eq!(pallet_networks::NetworkName::<Test>::get(42), None);
+		assert_eq!(pallet_networks::NetworkGatewayAddress::<Test>::get(42), None);
+		System::assert_has_event(pallet_networks::Event::NetworkPaused(42).into());
+		System::assert_has_event(pallet_networks::Event::NetworkResumed(42).into());
+		System::assert_has_event(pallet_networks::Event::NetworkDraining(42).into());
+		System::assert_last_event(pallet_networks::Event::NetworkRemoved(42).into());
+		// a removed network can be registered again
+		assert_ok!(Networks::register_network(RawOrigin::Root.into(), network));
+		assert_eq!(pallet_networks::NetworkStatus::<Test>::get(42), NetworkStatus::Active);
+	});
+}
+
+#[test]
+fn test_invalid_network_status_transitions() {
+	let network = mock_network();
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Networks::pause_network(RawOrigin::Root.into(), 42),
+			<Error<Test>>::NetworkNotFound
+		);
+		assert_ok!(Networks::register_network(RawOrigin::Root.into(), network));
+		assert_noop!(
+			Networks::resume_network(RawOrigin::Root.into(), 42),
+			<Error<Test>>::InvalidNetworkStatus
+		);
+		assert_noop!(
+			Networks::remove_network(RawOrigin::Root.into(), 42),
+			<Error<Test>>::InvalidNetworkStatus
+		);
+		assert_ok!(Networks::drain_network(RawOrigin::Root.into(), 42));
+		assert_noop!(
+			Networks::pause_network(RawOrigin::Root.into(), 42),
+			<Error<Test>>::InvalidNetworkStatus
+		);
+		assert_noop!(
+			Networks::resume_network(RawOrigin::Root.into(), 42),
+			<Error<Test>>::InvalidNetworkStatus
+		);
+	});
+}
+
+#[test]
+fn test_remove_network_waits_for_drain() {
+	let network = mock_network();
+	new_test_ext().execute_with(|| {
+		assert_ok!(Networks::register_network(RawOrigin::Root.into(), network));
+		assert_ok!(Networks::drain_network(RawOrigin::Root.into(), 42));
+		// the queued ops aren't refunded yet
+		assert_noop!(
+			Networks::remove_network(RawOrigin::Root.into(), 42),
+			<Error<Test>>::NetworkNotDrained
+		);
+		cleanup();
+		// a batch is still in flight
+		PENDING_BATCHES.with(|pending| pending.set(true));
+		assert_noop!(
+			Networks::remove_network(RawOrigin::Root.into(), 42),
+			<Error<Test>>::NetworkNotDrained
+		);
+		assert_eq!(pallet_networks::NetworkStatus::<Test>::get(42), NetworkStatus::Draining);
+		PENDING_BATCHES.with(|pending| pending.set(false));
+		assert_ok!(Networks::remove_network(RawOrigin::Root.into(), 42));
+		assert_eq!(pallet_networks::NetworkStatus::<Test>::get(42), NetworkStatus::Removed);
+	});
+}
+
+#[test]
+fn test_migrate_gateway() {
+	let network = mock_network();
+	new_test_ext().execute_with(|| {
+		assert_ok!(Networks::register_network(RawOrigin::Root.into(), network));
+		assert_noop!(
+			Networks::migrate_gateway(RawOrigin::Root.into(), 42, [0; 32], 120),
+			<Error<Test>>::GatewayUnchanged
+		);
+		assert_ok!(Networks::migrate_gateway(RawOrigin::Root.into(), 42, [1; 32], 120));
+		assert_eq!(pallet_networks::NetworkStatus::<Test>::get(42), NetworkStatus::Migrating);
+		System::assert_last_event(
+			pallet_networks::Event::GatewayMigrationStarted(42, [1; 32], 120).into(),
+		);
+		assert_noop!(
+			Networks::pause_network(RawOrigin::Root.into(), 42),
+			<Error<Test>>::InvalidNetworkStatus
+		);
+		// waits for the old gateway's batches to finish
+		PENDING_BATCHES.with(|pending| pending.set(true));
+		Networks::on_initialize(2);
+		assert_eq!(pallet_networks::NetworkGatewayAddress::<Test>::get(42), Some([0; 32]));
+		assert_eq!(pallet_networks::NetworkStatus::<Test>::get(42), NetworkStatus::Migrating);
+		PENDING_BATCHES.with(|pending| pending.set(false));
+		Networks::on_initialize(3);
+		assert_eq!(pallet_networks::NetworkGatewayAddress::<Test>::get(42), Some([1; 32]));
+		assert_eq!(pallet_networks::NetworkGatewayBlock::<Test>::get(42), Some(120));
+		assert_eq!(pallet_networks::NetworkStatus::<Test>::get(42), NetworkStatus::Active);
+		assert!(pallet_networks::NetworkGatewayMigration::<Test>::get(42).is_none());
+		System::assert_last_event(pallet_networks::Event::GatewayMigrated(42, [1; 32], 120).into());
+	});
+}
+
+#[test]
+fn test_register_network_is_bounded() {
+	new_test_ext().execute_with(|| {
+		let max = <Test as pallet_networks::Config>::MaxNetworks::get() as u16;
+		for id in 0..max {
+			let mut network = mock_network();
+			network.id = id;
+			network.chain_network = ChainNetwork(BoundedVec::truncate_from(id.encode()));
+			assert_ok!(Networks::register_network(RawOrigin::Root.into(), network));
+		}
+		let mut network = mock_network();
+		network.id = max;
+		assert_noop!(
+			Networks::register_network(RawOrigin::Root.into(), network),
+			<Error<Test>>::TooManyNetworks
+		);
+	});
+}
+
+#[test]
+fn test_gateway_migrations_are_bounded_per_block() {
+	new_test_ext().execute_with(|| {
+		for id in 0..11u16 {
+			let mut network = mock_network();
+			network.id = id;
+			network.chain_network = ChainNetwork(BoundedVec::truncate_from(id.encode()));
+			assert_ok!(Networks::register_network(RawOrigin::Root.into(), network));
+			assert_ok!(Networks::migrate_gateway(RawOrigin::Root.into(), id, [1; 32], 120));
+		}
+		Networks::on_initialize(2);
+		assert_eq!(pallet_networks::NetworkGatewayMigration::<Test>::iter().count(), 1);
+		assert!(pallet_networks::GatewayMigrationCursor::<Test>::get().is_some());
+		Networks::on_initialize(3);
+		assert_eq!(pallet_networks::NetworkGatewayMigration::<Test>::iter().count(), 0);
+		assert!(pallet_networks::GatewayMigrationCursor::<Test>::get().is_none());
+	});
+}
+
+#[test]
+fn test_set_route() {
+	let network = mock_network();
+	new_test_ext().execute_with(|| {
+		assert_ok!(Networks::register_network(RawOrigin::Root.into(), network));
+		assert_noop!(
+			Networks::set_route(RawOrigin::Root.into(), 42, mock_route([1; 32])),
+			<Error<Test>>::InvalidRoute
+		);
+		let mut route = mock_route([0; 32]);
+		route.relative_gas_price = (1, 0);
+		assert_noop!(
+			Networks::set_route(RawOrigin::Root.into(), 42, route),
+			<Error<Test>>::InvalidRoute
+		);
+		assert_noop!(
+			Networks::set_route(RawOrigin::Root.into(), 1, mock_route([0; 32])),
+			<Error<Test>>::NetworkNotFound
+		);
+		assert_ok!(Networks::set_route(RawOrigin::Root.into(), 42, mock_route([0; 32])));
+		assert_eq!(Networks::get_routes(42), vec![mock_route([0; 32])]);
+		assert_eq!(ROUTE_CHANGES.with(|changes| changes.take()), vec![(42, mock_route([0; 32]))]);
+		System::assert_last_event(
+			pallet_networks::Event::RouteChanged(42, mock_route([0; 32])).into(),
+		);
+		// base fee + (gas limit + 16 gas per payload byte) * relative gas price
+		assert_eq!(Networks::estimate_message_cost(42, 42, 10_000, 100), Some(18_400));
+		assert_eq!(Networks::estimate_message_cost(42, 1, 10_000, 100), None);
+	});
+}
+
+#[test]
+fn test_gateway_migration_updates_routes() {
+	let network = mock_network();
+	new_test_ext().execute_with(|| {
+		assert_ok!(Networks::register_network(RawOrigin::Root.into(), network));
+		assert_ok!(Networks::set_route(RawOrigin::Root.into(), 42, mock_route([0; 32])));
+		ROUTE_CHANGES.with(|changes| changes.take());
+		assert_ok!(Networks::migrate_gateway(RawOrigin::Root.into(), 42, [1; 32], 120));
+		Networks::on_initialize(2);
+		assert_eq!(Networks::get_routes(42), vec![mock_route([1; 32])]);
+		// the new gateway gets all routes of the network
+		assert_eq!(ROUTE_CHANGES.with(|changes| changes.take()), vec![(42, mock_route([1; 32]))]);
+		assert_ok!(Networks::drain_network(RawOrigin::Root.into(), 42));
+		cleanup();
+		assert_ok!(Networks::remove_network(RawOrigin::Root.into(), 42));
+		assert_noop!(
+			Networks::set_route(RawOrigin::Root.into(), 42, mock_route([1; 32])),
+			<Error<Test>>::InvalidNetworkStatus
+		);
+		cleanup();
+		assert!(Networks::get_routes(42).is_empty());
+	});
+}
@@ -0,0 +1,61 @@
+use super::*;
+use crate::Pallet;
+
+use frame_system::RawOrigin;
+use polkadot_sdk::frame_benchmarking::benchmarks;
+use polkadot_sdk::{frame_system, sp_runtime};
+use scale_info::prelude::vec::Vec;
+use sp_runtime::{BoundedVec, FixedU128, Perbill};
+use time_primitives::{AccountId, NetworkId};
+
+const FEEDER: [u8; 32] = [1; 32];
+
+fn mock_token_config() -> TokenConfig {
+	TokenConfig {
+		decimals: 18,
+		gas_price: 1_000_000_000,
+		margin: Perbill::from_percent(10),
+	}
+}
+
+benchmarks! {
+	add_feeder {
+	}: _(RawOrigin::Root, AccountId::from(FEEDER))
+	verify {}
+
+	remove_feeder {
+		Pallet::<T>::add_feeder(RawOrigin::Root.into(), FEEDER.into())?;
+	}: _(RawOrigin::Root, AccountId::from(FEEDER))
+	verify {}
+
+	set_token_config {
+		let n in 1..T::MaxNetworks::get();
+		// every route from and to the network drifted past the tolerance
+		Prices::<T>::insert(0, FixedU128::from_u32(1));
+		for i in 1..n {
+			let network = i as NetworkId;
+			T::BenchmarkHelper::create_route(0, network);
+			T::BenchmarkHelper::create_route(network, 0);
+			Prices::<T>::insert(network, FixedU128::from_u32(i + 1));
+			TokenConfigs::<T>::insert(network, mock_token_config());
+		}
+	}: _(RawOrigin::Root, 0, mock_token_config())
+	verify {}
+
+	set_tolerance {
+	}: _(RawOrigin::Root, Perbill::from_percent(1))
+	verify {}
+
+	submit_prices {
+		let n in 1..T::MaxPrices::get();
+		Pallet::<T>::add_feeder(RawOrigin::Root.into(), FEEDER.into())?;
+		let prices: Vec<(NetworkId, FixedU128)> =
+			(0..n).map(|i| (i as NetworkId, FixedU128::from_u32(i + 1))).collect();
+		for (network, _) in &prices {
+			TokenConfigs::<T>::insert(*network, mock_token_config());
+		}
+	}: _(RawOrigin::Signed(FEEDER.into()), BoundedVec::truncate_from(prices))
+	verify {}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
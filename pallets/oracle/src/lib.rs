@@ -0,0 +1,393 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::manual_inspect)]
+//! The oracle pallet aggregates the token prices submitted by authorized
+//! feeders and keeps the relative gas prices of routes in line with them.
+//!
+//! ## Features
+//!
+//! - Feeders are added and removed by the admin origin and submit the USD
+//!   price of the currency of each network.
+//!
+//! - The price of a network is the median of the prices submitted in the last
+//!   `MaxPriceAge` blocks. It is only updated while at least `MinFeeders`
+//!   feeders submitted a fresh price.
+//!
+//! - The relative gas price of a route is computed from the prices, currency
+//!   decimals and gas prices of both networks plus the margin of the source
+//!   network. Routes of the networks pallet are updated once their relative
+//!   gas price drifted past the tolerance. Every price update visits the
+//!   routes from and to all networks, their number is bounded by `MaxNetworks`.
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub use pallet::*;
+
+#[polkadot_sdk::frame_support::pallet]
+pub mod pallet {
+	use polkadot_sdk::{frame_support, frame_system, sp_core, sp_runtime};
+
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use scale_info::prelude::vec::Vec;
+	use sp_core::U256;
+	use sp_runtime::{FixedPointNumber, FixedU128, Perbill, Saturating};
+	use time_primitives::{AccountId, NetworkId, NetworksInterface};
+
+	pub trait WeightInfo {
+		fn add_feeder() -> Weight;
+		fn remove_feeder() -> Weight;
+		fn set_token_config(n: u32) -> Weight;
+		fn set_tolerance() -> Weight;
+		fn submit_prices(n: u32) -> Weight;
+	}
+
+	impl WeightInfo for () {
+		fn add_feeder() -> Weight {
+			Weight::default()
+		}
+
+		fn remove_feeder() -> Weight {
+			Weight::default()
+		}
+
+		fn set_token_config(_: u32) -> Weight {
+			Weight::default()
+		}
+
+		fn set_tolerance() -> Weight {
+			Weight::default()
+		}
+
+		fn submit_prices(_: u32) -> Weight {
+			Weight::default()
+		}
+	}
+
+	/// Maximum decimals of a network's currency, `10^MAX_DECIMALS` fits into a `u128`.
+	pub const MAX_DECIMALS: u32 = 38;
+
+	/// Parameters converting the price of a network's currency into relative gas prices.
+	#[derive(Clone, Debug, Default, Decode, Encode, TypeInfo, PartialEq, Eq)]
+	pub struct TokenConfig {
+		/// Decimals of the network's currency.
+		pub decimals: u32,
+		/// Price of a unit of gas in the smallest unit of the network's currency.
+		pub gas_price: u128,
+		/// Margin added to the relative gas price of routes from the network.
+		pub margin: Perbill,
+	}
+
+	#[pallet::pallet]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: polkadot_sdk::frame_system::Config<AccountId = AccountId> {
+		type RuntimeEvent: From<Event<Self>>
+			+ IsType<<Self as polkadot_sdk::frame_system::Config>::RuntimeEvent>;
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		type WeightInfo: WeightInfo;
+		type Networks: NetworksInterface;
+		/// Maximum number of prices submitted at once.
+		#[pallet::constant]
+		type MaxPrices: Get<u32>;
+		/// Number of blocks after which a submitted price is stale.
+		#[pallet::constant]
+		type MaxPriceAge: Get<BlockNumberFor<Self>>;
+		/// Minimum number of fresh prices required to price a network.
+		#[pallet::constant]
+		type MinFeeders: Get<u32>;
+		/// Maximum number of networks whose routes are updated on a price change.
+		#[pallet::constant]
+		type MaxNetworks: Get<u32>;
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper;
+	}
+
+	/// Creates the networks and routes updated in the benchmarks.
+	#[cfg(feature = "runtime-benchmarks")]
+	pub trait BenchmarkHelper {
+		fn create_route(src: NetworkId, dest: NetworkId);
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Feeder added.
+		FeederAdded(AccountId),
+		/// Feeder removed.
+		FeederRemoved(AccountId),
+		/// Token config of a network changed.
+		TokenConfigChanged(NetworkId, TokenConfig),
+		/// Tolerance of relative gas price changes before routes are updated changed.
+		ToleranceChanged(Perbill),
+		/// Aggregated price of a network changed.
+		PriceUpdated(NetworkId, FixedU128),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Account is already a feeder.
+		FeederExists,
+		/// Account isn't a feeder.
+		NotFeeder,
+		/// Price is zero.
+		InvalidPrice,
+		/// Token decimals are above [`MAX_DECIMALS`].
+		InvalidDecimals,
+		/// More networks than `MaxNetworks` are registered.
+		TooManyNetworks,
+	}
+
+	#[pallet::type_value]
+	pub fn DefaultTolerance() -> Perbill {
+		Perbill::from_percent(5)
+	}
+
+	/// Map storage for accounts allowed to submit prices.
+	#[pallet::storage]
+	pub type Feeders<T: Config> = StorageMap<_, Blake2_128Concat, AccountId, (), OptionQuery>;
+
+	/// Double map storage for the last price submitted by a feeder and the block it was
+	/// submitted at.
+	#[pallet::storage]
+	pub type FeederPrices<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		NetworkId,
+		Blake2_128Concat,
+		AccountId,
+		(FixedU128, BlockNumberFor<T>),
+		OptionQuery,
+	>;
+
+	/// Map storage for the aggregated USD price of a network's currency.
+	#[pallet::storage]
+	pub type Prices<T: Config> = StorageMap<_, Twox64Concat, NetworkId, FixedU128, OptionQuery>;
+
+	/// Map storage for the token config of networks.
+	#[pallet::storage]
+	pub type TokenConfigs<T: Config> =
+		StorageMap<_, Twox64Concat, NetworkId, TokenConfig, OptionQuery>;
+
+	/// Relative gas price changes below the tolerance don't update routes.
+	#[pallet::storage]
+	pub type Tolerance<T: Config> = StorageValue<_, Perbill, ValueQuery, DefaultTolerance>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Allows an account to submit prices.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::add_feeder())]
+		pub fn add_feeder(origin: OriginFor<T>, feeder: AccountId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(Feeders::<T>::get(&feeder).is_none(), Error::<T>::FeederExists);
+			Feeders::<T>::insert(&feeder, ());
+			Self::deposit_event(Event::FeederAdded(feeder));
+			Ok(())
+		}
+
+		/// Removes a feeder and the prices it submitted.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::remove_feeder())]
+		pub fn remove_feeder(origin: OriginFor<T>, feeder: AccountId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(Feeders::<T>::take(&feeder).is_some(), Error::<T>::NotFeeder);
+			for network in T::Networks::get_networks() {
+				FeederPrices::<T>::remove(network, &feeder);
+			}
+			Self::deposit_event(Event::FeederRemoved(feeder));
+			Ok(())
+		}
+
+		/// Sets the token config of a network and updates its routes.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::set_token_config(T::MaxNetworks::get()))]
+		pub fn set_token_config(
+			origin: OriginFor<T>,
+			network: NetworkId,
+			config: TokenConfig,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(config.decimals <= MAX_DECIMALS, Error::<T>::InvalidDecimals);
+			let networks = Self::networks()?;
+			TokenConfigs::<T>::insert(network, config.clone());
+			Self::deposit_event(Event::TokenConfigChanged(network, config));
+			Self::update_routes(network, &networks);
+			Ok(())
+		}
+
+		/// Sets the tolerance of relative gas price changes before routes are updated.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::set_tolerance())]
+		pub fn set_tolerance(origin: OriginFor<T>, tolerance: Perbill) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Tolerance::<T>::put(tolerance);
+			Self::deposit_event(Event::ToleranceChanged(tolerance));
+			Ok(())
+		}
+
+		/// Submits the USD prices of the currencies of networks.
+		///
+		/// # Flow
+		///   1. Ensure the origin is a feeder.
+		///   2. Store the prices in [`FeederPrices`].
+		///   3. Aggregate the fresh prices of every network into [`Prices`].
+		///   4. Update the routes from and to networks whose price changed.
+		///
+		/// Each price may update the routes from and to all networks, which is
+		/// weighed like a [`Pallet::set_token_config`] per price.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::submit_prices(prices.len() as u32).saturating_add(
+			T::WeightInfo::set_token_config(T::MaxNetworks::get())
+				.saturating_mul(prices.len() as u64)
+		))]
+		pub fn submit_prices(
+			origin: OriginFor<T>,
+			prices: BoundedVec<(NetworkId, FixedU128), T::MaxPrices>,
+		) -> DispatchResult {
+			let feeder = ensure_signed(origin)?;
+			ensure!(Feeders::<T>::get(&feeder).is_some(), Error::<T>::NotFeeder);
+			ensure!(prices.iter().all(|(_, price)| !price.is_zero()), Error::<T>::InvalidPrice);
+			let networks = Self::networks()?;
+			let block = frame_system::Pallet::<T>::block_number();
+			for (network, price) in prices {
+				FeederPrices::<T>::insert(network, &feeder, (price, block));
+				let Some(price) = Self::aggregate(network) else {
+					continue;
+				};
+				if Prices::<T>::get(network) == Some(price) {
+					continue;
+				}
+				Prices::<T>::insert(network, price);
+				Self::deposit_event(Event::PriceUpdated(network, price));
+				Self::update_routes(network, &networks);
+			}
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Networks whose routes are updated, at most `MaxNetworks`.
+		fn networks() -> Result<Vec<NetworkId>, Error<T>> {
+			let networks = T::Networks::get_networks();
+			ensure!(networks.len() <= T::MaxNetworks::get() as usize, Error::<T>::TooManyNetworks);
+			Ok(networks)
+		}
+
+		/// Median of the fresh prices of a network, `None` if there are too few.
+		fn aggregate(network: NetworkId) -> Option<FixedU128> {
+			let now = frame_system::Pallet::<T>::block_number();
+			let mut prices: Vec<FixedU128> = FeederPrices::<T>::iter_prefix_values(network)
+				.filter(|(_, block)| now.saturating_sub(*block) <= T::MaxPriceAge::get())
+				.map(|(price, _)| price)
+				.collect();
+			if prices.is_empty() || (prices.len() as u32) < T::MinFeeders::get() {
+				return None;
+			}
+			prices.sort();
+			let mid = prices.len() / 2;
+			if prices.len() % 2 == 0 {
+				let sum = prices[mid - 1].saturating_add(prices[mid]);
+				Some(FixedU128::from_inner(sum.into_inner() / 2))
+			} else {
+				Some(prices[mid])
+			}
+		}
+
+		/// Relative gas price of the route from `src` to `dest`: the cost in the smallest
+		/// unit of the currency of `src` of a unit of gas on `dest`, including the margin
+		/// of `src`.
+		pub fn relative_gas_price(src: NetworkId, dest: NetworkId) -> Option<(u128, u128)> {
+			let src_price = Prices::<T>::get(src)?.into_inner();
+			let dest_price = Prices::<T>::get(dest)?.into_inner();
+			let src_config = TokenConfigs::<T>::get(src)?;
+			let dest_config = TokenConfigs::<T>::get(dest)?;
+			if src_config.decimals > MAX_DECIMALS || dest_config.decimals > MAX_DECIMALS {
+				return None;
+			}
+			let billion = U256::from(Perbill::ACCURACY);
+			let num = U256::from(dest_price)
+				.checked_mul(U256::from(dest_config.gas_price))?
+				.checked_mul(U256::exp10(src_config.decimals as usize))?
+				.checked_mul(billion.checked_add(src_config.margin.deconstruct().into())?)?;
+			let den = U256::from(src_price)
+				.checked_mul(U256::exp10(dest_config.decimals as usize))?
+				.checked_mul(billion)?;
+			reduce(num, den)
+		}
+
+		/// Updates the routes from and to `network`.
+		fn update_routes(network: NetworkId, networks: &[NetworkId]) {
+			for other in networks.iter().copied() {
+				Self::update_route(network, other);
+				if other != network {
+					Self::update_route(other, network);
+				}
+			}
+		}
+
+		fn update_route(src: NetworkId, dest: NetworkId) {
+			let Some(mut route) = T::Networks::route(src, dest) else {
+				return;
+			};
+			let Some(relative_gas_price) = Self::relative_gas_price(src, dest) else {
+				return;
+			};
+			if !drifted(route.relative_gas_price, relative_gas_price, Tolerance::<T>::get()) {
+				return;
+			}
+			route.relative_gas_price = relative_gas_price;
+			if let Err(err) = T::Networks::set_route(src, route) {
+				log::warn!("failed to update route {src} -> {dest}: {err:?}");
+			}
+		}
+	}
+
+	fn gcd(mut a: U256, mut b: U256) -> U256 {
+		while !b.is_zero() {
+			let r = a % b;
+			a = b;
+			b = r;
+		}
+		a
+	}
+
+	/// Reduces a ratio to lowest terms and drops precision until it fits into `u128`.
+	fn reduce(mut num: U256, mut den: U256) -> Option<(u128, u128)> {
+		if den.is_zero() {
+			return None;
+		}
+		let gcd = gcd(num, den);
+		if !gcd.is_zero() {
+			num /= gcd;
+			den /= gcd;
+		}
+		let max = U256::from(u128::MAX);
+		while num > max || den > max {
+			num >>= 1u32;
+			den >>= 1u32;
+		}
+		if den.is_zero() {
+			return None;
+		}
+		Some((num.as_u128(), den.as_u128()))
+	}
+
+	/// Whether `new` differs from `old` by more than `tolerance` relative to `old`.
+	fn drifted(old: (u128, u128), new: (u128, u128), tolerance: Perbill) -> bool {
+		if old.0 == 0 || old.1 == 0 {
+			return true;
+		}
+		let a = U256::from(new.0).saturating_mul(U256::from(old.1));
+		let b = U256::from(old.0).saturating_mul(U256::from(new.1));
+		let diff = if a > b { a - b } else { b - a };
+		diff.saturating_mul(U256::from(Perbill::ACCURACY))
+			> b.saturating_mul(U256::from(tolerance.deconstruct()))
+	}
+}
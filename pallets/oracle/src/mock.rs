@@ -0,0 +1,119 @@
+use crate::{self as pallet_oracle};
+use core::cell::RefCell;
+use std::collections::BTreeMap;
+
+use polkadot_sdk::frame_support::derive_impl;
+use polkadot_sdk::sp_core::{ConstU32, ConstU64};
+use polkadot_sdk::sp_runtime::{
+	traits::{IdentifyAccount, IdentityLookup, Verify},
+	BuildStorage, DispatchResult, MultiSignature,
+};
+use polkadot_sdk::{frame_support, frame_system, sp_io};
+use time_primitives::{Address, NetworkId, NetworkStatus, NetworksInterface, Route};
+
+pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
+pub type Signature = MultiSignature;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+thread_local! {
+	pub static ROUTES: RefCell<BTreeMap<(NetworkId, NetworkId), Route>> = const { RefCell::new(BTreeMap::new()) };
+	pub static NETWORKS: RefCell<Vec<NetworkId>> = RefCell::new(vec![1, 2]);
+}
+
+pub struct MockNetworks;
+
+impl NetworksInterface for MockNetworks {
+	fn get_networks() -> Vec<NetworkId> {
+		NETWORKS.with(|networks| networks.borrow().clone())
+	}
+	fn gateway(_network: NetworkId) -> Option<Address> {
+		Some([0; 32])
+	}
+	fn next_batch_size(_network: NetworkId, _block_height: u64) -> u32 {
+		5
+	}
+	fn batch_gas_limit(_network: NetworkId) -> u128 {
+		10
+	}
+	fn shard_task_limit(_network: NetworkId) -> u32 {
+		10
+	}
+	fn shard_size(_network: NetworkId) -> u16 {
+		3
+	}
+	fn shard_threshold(_network: NetworkId) -> u16 {
+		2
+	}
+	fn network_status(_network: NetworkId) -> NetworkStatus {
+		NetworkStatus::Active
+	}
+	fn route(src: NetworkId, dest: NetworkId) -> Option<Route> {
+		ROUTES.with(|routes| routes.borrow().get(&(src, dest)).cloned())
+	}
+	fn set_route(src: NetworkId, route: Route) -> DispatchResult {
+		ROUTES.with(|routes| routes.borrow_mut().insert((src, route.network_id), route));
+		Ok(())
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_oracle::BenchmarkHelper for MockNetworks {
+	fn create_route(src: NetworkId, dest: NetworkId) {
+		NETWORKS.with(|networks| {
+			let mut networks = networks.borrow_mut();
+			for network in [src, dest] {
+				if !networks.contains(&network) {
+					networks.push(network);
+				}
+			}
+		});
+		let route = Route {
+			network_id: dest,
+			gateway: [0; 32],
+			relative_gas_price: (1, 1),
+			gas_limit: 100_000,
+			base_fee: 0,
+		};
+		Self::set_route(src, route).unwrap();
+	}
+}
+
+frame_support::construct_runtime!(
+	pub struct Test {
+		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
+		Oracle: pallet_oracle::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type RuntimeTask = RuntimeTask;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type PalletInfo = PalletInfo;
+}
+
+impl pallet_oracle::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type WeightInfo = ();
+	type Networks = MockNetworks;
+	type MaxPrices = ConstU32<10>;
+	type MaxPriceAge = ConstU64<10>;
+	type MinFeeders = ConstU32<1>;
+	type MaxNetworks = ConstU32<10>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockNetworks;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	let mut ext: sp_io::TestExternalities = storage.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,197 @@
+use crate::mock::*;
+use crate::{Error, Event, FeederPrices, Prices, TokenConfig, Tolerance, MAX_DECIMALS};
+
+use polkadot_sdk::{frame_support, frame_system, sp_runtime};
+
+use frame_support::{assert_noop, assert_ok};
+use frame_system::RawOrigin;
+use sp_runtime::{BoundedVec, FixedU128, Perbill};
+use time_primitives::{NetworkId, NetworksInterface, Route};
+
+fn feeder(id: u8) -> AccountId {
+	[id; 32].into()
+}
+
+fn add_feeder(id: u8) {
+	assert_ok!(Oracle::add_feeder(RawOrigin::Root.into(), feeder(id)));
+}
+
+fn submit(id: u8, prices: Vec<(NetworkId, FixedU128)>) {
+	assert_ok!(Oracle::submit_prices(
+		RawOrigin::Signed(feeder(id)).into(),
+		BoundedVec::truncate_from(prices)
+	));
+}
+
+fn set_token_config(network: NetworkId, gas_price: u128) {
+	assert_ok!(Oracle::set_token_config(
+		RawOrigin::Root.into(),
+		network,
+		TokenConfig {
+			decimals: 18,
+			gas_price,
+			margin: Perbill::zero(),
+		}
+	));
+}
+
+fn set_route(src: NetworkId, dest: NetworkId, relative_gas_price: (u128, u128)) {
+	assert_ok!(MockNetworks::set_route(
+		src,
+		Route {
+			network_id: dest,
+			gateway: [0; 32],
+			relative_gas_price,
+			gas_limit: 100_000,
+			base_fee: 0,
+		}
+	));
+}
+
+fn route_price(src: NetworkId, dest: NetworkId) -> (u128, u128) {
+	MockNetworks::route(src, dest).unwrap().relative_gas_price
+}
+
+#[test]
+fn test_add_remove_feeder() {
+	new_test_ext().execute_with(|| {
+		add_feeder(1);
+		System::assert_last_event(Event::<Test>::FeederAdded(feeder(1)).into());
+		assert_noop!(
+			Oracle::add_feeder(RawOrigin::Root.into(), feeder(1)),
+			Error::<Test>::FeederExists
+		);
+		submit(1, vec![(1, FixedU128::from_u32(2))]);
+		assert!(FeederPrices::<Test>::get(1, feeder(1)).is_some());
+		assert_ok!(Oracle::remove_feeder(RawOrigin::Root.into(), feeder(1)));
+		System::assert_last_event(Event::<Test>::FeederRemoved(feeder(1)).into());
+		assert!(FeederPrices::<Test>::get(1, feeder(1)).is_none());
+		assert_noop!(
+			Oracle::remove_feeder(RawOrigin::Root.into(), feeder(1)),
+			Error::<Test>::NotFeeder
+		);
+	});
+}
+
+#[test]
+fn test_submit_prices_requires_feeder() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Oracle::submit_prices(
+				RawOrigin::Signed(feeder(1)).into(),
+				BoundedVec::truncate_from(vec![(1, FixedU128::from_u32(1))])
+			),
+			Error::<Test>::NotFeeder
+		);
+		add_feeder(1);
+		assert_noop!(
+			Oracle::submit_prices(
+				RawOrigin::Signed(feeder(1)).into(),
+				BoundedVec::truncate_from(vec![(1, FixedU128::from_inner(0))])
+			),
+			Error::<Test>::InvalidPrice
+		);
+	});
+}
+
+#[test]
+fn test_price_is_median() {
+	new_test_ext().execute_with(|| {
+		for id in 1..=3 {
+			add_feeder(id);
+		}
+		submit(1, vec![(1, FixedU128::from_u32(1))]);
+		submit(2, vec![(1, FixedU128::from_u32(4))]);
+		assert_eq!(Prices::<Test>::get(1), Some(FixedU128::from_rational(5, 2)));
+		submit(3, vec![(1, FixedU128::from_u32(2))]);
+		assert_eq!(Prices::<Test>::get(1), Some(FixedU128::from_u32(2)));
+		System::assert_last_event(Event::<Test>::PriceUpdated(1, FixedU128::from_u32(2)).into());
+	});
+}
+
+#[test]
+fn test_stale_prices_are_ignored() {
+	new_test_ext().execute_with(|| {
+		add_feeder(1);
+		add_feeder(2);
+		submit(1, vec![(1, FixedU128::from_u32(1))]);
+		System::set_block_number(20);
+		submit(2, vec![(1, FixedU128::from_u32(3))]);
+		assert_eq!(Prices::<Test>::get(1), Some(FixedU128::from_u32(3)));
+	});
+}
+
+#[test]
+fn test_relative_gas_price() {
+	new_test_ext().execute_with(|| {
+		add_feeder(1);
+		assert_eq!(Oracle::relative_gas_price(1, 2), None);
+		set_token_config(1, 1);
+		set_token_config(2, 10);
+		submit(1, vec![(1, FixedU128::from_u32(2)), (2, FixedU128::from_u32(1))]);
+		assert_eq!(Oracle::relative_gas_price(1, 2), Some((5, 1)));
+		assert_eq!(Oracle::relative_gas_price(2, 1), Some((2, 1)));
+	});
+}
+
+#[test]
+fn test_token_decimals_are_bounded() {
+	new_test_ext().execute_with(|| {
+		let config = TokenConfig {
+			decimals: MAX_DECIMALS + 1,
+			gas_price: 1,
+			margin: Perbill::zero(),
+		};
+		assert_noop!(
+			Oracle::set_token_config(RawOrigin::Root.into(), 1, config),
+			Error::<Test>::InvalidDecimals
+		);
+		// overflowing relative gas prices aren't computed
+		let config = TokenConfig {
+			decimals: MAX_DECIMALS,
+			gas_price: u128::MAX,
+			margin: Perbill::zero(),
+		};
+		assert_ok!(Oracle::set_token_config(RawOrigin::Root.into(), 1, config.clone()));
+		assert_ok!(Oracle::set_token_config(RawOrigin::Root.into(), 2, config));
+		add_feeder(1);
+		submit(1, vec![(1, FixedU128::from_u32(u32::MAX)), (2, FixedU128::from_u32(u32::MAX))]);
+		assert_eq!(Oracle::relative_gas_price(1, 2), None);
+	});
+}
+
+#[test]
+fn test_price_updates_are_bounded_by_networks() {
+	new_test_ext().execute_with(|| {
+		add_feeder(1);
+		NETWORKS.with(|networks| *networks.borrow_mut() = (0..11).collect());
+		assert_noop!(
+			Oracle::submit_prices(
+				RawOrigin::Signed(feeder(1)).into(),
+				BoundedVec::truncate_from(vec![(1, FixedU128::from_u32(1))])
+			),
+			Error::<Test>::TooManyNetworks
+		);
+	});
+}
+
+#[test]
+fn test_routes_update_past_tolerance() {
+	new_test_ext().execute_with(|| {
+		add_feeder(1);
+		set_token_config(1, 1);
+		set_token_config(2, 10);
+		set_route(1, 2, (1, 1));
+		submit(1, vec![(1, FixedU128::from_u32(2)), (2, FixedU128::from_u32(1))]);
+		assert_eq!(route_price(1, 2), (5, 1));
+		// 2.4% drift stays within the default tolerance
+		submit(1, vec![(1, FixedU128::from_rational(205, 100))]);
+		assert_eq!(route_price(1, 2), (5, 1));
+		submit(1, vec![(1, FixedU128::from_u32(1))]);
+		assert_eq!(route_price(1, 2), (10, 1));
+		assert_ok!(Oracle::set_tolerance(RawOrigin::Root.into(), Perbill::from_percent(1)));
+		assert_eq!(Tolerance::<Test>::get(), Perbill::from_percent(1));
+		submit(1, vec![(1, FixedU128::from_rational(98, 100))]);
+		assert_ne!(route_price(1, 2), (10, 1));
+	});
+}
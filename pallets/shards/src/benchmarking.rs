@@ -176,5 +176,18 @@ benchmarks! {
 		}
 	}
 
+	network_removed {
+		let n in 1..100;
+		for i in 0..n {
+			assert_ok!(Pallet::<T>::create_shard(ETHEREUM, vec![ALICE.into(), BOB.into(), CHARLIE.into()], 1));
+		}
+	}: {
+		<Pallet<T> as ShardsInterface>::network_removed(ETHEREUM, n);
+	} verify {
+		for i in 0..n {
+			assert_eq!(ShardState::<T>::get(i as u64), Some(ShardStatus::Offline));
+		}
+	}
+
 	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
 }
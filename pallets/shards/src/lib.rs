@@ -98,6 +98,7 @@ pub mod pallet {
 		fn ready() -> Weight;
 		fn force_shard_offline() -> Weight;
 		fn timeout_dkgs(b: u32) -> Weight;
+		fn network_removed(n: u32) -> Weight;
 	}
 
 	impl WeightInfo for () {
@@ -116,6 +117,10 @@ pub mod pallet {
 		fn timeout_dkgs(_: u32) -> Weight {
 			Weight::default()
 		}
+
+		fn network_removed(_: u32) -> Weight {
+			Weight::default()
+		}
 	}
 
 	#[pallet::pallet]
@@ -147,6 +152,11 @@ pub mod pallet {
 	pub type ShardNetwork<T: Config> =
 		StorageMap<_, Blake2_128Concat, ShardId, NetworkId, OptionQuery>;
 
+	/// Position in [`ShardNetwork`] of taking the shards of a removed network offline.
+	#[pallet::storage]
+	pub type RemovedNetworkCursor<T: Config> =
+		StorageMap<_, Blake2_128Concat, NetworkId, Vec<u8>, OptionQuery>;
+
 	/// Maps `ShardId` to `ShardStatus` indicating the status of each shard.
 	#[pallet::storage]
 	pub type ShardState<T: Config> =
@@ -657,5 +667,27 @@ pub mod pallet {
 		fn tss_public_key(shard_id: ShardId) -> Option<TssPublicKey> {
 			ShardCommitment::<T>::get(shard_id).map(|commitment| commitment.0[0])
 		}
+		/// Takes the shards of a removed network offline.
+		///
+		/// # Flow
+		///   1. Visits at most `limit` entries of [`ShardNetwork`], starting after the
+		///      [`RemovedNetworkCursor`] of the network.
+		///   2. Calls `remove_shard_offline` for the visited shards of the network.
+		///   3. Stores the cursor if there are entries left to visit.
+		fn network_removed(network: NetworkId, limit: u32) -> (Weight, bool) {
+			let mut iter = match RemovedNetworkCursor::<T>::take(network) {
+				Some(cursor) => ShardNetwork::<T>::iter_from(cursor),
+				None => ShardNetwork::<T>::iter(),
+			};
+			let visited = iter.by_ref().take(limit as usize).collect::<Vec<_>>();
+			let done = visited.len() < limit as usize;
+			if !done {
+				RemovedNetworkCursor::<T>::insert(network, iter.last_raw_key().to_vec());
+			}
+			for (shard_id, _) in visited.iter().filter(|(_, n)| *n == network) {
+				Self::remove_shard_offline(*shard_id);
+			}
+			(<T as Config>::WeightInfo::network_removed(visited.len() as u32), done)
+		}
 	}
 }
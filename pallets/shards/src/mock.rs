@@ -7,9 +7,11 @@ use frame_support::traits::OnInitialize;
 use sp_core::{ConstU128, ConstU32, ConstU64};
 use sp_runtime::{
 	traits::{IdentifyAccount, IdentityLookup, Verify},
-	BuildStorage, MultiSignature,
+	BuildStorage, DispatchResult, MultiSignature, Weight,
+};
+use time_primitives::{
+	Address, NetworkId, NetworkStatus, NetworksInterface, Route, ShardId, TasksInterface,
 };
-use time_primitives::{Address, NetworkId, NetworksInterface, ShardId, TasksInterface};
 
 pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -22,6 +24,18 @@ impl TasksInterface for MockTasks {
 	fn shard_online(_: ShardId, _: NetworkId) {}
 	fn shard_offline(_: ShardId, _: NetworkId) {}
 	fn gateway_registered(_: NetworkId, _: u64) {}
+	fn network_status_changed(_: NetworkId, _: NetworkStatus) {}
+	fn refund_queued_ops(_: NetworkId, _: u32) -> (Weight, bool) {
+		(Weight::zero(), true)
+	}
+	fn network_removed(_: NetworkId, _: u32) -> (Weight, bool) {
+		(Weight::zero(), true)
+	}
+	fn has_pending_batches(_: NetworkId) -> bool {
+		false
+	}
+	fn gateway_migrated(_: NetworkId, _: u64) {}
+	fn route_changed(_: NetworkId, _: Route) {}
 }
 
 pub struct MockNetworks;
@@ -48,6 +62,15 @@ impl NetworksInterface for MockNetworks {
 	fn shard_threshold(_network: NetworkId) -> u16 {
 		2
 	}
+	fn network_status(_network: NetworkId) -> NetworkStatus {
+		NetworkStatus::Active
+	}
+	fn route(_src: NetworkId, _dest: NetworkId) -> Option<Route> {
+		None
+	}
+	fn set_route(_src: NetworkId, _route: Route) -> DispatchResult {
+		Ok(())
+	}
 }
 
 frame_support::construct_runtime!(
@@ -175,6 +175,22 @@ fn dkg_times_out() {
 	});
 }
 
+#[test]
+fn network_removed_takes_shards_offline_in_steps() {
+	new_test_ext().execute_with(|| {
+		let members = || shard().iter().map(|m| m.account_id.clone()).collect::<Vec<_>>();
+		assert_ok!(Shards::create_shard(ETHEREUM, members(), 1));
+		assert_ok!(Shards::create_shard(ETHEREUM + 1, members(), 1));
+		// visits one of the two shards per step
+		assert!(!Shards::network_removed(ETHEREUM, 1).1);
+		assert!(!Shards::network_removed(ETHEREUM, 1).1);
+		assert!(Shards::network_removed(ETHEREUM, 1).1);
+		assert_eq!(ShardState::<Test>::get(0), Some(ShardStatus::Offline));
+		assert!(ShardNetwork::<Test>::get(0).is_none());
+		assert_eq!(ShardNetwork::<Test>::get(1), Some(ETHEREUM + 1));
+	});
+}
+
 #[test]
 fn member_offline_above_threshold_sets_online_shard_offline() {
 	let shard = shard();
@@ -1,6 +1,6 @@
 use crate::{
-	BatchIdCounter, BatchTaskId, Call, Config, FailedBatchIds, Pallet, ReadEventsTask,
-	ShardRegistered, TaskIdCounter, TaskNetwork, TaskOutput, TaskShard,
+	BatchIdCounter, BatchMessage, BatchTaskId, Call, Config, FailedBatchIds, Pallet,
+	ReadEventsTask, ShardRegistered, TaskIdCounter, TaskNetwork, TaskOutput, TaskShard, UATasks,
 };
 use frame_benchmarking::benchmarks;
 use frame_support::pallet_prelude::Get;
@@ -13,9 +13,9 @@ use polkadot_sdk::{frame_benchmarking, frame_support, frame_system, sp_core, sp_
 use sp_runtime::{BoundedVec, Vec};
 use sp_std::vec;
 use time_primitives::{
-	AccountId, Commitment, ElectionsInterface, ErrorMsg, GmpEvents, NetworkId, PublicKey,
-	ShardStatus, ShardsInterface, Task, TaskId, TaskResult, TasksInterface, TssPublicKey,
-	TssSignature,
+	AccountId, Commitment, ElectionsInterface, ErrorMsg, GatewayMessage, GatewayOp, GmpEvents,
+	GmpMessage, NetworkId, PublicKey, ShardStatus, ShardsInterface, Task, TaskId, TaskResult,
+	TasksInterface, TssPublicKey, TssSignature,
 };
 
 const ETHEREUM: NetworkId = 0;
@@ -58,6 +58,19 @@ fn create_task<T: Config>(network: NetworkId) -> TaskId {
 	Pallet::<T>::create_task(network, Task::ReadGatewayEvents { blocks: 0..10 })
 }
 
+fn send_message(nonce: u64) -> GatewayOp {
+	GatewayOp::SendMessage(GmpMessage {
+		src_network: ETHEREUM,
+		dest_network: ETHEREUM,
+		src: [0; 32],
+		dest: [0; 32],
+		nonce,
+		gas_limit: 10_000,
+		gas_cost: 10_000,
+		bytes: vec![],
+	})
+}
+
 benchmarks! {
 	where_clause {  where T: pallet_shards::Config + pallet_networks::Config + pallet_members::Config }
 
@@ -168,5 +181,30 @@ benchmarks! {
 			"Batch not removed from failed list"
 		);
 	}
+
+	refund_queued_ops {
+		let n in 1..100;
+		for i in 0..n {
+			Pallet::<T>::ops_queue(ETHEREUM).push(send_message(i.into()));
+		}
+	}: {
+		<Pallet<T> as TasksInterface>::refund_queued_ops(ETHEREUM, n);
+	} verify { }
+
+	network_removed {
+		let n in 1..100;
+		// unassigned batches are the most expensive tasks to remove
+		for i in 0..n {
+			let batch_id: u64 = i.into();
+			BatchMessage::<T>::insert(batch_id, GatewayMessage { ops: vec![send_message(batch_id)] });
+			let task_id = Pallet::<T>::create_task(ETHEREUM, Task::SubmitGatewayMessage { batch_id });
+			BatchTaskId::<T>::insert(batch_id, task_id);
+		}
+	}: {
+		<Pallet<T> as TasksInterface>::network_removed(ETHEREUM, n);
+	} verify {
+		assert!(UATasks::<T>::iter_prefix(ETHEREUM).next().is_none());
+	}
+
 	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -42,6 +42,7 @@
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
 pub use pallet::*;
 #[cfg(test)]
 mod mock;
@@ -69,10 +70,10 @@ pub mod pallet {
 	use sp_std::vec::Vec;
 
 	use time_primitives::{
-		AccountId, Balance, BatchBuilder, BatchId, ErrorMsg, GatewayMessage, GatewayOp, GmpEvent,
-		GmpEvents, Hash as TxHash, MessageId, NetworkId, NetworksInterface, PublicKey, ShardId,
-		ShardsInterface, Task, TaskId, TaskResult, TasksInterface, TssPublicKey, TssSignature,
-		MAX_GMP_EVENTS,
+		AccountId, Address, Balance, BatchBuilder, BatchId, ErrorMsg, GatewayMessage, GatewayOp,
+		GmpEvent, GmpEvents, GmpMessage, Hash as TxHash, MessageId, NetworkId, NetworkStatus,
+		NetworksInterface, PublicKey, Route, ShardId, ShardsInterface, Task, TaskId, TaskResult,
+		TasksInterface, TssPublicKey, TssSignature, MAX_GMP_EVENTS,
 	};
 
 	/// Trait to define the weights for various extrinsics in the pallet.
@@ -85,6 +86,8 @@ pub mod pallet {
 		fn stop_network() -> Weight;
 		fn remove_task() -> Weight;
 		fn restart_batch() -> Weight;
+		fn refund_queued_ops(n: u32) -> Weight;
+		fn network_removed(n: u32) -> Weight;
 	}
 
 	impl WeightInfo for () {
@@ -112,9 +115,19 @@ pub mod pallet {
 		fn restart_batch() -> Weight {
 			Weight::default()
 		}
+		fn refund_queued_ops(_: u32) -> Weight {
+			Weight::default()
+		}
+		fn network_removed(_: u32) -> Weight {
+			Weight::default()
+		}
 	}
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -276,6 +289,16 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type BatchTxHash<T: Config> = StorageMap<_, Blake2_128Concat, BatchId, TxHash, OptionQuery>;
 
+	/// Map storage for the number of batches of a network which are in flight.
+	#[pallet::storage]
+	pub type PendingBatchCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, NetworkId, u32, ValueQuery>;
+
+	/// Position in [`FailedBatchIds`] of refunding the failed batches of a removed network.
+	#[pallet::storage]
+	pub type RemovedNetworkCursor<T: Config> =
+		StorageMap<_, Blake2_128Concat, NetworkId, Vec<u8>, OptionQuery>;
+
 	/// Map storage for task signers.
 	#[pallet::storage]
 	pub type TaskSubmitter<T: Config> =
@@ -300,6 +323,10 @@ pub mod pallet {
 		MessageReceived(MessageId),
 		/// Message executed
 		MessageExecuted(MessageId),
+		/// Message dropped because its destination network is draining or removed,
+		/// the gas cost is owed to the sender on the source network
+		/// (message_id, src_network, src, gas_cost)
+		MessageRefunded(MessageId, NetworkId, Address, u128),
 	}
 
 	#[pallet::error]
@@ -367,8 +394,10 @@ pub mod pallet {
 					if curr == blocks.start {
 						SyncHeight::<T>::insert(network, blocks.end);
 					}
-					// start next batch if network wasn't stopped
-					if ReadEventsTask::<T>::get(network).is_some() {
+					// start next batch if network wasn't stopped or paused
+					if ReadEventsTask::<T>::get(network).is_some()
+						&& Self::is_network_reading(network)
+					{
 						Self::read_gateway_events(network);
 					}
 					// process events
@@ -483,9 +512,18 @@ pub mod pallet {
 					},
 					GmpEvent::MessageReceived(msg) => {
 						let msg_id = msg.message_id();
-						Self::ops_queue(msg.dest_network).push(GatewayOp::SendMessage(msg));
 						MessageReceivedTaskId::<T>::insert(msg_id, task_id);
 						Self::deposit_event(Event::<T>::MessageReceived(msg_id));
+						match T::Networks::network_status(msg.dest_network) {
+							NetworkStatus::Active
+							| NetworkStatus::Paused
+							| NetworkStatus::Migrating => {
+								Self::ops_queue(msg.dest_network).push(GatewayOp::SendMessage(msg));
+							},
+							NetworkStatus::Draining | NetworkStatus::Removed => {
+								Self::refund_message(msg);
+							},
+						}
 					},
 					GmpEvent::MessageExecuted(msg_id) => {
 						MessageExecutedTaskId::<T>::insert(msg_id, task_id);
@@ -554,6 +592,9 @@ pub mod pallet {
 		pub(crate) fn create_task(network: NetworkId, task: Task) -> TaskId {
 			let task_id = TaskIdCounter::<T>::get().saturating_plus_one();
 			let needs_registration = task.needs_registration();
+			if matches!(task, Task::SubmitGatewayMessage { .. }) {
+				PendingBatchCount::<T>::mutate(network, |count| *count = count.saturating_add(1));
+			}
 			Tasks::<T>::insert(task_id, task);
 			TaskNetwork::<T>::insert(task_id, network);
 			TaskIdCounter::<T>::put(task_id);
@@ -570,6 +611,9 @@ pub mod pallet {
 
 		fn finish_task(network: NetworkId, task_id: TaskId, result: Result<(), ErrorMsg>) {
 			TaskOutput::<T>::insert(task_id, result.clone());
+			if Self::is_batch(task_id) {
+				Self::batch_finished(network);
+			}
 			if let Some(shard) = Some(TaskShard::<T>::take(task_id).unwrap()) {
 				log::debug!("finish task {task_id} on {shard}");
 				ShardTasks::<T>::remove(shard, task_id);
@@ -663,6 +707,9 @@ pub mod pallet {
 		pub(crate) fn schedule_tasks() -> Weight {
 			let mut num_tasks_assigned: u32 = 0u32;
 			for (network, task_id) in ReadEventsTask::<T>::iter() {
+				if T::Networks::network_status(network) == NetworkStatus::Paused {
+					continue;
+				}
 				let max_assignable_tasks = T::Networks::shard_task_limit(network);
 
 				// handle read events task assignment
@@ -731,6 +778,9 @@ pub mod pallet {
 		pub(crate) fn prepare_batches() -> Weight {
 			let mut num_batches_started = 0u32;
 			for (network, _) in ReadEventsTask::<T>::iter() {
+				if T::Networks::network_status(network) != NetworkStatus::Active {
+					continue;
+				}
 				let batch_gas_limit = T::Networks::batch_gas_limit(network);
 				let mut batcher = BatchBuilder::new(batch_gas_limit);
 				let queue = Self::ops_queue(network);
@@ -764,6 +814,171 @@ pub mod pallet {
 			let task_id = Self::create_task(network, Task::SubmitGatewayMessage { batch_id });
 			BatchTaskId::<T>::insert(batch_id, task_id);
 		}
+
+		/// Paused and removed networks don't read gateway events, draining and migrating
+		/// networks keep reading them to observe the execution of in-flight batches.
+		fn is_network_reading(network: NetworkId) -> bool {
+			matches!(
+				T::Networks::network_status(network),
+				NetworkStatus::Active | NetworkStatus::Draining | NetworkStatus::Migrating
+			)
+		}
+
+		fn is_batch(task_id: TaskId) -> bool {
+			matches!(Tasks::<T>::get(task_id), Some(Task::SubmitGatewayMessage { .. }))
+		}
+
+		fn batch_finished(network: NetworkId) {
+			PendingBatchCount::<T>::mutate(network, |count| *count = count.saturating_sub(1));
+		}
+
+		/// Restarts reading gateway events if the last read task finished while the
+		/// network was paused.
+		fn restart_read_gateway_events(network: NetworkId) {
+			let Some(task_id) = ReadEventsTask::<T>::get(network) else {
+				return;
+			};
+			if TaskOutput::<T>::get(task_id).is_some() {
+				Self::read_gateway_events(network);
+			}
+		}
+
+		fn refund_message(msg: GmpMessage) {
+			let msg_id = msg.message_id();
+			MessageBatchId::<T>::remove(msg_id);
+			Self::deposit_event(Event::<T>::MessageRefunded(
+				msg_id,
+				msg.src_network,
+				msg.src,
+				msg.gas_cost,
+			));
+		}
+
+		/// Refunds at most `limit` queued ops, returns the number of popped ops and
+		/// whether the queue is empty.
+		fn refund_ops(network: NetworkId, limit: u32) -> (u32, bool) {
+			let queue = Self::ops_queue(network);
+			for popped in 0..limit {
+				let Some(op) = queue.pop() else {
+					OpsInsertIndex::<T>::remove(network);
+					OpsRemoveIndex::<T>::remove(network);
+					return (popped, true);
+				};
+				if let GatewayOp::SendMessage(msg) = op {
+					Self::refund_message(msg);
+				}
+			}
+			(limit, false)
+		}
+
+		/// Refunds the messages of a batch which will never be executed.
+		fn refund_batch(batch_id: BatchId) {
+			if let Some(msg) = BatchMessage::<T>::take(batch_id) {
+				for op in msg.ops {
+					if let GatewayOp::SendMessage(msg) = op {
+						Self::refund_message(msg);
+					}
+				}
+			}
+			BatchTaskId::<T>::remove(batch_id);
+			FailedBatchIds::<T>::remove(batch_id);
+		}
+
+		/// Removes a task which was never completed.
+		fn remove_unfinished_task(task_id: TaskId) {
+			if TaskOutput::<T>::get(task_id).is_some() {
+				return;
+			}
+			let network = TaskNetwork::<T>::take(task_id);
+			if let Some(Task::SubmitGatewayMessage { batch_id }) = Tasks::<T>::take(task_id) {
+				Self::refund_batch(batch_id);
+				if let Some(network) = network {
+					Self::batch_finished(network);
+				}
+			}
+			if let Some(shard) = TaskShard::<T>::take(task_id) {
+				ShardTasks::<T>::remove(shard, task_id);
+			}
+			TaskSubmitter::<T>::remove(task_id);
+		}
+
+		/// Removes the tasks, queues and batches of a removed network. Its shards are
+		/// expected to be offline already, which moved their tasks to the unassigned queue.
+		///
+		/// # Flow
+		///   1. Remove the read events task and the sync height.
+		///   2. Refund the queued ops.
+		///   3. Remove the unassigned tasks and the tasks of shards which are still online.
+		///   4. Refund the failed batches, visiting [`FailedBatchIds`] from the
+		///      [`RemovedNetworkCursor`] of the network.
+		///   5. Remove the task counters.
+		///
+		/// Stops after `limit` items, returns the number of items and whether all state
+		/// is removed.
+		fn remove_network_state(network: NetworkId, limit: u32) -> (u32, bool) {
+			let mut removed = 0u32;
+			if let Some(task_id) = ReadEventsTask::<T>::take(network) {
+				Self::remove_unfinished_task(task_id);
+				SyncHeight::<T>::remove(network);
+				removed = removed.saturating_plus_one();
+			}
+			let (refunded, done) = Self::refund_ops(network, limit.saturating_sub(removed));
+			removed = removed.saturating_add(refunded);
+			if !done {
+				return (removed, false);
+			}
+			let tasks = UATasks::<T>::iter_prefix(network)
+				.take(limit.saturating_sub(removed) as usize)
+				.collect::<Vec<_>>();
+			for (index, task_id) in &tasks {
+				UATasks::<T>::remove(network, index);
+				Self::remove_unfinished_task(*task_id);
+			}
+			removed = removed.saturating_add(tasks.len() as u32);
+			if removed >= limit {
+				return (removed, false);
+			}
+			UATasksInsertIndex::<T>::remove(network);
+			UATasksRemoveIndex::<T>::remove(network);
+			while let Some(shard) = NetworkShards::<T>::iter_key_prefix(network).next() {
+				let tasks = ShardTasks::<T>::iter_key_prefix(shard)
+					.take(limit.saturating_sub(removed) as usize)
+					.collect::<Vec<_>>();
+				for task_id in &tasks {
+					ShardTasks::<T>::remove(shard, task_id);
+					Self::remove_unfinished_task(*task_id);
+				}
+				removed = removed.saturating_add(tasks.len() as u32);
+				if removed >= limit {
+					return (removed, false);
+				}
+				NetworkShards::<T>::remove(network, shard);
+				ShardTaskCount::<T>::remove(shard);
+			}
+			let mut iter = match RemovedNetworkCursor::<T>::take(network) {
+				Some(cursor) => FailedBatchIds::<T>::iter_keys_from(cursor),
+				None => FailedBatchIds::<T>::iter_keys(),
+			};
+			let remaining = limit.saturating_sub(removed);
+			let batches = iter.by_ref().take(remaining as usize).collect::<Vec<_>>();
+			removed = removed.saturating_add(batches.len() as u32);
+			if batches.len() == remaining as usize {
+				RemovedNetworkCursor::<T>::insert(network, iter.last_raw_key().to_vec());
+			}
+			for batch_id in &batches {
+				if BatchTaskId::<T>::get(batch_id).and_then(TaskNetwork::<T>::get) == Some(network)
+				{
+					Self::refund_batch(*batch_id);
+				}
+			}
+			if batches.len() == remaining as usize {
+				return (removed, false);
+			}
+			TaskCount::<T>::remove(network);
+			ExecutedTaskCount::<T>::remove(network);
+			PendingBatchCount::<T>::remove(network);
+			(removed, true)
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -840,5 +1055,51 @@ pub mod pallet {
 			SyncHeight::<T>::insert(network, block);
 			Self::read_gateway_events(network);
 		}
+
+		fn network_status_changed(network: NetworkId, status: NetworkStatus) {
+			match status {
+				NetworkStatus::Active => Self::restart_read_gateway_events(network),
+				NetworkStatus::Paused => {},
+				NetworkStatus::Draining => Self::restart_read_gateway_events(network),
+				NetworkStatus::Removed => {},
+				NetworkStatus::Migrating => {},
+			}
+		}
+
+		fn refund_queued_ops(network: NetworkId, limit: u32) -> (Weight, bool) {
+			let (refunded, done) = Self::refund_ops(network, limit);
+			(<T as Config>::WeightInfo::refund_queued_ops(refunded), done)
+		}
+
+		fn network_removed(network: NetworkId, limit: u32) -> (Weight, bool) {
+			let (removed, done) = Self::remove_network_state(network, limit);
+			(<T as Config>::WeightInfo::network_removed(removed), done)
+		}
+
+		fn has_pending_batches(network: NetworkId) -> bool {
+			PendingBatchCount::<T>::get(network) > 0
+		}
+
+		/// Starts reading events from the new gateway and registers the online shards
+		/// on it ahead of the ops queued during the migration.
+		fn gateway_migrated(network: NetworkId, block: u64) {
+			// a read task in flight doesn't update the sync height, as it doesn't
+			// start at it, but starts the next read task from it
+			SyncHeight::<T>::insert(network, block);
+			Self::restart_read_gateway_events(network);
+			let queue = Self::ops_queue(network);
+			for shard in NetworkShards::<T>::iter_key_prefix(network) {
+				if !T::Shards::is_shard_online(shard) {
+					continue;
+				}
+				if let Some(key) = T::Shards::tss_public_key(shard) {
+					queue.push_front(GatewayOp::RegisterShard(key));
+				}
+			}
+		}
+
+		fn route_changed(network: NetworkId, route: Route) {
+			Self::ops_queue(network).push(GatewayOp::SetRoute(route));
+		}
 	}
 }
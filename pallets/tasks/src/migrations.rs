@@ -0,0 +1,50 @@
+//! Storage migrations of the tasks pallet.
+use crate::{Config, Pallet, PendingBatchCount, TaskNetwork, TaskOutput, Tasks};
+
+use polkadot_sdk::{frame_support, frame_system, sp_std};
+
+use frame_support::{
+	migrations::VersionedMigration,
+	traits::{Get, UncheckedOnRuntimeUpgrade},
+	weights::Weight,
+};
+use sp_std::marker::PhantomData;
+
+use time_primitives::Task;
+
+/// Counts the unfinished batch tasks of every network into [`PendingBatchCount`],
+/// which gateway migrations and network removals wait on.
+pub struct InitPendingBatchCount<T>(PhantomData<T>);
+
+impl<T: Config> UncheckedOnRuntimeUpgrade for InitPendingBatchCount<T> {
+	fn on_runtime_upgrade() -> Weight {
+		let mut reads = 0u64;
+		let mut writes = 0u64;
+		let _ = PendingBatchCount::<T>::clear(u32::MAX, None);
+		for (task_id, task) in Tasks::<T>::iter() {
+			reads += 1;
+			if !matches!(task, Task::SubmitGatewayMessage { .. }) {
+				continue;
+			}
+			reads += 2;
+			if TaskOutput::<T>::contains_key(task_id) {
+				continue;
+			}
+			let Some(network) = TaskNetwork::<T>::get(task_id) else {
+				continue;
+			};
+			PendingBatchCount::<T>::mutate(network, |count| *count = count.saturating_add(1));
+			writes += 1;
+		}
+		T::DbWeight::get().reads_writes(reads, writes.saturating_add(1))
+	}
+}
+
+/// Initializes [`PendingBatchCount`] and bumps the storage version to 1.
+pub type MigrateV0ToV1<T> = VersionedMigration<
+	0,
+	1,
+	InitPendingBatchCount<T>,
+	Pallet<T>,
+	<T as frame_system::Config>::DbWeight,
+>;
@@ -16,14 +16,14 @@ use frame_support::PalletId;
 use sp_core::{ConstU128, ConstU32, ConstU64};
 use sp_runtime::{
 	traits::{parameter_types, Get, IdentifyAccount, IdentityLookup, Verify},
-	BuildStorage, DispatchResult, MultiSignature, Permill,
+	BuildStorage, DispatchResult, MultiSignature, Permill, Weight,
 };
 use sp_std::cell::RefCell;
 use sp_std::collections::btree_map::BTreeMap;
 
 use time_primitives::{
-	Address, Balance, ElectionsInterface, MembersInterface, NetworkId, NetworksInterface, PeerId,
-	PublicKey, ShardsInterface,
+	Address, Balance, ElectionsInterface, MembersInterface, NetworkId, NetworkStatus,
+	NetworksInterface, PeerId, PublicKey, Route, ShardsInterface,
 };
 
 pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -31,6 +31,10 @@ type Block = frame_system::mocking::MockBlock<Test>;
 pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
 pub type Signature = MultiSignature;
 
+thread_local! {
+	pub static NETWORK_STATUS: RefCell<BTreeMap<NetworkId, NetworkStatus>> = const { RefCell::new(BTreeMap::new()) };
+}
+
 pub struct MockNetworks;
 
 impl NetworksInterface for MockNetworks {
@@ -55,6 +59,15 @@ impl NetworksInterface for MockNetworks {
 	fn shard_threshold(_network: NetworkId) -> u16 {
 		2
 	}
+	fn network_status(network: NetworkId) -> NetworkStatus {
+		NETWORK_STATUS.with(|s| s.borrow().get(&network).copied().unwrap_or_default())
+	}
+	fn route(_src: NetworkId, _dest: NetworkId) -> Option<Route> {
+		None
+	}
+	fn set_route(_src: NetworkId, _route: Route) -> DispatchResult {
+		Ok(())
+	}
 }
 
 pub struct MockMembers;
@@ -82,6 +95,9 @@ impl MembersInterface for MockMembers {
 	fn is_member_registered(_account: &AccountId) -> bool {
 		true
 	}
+	fn network_removed(_network: NetworkId, _limit: u32) -> (Weight, bool) {
+		(Weight::zero(), true)
+	}
 }
 
 pub struct MockElections;
@@ -95,6 +111,7 @@ impl ElectionsInterface for MockElections {
 	fn member_offline(member: &AccountId, network: NetworkId) {
 		Shards::member_offline(member, network)
 	}
+	fn network_removed(_: NetworkId) {}
 }
 
 // Configure a mock runtime to test the pallet.
@@ -265,8 +282,15 @@ impl pallet_shards::Config for Test {
 impl pallet_networks::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type RouteOrigin = frame_system::EnsureRoot<AccountId>;
 	type WeightInfo = ();
 	type Tasks = Tasks;
+	type Shards = Shards;
+	type Elections = Elections;
+	type Members = Members;
+	type MaxCleanupPerBlock = ConstU32<10>;
+	type MaxGatewayMigrationsPerBlock = ConstU32<10>;
+	type MaxNetworks = ConstU32<64>;
 }
 
 impl pallet_tasks::Config for Test {
@@ -11,6 +11,9 @@ pub type Index = u64;
 pub trait QueueT<T: Config, Value> {
 	/// Push an item onto the end of the queue.
 	fn push(&self, value: Value);
+	/// Push an item onto the beginning of the queue. A queue which was never
+	/// popped has no room in front, the item is pushed onto the end instead.
+	fn push_front(&self, value: Value);
 	/// Remove an item from the queue.
 	fn remove(&self, index: Index) -> Option<Value>;
 	/// Pop an item from the beginning of the queue.
@@ -49,6 +52,16 @@ where
 		InsertIndex::insert(self.network, insert_i.saturating_plus_one());
 	}
 
+	fn push_front(&self, value: Value) {
+		let remove_i = RemoveIndex::get(self.network).unwrap_or_default();
+		if remove_i == 0 {
+			return self.push(value);
+		}
+		let remove_i = remove_i.saturating_sub(1);
+		Queue::insert(self.network, remove_i, value);
+		RemoveIndex::insert(self.network, remove_i);
+	}
+
 	fn remove(&self, index: Index) -> Option<Value> {
 		let insert_i = InsertIndex::get(self.network).unwrap_or_default();
 		let mut remove_i = RemoveIndex::get(self.network).unwrap_or_default();
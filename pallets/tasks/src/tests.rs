@@ -1,5 +1,6 @@
 use crate::{mock::*, BatchTaskId, Event, FailedBatchIds};
-use crate::{BatchIdCounter, BatchTxHash, ShardRegistered};
+use crate::{BatchIdCounter, BatchMessage, BatchTxHash, ShardRegistered};
+use crate::{NetworkShards, PendingBatchCount, ReadEventsTask, SyncHeight, TaskCount};
 
 use frame_support::assert_ok;
 use frame_system::RawOrigin;
@@ -9,8 +10,9 @@ use scale_codec::Encode;
 use sp_runtime::BoundedVec;
 use time_primitives::{
 	traits::IdentifyAccount, Commitment, ErrorMsg, GatewayMessage, GatewayOp, GmpEvent, GmpEvents,
-	GmpMessage, MockTssSigner, NetworkId, PublicKey, ShardId, ShardStatus, ShardsInterface, Task,
-	TaskId, TaskResult, TasksInterface, TssPublicKey, TssSignature,
+	GmpMessage, MessageId, MockTssSigner, NetworkId, NetworkStatus, PublicKey, Route, ShardId,
+	ShardStatus, ShardsInterface, Task, TaskId, TaskResult, TasksInterface, TssPublicKey,
+	TssSignature,
 };
 
 const ETHEREUM: NetworkId = 0;
@@ -77,6 +79,21 @@ fn mock_gmp_msg(nonce: u64) -> GmpMessage {
 	}
 }
 
+fn set_network_status(network: NetworkId, status: NetworkStatus) {
+	NETWORK_STATUS.with(|s| s.borrow_mut().insert(network, status));
+	Tasks::network_status_changed(network, status);
+}
+
+fn refunded_messages() -> Vec<MessageId> {
+	System::events()
+		.into_iter()
+		.filter_map(|r| match r.event {
+			RuntimeEvent::Tasks(Event::MessageRefunded(msg_id, ..)) => Some(msg_id),
+			_ => None,
+		})
+		.collect()
+}
+
 fn queue_size<T: crate::Config>(network: NetworkId) -> usize {
 	let insert_i = crate::OpsInsertIndex::<T>::get(network).unwrap_or_default();
 	let remove_i = crate::OpsRemoveIndex::<T>::get(network).unwrap_or_default();
@@ -436,6 +453,190 @@ fn test_restart_failed_batch() {
 	});
 }
 
+#[test]
+fn test_paused_network_stops_reading_and_batching() {
+	new_test_ext().execute_with(|| {
+		register_gateway(ETHEREUM, 42);
+		let shard = create_shard(ETHEREUM, 3, 1);
+		set_network_status(ETHEREUM, NetworkStatus::Paused);
+		roll(1);
+		assert!(Tasks::get_shard_tasks(shard).is_empty());
+		assert_eq!(BatchIdCounter::<Test>::get(), 0);
+		assert_eq!(queue_size::<Test>(ETHEREUM), 1);
+		set_network_status(ETHEREUM, NetworkStatus::Active);
+		roll(1);
+		assert_eq!(Tasks::get_shard_tasks(shard), vec![1]);
+		assert_eq!(BatchIdCounter::<Test>::get(), 1);
+	})
+}
+
+#[test]
+fn test_resumed_network_restarts_read_events() {
+	new_test_ext().execute_with(|| {
+		register_gateway(ETHEREUM, 42);
+		let shard = create_shard(ETHEREUM, 3, 1);
+		roll(1);
+		assert_eq!(Tasks::get_shard_tasks(shard), vec![1]);
+		set_network_status(ETHEREUM, NetworkStatus::Paused);
+		submit_gateway_events(shard, 1, &[]);
+		assert!(Tasks::get_task(3).is_none());
+		assert_eq!(ReadEventsTask::<Test>::get(ETHEREUM), Some(1));
+		set_network_status(ETHEREUM, NetworkStatus::Active);
+		assert_eq!(Tasks::get_task(3), Some(Task::ReadGatewayEvents { blocks: 47..52 }));
+	})
+}
+
+#[test]
+fn test_draining_network_refunds_messages() {
+	new_test_ext().execute_with(|| {
+		register_gateway(ETHEREUM, 42);
+		let shard = create_shard(ETHEREUM, 3, 1);
+		roll(1);
+		let queued = mock_gmp_msg(1);
+		Tasks::ops_queue(ETHEREUM).push(GatewayOp::SendMessage(queued.clone()));
+		Tasks::ops_queue(ETHEREUM).push(GatewayOp::SendMessage(mock_gmp_msg(3)));
+		set_network_status(ETHEREUM, NetworkStatus::Draining);
+		assert!(refunded_messages().is_empty());
+		// refunds queued messages in bounded steps
+		assert!(!Tasks::refund_queued_ops(ETHEREUM, 1).1);
+		assert_eq!(refunded_messages(), vec![queued.message_id()]);
+		assert!(Tasks::refund_queued_ops(ETHEREUM, 2).1);
+		assert_eq!(queue_size::<Test>(ETHEREUM), 0);
+		assert_eq!(refunded_messages(), vec![queued.message_id(), mock_gmp_msg(3).message_id()]);
+		let received = mock_gmp_msg(2);
+		submit_gateway_events(shard, 1, &[GmpEvent::MessageReceived(received.clone())]);
+		assert_eq!(queue_size::<Test>(ETHEREUM), 0);
+		assert_eq!(
+			refunded_messages(),
+			vec![queued.message_id(), mock_gmp_msg(3).message_id(), received.message_id()]
+		);
+		// keeps reading events to finish in-flight batches
+		assert_eq!(Tasks::get_task(3), Some(Task::ReadGatewayEvents { blocks: 47..52 }));
+		roll(1);
+		assert_eq!(BatchIdCounter::<Test>::get(), 1);
+	})
+}
+
+#[test]
+fn test_removed_network_cleans_up_state() {
+	new_test_ext().execute_with(|| {
+		register_gateway(ETHEREUM, 42);
+		let shard = create_shard(ETHEREUM, 3, 1);
+		roll(1);
+		assert_eq!(Tasks::get_task(2), Some(Task::SubmitGatewayMessage { batch_id: 0 }));
+		set_network_status(ETHEREUM, NetworkStatus::Draining);
+		shard_offline(ETHEREUM, shard);
+		set_network_status(ETHEREUM, NetworkStatus::Removed);
+		// removes one item per step
+		let mut steps = 1;
+		while !Tasks::network_removed(ETHEREUM, 1).1 {
+			steps += 1;
+		}
+		assert!(steps > 1);
+		assert!(ReadEventsTask::<Test>::get(ETHEREUM).is_none());
+		assert_eq!(SyncHeight::<Test>::get(ETHEREUM), 0);
+		assert_eq!(queue_size::<Test>(ETHEREUM), 0);
+		assert!(Tasks::get_task(1).is_none());
+		assert!(Tasks::get_task(2).is_none());
+		assert!(BatchMessage::<Test>::get(0).is_none());
+		assert!(BatchTaskId::<Test>::get(0).is_none());
+		assert!(NetworkShards::<Test>::iter_prefix(ETHEREUM).next().is_none());
+		assert_eq!(TaskCount::<Test>::get(ETHEREUM), 0);
+	})
+}
+
+#[test]
+fn test_gateway_migration_finishes_batches_and_registers_shards() {
+	new_test_ext().execute_with(|| {
+		register_gateway(ETHEREUM, 42);
+		let shard = create_shard(ETHEREUM, 3, 1);
+		roll(1);
+		assert_eq!(PendingBatchCount::<Test>::get(ETHEREUM), 1);
+		assert!(Tasks::has_pending_batches(ETHEREUM));
+		Tasks::assign_task(shard, 2);
+		assert!(Tasks::has_pending_batches(ETHEREUM));
+		set_network_status(ETHEREUM, NetworkStatus::Migrating);
+		let msg = mock_gmp_msg(1);
+		submit_gateway_events(
+			shard,
+			1,
+			&[
+				GmpEvent::MessageReceived(msg.clone()),
+				GmpEvent::BatchExecuted { batch_id: 0, tx_hash: None },
+			],
+		);
+		assert_eq!(PendingBatchCount::<Test>::get(ETHEREUM), 0);
+		assert!(!Tasks::has_pending_batches(ETHEREUM));
+		// queued messages aren't batched until the migration completes
+		roll(1);
+		assert_eq!(BatchIdCounter::<Test>::get(), 1);
+		assert_eq!(queue_size::<Test>(ETHEREUM), 1);
+		NETWORK_STATUS.with(|s| s.borrow_mut().insert(ETHEREUM, NetworkStatus::Active));
+		Tasks::gateway_migrated(ETHEREUM, 100);
+		assert_eq!(SyncHeight::<Test>::get(ETHEREUM), 100);
+		roll(1);
+		assert_eq!(
+			Tasks::get_batch_message(1),
+			Some(GatewayMessage {
+				ops: vec![
+					GatewayOp::RegisterShard(MockTssSigner::new(shard).public_key()),
+					GatewayOp::SendMessage(msg),
+				],
+			})
+		);
+	})
+}
+
+#[test]
+fn test_route_changed_sets_route() {
+	new_test_ext().execute_with(|| {
+		register_gateway(ETHEREUM, 42);
+		let route = Route {
+			network_id: ETHEREUM,
+			gateway: [0; 32],
+			relative_gas_price: (1, 1),
+			gas_limit: 100_000,
+			base_fee: 0,
+		};
+		Tasks::route_changed(ETHEREUM, route.clone());
+		roll(1);
+		assert_eq!(
+			Tasks::get_batch_message(0),
+			Some(GatewayMessage {
+				ops: vec![GatewayOp::SetRoute(route)]
+			})
+		);
+	})
+}
+
+#[test]
+fn test_migration_counts_pending_batches() {
+	use crate::migrations::MigrateV0ToV1;
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+	new_test_ext().execute_with(|| {
+		let batches =
+			[(1, ETHEREUM, false), (2, ETHEREUM, true), (3, ETHEREUM, false), (4, 2, false)];
+		for (task_id, network, finished) in batches {
+			crate::Tasks::<Test>::insert(task_id, Task::SubmitGatewayMessage { batch_id: task_id });
+			crate::TaskNetwork::<Test>::insert(task_id, network);
+			if finished {
+				crate::TaskOutput::<Test>::insert(task_id, Ok(()));
+			}
+		}
+		crate::Tasks::<Test>::insert(5, Task::ReadGatewayEvents { blocks: 0..1 });
+		crate::TaskNetwork::<Test>::insert(5, ETHEREUM);
+		StorageVersion::new(0).put::<Tasks>();
+		MigrateV0ToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(PendingBatchCount::<Test>::get(ETHEREUM), 2);
+		assert_eq!(PendingBatchCount::<Test>::get(2), 1);
+		assert_eq!(Tasks::on_chain_storage_version(), 1);
+		// runs only once
+		crate::TaskOutput::<Test>::insert(1, Ok(()));
+		MigrateV0ToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(PendingBatchCount::<Test>::get(ETHEREUM), 2);
+	})
+}
+
 mod bench_helper {
 	use super::*;
 
@@ -13,7 +13,9 @@ use frame_system::RawOrigin;
 #[benchmarks]
 mod benchmarks {
 	use super::*;
-	use frame_support::traits::{Currency, ReservableCurrency};
+	use frame_support::traits::{Currency, Hooks};
+	use polkadot_sdk::sp_runtime::BoundedVec;
+	use scale_info::prelude::vec::Vec;
 
 	#[benchmark]
 	fn deposit() {
@@ -31,12 +33,34 @@ mod benchmarks {
 		let amount: BalanceOf<T> = 5_000_000u32.into();
 		let amount_be: BalanceOf<T> = amount * 100u32.into();
 		T::Currency::resolve_creating(&caller, T::Currency::issue(amount_be));
-		let _ = T::Currency::reserve(&caller, Threshold::<T>::get() + amount);
+		let _ = Timegraph::<T>::reserve(&caller, Threshold::<T>::get() + amount);
 
 		#[extrinsic_call]
 		withdraw(RawOrigin::Signed(caller), amount);
 	}
 
+	#[benchmark]
+	fn claim_withdrawal() {
+		let caller = whitelisted_caller();
+		let amount: BalanceOf<T> = 5_000_000u32.into();
+		let amount_be: BalanceOf<T> = amount * 100u32.into();
+		T::Currency::resolve_creating(&caller, T::Currency::issue(amount_be));
+		let _ = Timegraph::<T>::reserve(&caller, Threshold::<T>::get() + amount);
+		let _ = Timegraph::<T>::withdraw(RawOrigin::Signed(caller.clone()).into(), amount);
+		frame_system::Pallet::<T>::set_block_number(
+			frame_system::Pallet::<T>::block_number() + WithdrawalDelay::<T>::get(),
+		);
+
+		#[extrinsic_call]
+		claim_withdrawal(RawOrigin::Signed(caller), 1);
+	}
+
+	#[benchmark]
+	fn set_withdrawal_delay() {
+		#[extrinsic_call]
+		set_withdrawal_delay(RawOrigin::Root, 10_u32.into());
+	}
+
 	#[benchmark]
 	fn transfer_to_pool() {
 		let caller: T::AccountId = whitelisted_caller();
@@ -47,7 +71,10 @@ mod benchmarks {
 			T::Currency::issue(amount * 100u32.into() + Threshold::<T>::get()),
 		);
 		TimegraphAccount::<T>::set(caller.clone());
-		let _ = T::Currency::reserve(&account, Threshold::<T>::get() + amount);
+		let _ = Timegraph::<T>::reserve(&account, Threshold::<T>::get() + amount * 2u32.into());
+		Plans::<T>::insert(0, mock_plan::<T>());
+		let _ = Timegraph::<T>::subscribe(RawOrigin::Signed(account.clone()).into(), 0);
+		SpendingLimits::<T>::insert(&account, BalanceOf::<T>::from(10_000_000_u32));
 
 		#[extrinsic_call]
 		transfer_to_pool(RawOrigin::Signed(caller), account, amount);
@@ -94,5 +121,95 @@ mod benchmarks {
 		set_threshold(RawOrigin::Root, amount);
 	}
 
+	fn mock_plan<T: Config>() -> SubscriptionPlanOf<T> {
+		SubscriptionPlan {
+			price: 1_000_000_u32.into(),
+			period: 10_u32.into(),
+			usage_cap: 5_000_000_u32.into(),
+		}
+	}
+
+	fn subscriber<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+		let account: T::AccountId = account(name, index, 0);
+		let amount: BalanceOf<T> = 5_000_000_u32.into();
+		T::Currency::resolve_creating(&account, T::Currency::issue(amount * 100u32.into()));
+		let _ = Timegraph::<T>::reserve(&account, amount * 10u32.into());
+		account
+	}
+
+	#[benchmark]
+	fn create_plan() {
+		#[extrinsic_call]
+		create_plan(RawOrigin::Root, mock_plan::<T>());
+	}
+
+	#[benchmark]
+	fn remove_plan() {
+		Plans::<T>::insert(0, mock_plan::<T>());
+
+		#[extrinsic_call]
+		remove_plan(RawOrigin::Root, 0);
+	}
+
+	#[benchmark]
+	fn subscribe() {
+		let caller = subscriber::<T>("caller", 0);
+		Plans::<T>::insert(0, mock_plan::<T>());
+		SpendingLimits::<T>::insert(&caller, BalanceOf::<T>::from(5_000_000_u32));
+
+		#[extrinsic_call]
+		subscribe(RawOrigin::Signed(caller), 0);
+	}
+
+	#[benchmark]
+	fn unsubscribe() {
+		let caller = subscriber::<T>("caller", 0);
+		Plans::<T>::insert(0, mock_plan::<T>());
+		let _ = Timegraph::<T>::subscribe(RawOrigin::Signed(caller.clone()).into(), 0);
+
+		#[extrinsic_call]
+		unsubscribe(RawOrigin::Signed(caller));
+	}
+
+	#[benchmark]
+	fn charge_usage(n: Linear<1, { T::MaxUsageCharges::get() }>) {
+		let caller: T::AccountId = whitelisted_caller();
+		TimegraphAccount::<T>::set(caller.clone());
+		Plans::<T>::insert(0, mock_plan::<T>());
+		let mut charges = Vec::new();
+		for i in 0..n {
+			let account = subscriber::<T>("subscriber", i);
+			let _ = Timegraph::<T>::subscribe(RawOrigin::Signed(account.clone()).into(), 0);
+			SpendingLimits::<T>::insert(&account, BalanceOf::<T>::from(5_000_000_u32));
+			charges.push((account, BalanceOf::<T>::from(1_000_u32)));
+		}
+
+		#[extrinsic_call]
+		charge_usage(RawOrigin::Signed(caller), BoundedVec::truncate_from(charges));
+	}
+
+	#[benchmark]
+	fn set_spending_limit() {
+		let caller: T::AccountId = whitelisted_caller();
+
+		#[extrinsic_call]
+		set_spending_limit(RawOrigin::Signed(caller), Some(5_000_000_u32.into()));
+	}
+
+	#[benchmark]
+	fn charge_subscriptions(n: Linear<1, { T::MaxChargesPerBlock::get() }>) {
+		Plans::<T>::insert(0, mock_plan::<T>());
+		for i in 0..n {
+			let account = subscriber::<T>("subscriber", i);
+			let _ = Timegraph::<T>::subscribe(RawOrigin::Signed(account).into(), 0);
+		}
+		let block = frame_system::Pallet::<T>::block_number() + 10_u32.into();
+
+		#[block]
+		{
+			Timegraph::<T>::on_initialize(block);
+		}
+	}
+
 	impl_benchmark_test_suite!(Timegraph, crate::mock::new_test_ext(), crate::mock::Test);
 }
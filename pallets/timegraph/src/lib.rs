@@ -4,6 +4,18 @@
 //! It maintains a sequence for deposits and withdrawals to ensure order
 //!  and prevent replay attacks.
 //!
+//! Withdrawals are requested first and claimed after a cooldown, during which
+//! the timegraph account can still charge the pending amount.
+//!
+//! The balance reserved by the pallet is tracked per account in `Reserves`,
+//! reserves of other pallets can't be withdrawn or charged.
+//!
+//! Usage is billed from the reserved balance of users. Users subscribe to a
+//! plan created by the admin, which is charged every period in
+//! `on_initialize`, and the timegraph account submits metered charges in
+//! batches, capped per period by the plan. Every charge is paid to the reward
+//! pool and identified by an invoice id. Users can set a spending limit per
+//! period, charges exceeding it are rejected.
 //!
 #![doc = simple_mermaid::mermaid!("../docs/timegraph_flows.mmd")]
 
@@ -12,6 +24,8 @@ pub use pallet::*;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod migrations;
+
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -22,9 +36,12 @@ pub mod pallet {
 	use polkadot_sdk::{frame_support, frame_system, sp_runtime};
 
 	use frame_support::pallet_prelude::*;
-	use frame_support::traits::{Currency, ExistenceRequirement, ReservableCurrency};
+	use frame_support::traits::{
+		BalanceStatus, Currency, ExistenceRequirement, ReservableCurrency,
+	};
 	use frame_system::pallet_prelude::*;
-	use sp_runtime::traits::Saturating;
+	use scale_info::prelude::vec::Vec;
+	use sp_runtime::traits::{One, Saturating, Zero};
 
 	pub type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
@@ -37,6 +54,15 @@ pub mod pallet {
 		fn set_timegraph_account() -> Weight;
 		fn set_reward_pool_account() -> Weight;
 		fn set_threshold() -> Weight;
+		fn create_plan() -> Weight;
+		fn remove_plan() -> Weight;
+		fn subscribe() -> Weight;
+		fn unsubscribe() -> Weight;
+		fn charge_usage(n: u32) -> Weight;
+		fn set_spending_limit() -> Weight;
+		fn charge_subscriptions(n: u32) -> Weight;
+		fn claim_withdrawal() -> Weight;
+		fn set_withdrawal_delay() -> Weight;
 	}
 
 	impl WeightInfo for () {
@@ -67,9 +93,92 @@ pub mod pallet {
 		fn set_threshold() -> Weight {
 			Weight::default()
 		}
+
+		fn create_plan() -> Weight {
+			Weight::default()
+		}
+
+		fn remove_plan() -> Weight {
+			Weight::default()
+		}
+
+		fn subscribe() -> Weight {
+			Weight::default()
+		}
+
+		fn unsubscribe() -> Weight {
+			Weight::default()
+		}
+
+		fn charge_usage(_: u32) -> Weight {
+			Weight::default()
+		}
+
+		fn set_spending_limit() -> Weight {
+			Weight::default()
+		}
+
+		fn charge_subscriptions(_: u32) -> Weight {
+			Weight::default()
+		}
+
+		fn claim_withdrawal() -> Weight {
+			Weight::default()
+		}
+
+		fn set_withdrawal_delay() -> Weight {
+			Weight::default()
+		}
+	}
+
+	/// Withdrawal waiting for its cooldown to pass.
+	#[derive(Clone, Debug, Decode, Encode, TypeInfo, PartialEq, Eq)]
+	pub struct WithdrawalRequest<Balance, BlockNumber> {
+		/// Requested amount.
+		pub amount: Balance,
+		/// Block from which the withdrawal can be claimed.
+		pub unlocks_at: BlockNumber,
+		/// Deposit sequence number of the account when the withdrawal was requested.
+		pub deposit_sequence: u64,
+	}
+
+	pub type WithdrawalRequestOf<T> = WithdrawalRequest<BalanceOf<T>, BlockNumberFor<T>>;
+
+	pub type PlanId = u32;
+	pub type InvoiceId = u64;
+
+	/// Subscription plan created by the admin.
+	#[derive(Clone, Debug, Decode, Encode, TypeInfo, PartialEq, Eq)]
+	pub struct SubscriptionPlan<Balance, BlockNumber> {
+		/// Amount charged every period.
+		pub price: Balance,
+		/// Number of blocks between two charges.
+		pub period: BlockNumber,
+		/// Maximum amount of metered charges per period.
+		pub usage_cap: Balance,
+	}
+
+	/// Subscription of a user to a plan.
+	#[derive(Clone, Debug, Decode, Encode, TypeInfo, PartialEq, Eq)]
+	pub struct Subscription<Balance, BlockNumber> {
+		/// Plan the user subscribed to.
+		pub plan: PlanId,
+		/// Block at which the next period is charged.
+		pub renews_at: BlockNumber,
+		/// Metered charges of the current period.
+		pub usage: Balance,
+		/// Total amount charged in the current period.
+		pub spent: Balance,
 	}
 
+	pub type SubscriptionPlanOf<T> = SubscriptionPlan<BalanceOf<T>, BlockNumberFor<T>>;
+	pub type SubscriptionOf<T> = Subscription<BalanceOf<T>, BlockNumberFor<T>>;
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -85,7 +194,15 @@ pub mod pallet {
 		type InitialTimegraphAccount: Get<Self::AccountId>;
 		#[pallet::constant]
 		type InitialRewardPoolAccount: Get<Self::AccountId>;
+		#[pallet::constant]
+		type InitialWithdrawalDelay: Get<BlockNumberFor<Self>>;
 		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Maximum number of subscriptions charged in `on_initialize`.
+		#[pallet::constant]
+		type MaxChargesPerBlock: Get<u32>;
+		/// Maximum number of metered charges submitted at once.
+		#[pallet::constant]
+		type MaxUsageCharges: Get<u32>;
 	}
 
 	///Stores the next deposit sequence number for each account.
@@ -114,6 +231,73 @@ pub mod pallet {
 	#[pallet::getter(fn threshold)]
 	pub type Threshold<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery, T::InitialThreshold>;
 
+	///Stores the number of blocks between requesting and claiming a withdrawal.
+	#[pallet::storage]
+	pub type WithdrawalDelay<T: Config> =
+		StorageValue<_, BlockNumberFor<T>, ValueQuery, T::InitialWithdrawalDelay>;
+
+	///Stores the pending withdrawals of each account by withdrawal sequence number.
+	#[pallet::storage]
+	pub type WithdrawalRequests<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		u64,
+		WithdrawalRequestOf<T>,
+		OptionQuery,
+	>;
+
+	///Stores the total amount of pending withdrawals of each account.
+	#[pallet::storage]
+	pub type PendingWithdrawals<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	///Stores the id of the next subscription plan.
+	#[pallet::storage]
+	pub type NextPlanId<T: Config> = StorageValue<_, PlanId, ValueQuery>;
+
+	///Stores the subscription plans.
+	#[pallet::storage]
+	pub type Plans<T: Config> =
+		StorageMap<_, Twox64Concat, PlanId, SubscriptionPlanOf<T>, OptionQuery>;
+
+	///Stores the subscription of each account.
+	#[pallet::storage]
+	pub type Subscriptions<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, SubscriptionOf<T>, OptionQuery>;
+
+	///Stores the accounts whose subscription is charged at a block.
+	#[pallet::storage]
+	pub type ChargeQueue<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	///Stores the oldest block of the charge queue which may still have pending charges.
+	#[pallet::storage]
+	pub type NextChargeBlock<T: Config> = StorageValue<_, BlockNumberFor<T>, OptionQuery>;
+
+	///Stores the maximum amount charged to an account per period.
+	#[pallet::storage]
+	pub type SpendingLimits<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, OptionQuery>;
+
+	///Stores the id of the next invoice.
+	#[pallet::storage]
+	pub type NextInvoiceId<T: Config> = StorageValue<_, InvoiceId, ValueQuery>;
+
+	///Stores the balance reserved by timegraph for an account, other pallets'
+	///reserves of the account aren't part of it.
+	#[pallet::storage]
+	pub type Reserves<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -124,11 +308,25 @@ pub mod pallet {
 		/// - `amount`: The amount of funds deposited.
 		/// - `sequence`: The sequence number of the deposit.
 		Deposit { who: T::AccountId, amount: BalanceOf<T>, sequence: u64 },
+		/// Withdrawal requested event
+		///
+		/// # Parameters
+		/// - `who`: The account ID of the user making the withdrawal.
+		/// - `amount`: The amount of funds requested.
+		/// - `sequence`: The sequence number of the withdrawal.
+		/// - `unlocks_at`: The block from which the withdrawal can be claimed.
+		WithdrawalRequested {
+			who: T::AccountId,
+			amount: BalanceOf<T>,
+			sequence: u64,
+			unlocks_at: BlockNumberFor<T>,
+		},
 		/// Withdrawal event
 		///
 		/// # Parameters
 		/// - `who`: The account ID of the user making the withdrawal.
-		/// - `amount`: The amount of funds withdrawn.
+		/// - `amount`: The amount of funds withdrawn, less than requested if the pending
+		///   amount was charged during the cooldown.
 		/// - `sequence`: The sequence number of the withdrawal.
 		Withdrawal { who: T::AccountId, amount: BalanceOf<T>, sequence: u64 },
 
@@ -138,7 +336,13 @@ pub mod pallet {
 		/// - `from`: The account ID of the user transferring the funds.
 		/// - `to`: The account ID of the pool receiving the funds.
 		/// - `amount`: The amount of funds transferred.
-		TransferToPool { from: T::AccountId, to: T::AccountId, amount: BalanceOf<T> },
+		/// - `invoice`: The invoice of the charge.
+		TransferToPool {
+			from: T::AccountId,
+			to: T::AccountId,
+			amount: BalanceOf<T>,
+			invoice: InvoiceId,
+		},
 
 		/// Transfer award to user event
 		///
@@ -162,12 +366,84 @@ pub mod pallet {
 		/// - `new`: The new reward pool account ID.
 		RewardPoolAccountReset { old: T::AccountId, new: T::AccountId },
 
+		/// Withdrawal delay reset event
+		///
+		/// # Parameters
+		/// - `old`: The old withdrawal delay.
+		/// - `new`: The new withdrawal delay.
+		WithdrawalDelayReset { old: BlockNumberFor<T>, new: BlockNumberFor<T> },
+
 		/// Threshold reset event
 		///
 		/// # Parameters
 		/// - `old`: The old threshold value.
 		/// - `new`: The new threshold value.
 		ThresholdReset { old: BalanceOf<T>, new: BalanceOf<T> },
+
+		/// Subscription plan created event
+		///
+		/// # Parameters
+		/// - `plan_id`: The id of the new plan.
+		/// - `plan`: The price, period and usage cap of the plan.
+		PlanCreated { plan_id: PlanId, plan: SubscriptionPlanOf<T> },
+
+		/// Subscription plan removed event
+		///
+		/// # Parameters
+		/// - `plan_id`: The id of the removed plan.
+		PlanRemoved { plan_id: PlanId },
+
+		/// Subscribed event
+		///
+		/// # Parameters
+		/// - `who`: The account ID of the subscriber.
+		/// - `plan_id`: The id of the plan.
+		Subscribed { who: T::AccountId, plan_id: PlanId },
+
+		/// Subscription cancelled event, either by the user or because a period
+		/// couldn't be charged.
+		///
+		/// # Parameters
+		/// - `who`: The account ID of the subscriber.
+		/// - `plan_id`: The id of the plan.
+		SubscriptionCancelled { who: T::AccountId, plan_id: PlanId },
+
+		/// Subscription charged event
+		///
+		/// # Parameters
+		/// - `who`: The account ID of the subscriber.
+		/// - `plan_id`: The id of the plan.
+		/// - `amount`: The amount charged.
+		/// - `invoice`: The id of the invoice.
+		SubscriptionCharged {
+			who: T::AccountId,
+			plan_id: PlanId,
+			amount: BalanceOf<T>,
+			invoice: InvoiceId,
+		},
+
+		/// Metered usage charged event
+		///
+		/// # Parameters
+		/// - `who`: The account ID of the user.
+		/// - `amount`: The amount charged.
+		/// - `invoice`: The id of the invoice.
+		UsageCharged { who: T::AccountId, amount: BalanceOf<T>, invoice: InvoiceId },
+
+		/// Metered usage rejected event, the charge exceeds the usage cap, the
+		/// spending limit or the reserved balance of the user.
+		///
+		/// # Parameters
+		/// - `who`: The account ID of the user.
+		/// - `amount`: The amount of the rejected charge.
+		UsageRejected { who: T::AccountId, amount: BalanceOf<T> },
+
+		/// Spending limit set event
+		///
+		/// # Parameters
+		/// - `who`: The account ID of the user.
+		/// - `limit`: The maximum amount charged per period, `None` if unlimited.
+		SpendingLimitSet { who: T::AccountId, limit: Option<BalanceOf<T>> },
 	}
 
 	#[pallet::error]
@@ -192,6 +468,55 @@ pub mod pallet {
 		SameThreshold,
 		/// The sender is not a timegraph account.
 		SenderIsNotTimegraph,
+		/// The withdrawal request doesn't exist.
+		WithdrawalNotFound,
+		/// The cooldown of the withdrawal request hasn't passed yet.
+		WithdrawalLocked,
+		/// The subscription plan doesn't exist.
+		UnknownPlan,
+		/// The period of a subscription plan is zero.
+		ZeroPeriod,
+		/// The account already has a subscription.
+		AlreadySubscribed,
+		/// The account doesn't have a subscription.
+		NotSubscribed,
+		/// The charge exceeds the spending limit of the account.
+		SpendingLimitExceeded,
+		/// The reserved balance of the account doesn't cover the charge.
+		InsufficientReserve,
+		/// The charge exceeds the usage cap of the plan.
+		UsageCapExceeded,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Charges the subscriptions due at or before `n`, at most `MaxChargesPerBlock`.
+		/// Charges that don't fit into the block are carried over to the next block.
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+			let max = T::MaxChargesPerBlock::get();
+			let mut charged = 0;
+			let mut steps = 0;
+			let mut block = NextChargeBlock::<T>::get().unwrap_or(n);
+			while block <= n && charged < max && steps < max {
+				steps += 1;
+				let remaining = max - charged;
+				let due: Vec<T::AccountId> =
+					ChargeQueue::<T>::iter_key_prefix(block).take(remaining as usize).collect();
+				let exhausted = (due.len() as u32) < remaining;
+				for who in due {
+					ChargeQueue::<T>::remove(block, &who);
+					Self::renew_subscription(&who);
+					charged += 1;
+				}
+				if !exhausted {
+					break;
+				}
+				block = block.saturating_add(One::one());
+			}
+			NextChargeBlock::<T>::put(block);
+			T::WeightInfo::charge_subscriptions(charged)
+				.saturating_add(T::DbWeight::get().reads_writes(steps.into(), 1))
+		}
 	}
 
 	#[pallet::call]
@@ -211,7 +536,7 @@ pub mod pallet {
 			let who = ensure_signed(origin)?;
 			ensure!(amount > 0_u32.into(), Error::<T>::ZeroAmount);
 
-			T::Currency::reserve(&who, amount)?;
+			Self::reserve(&who, amount)?;
 
 			NextDepositSequence::<T>::try_mutate(&who, |x| -> DispatchResult {
 				*x = x.checked_add(1).ok_or(Error::<T>::SequenceNumberOverflow)?;
@@ -227,34 +552,31 @@ pub mod pallet {
 			Ok(())
 		}
 
-		/// The extrinsic from timegraph allows account to refund the token to the user
+		/// The extrinsic from timegraph allows the user to request a refund, which can be
+		/// claimed with [`Pallet::claim_withdrawal`] once the withdrawal delay passed.
 		///
 		/// # Flow
 		/// 1. Ensure the origin is a signed account.
 		/// 2. Validate the amount is greater than zero.
-		/// 3. Ensure the sender and receiver are not the same.
-		/// 4. Validate the withdrawal sequence number.
-		/// 5. Transfer the funds.
-		/// 6. Increment the withdrawal sequence number.
-		/// 7. Emit a [`Event::Withdrawal`] event.
+		/// 3. Ensure the reserve covers the pending withdrawals, the amount and the threshold.
+		/// 4. Increment the withdrawal sequence number.
+		/// 5. Queue the request under the withdrawal sequence number.
+		/// 6. Emit a [`Event::WithdrawalRequested`] event.
 		#[pallet::call_index(1)]
 		#[pallet::weight(T::WeightInfo::withdraw())]
 		pub fn withdraw(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			ensure!(amount > 0_u32.into(), Error::<T>::ZeroAmount);
 
-			let current_reserve = T::Currency::reserved_balance(&who);
+			let current_reserve = Reserves::<T>::get(&who);
 
 			let threshold = Threshold::<T>::get();
 
-			ensure!(
-				amount.saturating_add(threshold) <= current_reserve,
-				Error::<T>::WithdrawalAmountOverReserve
-			);
+			let pending = PendingWithdrawals::<T>::get(&who);
 
 			ensure!(
-				T::Currency::unreserve(&who, amount) == 0_u32.into(),
-				Error::<T>::NotWithdrawalRequired
+				pending.saturating_add(amount).saturating_add(threshold) <= current_reserve,
+				Error::<T>::WithdrawalAmountOverReserve
 			);
 
 			NextWithdrawalSequence::<T>::try_mutate(&who, |x| -> DispatchResult {
@@ -262,10 +584,25 @@ pub mod pallet {
 				Ok(())
 			})?;
 
-			Self::deposit_event(Event::Withdrawal {
-				who: who.clone(),
+			let sequence = NextWithdrawalSequence::<T>::get(&who);
+			let unlocks_at = frame_system::Pallet::<T>::block_number()
+				.saturating_add(WithdrawalDelay::<T>::get());
+			WithdrawalRequests::<T>::insert(
+				&who,
+				sequence,
+				WithdrawalRequest {
+					amount,
+					unlocks_at,
+					deposit_sequence: NextDepositSequence::<T>::get(&who),
+				},
+			);
+			PendingWithdrawals::<T>::insert(&who, pending.saturating_add(amount));
+
+			Self::deposit_event(Event::WithdrawalRequested {
+				who,
 				amount,
-				sequence: NextWithdrawalSequence::<T>::get(&who),
+				sequence,
+				unlocks_at,
 			});
 			Ok(())
 		}
@@ -274,8 +611,9 @@ pub mod pallet {
 		///
 		/// # Flow
 		/// 1. Ensure the origin is the timegraph account.
-		/// 2. Unreserve the specified amount from the given account.
-		/// 3. Transfer the unreserved funds to the reward pool account.
+		/// 2. Ensure the amount is within the reserve of the account, the usage cap of
+		///    its plan if it is subscribed and its spending limit.
+		/// 3. Charge the amount from the reserved balance and record it as spent.
 		/// 4. Emit a [`Event::TransferToPool`] event.
 		#[pallet::call_index(2)]
 		#[pallet::weight(T::WeightInfo::transfer_to_pool())]
@@ -285,20 +623,18 @@ pub mod pallet {
 			amount: BalanceOf<T>,
 		) -> DispatchResult {
 			Self::ensure_timegraph(origin)?;
-			let unserved = T::Currency::unreserve(&account, amount);
-			ensure!(unserved == 0_u32.into(), Error::<T>::NotWithdrawalRequired);
-
-			T::Currency::transfer(
-				&account,
-				&RewardPoolAccount::<T>::get(),
-				amount,
-				ExistenceRequirement::KeepAlive,
-			)?;
+			ensure!(Reserves::<T>::get(&account) >= amount, Error::<T>::NotWithdrawalRequired);
+			let subscription = Self::spend(&account, amount)?;
+			let invoice = Self::charge(&account, amount)?;
+			if let Some(subscription) = subscription {
+				Subscriptions::<T>::insert(&account, subscription);
+			}
 
 			Self::deposit_event(Event::TransferToPool {
-				from: account.clone(),
+				from: account,
 				to: RewardPoolAccount::<T>::get(),
 				amount,
+				invoice,
 			});
 
 			Ok(())
@@ -334,7 +670,7 @@ pub mod pallet {
 				ExistenceRequirement::KeepAlive,
 			)?;
 
-			T::Currency::reserve(&account, amount)?;
+			Self::reserve(&account, amount)?;
 
 			Self::deposit_event(Event::TransferAwardToUser {
 				from: RewardPoolAccount::<T>::get(),
@@ -427,9 +763,333 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// The extrinsic allows creating a subscription plan
+		///
+		/// # Flow
+		/// 1. Ensure the origin is the admin.
+		/// 2. Ensure the period is not zero.
+		/// 3. Store the plan under the next plan id.
+		/// 4. Emit a [`Event::PlanCreated`] event.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::create_plan())]
+		pub fn create_plan(origin: OriginFor<T>, plan: SubscriptionPlanOf<T>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(!plan.period.is_zero(), Error::<T>::ZeroPeriod);
+
+			let plan_id = NextPlanId::<T>::get();
+			NextPlanId::<T>::put(plan_id.saturating_add(1));
+			Plans::<T>::insert(plan_id, plan.clone());
+
+			Self::deposit_event(Event::PlanCreated { plan_id, plan });
+
+			Ok(())
+		}
+
+		/// The extrinsic allows removing a subscription plan. Subscriptions to the plan
+		/// are cancelled when their next period is due.
+		///
+		/// # Flow
+		/// 1. Ensure the origin is the admin.
+		/// 2. Remove the plan.
+		/// 3. Emit a [`Event::PlanRemoved`] event.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::remove_plan())]
+		pub fn remove_plan(origin: OriginFor<T>, plan_id: PlanId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(Plans::<T>::take(plan_id).is_some(), Error::<T>::UnknownPlan);
+
+			Self::deposit_event(Event::PlanRemoved { plan_id });
+
+			Ok(())
+		}
+
+		/// The extrinsic allows a user to subscribe to a plan
+		///
+		/// # Flow
+		/// 1. Ensure the origin is a signed account without a subscription.
+		/// 2. Ensure the plan exists and its price is within the spending limit.
+		/// 3. Charge the first period from the reserved balance.
+		/// 4. Schedule the next charge.
+		/// 5. Emit [`Event::Subscribed`] and [`Event::SubscriptionCharged`] events.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::subscribe())]
+		pub fn subscribe(origin: OriginFor<T>, plan_id: PlanId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Subscriptions::<T>::get(&who).is_none(), Error::<T>::AlreadySubscribed);
+			let plan = Plans::<T>::get(plan_id).ok_or(Error::<T>::UnknownPlan)?;
+			Self::ensure_spending_limit(&who, plan.price)?;
+
+			let invoice = Self::charge(&who, plan.price)?;
+			let renews_at = frame_system::Pallet::<T>::block_number().saturating_add(plan.period);
+			Subscriptions::<T>::insert(
+				&who,
+				Subscription {
+					plan: plan_id,
+					renews_at,
+					usage: Zero::zero(),
+					spent: plan.price,
+				},
+			);
+			ChargeQueue::<T>::insert(renews_at, &who, ());
+
+			Self::deposit_event(Event::Subscribed { who: who.clone(), plan_id });
+			Self::deposit_event(Event::SubscriptionCharged {
+				who,
+				plan_id,
+				amount: plan.price,
+				invoice,
+			});
+
+			Ok(())
+		}
+
+		/// The extrinsic allows a user to cancel its subscription. The current period
+		/// is not refunded.
+		///
+		/// # Flow
+		/// 1. Ensure the origin is a signed account with a subscription.
+		/// 2. Remove the subscription and its scheduled charge.
+		/// 3. Emit a [`Event::SubscriptionCancelled`] event.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::unsubscribe())]
+		pub fn unsubscribe(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let subscription = Subscriptions::<T>::take(&who).ok_or(Error::<T>::NotSubscribed)?;
+			ChargeQueue::<T>::remove(subscription.renews_at, &who);
+
+			Self::deposit_event(Event::SubscriptionCancelled {
+				who,
+				plan_id: subscription.plan,
+			});
+
+			Ok(())
+		}
+
+		/// The extrinsic from timegraph allows charging metered usage in batches
+		///
+		/// # Flow
+		/// 1. Ensure the origin is the timegraph account.
+		/// 2. For every charge, ensure the user has a subscription and the charge is
+		///    within the usage cap of the plan and the spending limit of the user.
+		/// 3. Charge the amount from the reserved balance.
+		/// 4. Emit a [`Event::UsageCharged`] event, or a [`Event::UsageRejected`] event if
+		///    the charge failed.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::charge_usage(charges.len() as u32))]
+		pub fn charge_usage(
+			origin: OriginFor<T>,
+			charges: BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxUsageCharges>,
+		) -> DispatchResult {
+			Self::ensure_timegraph(origin)?;
+			for (who, amount) in charges {
+				if amount.is_zero() {
+					continue;
+				}
+				if Self::charge_usage_of(&who, amount).is_err() {
+					Self::deposit_event(Event::UsageRejected { who, amount });
+				}
+			}
+			Ok(())
+		}
+
+		/// The extrinsic allows a user to limit the amount charged per period
+		///
+		/// # Flow
+		/// 1. Ensure the origin is a signed account.
+		/// 2. Store the spending limit, `None` removes it.
+		/// 3. Emit a [`Event::SpendingLimitSet`] event.
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::set_spending_limit())]
+		pub fn set_spending_limit(
+			origin: OriginFor<T>,
+			limit: Option<BalanceOf<T>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			SpendingLimits::<T>::set(&who, limit);
+
+			Self::deposit_event(Event::SpendingLimitSet { who, limit });
+
+			Ok(())
+		}
+
+		/// The extrinsic allows the user to claim a withdrawal after its delay
+		///
+		/// # Flow
+		/// 1. Ensure the origin is a signed account.
+		/// 2. Ensure the withdrawal request exists and its delay passed.
+		/// 3. Unreserve the requested amount, keeping the threshold if the reserve was
+		///    charged during the cooldown.
+		/// 4. Remove the request.
+		/// 5. Emit a [`Event::Withdrawal`] event.
+		#[pallet::call_index(13)]
+		#[pallet::weight(T::WeightInfo::claim_withdrawal())]
+		pub fn claim_withdrawal(origin: OriginFor<T>, sequence: u64) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let request = WithdrawalRequests::<T>::get(&who, sequence)
+				.ok_or(Error::<T>::WithdrawalNotFound)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() >= request.unlocks_at,
+				Error::<T>::WithdrawalLocked
+			);
+
+			let available = Reserves::<T>::get(&who).saturating_sub(Threshold::<T>::get());
+			let amount = request.amount.min(available);
+			let remaining = Self::unreserve(&who, amount);
+			let amount = amount.saturating_sub(remaining);
+
+			WithdrawalRequests::<T>::remove(&who, sequence);
+			PendingWithdrawals::<T>::mutate_exists(&who, |pending| {
+				let rest = pending.unwrap_or_default().saturating_sub(request.amount);
+				*pending = if rest.is_zero() { None } else { Some(rest) };
+			});
+
+			Self::deposit_event(Event::Withdrawal { who, amount, sequence });
+			Ok(())
+		}
+
+		/// The extrinsic allows setting a new withdrawal delay
+		///
+		/// # Flow
+		/// 1. Ensure the origin is the admin.
+		/// 2. Emit a [`Event::WithdrawalDelayReset`] event.
+		/// 3. Set the new withdrawal delay, pending requests keep their delay.
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::WeightInfo::set_withdrawal_delay())]
+		pub fn set_withdrawal_delay(
+			origin: OriginFor<T>,
+			delay: BlockNumberFor<T>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Self::deposit_event(Event::WithdrawalDelayReset {
+				old: WithdrawalDelay::<T>::get(),
+				new: delay,
+			});
+
+			WithdrawalDelay::<T>::set(delay);
+
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		/// Reserves `amount` of `who` and adds it to [`Reserves`].
+		pub(crate) fn reserve(who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+			T::Currency::reserve(who, amount)?;
+			Reserves::<T>::mutate(who, |reserve| *reserve = reserve.saturating_add(amount));
+			Ok(())
+		}
+
+		/// Unreserves up to `amount` of the [`Reserves`] of `who`, returns the amount
+		/// which couldn't be unreserved.
+		fn unreserve(who: &T::AccountId, amount: BalanceOf<T>) -> BalanceOf<T> {
+			let amount = amount.min(Reserves::<T>::get(who));
+			let remaining = T::Currency::unreserve(who, amount);
+			Self::sub_reserve(who, amount.saturating_sub(remaining));
+			remaining
+		}
+
+		fn sub_reserve(who: &T::AccountId, amount: BalanceOf<T>) {
+			Reserves::<T>::mutate_exists(who, |reserve| {
+				let rest = reserve.unwrap_or_default().saturating_sub(amount);
+				*reserve = if rest.is_zero() { None } else { Some(rest) };
+			});
+		}
+
+		/// Charges `amount` from the [`Reserves`] of `who` to the reward pool.
+		fn charge(who: &T::AccountId, amount: BalanceOf<T>) -> Result<InvoiceId, DispatchError> {
+			ensure!(Reserves::<T>::get(who) >= amount, Error::<T>::InsufficientReserve);
+			let remaining = T::Currency::repatriate_reserved(
+				who,
+				&RewardPoolAccount::<T>::get(),
+				amount,
+				BalanceStatus::Free,
+			)?;
+			ensure!(remaining.is_zero(), Error::<T>::InsufficientReserve);
+			Self::sub_reserve(who, amount);
+			Ok(NextInvoiceId::<T>::mutate(|id| {
+				let invoice = *id;
+				*id = id.saturating_add(1);
+				invoice
+			}))
+		}
+
+		/// Ensures the spending limit of `who` allows spending `spent` in a period.
+		fn ensure_spending_limit(who: &T::AccountId, spent: BalanceOf<T>) -> DispatchResult {
+			if let Some(limit) = SpendingLimits::<T>::get(who) {
+				ensure!(spent <= limit, Error::<T>::SpendingLimitExceeded);
+			}
+			Ok(())
+		}
+
+		/// Charges the next period of the subscription of `who` or cancels it if the
+		/// plan was removed or the period can't be charged.
+		fn renew_subscription(who: &T::AccountId) {
+			let Some(mut subscription) = Subscriptions::<T>::get(who) else {
+				return;
+			};
+			let plan_id = subscription.plan;
+			let result: Result<_, DispatchError> =
+				Plans::<T>::get(plan_id).ok_or(Error::<T>::UnknownPlan.into()).and_then(|plan| {
+					Self::ensure_spending_limit(who, plan.price)?;
+					let invoice = Self::charge(who, plan.price)?;
+					Ok((plan, invoice))
+				});
+			match result {
+				Ok((plan, invoice)) => {
+					subscription.renews_at = subscription.renews_at.saturating_add(plan.period);
+					subscription.usage = Zero::zero();
+					subscription.spent = plan.price;
+					ChargeQueue::<T>::insert(subscription.renews_at, who, ());
+					Subscriptions::<T>::insert(who, subscription);
+					Self::deposit_event(Event::SubscriptionCharged {
+						who: who.clone(),
+						plan_id,
+						amount: plan.price,
+						invoice,
+					});
+				},
+				Err(_) => {
+					Subscriptions::<T>::remove(who);
+					Self::deposit_event(Event::SubscriptionCancelled { who: who.clone(), plan_id });
+				},
+			}
+		}
+
+		/// Ensures spending `amount` is within the usage cap of the plan of `who`, if
+		/// it is subscribed, and its spending limit. Returns the subscription with the
+		/// amount recorded, which is stored once the amount was charged.
+		fn spend(
+			who: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> Result<Option<SubscriptionOf<T>>, DispatchError> {
+			let Some(mut subscription) = Subscriptions::<T>::get(who) else {
+				Self::ensure_spending_limit(who, amount)?;
+				return Ok(None);
+			};
+			let plan = Plans::<T>::get(subscription.plan).ok_or(Error::<T>::UnknownPlan)?;
+			subscription.usage = subscription.usage.saturating_add(amount);
+			ensure!(subscription.usage <= plan.usage_cap, Error::<T>::UsageCapExceeded);
+			subscription.spent = subscription.spent.saturating_add(amount);
+			Self::ensure_spending_limit(who, subscription.spent)?;
+			Ok(Some(subscription))
+		}
+
+		/// Charges metered usage of `who` within the usage cap of its plan and its
+		/// spending limit.
+		fn charge_usage_of(who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+			let subscription = Self::spend(who, amount)?.ok_or(Error::<T>::NotSubscribed)?;
+			let invoice = Self::charge(who, amount)?;
+			Subscriptions::<T>::insert(who, subscription);
+			Self::deposit_event(Event::UsageCharged {
+				who: who.clone(),
+				amount,
+				invoice,
+			});
+			Ok(())
+		}
+
 		/// Ensures that the origin is the current timegraph account.
 		///
 		/// # Parameters
@@ -0,0 +1,38 @@
+//! Storage migrations of the timegraph pallet.
+use crate::{Config, NextDepositSequence, Pallet, Reserves};
+
+use polkadot_sdk::{frame_support, frame_system, sp_runtime};
+
+use core::marker::PhantomData;
+
+use frame_support::{
+	migrations::VersionedMigration,
+	traits::{Get, ReservableCurrency, UncheckedOnRuntimeUpgrade},
+	weights::Weight,
+};
+use sp_runtime::traits::Zero;
+
+/// Seeds [`Reserves`] with the reserved balance of every account which deposited
+/// to timegraph, before the pallet tracked its own reserves all of them were
+/// withdrawable and chargeable.
+pub struct InitReserves<T>(PhantomData<T>);
+
+impl<T: Config> UncheckedOnRuntimeUpgrade for InitReserves<T> {
+	fn on_runtime_upgrade() -> Weight {
+		let mut reads = 0u64;
+		let mut writes = 0u64;
+		for account in NextDepositSequence::<T>::iter_keys() {
+			reads += 2;
+			let reserved = T::Currency::reserved_balance(&account);
+			if !reserved.is_zero() {
+				Reserves::<T>::insert(&account, reserved);
+				writes += 1;
+			}
+		}
+		T::DbWeight::get().reads_writes(reads, writes.saturating_add(1))
+	}
+}
+
+/// Initializes [`Reserves`] and bumps the storage version to 1.
+pub type MigrateV0ToV1<T> =
+	VersionedMigration<0, 1, InitReserves<T>, Pallet<T>, <T as frame_system::Config>::DbWeight>;
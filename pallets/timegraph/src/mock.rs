@@ -5,7 +5,7 @@ use polkadot_sdk::{
 };
 
 use frame_support::derive_impl;
-use sp_core::{ConstU128, ConstU64};
+use sp_core::{ConstU128, ConstU32, ConstU64};
 use sp_runtime::{traits::IdentityLookup, BuildStorage};
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -50,7 +50,10 @@ impl pallet_timegraph::Config for Test {
 	type InitialThreshold = ConstU128<1_000>;
 	type InitialRewardPoolAccount = ConstU64<1>;
 	type InitialTimegraphAccount = ConstU64<2>;
+	type InitialWithdrawalDelay = ConstU64<5>;
 	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxChargesPerBlock = ConstU32<2>;
+	type MaxUsageCharges = ConstU32<10>;
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
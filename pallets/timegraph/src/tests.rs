@@ -1,12 +1,15 @@
 use crate as pallet_timegraph;
 use crate::mock::*;
-use crate::Error;
+use crate::{Error, Event, SubscriptionPlan};
 
 use polkadot_sdk::{frame_support, frame_system, pallet_balances, sp_runtime};
 
-use frame_support::{assert_noop, assert_ok, traits::Currency};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Currency, Hooks, ReservableCurrency},
+};
 use frame_system::RawOrigin;
-use sp_runtime::traits::BadOrigin;
+use sp_runtime::{traits::BadOrigin, BoundedVec};
 
 #[test]
 fn deposit_works() {
@@ -89,9 +92,134 @@ fn test_withdraw_success() {
 		));
 		let reserved = <Test as crate::pallet::Config>::Currency::reserved_balance(user);
 		assert_eq!(reserved, amount + pallet_timegraph::Threshold::<Test>::get(),);
-		assert_ok!(Timegraph::withdraw(origin.into(), amount));
+		assert_ok!(Timegraph::withdraw(origin.clone().into(), amount));
+		System::assert_last_event(
+			Event::<Test>::WithdrawalRequested {
+				who: user,
+				amount,
+				sequence: 1,
+				unlocks_at: 6,
+			}
+			.into(),
+		);
+		// funds stay reserved until the withdrawal is claimed
+		let reserved = <Test as crate::pallet::Config>::Currency::reserved_balance(user);
+		assert_eq!(reserved, amount + pallet_timegraph::Threshold::<Test>::get());
+		assert_noop!(
+			Timegraph::claim_withdrawal(origin.clone().into(), 1),
+			Error::<Test>::WithdrawalLocked
+		);
+		System::set_block_number(6);
+		assert_ok!(Timegraph::claim_withdrawal(origin.clone().into(), 1));
+		System::assert_last_event(
+			Event::<Test>::Withdrawal { who: user, amount, sequence: 1 }.into(),
+		);
 		let reserved = <Test as crate::pallet::Config>::Currency::reserved_balance(user);
 		assert_eq!(reserved, pallet_timegraph::Threshold::<Test>::get());
+		assert_noop!(
+			Timegraph::claim_withdrawal(origin.into(), 1),
+			Error::<Test>::WithdrawalNotFound
+		);
+	});
+}
+
+#[test]
+fn test_withdraw_counts_pending_requests() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let threshold = pallet_timegraph::Threshold::<Test>::get();
+		assert_ok!(Timegraph::deposit(RawOrigin::Signed(user).into(), 1000 + threshold));
+		assert_ok!(Timegraph::withdraw(RawOrigin::Signed(user).into(), 600));
+		assert_noop!(
+			Timegraph::withdraw(RawOrigin::Signed(user).into(), 600),
+			Error::<Test>::WithdrawalAmountOverReserve
+		);
+		assert_ok!(Timegraph::withdraw(RawOrigin::Signed(user).into(), 400));
+		assert_eq!(pallet_timegraph::PendingWithdrawals::<Test>::get(user), 1000);
+		let request = pallet_timegraph::WithdrawalRequests::<Test>::get(user, 2).unwrap();
+		assert_eq!(request.amount, 400);
+		assert_eq!(request.deposit_sequence, 1);
+	});
+}
+
+#[test]
+fn test_claim_withdrawal_after_transfer_to_pool() {
+	new_test_ext().execute_with(|| {
+		let user = 3;
+		let timegraph = Timegraph::timegraph_account();
+		let threshold = pallet_timegraph::Threshold::<Test>::get();
+		Balances::make_free_balance_be(&user, 10_000);
+		assert_ok!(Timegraph::deposit(RawOrigin::Signed(user).into(), 1000 + threshold));
+		assert_ok!(Timegraph::withdraw(RawOrigin::Signed(user).into(), 1000));
+		// the timegraph account charges in flight queries during the cooldown
+		assert_ok!(Timegraph::transfer_to_pool(RawOrigin::Signed(timegraph).into(), user, 300));
+		System::set_block_number(6);
+		assert_ok!(Timegraph::claim_withdrawal(RawOrigin::Signed(user).into(), 1));
+		System::assert_last_event(
+			Event::<Test>::Withdrawal {
+				who: user,
+				amount: 700,
+				sequence: 1,
+			}
+			.into(),
+		);
+		assert_eq!(Balances::reserved_balance(user), threshold);
+		assert_eq!(pallet_timegraph::PendingWithdrawals::<Test>::get(user), 0);
+	});
+}
+
+#[test]
+fn reserves_of_other_pallets_cannot_be_withdrawn() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let threshold = pallet_timegraph::Threshold::<Test>::get();
+		Balances::make_free_balance_be(&user, 10_000);
+		// reserved by another pallet
+		assert_ok!(Balances::reserve(&user, 5_000));
+		assert_noop!(
+			Timegraph::withdraw(RawOrigin::Signed(user).into(), 1_000),
+			Error::<Test>::WithdrawalAmountOverReserve
+		);
+		assert_ok!(Timegraph::deposit(RawOrigin::Signed(user).into(), 1_000 + threshold));
+		assert_eq!(pallet_timegraph::Reserves::<Test>::get(user), 1_000 + threshold);
+		assert_noop!(
+			Timegraph::withdraw(RawOrigin::Signed(user).into(), 1_001),
+			Error::<Test>::WithdrawalAmountOverReserve
+		);
+		assert_ok!(Timegraph::withdraw(RawOrigin::Signed(user).into(), 1_000));
+		System::set_block_number(6);
+		assert_ok!(Timegraph::claim_withdrawal(RawOrigin::Signed(user).into(), 1));
+		assert_eq!(pallet_timegraph::Reserves::<Test>::get(user), threshold);
+		assert_eq!(Balances::reserved_balance(user), 5_000 + threshold);
+	});
+}
+
+#[test]
+fn reserves_of_other_pallets_cannot_be_transferred_to_pool() {
+	new_test_ext().execute_with(|| {
+		let timegraph = 1;
+		let user = 2;
+		pallet_timegraph::TimegraphAccount::<Test>::set(timegraph);
+		Balances::make_free_balance_be(&user, 10_000);
+		assert_ok!(Balances::reserve(&user, 5_000));
+		assert_ok!(Timegraph::deposit(RawOrigin::Signed(user).into(), 500));
+		assert_noop!(
+			Timegraph::transfer_to_pool(RawOrigin::Signed(timegraph).into(), user, 501),
+			Error::<Test>::NotWithdrawalRequired
+		);
+		assert_ok!(Timegraph::transfer_to_pool(RawOrigin::Signed(timegraph).into(), user, 500));
+		assert!(!pallet_timegraph::Reserves::<Test>::contains_key(user));
+		assert_eq!(Balances::reserved_balance(user), 5_000);
+	});
+}
+
+#[test]
+fn test_set_withdrawal_delay() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Timegraph::set_withdrawal_delay(RawOrigin::Signed(1).into(), 10), BadOrigin);
+		assert_ok!(Timegraph::set_withdrawal_delay(RawOrigin::Root.into(), 10));
+		System::assert_last_event(Event::<Test>::WithdrawalDelayReset { old: 5, new: 10 }.into());
+		assert_eq!(pallet_timegraph::WithdrawalDelay::<Test>::get(), 10);
 	});
 }
 
@@ -464,3 +592,322 @@ fn ensure_timegraph_fails_with_incorrect_origin() {
 		);
 	});
 }
+
+const USER: AccountId = 3;
+const PRICE: u128 = 1_000;
+const PERIOD: u64 = 10;
+const USAGE_CAP: u128 = 500;
+
+fn create_plan() -> u32 {
+	let plan_id = pallet_timegraph::NextPlanId::<Test>::get();
+	assert_ok!(Timegraph::create_plan(
+		RawOrigin::Root.into(),
+		SubscriptionPlan {
+			price: PRICE,
+			period: PERIOD,
+			usage_cap: USAGE_CAP,
+		},
+	));
+	plan_id
+}
+
+fn subscribe(user: AccountId, deposit: u128) -> u32 {
+	Balances::make_free_balance_be(&user, 10_000);
+	assert_ok!(Timegraph::deposit(RawOrigin::Signed(user).into(), deposit));
+	let plan_id = create_plan();
+	assert_ok!(Timegraph::subscribe(RawOrigin::Signed(user).into(), plan_id));
+	plan_id
+}
+
+fn charge_usage(charges: Vec<(AccountId, u128)>) {
+	assert_ok!(Timegraph::charge_usage(
+		RawOrigin::Signed(Timegraph::timegraph_account()).into(),
+		BoundedVec::truncate_from(charges),
+	));
+}
+
+#[test]
+fn create_plan_requires_admin_and_period() {
+	new_test_ext().execute_with(|| {
+		let plan = SubscriptionPlan {
+			price: PRICE,
+			period: 0,
+			usage_cap: 0,
+		};
+		assert_noop!(Timegraph::create_plan(RawOrigin::Signed(1).into(), plan.clone()), BadOrigin);
+		assert_noop!(
+			Timegraph::create_plan(RawOrigin::Root.into(), plan),
+			Error::<Test>::ZeroPeriod
+		);
+		let plan_id = create_plan();
+		assert_eq!(plan_id, 0);
+		assert_ok!(Timegraph::remove_plan(RawOrigin::Root.into(), plan_id));
+		assert_noop!(
+			Timegraph::remove_plan(RawOrigin::Root.into(), plan_id),
+			Error::<Test>::UnknownPlan
+		);
+	});
+}
+
+#[test]
+fn subscribe_charges_first_period() {
+	new_test_ext().execute_with(|| {
+		let pool = Timegraph::reward_pool_account();
+		let pool_balance = Balances::free_balance(pool);
+		let plan_id = subscribe(USER, 5_000);
+		assert_eq!(Balances::reserved_balance(USER), 4_000);
+		assert_eq!(Balances::free_balance(pool), pool_balance + PRICE);
+		System::assert_last_event(
+			Event::<Test>::SubscriptionCharged {
+				who: USER,
+				plan_id,
+				amount: PRICE,
+				invoice: 0,
+			}
+			.into(),
+		);
+		assert_noop!(
+			Timegraph::subscribe(RawOrigin::Signed(USER).into(), plan_id),
+			Error::<Test>::AlreadySubscribed
+		);
+	});
+}
+
+#[test]
+fn subscribe_fails_without_reserve() {
+	new_test_ext().execute_with(|| {
+		let plan_id = create_plan();
+		assert_noop!(
+			Timegraph::subscribe(RawOrigin::Signed(USER).into(), plan_id),
+			Error::<Test>::InsufficientReserve
+		);
+		assert_noop!(
+			Timegraph::subscribe(RawOrigin::Signed(USER).into(), plan_id + 1),
+			Error::<Test>::UnknownPlan
+		);
+	});
+}
+
+#[test]
+fn reserves_of_other_pallets_cannot_be_charged() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&USER, 10_000);
+		assert_ok!(Balances::reserve(&USER, 5_000));
+		let plan_id = create_plan();
+		assert_noop!(
+			Timegraph::subscribe(RawOrigin::Signed(USER).into(), plan_id),
+			Error::<Test>::InsufficientReserve
+		);
+		assert_ok!(Timegraph::deposit(RawOrigin::Signed(USER).into(), PRICE));
+		assert_ok!(Timegraph::subscribe(RawOrigin::Signed(USER).into(), plan_id));
+		assert_eq!(pallet_timegraph::Reserves::<Test>::get(USER), 0);
+		assert_eq!(Balances::reserved_balance(USER), 5_000);
+	});
+}
+
+#[test]
+fn subscription_renews_in_on_initialize() {
+	new_test_ext().execute_with(|| {
+		let plan_id = subscribe(USER, 5_000);
+		Timegraph::on_initialize(PERIOD);
+		assert_eq!(Balances::reserved_balance(USER), 4_000);
+		Timegraph::on_initialize(PERIOD + 1);
+		assert_eq!(Balances::reserved_balance(USER), 3_000);
+		System::assert_last_event(
+			Event::<Test>::SubscriptionCharged {
+				who: USER,
+				plan_id,
+				amount: PRICE,
+				invoice: 1,
+			}
+			.into(),
+		);
+		let subscription = pallet_timegraph::Subscriptions::<Test>::get(USER).unwrap();
+		assert_eq!(subscription.renews_at, 2 * PERIOD + 1);
+		assert_eq!(subscription.spent, PRICE);
+	});
+}
+
+#[test]
+fn subscription_cancelled_when_reserve_runs_out() {
+	new_test_ext().execute_with(|| {
+		let plan_id = subscribe(USER, 1_500);
+		Timegraph::on_initialize(PERIOD + 1);
+		assert_eq!(Balances::reserved_balance(USER), 500);
+		assert!(pallet_timegraph::Subscriptions::<Test>::get(USER).is_none());
+		System::assert_last_event(
+			Event::<Test>::SubscriptionCancelled { who: USER, plan_id }.into(),
+		);
+	});
+}
+
+#[test]
+fn subscription_cancelled_when_plan_removed() {
+	new_test_ext().execute_with(|| {
+		let plan_id = subscribe(USER, 5_000);
+		assert_ok!(Timegraph::remove_plan(RawOrigin::Root.into(), plan_id));
+		Timegraph::on_initialize(PERIOD + 1);
+		assert_eq!(Balances::reserved_balance(USER), 4_000);
+		System::assert_last_event(
+			Event::<Test>::SubscriptionCancelled { who: USER, plan_id }.into(),
+		);
+	});
+}
+
+#[test]
+fn unsubscribe_stops_charges() {
+	new_test_ext().execute_with(|| {
+		subscribe(USER, 5_000);
+		assert_ok!(Timegraph::unsubscribe(RawOrigin::Signed(USER).into()));
+		assert_noop!(
+			Timegraph::unsubscribe(RawOrigin::Signed(USER).into()),
+			Error::<Test>::NotSubscribed
+		);
+		Timegraph::on_initialize(PERIOD + 1);
+		assert_eq!(Balances::reserved_balance(USER), 4_000);
+	});
+}
+
+#[test]
+fn charges_exceeding_block_limit_are_carried_over() {
+	new_test_ext().execute_with(|| {
+		for user in 3..6 {
+			subscribe(user, 5_000);
+		}
+		Timegraph::on_initialize(PERIOD + 1);
+		let charged = (3..6).filter(|user| Balances::reserved_balance(user) == 3_000).count();
+		assert_eq!(charged, 2);
+		Timegraph::on_initialize(PERIOD + 2);
+		for user in 3..6 {
+			assert_eq!(Balances::reserved_balance(user), 3_000);
+		}
+	});
+}
+
+#[test]
+fn charge_usage_respects_usage_cap() {
+	new_test_ext().execute_with(|| {
+		subscribe(USER, 5_000);
+		assert_noop!(
+			Timegraph::charge_usage(
+				RawOrigin::Signed(USER).into(),
+				BoundedVec::truncate_from(vec![(USER, 100)]),
+			),
+			Error::<Test>::SenderIsNotTimegraph
+		);
+		charge_usage(vec![(USER, 300)]);
+		System::assert_last_event(
+			Event::<Test>::UsageCharged {
+				who: USER,
+				amount: 300,
+				invoice: 1,
+			}
+			.into(),
+		);
+		charge_usage(vec![(USER, 300)]);
+		System::assert_last_event(Event::<Test>::UsageRejected { who: USER, amount: 300 }.into());
+		assert_eq!(Balances::reserved_balance(USER), 3_700);
+		// usage is reset every period
+		Timegraph::on_initialize(PERIOD + 1);
+		charge_usage(vec![(USER, 300), (4, 100)]);
+		assert_eq!(Balances::reserved_balance(USER), 2_400);
+		System::assert_last_event(Event::<Test>::UsageRejected { who: 4, amount: 100 }.into());
+	});
+}
+
+#[test]
+fn spending_limit_is_enforced() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Timegraph::set_spending_limit(RawOrigin::Signed(USER).into(), Some(PRICE - 1)));
+		System::assert_last_event(
+			Event::<Test>::SpendingLimitSet {
+				who: USER,
+				limit: Some(PRICE - 1),
+			}
+			.into(),
+		);
+		Balances::make_free_balance_be(&USER, 10_000);
+		assert_ok!(Timegraph::deposit(RawOrigin::Signed(USER).into(), 5_000));
+		let plan_id = create_plan();
+		assert_noop!(
+			Timegraph::subscribe(RawOrigin::Signed(USER).into(), plan_id),
+			Error::<Test>::SpendingLimitExceeded
+		);
+		assert_ok!(Timegraph::set_spending_limit(
+			RawOrigin::Signed(USER).into(),
+			Some(PRICE + 100)
+		));
+		assert_ok!(Timegraph::subscribe(RawOrigin::Signed(USER).into(), plan_id));
+		charge_usage(vec![(USER, 200)]);
+		System::assert_last_event(Event::<Test>::UsageRejected { who: USER, amount: 200 }.into());
+		charge_usage(vec![(USER, 100)]);
+		assert_eq!(Balances::reserved_balance(USER), 3_900);
+		// lowering the limit below the plan price cancels the subscription on renewal
+		assert_ok!(Timegraph::set_spending_limit(RawOrigin::Signed(USER).into(), Some(PRICE - 1)));
+		Timegraph::on_initialize(PERIOD + 1);
+		assert!(pallet_timegraph::Subscriptions::<Test>::get(USER).is_none());
+	});
+}
+
+#[test]
+fn transfer_to_pool_is_within_usage_cap_and_spending_limit() {
+	new_test_ext().execute_with(|| {
+		let timegraph = Timegraph::timegraph_account();
+		subscribe(USER, 5_000);
+		assert_ok!(Timegraph::set_spending_limit(
+			RawOrigin::Signed(USER).into(),
+			Some(PRICE + 300)
+		));
+		assert_ok!(Timegraph::transfer_to_pool(RawOrigin::Signed(timegraph).into(), USER, 200));
+		System::assert_last_event(
+			Event::<Test>::TransferToPool {
+				from: USER,
+				to: Timegraph::reward_pool_account(),
+				amount: 200,
+				invoice: 1,
+			}
+			.into(),
+		);
+		let subscription = pallet_timegraph::Subscriptions::<Test>::get(USER).unwrap();
+		assert_eq!(subscription.usage, 200);
+		assert_eq!(subscription.spent, PRICE + 200);
+		assert_noop!(
+			Timegraph::transfer_to_pool(RawOrigin::Signed(timegraph).into(), USER, 200),
+			Error::<Test>::SpendingLimitExceeded
+		);
+		assert_ok!(Timegraph::set_spending_limit(RawOrigin::Signed(USER).into(), None));
+		assert_noop!(
+			Timegraph::transfer_to_pool(RawOrigin::Signed(timegraph).into(), USER, 400),
+			Error::<Test>::UsageCapExceeded
+		);
+		// without a subscription only the spending limit applies
+		assert_ok!(Timegraph::unsubscribe(RawOrigin::Signed(USER).into()));
+		assert_ok!(Timegraph::set_spending_limit(RawOrigin::Signed(USER).into(), Some(100)));
+		assert_noop!(
+			Timegraph::transfer_to_pool(RawOrigin::Signed(timegraph).into(), USER, 200),
+			Error::<Test>::SpendingLimitExceeded
+		);
+		assert_ok!(Timegraph::transfer_to_pool(RawOrigin::Signed(timegraph).into(), USER, 100));
+	});
+}
+
+#[test]
+fn migration_seeds_reserves_of_depositors() {
+	use crate::migrations::MigrateV0ToV1;
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&USER, 10_000);
+		Balances::make_free_balance_be(&2, 10_000);
+		// reserves made before the pallet tracked them
+		assert_ok!(Balances::reserve(&USER, 3_000));
+		assert_ok!(Balances::reserve(&2, 1_000));
+		pallet_timegraph::NextDepositSequence::<Test>::insert(USER, 2);
+		StorageVersion::new(0).put::<Timegraph>();
+		MigrateV0ToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(pallet_timegraph::Reserves::<Test>::get(USER), 3_000);
+		// accounts which never deposited keep their reserves to other pallets
+		assert_eq!(pallet_timegraph::Reserves::<Test>::get(2), 0);
+		assert_eq!(Timegraph::on_chain_storage_version(), 1);
+		assert_ok!(Timegraph::withdraw(RawOrigin::Signed(USER).into(), 1_000));
+	});
+}
@@ -0,0 +1,90 @@
+use super::*;
+use crate::Pallet;
+
+use polkadot_sdk::frame_benchmarking::{account, benchmarks, BenchmarkError};
+use polkadot_sdk::{frame_support, frame_system, sp_std};
+
+use frame_support::{
+	traits::{EnsureOrigin, Get},
+	BoundedVec,
+};
+use frame_system::RawOrigin;
+use sp_std::vec::Vec;
+
+fn candidate<T: Config>(index: u32) -> T::AccountId {
+	let who: T::AccountId = account("candidate", index, 0);
+	let balance = T::CandidateDeposit::get() * 10u32.into() + T::Currency::minimum_balance();
+	T::Currency::make_free_balance_be(&who, balance);
+	who
+}
+
+fn apply<T: Config>(index: u32) -> T::AccountId {
+	let who = candidate::<T>(index);
+	let (keys, proof) = T::BenchmarkHelper::candidate(&who);
+	Pallet::<T>::apply(RawOrigin::Signed(who.clone()).into(), keys, proof).unwrap();
+	who
+}
+
+fn fill_queue<T: Config>(n: u32) -> Vec<T::AccountId> {
+	(0..n).map(apply::<T>).collect()
+}
+
+fn validators<T: Config>(n: u32) -> Vec<T::ValidatorId> {
+	(0..n).map(|i| account("validator", i, 0)).collect()
+}
+
+benchmarks! {
+	register_validators {
+		let n in 1 .. T::MaxValidators::get();
+		let origin = T::PrivilegedOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let validators = BoundedVec::truncate_from(validators::<T>(n));
+	}: _<T::RuntimeOrigin>(origin, validators)
+	verify {
+		assert_eq!(ValidatorsToAdd::<T>::decode_len(), Some(n as usize));
+	}
+
+	deregister_validators {
+		let n in 1 .. T::MaxValidators::get();
+		let origin = T::PrivilegedOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let validators = BoundedVec::truncate_from(validators::<T>(n));
+	}: _<T::RuntimeOrigin>(origin, validators)
+	verify {
+		assert_eq!(ValidatorsToRetire::<T>::decode_len(), Some(n as usize));
+	}
+
+	apply {
+		fill_queue::<T>(T::MaxCandidates::get() - 1);
+		let who = candidate::<T>(T::MaxCandidates::get());
+		let (keys, proof) = T::BenchmarkHelper::candidate(&who);
+	}: _(RawOrigin::Signed(who.clone()), keys, proof)
+	verify {
+		assert_eq!(Pallet::<T>::candidate_status(who), Some(CandidateStatus::Pending));
+	}
+
+	withdraw_application {
+		let who = fill_queue::<T>(T::MaxCandidates::get()).pop().unwrap();
+	}: _(RawOrigin::Signed(who.clone()))
+	verify {
+		assert_eq!(Pallet::<T>::candidate_status(who), None);
+	}
+
+	approve_candidates {
+		let n in 1 .. T::MaxCandidates::get();
+		let origin = T::PrivilegedOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let candidates = BoundedVec::truncate_from(fill_queue::<T>(n));
+	}: _<T::RuntimeOrigin>(origin, candidates)
+	verify {
+		assert_eq!(ApprovedCandidates::<T>::decode_len(), Some(n as usize));
+	}
+
+	reject_candidates {
+		let n in 1 .. T::MaxCandidates::get();
+		let origin = T::PrivilegedOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let candidates = BoundedVec::truncate_from(fill_queue::<T>(n));
+	}: _<T::RuntimeOrigin>(origin, candidates)
+	verify {
+		assert!(CandidateQueue::<T>::get().is_empty());
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -17,13 +17,62 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 //! A pallet for managing validators on early timechain, stolen from Rococo.
+//!
+//! Besides the privileged registration, candidates can onboard themselves:
+//! they reserve a deposit and submit their session keys with a proof of
+//! possession through [`Pallet::apply`]. Governance approves or rejects the
+//! queued candidates in bulk, approved candidates join the validator set at
+//! the next session. Deposits are returned on rejection, withdrawal or once
+//! the validator is retired.
+//!
+//! The proof of possession is checked by [`Config::KeysProof`]: each session
+//! key signs the [`keys_proof_statement`] of the candidate account.
+//!
+//! The pallet wraps the session manager of the runtime, usually staking, and
+//! merges the validators it manages into the set elected by it.
 pub use pallet::*;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
 use polkadot_sdk::*;
 
+use frame_support::traits::{Currency, ReservableCurrency};
+use scale_codec::Encode;
+use sp_runtime::traits::Convert;
 use sp_std::vec::Vec;
+use time_primitives::CandidateStatus;
 
 type Session<T> = pallet_session::Pallet<T>;
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Context of the statement signed by the session keys of a candidate.
+pub const KEYS_PROOF_CONTEXT: &[u8] = b"analog/validator-keys";
+
+/// Returns the statement each session key of `who` signs to prove its
+/// possession.
+pub fn keys_proof_statement<AccountId: Encode>(who: &AccountId) -> Vec<u8> {
+	(KEYS_PROOF_CONTEXT, who).encode()
+}
+
+/// Verifies the proof of possession of the session keys of a candidate.
+pub trait KeysProof<AccountId, Keys> {
+	/// Returns `true` if `proof` holds a signature of the
+	/// [`keys_proof_statement`] of `who` by each key of `keys`.
+	fn verify(who: &AccountId, keys: &Keys, proof: &[u8]) -> bool;
+}
+
+/// Prepares candidates for the benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<AccountId, Keys> {
+	/// Enables `who` to set session keys, returns new keys and their proof.
+	fn candidate(who: &AccountId) -> (Keys, Vec<u8>);
+}
 
 #[polkadot_sdk::frame_support::pallet]
 pub mod pallet {
@@ -31,6 +80,48 @@ pub mod pallet {
 	use frame_support::{dispatch::DispatchResult, pallet_prelude::*, traits::EnsureOrigin};
 	use frame_system::pallet_prelude::*;
 
+	pub trait WeightInfo {
+		fn register_validators(n: u32) -> Weight;
+		fn deregister_validators(n: u32) -> Weight;
+		fn apply() -> Weight;
+		fn withdraw_application() -> Weight;
+		fn approve_candidates(n: u32) -> Weight;
+		fn reject_candidates(n: u32) -> Weight;
+	}
+
+	impl WeightInfo for () {
+		fn register_validators(_: u32) -> Weight {
+			Weight::default()
+		}
+
+		fn deregister_validators(_: u32) -> Weight {
+			Weight::default()
+		}
+
+		fn apply() -> Weight {
+			Weight::default()
+		}
+
+		fn withdraw_application() -> Weight {
+			Weight::default()
+		}
+
+		fn approve_candidates(_: u32) -> Weight {
+			Weight::default()
+		}
+
+		fn reject_candidates(_: u32) -> Weight {
+			Weight::default()
+		}
+	}
+
+	/// Validator candidate which onboarded itself.
+	#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct Candidate<Balance> {
+		pub deposit: Balance,
+		pub status: CandidateStatus,
+	}
+
 	#[pallet::pallet]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
@@ -38,16 +129,50 @@ pub mod pallet {
 	/// Configuration for the parachain proposer.
 	#[pallet::config]
 	pub trait Config:
-		polkadot_sdk::frame_system::Config + polkadot_sdk::pallet_session::Config
+		polkadot_sdk::frame_system::Config
+		+ polkadot_sdk::pallet_session::Config
+		+ polkadot_sdk::pallet_session::historical::Config
 	{
 		/// The overreaching event type.
 		type RuntimeEvent: From<Event<Self>>
 			+ IsType<<Self as polkadot_sdk::frame_system::Config>::RuntimeEvent>;
 
+		/// The weight information of this pallet.
+		type WeightInfo: WeightInfo;
+
 		/// Privileged origin that can add or remove validators.
 		type PrivilegedOrigin: EnsureOrigin<
 			<Self as polkadot_sdk::frame_system::Config>::RuntimeOrigin,
 		>;
+
+		/// Session manager electing the validators the managed ones are
+		/// merged into.
+		type InnerSessionManager: pallet_session::historical::SessionManager<
+			Self::ValidatorId,
+			Self::FullIdentification,
+		>;
+
+		/// Verifies the proof of possession of the session keys of candidates.
+		type KeysProof: KeysProof<Self::AccountId, Self::Keys>;
+
+		/// Currency used for candidate deposits.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Deposit reserved from candidates until they are rejected or retired.
+		#[pallet::constant]
+		type CandidateDeposit: Get<BalanceOf<Self>>;
+
+		/// Maximum number of candidates waiting for approval.
+		#[pallet::constant]
+		type MaxCandidates: Get<u32>;
+
+		/// Maximum number of validators managed by the pallet.
+		#[pallet::constant]
+		type MaxValidators: Get<u32>;
+
+		/// Prepares candidates for the benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<Self::AccountId, Self::Keys>;
 	}
 
 	#[pallet::event]
@@ -57,16 +182,71 @@ pub mod pallet {
 		ValidatorsRegistered(Vec<T::ValidatorId>),
 		/// Validators were removed from the set.
 		ValidatorsDeregistered(Vec<T::ValidatorId>),
+		/// A candidate bonded its deposit and entered the queue.
+		CandidateApplied { who: T::AccountId, deposit: BalanceOf<T> },
+		/// A candidate left the queue and got its deposit back.
+		CandidateWithdrawn { who: T::AccountId },
+		/// A candidate was approved and is activated at the next session.
+		CandidateApproved { who: T::AccountId },
+		/// A candidate was rejected and got its deposit back.
+		CandidateRejected { who: T::AccountId },
+		/// An approved candidate joined the validator set.
+		CandidateActivated { who: T::AccountId },
+		/// A candidate was removed from the validator set and got its deposit back.
+		CandidateRetired { who: T::AccountId },
+		/// An approved candidate could not join the validator set and got its
+		/// deposit back.
+		CandidateDropped { who: T::AccountId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account already is a candidate.
+		AlreadyCandidate,
+		/// The candidate queue is full.
+		TooManyCandidates,
+		/// The account isn't a pending candidate.
+		NotPending,
+		/// The session keys aren't signed by their owner.
+		InvalidKeysProof,
+		/// The set of managed validators is full.
+		TooManyValidators,
 	}
 
 	/// Validators that should be retired, because their Parachain was deregistered.
 	#[pallet::storage]
 	pub(crate) type ValidatorsToRetire<T: Config> =
-		StorageValue<_, Vec<T::ValidatorId>, ValueQuery>;
+		StorageValue<_, BoundedVec<T::ValidatorId, T::MaxValidators>, ValueQuery>;
 
 	/// Validators that should be added.
 	#[pallet::storage]
-	pub(crate) type ValidatorsToAdd<T: Config> = StorageValue<_, Vec<T::ValidatorId>, ValueQuery>;
+	pub(crate) type ValidatorsToAdd<T: Config> =
+		StorageValue<_, BoundedVec<T::ValidatorId, T::MaxValidators>, ValueQuery>;
+
+	/// Validators merged into the set of the inner session manager.
+	#[pallet::storage]
+	pub type ManagedValidators<T: Config> =
+		StorageValue<_, BoundedVec<T::ValidatorId, T::MaxValidators>, ValueQuery>;
+
+	/// Self-onboarded candidates.
+	#[pallet::storage]
+	pub type Candidates<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, Candidate<BalanceOf<T>>, OptionQuery>;
+
+	/// Candidates waiting for approval, in application order.
+	#[pallet::storage]
+	pub type CandidateQueue<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxCandidates>, ValueQuery>;
+
+	/// Approved candidates activated at the next session.
+	#[pallet::storage]
+	pub type ApprovedCandidates<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxCandidates>, ValueQuery>;
+
+	/// Accounts of the active candidates by validator id.
+	#[pallet::storage]
+	pub type ActiveCandidates<T: Config> =
+		StorageMap<_, Twox64Concat, T::ValidatorId, T::AccountId, OptionQuery>;
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -74,17 +254,22 @@ pub mod pallet {
 		///
 		/// The new validators will be active from current session + 2.
 		#[pallet::call_index(0)]
-		#[pallet::weight({100_000})]
+		#[pallet::weight(<T as Config>::WeightInfo::register_validators(validators.len() as u32))]
 		pub fn register_validators(
 			origin: OriginFor<T>,
-			validators: Vec<T::ValidatorId>,
+			validators: BoundedVec<T::ValidatorId, T::MaxValidators>,
 		) -> DispatchResult {
 			T::PrivilegedOrigin::ensure_origin(origin)?;
+			Self::ensure_capacity(validators.len())?;
 
-			#[allow(clippy::redundant_closure)]
-			validators.clone().into_iter().for_each(|v| ValidatorsToAdd::<T>::append(v));
+			ValidatorsToAdd::<T>::try_mutate(|to_add| {
+				validators
+					.iter()
+					.try_for_each(|v| to_add.try_push(v.clone()))
+					.map_err(|_| Error::<T>::TooManyValidators)
+			})?;
 
-			Self::deposit_event(Event::ValidatorsRegistered(validators));
+			Self::deposit_event(Event::ValidatorsRegistered(validators.into_inner()));
 			Ok(())
 		}
 
@@ -92,61 +277,292 @@ pub mod pallet {
 		///
 		/// The removed validators will be deactivated from current session + 2.
 		#[pallet::call_index(1)]
-		#[pallet::weight({100_000})]
+		#[pallet::weight(<T as Config>::WeightInfo::deregister_validators(validators.len() as u32))]
 		pub fn deregister_validators(
 			origin: OriginFor<T>,
-			validators: Vec<T::ValidatorId>,
+			validators: BoundedVec<T::ValidatorId, T::MaxValidators>,
 		) -> DispatchResult {
 			T::PrivilegedOrigin::ensure_origin(origin)?;
 
-			#[allow(clippy::redundant_closure)]
-			validators.clone().into_iter().for_each(|v| ValidatorsToRetire::<T>::append(v));
+			ValidatorsToRetire::<T>::try_mutate(|to_retire| {
+				validators
+					.iter()
+					.try_for_each(|v| to_retire.try_push(v.clone()))
+					.map_err(|_| Error::<T>::TooManyValidators)
+			})?;
+
+			Self::deposit_event(Event::ValidatorsDeregistered(validators.into_inner()));
+			Ok(())
+		}
+
+		/// Applies as validator candidate.
+		///
+		/// Reserves the candidate deposit and sets the session keys of the
+		/// caller, `proof` holds the signatures of the keys checked by
+		/// [`Config::KeysProof`].
+		#[pallet::call_index(2)]
+		#[pallet::weight(<T as Config>::WeightInfo::apply())]
+		pub fn apply(origin: OriginFor<T>, keys: T::Keys, proof: Vec<u8>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Candidates::<T>::contains_key(&who), Error::<T>::AlreadyCandidate);
+			ensure!(T::KeysProof::verify(&who, &keys, &proof), Error::<T>::InvalidKeysProof);
+			CandidateQueue::<T>::try_append(&who).map_err(|_| Error::<T>::TooManyCandidates)?;
+			let deposit = T::CandidateDeposit::get();
+			T::Currency::reserve(&who, deposit)?;
+			Session::<T>::set_keys(
+				frame_system::RawOrigin::Signed(who.clone()).into(),
+				keys,
+				proof,
+			)?;
+			Candidates::<T>::insert(
+				&who,
+				Candidate {
+					deposit,
+					status: CandidateStatus::Pending,
+				},
+			);
+			Self::deposit_event(Event::CandidateApplied { who, deposit });
+			Ok(())
+		}
 
-			Self::deposit_event(Event::ValidatorsDeregistered(validators));
+		/// Leaves the candidate queue, returning the deposit.
+		#[pallet::call_index(3)]
+		#[pallet::weight(<T as Config>::WeightInfo::withdraw_application())]
+		pub fn withdraw_application(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::remove_pending(&who)?;
+			Self::deposit_event(Event::CandidateWithdrawn { who });
+			Ok(())
+		}
+
+		/// Approves pending candidates, which join the validator set at the
+		/// next session.
+		#[pallet::call_index(4)]
+		#[pallet::weight(<T as Config>::WeightInfo::approve_candidates(candidates.len() as u32))]
+		pub fn approve_candidates(
+			origin: OriginFor<T>,
+			candidates: BoundedVec<T::AccountId, T::MaxCandidates>,
+		) -> DispatchResult {
+			T::PrivilegedOrigin::ensure_origin(origin)?;
+			Self::ensure_capacity(candidates.len())?;
+			let mut queue = CandidateQueue::<T>::get();
+			let mut approved = ApprovedCandidates::<T>::get();
+			for who in candidates {
+				let pos = queue.iter().position(|c| *c == who).ok_or(Error::<T>::NotPending)?;
+				queue.remove(pos);
+				approved.try_push(who.clone()).map_err(|_| Error::<T>::TooManyCandidates)?;
+				Candidates::<T>::mutate(&who, |candidate| {
+					if let Some(candidate) = candidate {
+						candidate.status = CandidateStatus::Approved;
+					}
+				});
+				Self::deposit_event(Event::CandidateApproved { who });
+			}
+			CandidateQueue::<T>::put(queue);
+			ApprovedCandidates::<T>::put(approved);
+			Ok(())
+		}
+
+		/// Rejects pending candidates, returning their deposits.
+		#[pallet::call_index(5)]
+		#[pallet::weight(<T as Config>::WeightInfo::reject_candidates(candidates.len() as u32))]
+		pub fn reject_candidates(
+			origin: OriginFor<T>,
+			candidates: BoundedVec<T::AccountId, T::MaxCandidates>,
+		) -> DispatchResult {
+			T::PrivilegedOrigin::ensure_origin(origin)?;
+			for who in candidates {
+				Self::remove_pending(&who)?;
+				Self::deposit_event(Event::CandidateRejected { who });
+			}
 			Ok(())
 		}
 	}
-}
 
-impl<T: Config> pallet_session::SessionManager<T::ValidatorId> for Pallet<T> {
-	fn new_session(new_index: u32) -> Option<Vec<T::ValidatorId>> {
-		if new_index <= 1 {
-			return None;
+	impl<T: Config> Pallet<T> {
+		fn remove_pending(who: &T::AccountId) -> DispatchResult {
+			CandidateQueue::<T>::try_mutate(|queue| {
+				let pos = queue.iter().position(|c| c == who).ok_or(Error::<T>::NotPending)?;
+				queue.remove(pos);
+				Ok::<_, DispatchError>(())
+			})?;
+			if let Some(candidate) = Candidates::<T>::take(who) {
+				T::Currency::unreserve(who, candidate.deposit);
+			}
+			Ok(())
+		}
+
+		/// Ensures `additional` validators fit into the managed set, counting
+		/// the ones waiting to be added.
+		fn ensure_capacity(additional: usize) -> DispatchResult {
+			let len = ManagedValidators::<T>::decode_len().unwrap_or_default()
+				+ ValidatorsToAdd::<T>::decode_len().unwrap_or_default()
+				+ ApprovedCandidates::<T>::decode_len().unwrap_or_default();
+			ensure!(
+				len.saturating_add(additional) <= T::MaxValidators::get() as usize,
+				Error::<T>::TooManyValidators
+			);
+			Ok(())
+		}
+
+		/// Returns the onboarding status of `who`.
+		pub fn candidate_status(who: T::AccountId) -> Option<CandidateStatus> {
+			Candidates::<T>::get(who).map(|candidate| candidate.status)
+		}
+
+		/// Returns all self-onboarded candidates and their status.
+		pub fn candidates() -> Vec<(T::AccountId, CandidateStatus)> {
+			Candidates::<T>::iter()
+				.map(|(who, candidate)| (who, candidate.status))
+				.collect()
 		}
 
-		let mut validators = Session::<T>::validators();
+		/// Activates the approved candidates, candidates without validator id
+		/// or room in the managed set are dropped.
+		fn activate_candidates(managed: &mut BoundedVec<T::ValidatorId, T::MaxValidators>) {
+			for who in ApprovedCandidates::<T>::take() {
+				let validator = T::ValidatorIdOf::convert(who.clone()).filter(|validator| {
+					managed.contains(validator) || managed.try_push(validator.clone()).is_ok()
+				});
+				let Some(validator) = validator else {
+					if let Some(candidate) = Candidates::<T>::take(&who) {
+						T::Currency::unreserve(&who, candidate.deposit);
+					}
+					Self::deposit_event(Event::CandidateDropped { who });
+					continue;
+				};
+				ActiveCandidates::<T>::insert(&validator, &who);
+				Candidates::<T>::mutate(&who, |candidate| {
+					if let Some(candidate) = candidate {
+						candidate.status = CandidateStatus::Active;
+					}
+				});
+				Self::deposit_event(Event::CandidateActivated { who });
+			}
+		}
 
-		ValidatorsToRetire::<T>::take().iter().for_each(|v| {
-			if let Some(pos) = validators.iter().position(|r| r == v) {
-				validators.swap_remove(pos);
+		/// Returns the deposits of retired candidates.
+		fn retire_candidates(retired: &[T::ValidatorId]) {
+			for validator in retired {
+				let Some(who) = ActiveCandidates::<T>::take(validator) else {
+					continue;
+				};
+				if let Some(candidate) = Candidates::<T>::take(&who) {
+					T::Currency::unreserve(&who, candidate.deposit);
+				}
+				Self::deposit_event(Event::CandidateRetired { who });
 			}
-		});
+		}
 
-		ValidatorsToAdd::<T>::take().into_iter().for_each(|v| {
-			if !validators.contains(&v) {
-				validators.push(v);
+		/// Applies the pending changes to the managed validators, returns the
+		/// retired validators or `None` if nothing changed.
+		pub(crate) fn update_managed_validators() -> Option<Vec<T::ValidatorId>> {
+			let retired = ValidatorsToRetire::<T>::take();
+			let added = ValidatorsToAdd::<T>::take();
+			if retired.is_empty()
+				&& added.is_empty()
+				&& ApprovedCandidates::<T>::decode_len().unwrap_or_default() == 0
+			{
+				return None;
 			}
+
+			let mut managed = ManagedValidators::<T>::get();
+			managed.retain(|v| !retired.contains(v));
+			Self::retire_candidates(&retired);
+
+			for validator in added {
+				if !managed.contains(&validator) && managed.try_push(validator).is_err() {
+					log::warn!(target: "runtime::validators", "managed validator set is full");
+				}
+			}
+			Self::activate_candidates(&mut managed);
+
+			ManagedValidators::<T>::put(managed);
+			Some(retired.into_inner())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T>
+where
+	T::FullIdentification: Default,
+{
+	fn full_identification(validator: T::ValidatorId) -> (T::ValidatorId, T::FullIdentification) {
+		let identification =
+			T::FullIdentificationOf::convert(validator.clone()).unwrap_or_default();
+		(validator, identification)
+	}
+}
+
+impl<T: Config> pallet_session::historical::SessionManager<T::ValidatorId, T::FullIdentification>
+	for Pallet<T>
+where
+	T::FullIdentification: Default,
+{
+	fn new_session(new_index: u32) -> Option<Vec<(T::ValidatorId, T::FullIdentification)>> {
+		let elected = <T::InnerSessionManager as pallet_session::historical::SessionManager<
+			_,
+			_,
+		>>::new_session(new_index);
+		if new_index <= 1 {
+			return elected;
+		}
+
+		let retired = Self::update_managed_validators();
+		if elected.is_none() && retired.is_none() {
+			return None;
+		}
+		let retired = retired.unwrap_or_default();
+
+		let mut validators = elected.unwrap_or_else(|| {
+			Session::<T>::validators().into_iter().map(Self::full_identification).collect()
 		});
+		validators.retain(|(v, _)| !retired.contains(v));
+		for validator in ManagedValidators::<T>::get() {
+			if !validators.iter().any(|(v, _)| *v == validator) {
+				validators.push(Self::full_identification(validator));
+			}
+		}
 
 		Some(validators)
 	}
 
-	fn end_session(_: u32) {}
+	fn new_session_genesis(new_index: u32) -> Option<Vec<(T::ValidatorId, T::FullIdentification)>> {
+		<T::InnerSessionManager as pallet_session::historical::SessionManager<_, _>>::new_session_genesis(new_index)
+	}
+
+	fn start_session(start_index: u32) {
+		<T::InnerSessionManager as pallet_session::historical::SessionManager<_, _>>::start_session(
+			start_index,
+		)
+	}
 
-	fn start_session(_start_index: u32) {}
+	fn end_session(end_index: u32) {
+		<T::InnerSessionManager as pallet_session::historical::SessionManager<_, _>>::end_session(
+			end_index,
+		)
+	}
 }
 
-impl<T: Config> pallet_session::historical::SessionManager<T::ValidatorId, ()> for Pallet<T> {
-	fn new_session(new_index: u32) -> Option<Vec<(T::ValidatorId, ())>> {
-		<Self as pallet_session::SessionManager<_>>::new_session(new_index)
-			.map(|r| r.into_iter().map(|v| (v, Default::default())).collect())
+impl<T: Config> pallet_session::SessionManager<T::ValidatorId> for Pallet<T>
+where
+	T::FullIdentification: Default,
+{
+	fn new_session(new_index: u32) -> Option<Vec<T::ValidatorId>> {
+		<Self as pallet_session::historical::SessionManager<_, _>>::new_session(new_index)
+			.map(|r| r.into_iter().map(|(v, _)| v).collect())
 	}
 
-	fn start_session(start_index: u32) {
-		<Self as pallet_session::SessionManager<_>>::start_session(start_index)
+	fn new_session_genesis(new_index: u32) -> Option<Vec<T::ValidatorId>> {
+		<Self as pallet_session::historical::SessionManager<_, _>>::new_session_genesis(new_index)
+			.map(|r| r.into_iter().map(|(v, _)| v).collect())
 	}
 
 	fn end_session(end_index: u32) {
-		<Self as pallet_session::SessionManager<_>>::end_session(end_index)
+		<Self as pallet_session::historical::SessionManager<_, _>>::end_session(end_index)
+	}
+
+	fn start_session(start_index: u32) {
+		<Self as pallet_session::historical::SessionManager<_, _>>::start_session(start_index)
 	}
 }
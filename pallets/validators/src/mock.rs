@@ -0,0 +1,157 @@
+use crate::{self as pallet_validators, keys_proof_statement};
+
+use polkadot_sdk::*;
+
+use frame_support::{
+	construct_runtime, derive_impl, ord_parameter_types, parameter_types,
+	traits::{ConstU32, ConstU64},
+};
+use frame_system::EnsureSignedBy;
+use scale_codec::{DecodeAll, Encode};
+use sp_runtime::{
+	impl_opaque_keys,
+	testing::{TestSignature, UintAuthorityId},
+	traits::{Convert, OpaqueKeys},
+	BuildStorage, KeyTypeId, RuntimeAppPublic,
+};
+use sp_std::vec::Vec;
+
+pub type AccountId = u64;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		Balances: pallet_balances,
+		Session: pallet_session,
+		Validators: pallet_validators,
+	}
+);
+
+ord_parameter_types! {
+	pub const Admin: AccountId = 100;
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type AccountData = pallet_balances::AccountData<u64>;
+	type Block = Block;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+impl_opaque_keys! {
+	pub struct MockSessionKeys {
+		pub dummy: UintAuthorityId,
+	}
+}
+
+/// Returns session keys of `who` and their proof of possession.
+pub fn keys(who: AccountId) -> (MockSessionKeys, Vec<u8>) {
+	let keys = MockSessionKeys { dummy: UintAuthorityId(who) };
+	let proof = keys.dummy.sign(&keys_proof_statement(&who)).unwrap().encode();
+	(keys, proof)
+}
+
+pub struct TestSessionHandler;
+impl pallet_session::SessionHandler<AccountId> for TestSessionHandler {
+	const KEY_TYPE_IDS: &'static [KeyTypeId] = &[UintAuthorityId::ID];
+	fn on_genesis_session<Ks: OpaqueKeys>(_: &[(AccountId, Ks)]) {}
+	fn on_new_session<Ks: OpaqueKeys>(_: bool, _: &[(AccountId, Ks)], _: &[(AccountId, Ks)]) {}
+	fn on_disabled(_: u32) {}
+}
+
+parameter_types! {
+	/// Account without validator id, e.g. because it isn't bonded.
+	pub static Unbonded: Option<AccountId> = None;
+	/// Validators elected by the inner session manager.
+	pub static Elected: Option<Vec<AccountId>> = None;
+}
+
+pub struct ValidatorIdOf;
+impl Convert<AccountId, Option<AccountId>> for ValidatorIdOf {
+	fn convert(who: AccountId) -> Option<AccountId> {
+		(Unbonded::get() != Some(who)).then_some(who)
+	}
+}
+
+pub struct FullIdentificationOf;
+impl Convert<AccountId, Option<()>> for FullIdentificationOf {
+	fn convert(_: AccountId) -> Option<()> {
+		Some(())
+	}
+}
+
+pub struct MockStaking;
+impl pallet_session::historical::SessionManager<AccountId, ()> for MockStaking {
+	fn new_session(_: u32) -> Option<Vec<(AccountId, ())>> {
+		Elected::get().map(|elected| elected.into_iter().map(|v| (v, ())).collect())
+	}
+	fn start_session(_: u32) {}
+	fn end_session(_: u32) {}
+}
+
+impl pallet_session::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type ValidatorId = AccountId;
+	type ValidatorIdOf = ValidatorIdOf;
+	type ShouldEndSession = pallet_session::PeriodicSessions<ConstU64<1>, ConstU64<0>>;
+	type NextSessionRotation = pallet_session::PeriodicSessions<ConstU64<1>, ConstU64<0>>;
+	type SessionManager = Validators;
+	type SessionHandler = TestSessionHandler;
+	type Keys = MockSessionKeys;
+	type WeightInfo = ();
+}
+
+impl pallet_session::historical::Config for Test {
+	type FullIdentification = ();
+	type FullIdentificationOf = FullIdentificationOf;
+}
+
+pub struct MockKeysProof;
+impl pallet_validators::KeysProof<AccountId, MockSessionKeys> for MockKeysProof {
+	fn verify(who: &AccountId, keys: &MockSessionKeys, proof: &[u8]) -> bool {
+		let Ok(signature) = TestSignature::decode_all(&mut &proof[..]) else {
+			return false;
+		};
+		keys.dummy.verify(&keys_proof_statement(who), &signature)
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_validators::BenchmarkHelper<AccountId, MockSessionKeys> for MockKeysProof {
+	fn candidate(who: &AccountId) -> (MockSessionKeys, Vec<u8>) {
+		keys(*who)
+	}
+}
+
+impl pallet_validators::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type PrivilegedOrigin = EnsureSignedBy<Admin, AccountId>;
+	type InnerSessionManager = MockStaking;
+	type KeysProof = MockKeysProof;
+	type Currency = Balances;
+	type CandidateDeposit = ConstU64<100>;
+	type MaxCandidates = ConstU32<3>;
+	type MaxValidators = ConstU32<4>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockKeysProof;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: (1..=5).map(|who| (who, 1_000)).collect(),
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+	let mut ext: sp_io::TestExternalities = storage.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
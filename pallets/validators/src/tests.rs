@@ -0,0 +1,252 @@
+use crate::mock::*;
+use crate::{
+	ActiveCandidates, ApprovedCandidates, CandidateQueue, Candidates, Error, Event,
+	ManagedValidators,
+};
+
+use polkadot_sdk::*;
+
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+use pallet_session::SessionManager;
+use sp_runtime::traits::BadOrigin;
+
+use time_primitives::CandidateStatus;
+
+fn apply(who: AccountId) {
+	let (session_keys, proof) = keys(who);
+	assert_ok!(Validators::apply(RuntimeOrigin::signed(who), session_keys, proof));
+}
+
+fn approve(candidates: Vec<AccountId>) {
+	assert_ok!(Validators::approve_candidates(
+		RuntimeOrigin::signed(Admin::get()),
+		BoundedVec::truncate_from(candidates)
+	));
+}
+
+#[test]
+fn apply_reserves_deposit_and_sets_keys() {
+	new_test_ext().execute_with(|| {
+		let (session_keys, proof) = keys(1);
+		assert_ok!(Validators::apply(RuntimeOrigin::signed(1), session_keys.clone(), proof));
+		assert_eq!(Validators::candidate_status(1), Some(CandidateStatus::Pending));
+		assert_eq!(Balances::reserved_balance(1), 100);
+		assert_eq!(pallet_session::NextKeys::<Test>::get(1), Some(session_keys));
+		assert_eq!(CandidateQueue::<Test>::get().into_inner(), vec![1]);
+		System::assert_last_event(Event::CandidateApplied { who: 1, deposit: 100 }.into());
+	});
+}
+
+#[test]
+fn apply_rejects_invalid_keys_proof() {
+	new_test_ext().execute_with(|| {
+		// keys of another account
+		let (other_keys, other_proof) = keys(2);
+		assert_noop!(
+			Validators::apply(RuntimeOrigin::signed(1), other_keys, other_proof.clone()),
+			Error::<Test>::InvalidKeysProof
+		);
+		// proof of other keys
+		let (session_keys, _) = keys(1);
+		assert_noop!(
+			Validators::apply(RuntimeOrigin::signed(1), session_keys.clone(), other_proof),
+			Error::<Test>::InvalidKeysProof
+		);
+		assert_noop!(
+			Validators::apply(RuntimeOrigin::signed(1), session_keys, vec![]),
+			Error::<Test>::InvalidKeysProof
+		);
+	});
+}
+
+#[test]
+fn apply_is_bounded() {
+	new_test_ext().execute_with(|| {
+		apply(1);
+		let (session_keys, proof) = keys(1);
+		assert_noop!(
+			Validators::apply(RuntimeOrigin::signed(1), session_keys, proof),
+			Error::<Test>::AlreadyCandidate
+		);
+		apply(2);
+		apply(3);
+		let (session_keys, proof) = keys(4);
+		assert_noop!(
+			Validators::apply(RuntimeOrigin::signed(4), session_keys, proof),
+			Error::<Test>::TooManyCandidates
+		);
+	});
+}
+
+#[test]
+fn withdraw_application_returns_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Validators::withdraw_application(RuntimeOrigin::signed(1)),
+			Error::<Test>::NotPending
+		);
+		apply(1);
+		assert_ok!(Validators::withdraw_application(RuntimeOrigin::signed(1)));
+		assert_eq!(Validators::candidate_status(1), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(CandidateQueue::<Test>::get().is_empty());
+		System::assert_last_event(Event::CandidateWithdrawn { who: 1 }.into());
+	});
+}
+
+#[test]
+fn approve_candidates() {
+	new_test_ext().execute_with(|| {
+		apply(1);
+		apply(2);
+		assert_noop!(
+			Validators::approve_candidates(
+				RuntimeOrigin::signed(1),
+				BoundedVec::truncate_from(vec![1])
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			Validators::approve_candidates(
+				RuntimeOrigin::signed(Admin::get()),
+				BoundedVec::truncate_from(vec![1, 3])
+			),
+			Error::<Test>::NotPending
+		);
+		approve(vec![1]);
+		assert_eq!(Validators::candidate_status(1), Some(CandidateStatus::Approved));
+		assert_eq!(Validators::candidate_status(2), Some(CandidateStatus::Pending));
+		assert_eq!(ApprovedCandidates::<Test>::get().into_inner(), vec![1]);
+		assert_eq!(CandidateQueue::<Test>::get().into_inner(), vec![2]);
+		assert_eq!(Balances::reserved_balance(1), 100);
+		System::assert_last_event(Event::CandidateApproved { who: 1 }.into());
+		// approved candidates can't withdraw
+		assert_noop!(
+			Validators::withdraw_application(RuntimeOrigin::signed(1)),
+			Error::<Test>::NotPending
+		);
+	});
+}
+
+#[test]
+fn approve_candidates_is_bounded_by_max_validators() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Validators::register_validators(
+			RuntimeOrigin::signed(Admin::get()),
+			BoundedVec::truncate_from(vec![10, 11, 12])
+		));
+		apply(1);
+		apply(2);
+		assert_noop!(
+			Validators::approve_candidates(
+				RuntimeOrigin::signed(Admin::get()),
+				BoundedVec::truncate_from(vec![1, 2])
+			),
+			Error::<Test>::TooManyValidators
+		);
+		approve(vec![1]);
+	});
+}
+
+#[test]
+fn reject_candidates_returns_deposit() {
+	new_test_ext().execute_with(|| {
+		apply(1);
+		apply(2);
+		assert_noop!(
+			Validators::reject_candidates(
+				RuntimeOrigin::signed(1),
+				BoundedVec::truncate_from(vec![1])
+			),
+			BadOrigin
+		);
+		assert_ok!(Validators::reject_candidates(
+			RuntimeOrigin::signed(Admin::get()),
+			BoundedVec::truncate_from(vec![1, 2])
+		));
+		assert!(Candidates::<Test>::iter().next().is_none());
+		assert!(CandidateQueue::<Test>::get().is_empty());
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		System::assert_last_event(Event::CandidateRejected { who: 2 }.into());
+	});
+}
+
+#[test]
+fn approved_candidates_are_activated_and_retired() {
+	new_test_ext().execute_with(|| {
+		apply(1);
+		approve(vec![1]);
+		assert_eq!(Validators::new_session(2), Some(vec![1]));
+		assert_eq!(Validators::candidate_status(1), Some(CandidateStatus::Active));
+		assert_eq!(ActiveCandidates::<Test>::get(1), Some(1));
+		assert_eq!(ManagedValidators::<Test>::get().into_inner(), vec![1]);
+		System::assert_last_event(Event::CandidateActivated { who: 1 }.into());
+
+		// managed validators are merged into the elected ones
+		Elected::set(Some(vec![10, 11]));
+		assert_eq!(Validators::new_session(3), Some(vec![10, 11, 1]));
+
+		assert_ok!(Validators::deregister_validators(
+			RuntimeOrigin::signed(Admin::get()),
+			BoundedVec::truncate_from(vec![1])
+		));
+		assert_eq!(Validators::new_session(4), Some(vec![10, 11]));
+		assert_eq!(Validators::candidate_status(1), None);
+		assert_eq!(ActiveCandidates::<Test>::get(1), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		System::assert_last_event(Event::CandidateRetired { who: 1 }.into());
+	});
+}
+
+#[test]
+fn candidates_without_validator_id_are_dropped() {
+	new_test_ext().execute_with(|| {
+		apply(1);
+		approve(vec![1]);
+		Unbonded::set(Some(1));
+		assert_eq!(Validators::new_session(2), Some(vec![]));
+		assert_eq!(Validators::candidate_status(1), None);
+		assert!(ApprovedCandidates::<Test>::get().is_empty());
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 1_000);
+		System::assert_last_event(Event::CandidateDropped { who: 1 }.into());
+	});
+}
+
+#[test]
+fn registered_validators_are_bounded() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Validators::register_validators(
+				RuntimeOrigin::signed(1),
+				BoundedVec::truncate_from(vec![10])
+			),
+			BadOrigin
+		);
+		assert_ok!(Validators::register_validators(
+			RuntimeOrigin::signed(Admin::get()),
+			BoundedVec::truncate_from(vec![10, 11])
+		));
+		assert_eq!(Validators::new_session(2), Some(vec![10, 11]));
+		assert_noop!(
+			Validators::register_validators(
+				RuntimeOrigin::signed(Admin::get()),
+				BoundedVec::truncate_from(vec![12, 13, 14])
+			),
+			Error::<Test>::TooManyValidators
+		);
+	});
+}
+
+#[test]
+fn first_sessions_are_left_to_inner_manager() {
+	new_test_ext().execute_with(|| {
+		apply(1);
+		approve(vec![1]);
+		assert_eq!(Validators::new_session(1), None);
+		Elected::set(Some(vec![10]));
+		assert_eq!(Validators::new_session(1), Some(vec![10]));
+		assert_eq!(Validators::candidate_status(1), Some(CandidateStatus::Approved));
+	});
+}
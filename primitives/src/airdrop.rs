@@ -0,0 +1,106 @@
+use polkadot_sdk::sp_io::hashing::blake2_256;
+use scale_codec::Encode;
+#[cfg(feature = "std")]
+use scale_info::prelude::vec::Vec;
+
+/// Hash of a leaf or node of an airdrop merkle tree.
+pub type MerkleHash = [u8; 32];
+
+/// Maximum depth of an airdrop merkle tree, enough for 2^32 claims.
+pub const MAX_MERKLE_DEPTH: u32 = 32;
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// Hashes an encoded claim into a leaf.
+///
+/// Leaves and nodes are domain separated so a node can't be passed off as a
+/// leaf.
+pub fn merkle_leaf<T: Encode>(claim: &T) -> MerkleHash {
+	blake2_256(&(LEAF_PREFIX, claim).encode())
+}
+
+/// Hashes two nodes into their parent. The pair is sorted so proofs don't
+/// need to carry the position of the siblings.
+pub fn merkle_node(a: &MerkleHash, b: &MerkleHash) -> MerkleHash {
+	let (left, right) = if a <= b { (a, b) } else { (b, a) };
+	blake2_256(&(NODE_PREFIX, left, right).encode())
+}
+
+/// Folds a leaf with the siblings of a proof into a root.
+pub fn merkle_root_from_proof(leaf: MerkleHash, proof: &[MerkleHash]) -> MerkleHash {
+	proof.iter().fold(leaf, |node, sibling| merkle_node(&node, sibling))
+}
+
+/// Returns true if `proof` proves `leaf` to be part of the tree with `root`.
+pub fn verify_merkle_proof(root: &MerkleHash, leaf: MerkleHash, proof: &[MerkleHash]) -> bool {
+	&merkle_root_from_proof(leaf, proof) == root
+}
+
+/// Airdrop merkle tree, built off chain from the list of claims.
+///
+/// A node without a sibling is promoted to the next level unchanged.
+#[cfg(feature = "std")]
+pub struct MerkleTree {
+	levels: Vec<Vec<MerkleHash>>,
+}
+
+#[cfg(feature = "std")]
+impl MerkleTree {
+	pub fn new(leaves: Vec<MerkleHash>) -> Self {
+		let mut levels = vec![leaves];
+		while levels.last().unwrap().len() > 1 {
+			let next = levels
+				.last()
+				.unwrap()
+				.chunks(2)
+				.map(|pair| match pair {
+					[a, b] => merkle_node(a, b),
+					[a] => *a,
+					_ => unreachable!(),
+				})
+				.collect();
+			levels.push(next);
+		}
+		Self { levels }
+	}
+
+	/// Root of the tree, all zeros if there are no leaves.
+	pub fn root(&self) -> MerkleHash {
+		self.levels.last().unwrap().first().copied().unwrap_or_default()
+	}
+
+	/// Proof of the leaf at `index`.
+	pub fn proof(&self, mut index: usize) -> Option<Vec<MerkleHash>> {
+		if index >= self.levels[0].len() {
+			return None;
+		}
+		let mut proof = vec![];
+		for level in &self.levels[..self.levels.len() - 1] {
+			if let Some(sibling) = level.get(index ^ 1) {
+				proof.push(*sibling);
+			}
+			index /= 2;
+		}
+		Some(proof)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn merkle_proofs_verify() {
+		for len in 1..10u32 {
+			let leaves: Vec<_> = (0..len).map(|i| merkle_leaf(&i)).collect();
+			let tree = MerkleTree::new(leaves.clone());
+			for (i, leaf) in leaves.iter().enumerate() {
+				let proof = tree.proof(i).unwrap();
+				assert!(verify_merkle_proof(&tree.root(), *leaf, &proof));
+				assert!(!verify_merkle_proof(&tree.root(), merkle_leaf(&len), &proof));
+			}
+			assert!(tree.proof(len as usize).is_none());
+		}
+	}
+}
@@ -1,14 +1,20 @@
-use crate::AccountId;
+use crate::{AccountId, Balance, BlockNumber};
 use polkadot_sdk::{sp_core::ConstU32, sp_runtime::BoundedVec};
 use scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 
 pub const DMAIL_TO_LEN: u32 = 64;
 pub const DMAIL_PATH_LEN: u32 = 64;
+pub const DMAIL_PAYLOAD_LEN: u32 = 1024;
+
+pub type DmailMessageId = u64;
 
 #[derive(Encode, Decode, TypeInfo, PartialEq, Eq, Clone, Debug)]
 pub struct DmailTo(pub BoundedVec<u8, ConstU32<DMAIL_TO_LEN>>);
 #[derive(Encode, Decode, TypeInfo, PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct DmailPath(pub BoundedVec<u8, ConstU32<DMAIL_PATH_LEN>>);
 
 #[derive(Encode, Decode, TypeInfo, PartialEq, Eq, Clone, Debug)]
@@ -17,3 +23,31 @@ pub struct DmailMessage {
 	pub to: DmailTo,
 	pub path: DmailPath,
 }
+
+/// Payload encrypted to the x25519 key of the recipient.
+///
+/// The sender derives a shared secret from a fresh `ephemeral_key` and the
+/// recipient key and seals the payload with XChaCha20-Poly1305. The chain
+/// only stores the envelope, it never sees the plaintext.
+#[derive(Encode, Decode, TypeInfo, PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct DmailEnvelope {
+	pub ephemeral_key: [u8; 32],
+	pub nonce: [u8; 24],
+	pub ciphertext: BoundedVec<u8, ConstU32<DMAIL_PAYLOAD_LEN>>,
+}
+
+/// Message stored in the inbox of `recipient`.
+#[derive(Encode, Decode, TypeInfo, PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct DmailInboxMessage {
+	pub id: DmailMessageId,
+	pub sender: AccountId,
+	pub recipient: AccountId,
+	pub path: DmailPath,
+	pub envelope: Option<DmailEnvelope>,
+	pub sent_at: BlockNumber,
+	/// Deposit reserved from the sender until the message is read or pruned.
+	pub deposit: Balance,
+	pub read: bool,
+}
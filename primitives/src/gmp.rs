@@ -91,6 +91,44 @@ impl std::fmt::Display for GmpMessage {
 	}
 }
 
+/// Route from the gateway it is registered on to the gateway of `network_id`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Route {
+	pub network_id: NetworkId,
+	pub gateway: Gateway,
+	pub relative_gas_price: (u128, u128),
+	pub gas_limit: u64,
+	pub base_fee: u128,
+}
+
+impl Route {
+	#[cfg(feature = "std")]
+	pub fn relative_gas_price(&self) -> f64 {
+		self.relative_gas_price.0 as f64 / self.relative_gas_price.1 as f64
+	}
+
+	/// Cost of delivering a message in the currency of the source network, computed
+	/// the same way the gateways charge it.
+	pub fn message_cost(&self, gas_limit: u128, payload_len: usize) -> u128 {
+		let (num, den) = self.relative_gas_price;
+		let gas = gas_limit.saturating_add((payload_len as u128).saturating_mul(16));
+		self.base_fee.saturating_add(gas.saturating_mul(num).div_ceil(den.max(1)))
+	}
+
+	fn encode_to(&self, buf: &mut Vec<u8>) {
+		let mut op_bytes = [0u8; 192];
+		op_bytes[30..32].copy_from_slice(&self.network_id.to_be_bytes());
+		op_bytes[56..64].copy_from_slice(&self.gas_limit.to_be_bytes());
+		op_bytes[80..96].copy_from_slice(&self.base_fee.to_be_bytes());
+		op_bytes[96..128].copy_from_slice(&self.gateway);
+		op_bytes[144..160].copy_from_slice(&self.relative_gas_price.0.to_be_bytes());
+		op_bytes[176..192].copy_from_slice(&self.relative_gas_price.1.to_be_bytes());
+		let op_hash: [u8; 32] = Keccak256::digest(op_bytes).into();
+		buf.extend_from_slice(&op_hash);
+	}
+}
+
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq)]
 pub enum GatewayOp {
@@ -103,6 +141,7 @@ pub enum GatewayOp {
 		#[cfg_attr(feature = "std", serde(with = "crate::shard::serde_tss_public_key"))]
 		TssPublicKey,
 	),
+	SetRoute(Route),
 }
 
 impl GatewayOp {
@@ -111,6 +150,7 @@ impl GatewayOp {
 			GatewayOp::SendMessage(_) => 1,
 			GatewayOp::RegisterShard(_) => 2,
 			GatewayOp::UnregisterShard(_) => 3,
+			GatewayOp::SetRoute(_) => 4,
 		}
 	}
 
@@ -123,6 +163,7 @@ impl GatewayOp {
 	pub fn encoded_len(&self) -> usize {
 		match self {
 			Self::SendMessage(msg) => msg.encoded_len(),
+			Self::SetRoute(_) => 192,
 			_ => 32,
 		}
 	}
@@ -144,6 +185,7 @@ impl GatewayOp {
 				let op_hash: [u8; 32] = Keccak256::digest(op_bytes).into();
 				buf.extend_from_slice(&op_hash);
 			},
+			Self::SetRoute(route) => route.encode_to(buf),
 		}
 	}
 
@@ -168,6 +210,9 @@ impl std::fmt::Display for GatewayOp {
 			Self::UnregisterShard(key) => {
 				writeln!(f, "unregister_shard {}", hex::encode(key))
 			},
+			Self::SetRoute(route) => {
+				writeln!(f, "set_route {}", route.network_id)
+			},
 		}
 	}
 }
@@ -313,23 +358,6 @@ pub struct ConnectorParams {
 	pub cctp_attestation: Option<String>,
 }
 
-#[cfg(feature = "std")]
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
-pub struct Route {
-	pub network_id: NetworkId,
-	pub gateway: Gateway,
-	pub relative_gas_price: (u128, u128),
-	pub gas_limit: u64,
-	pub base_fee: u128,
-}
-
-#[cfg(feature = "std")]
-impl Route {
-	pub fn relative_gas_price(&self) -> f64 {
-		self.relative_gas_price.0 as f64 / self.relative_gas_price.1 as f64
-	}
-}
-
 #[cfg(feature = "std")]
 #[async_trait::async_trait]
 pub trait IChain: Send + Sync + 'static {
@@ -459,6 +487,10 @@ pub trait IConnectorAdmin: IConnector {
 	async fn load_state(&self, _state: String) -> Result<()> {
 		anyhow::bail!("loading chain state is not supported on this backend");
 	}
+	/// Advances a chain with a manual clock by `blocks` and returns the new block.
+	async fn advance_blocks(&self, _blocks: u64) -> Result<u64> {
+		anyhow::bail!("advancing blocks is not supported on this backend");
+	}
 }
 
 #[cfg(feature = "std")]
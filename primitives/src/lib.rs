@@ -10,12 +10,13 @@ use sp_core::crypto::{
 use sp_runtime::{
 	generic,
 	traits::{BlakeTwo256, Get, IdentifyAccount, Verify},
-	DispatchError, DispatchResult, MultiSignature, MultiSigner, OpaqueExtrinsic,
+	DispatchError, DispatchResult, MultiSignature, MultiSigner, OpaqueExtrinsic, Weight,
 };
 
 // Export scoped ...
 #[cfg(feature = "std")]
 pub mod admin;
+pub mod airdrop;
 #[cfg(feature = "std")]
 pub mod balance;
 pub mod bounds;
@@ -25,8 +26,10 @@ pub mod gmp;
 pub mod network;
 pub mod shard;
 pub mod task;
+pub mod validator;
 
 // ... and unscoped
+pub use crate::airdrop::*;
 pub use crate::bounds::*;
 pub use crate::currency::*;
 pub use crate::dmail::*;
@@ -34,6 +37,7 @@ pub use crate::gmp::*;
 pub use crate::network::*;
 pub use crate::shard::*;
 pub use crate::task::*;
+pub use crate::validator::*;
 
 /// Re-exported substrate traits
 pub mod traits {
@@ -118,15 +122,34 @@ uint::construct_uint! {
 }
 
 sp_api::decl_runtime_apis! {
+	pub trait DmailApi {
+		fn get_inbox(account: AccountId, offset: u32, limit: u32) -> Vec<DmailInboxMessage>;
+		fn get_inbox_len(account: AccountId) -> u32;
+	}
+
 	pub trait MembersApi {
 		fn get_member_peer_id(account: &AccountId) -> Option<PeerId>;
 		fn get_heartbeat_timeout() -> BlockNumber;
 		fn get_min_stake() -> Balance;
 	}
 
+	#[api_version(2)]
 	pub trait NetworksApi {
 		fn get_network(network_id: NetworkId) -> Option<(ChainName, ChainNetwork)>;
 		fn get_gateway(network: NetworkId) -> Option<Gateway>;
+		#[api_version(2)]
+		fn get_routes(network: NetworkId) -> Vec<Route>;
+		#[api_version(2)]
+		fn estimate_message_cost(
+			src: NetworkId,
+			dest: NetworkId,
+			gas_limit: u128,
+			payload_len: u32,
+		) -> Option<u128>;
+	}
+
+	pub trait OracleApi {
+		fn relative_gas_price(src: NetworkId, dest: NetworkId) -> Option<(u128, u128)>;
 	}
 
 	pub trait ShardsApi {
@@ -147,12 +170,29 @@ sp_api::decl_runtime_apis! {
 		fn get_failed_tasks() -> Vec<TaskId>;
 	}
 
+	pub trait ValidatorsApi {
+		fn get_candidate_status(account: AccountId) -> Option<CandidateStatus>;
+		fn get_candidates() -> Vec<(AccountId, CandidateStatus)>;
+	}
+
 	pub trait SubmitTransactionApi{
 		#[allow(clippy::result_unit_err)]
 		fn submit_transaction(encoded_tx: Vec<u8>) -> Result<(), ()>;
 	}
 }
 
+/// Version of [`NetworksApi`] which added `get_routes` and `estimate_message_cost`.
+pub const NETWORKS_API_ROUTES_VERSION: u32 = 2;
+
+/// Returns true if a runtime implementing the runtime apis `apis` supports
+/// `get_routes` and `estimate_message_cost` of [`NetworksApi`].
+pub fn supports_networks_api_routes(apis: &[([u8; 8], u32)]) -> bool {
+	use sp_api::RuntimeApiInfo;
+	let id = <dyn NetworksApi<Block> as RuntimeApiInfo>::ID;
+	apis.iter()
+		.any(|(api, version)| *api == id && *version >= NETWORKS_API_ROUTES_VERSION)
+}
+
 pub trait NetworksInterface {
 	fn get_networks() -> Vec<NetworkId>;
 	fn gateway(network: NetworkId) -> Option<Address>;
@@ -161,6 +201,9 @@ pub trait NetworksInterface {
 	fn shard_task_limit(network: NetworkId) -> u32;
 	fn shard_size(network: NetworkId) -> u16;
 	fn shard_threshold(network: NetworkId) -> u16;
+	fn network_status(network: NetworkId) -> NetworkStatus;
+	fn route(src: NetworkId, dest: NetworkId) -> Option<Route>;
+	fn set_route(src: NetworkId, route: Route) -> DispatchResult;
 }
 
 pub trait MembersInterface {
@@ -172,6 +215,9 @@ pub trait MembersInterface {
 	fn total_stake() -> Balance;
 	fn transfer_stake(from: &AccountId, to: &AccountId, amount: Balance) -> DispatchResult;
 	fn unstake_member(account: &AccountId);
+	/// Unregisters the members of a removed network, visiting at most `limit`
+	/// members. Returns the consumed weight and whether all members are unregistered.
+	fn network_removed(network: NetworkId, limit: u32) -> (Weight, bool);
 }
 
 pub trait ElectionsInterface {
@@ -179,6 +225,7 @@ pub trait ElectionsInterface {
 	fn shard_offline(network: NetworkId, members: Vec<AccountId>);
 	fn member_online(id: &AccountId, network: NetworkId);
 	fn member_offline(id: &AccountId, network: NetworkId);
+	fn network_removed(network: NetworkId);
 }
 
 pub trait ShardsInterface {
@@ -195,12 +242,25 @@ pub trait ShardsInterface {
 	) -> Result<ShardId, DispatchError>;
 	fn next_signer(shard_id: ShardId) -> PublicKey;
 	fn tss_public_key(shard_id: ShardId) -> Option<TssPublicKey>;
+	/// Takes the shards of a removed network offline, visiting at most `limit`
+	/// shards. Returns the consumed weight and whether all shards are offline.
+	fn network_removed(network: NetworkId, limit: u32) -> (Weight, bool);
 }
 
 pub trait TasksInterface {
 	fn shard_online(shard_id: ShardId, network: NetworkId);
 	fn shard_offline(shard_id: ShardId, network: NetworkId);
 	fn gateway_registered(network: NetworkId, block: u64);
+	fn network_status_changed(network: NetworkId, status: NetworkStatus);
+	/// Refunds at most `limit` queued ops of a draining network. Returns the
+	/// consumed weight and whether the queue is empty.
+	fn refund_queued_ops(network: NetworkId, limit: u32) -> (Weight, bool);
+	/// Removes the tasks, queues and batches of a removed network, removing at
+	/// most `limit` items. Returns the consumed weight and whether all state is removed.
+	fn network_removed(network: NetworkId, limit: u32) -> (Weight, bool);
+	fn has_pending_batches(network: NetworkId) -> bool;
+	fn gateway_migrated(network: NetworkId, block: u64);
+	fn route_changed(network: NetworkId, route: Route);
 }
 
 #[cfg(test)]
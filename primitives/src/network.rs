@@ -32,3 +32,35 @@ pub struct NetworkConfig {
 	pub shard_size: u16,
 	pub shard_threshold: u16,
 }
+
+/// Lifecycle of a registered network.
+///
+/// Paused networks keep their state but don't read gateway events or start
+/// new batches. Draining networks finish their in-flight batches and refund
+/// queued messages. Removed networks have all of their state cleaned up.
+/// Migrating networks finish the in-flight batches of their old gateway before
+/// switching to a new one, queued messages are batched after the switch.
+#[derive(
+	Clone, Copy, Debug, Default, Eq, PartialEq, Encode, Decode, TypeInfo, Serialize, Deserialize,
+)]
+pub enum NetworkStatus {
+	#[default]
+	Active,
+	Paused,
+	Draining,
+	Removed,
+	Migrating,
+}
+
+impl core::fmt::Display for NetworkStatus {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		let status = match self {
+			Self::Active => "active",
+			Self::Paused => "paused",
+			Self::Draining => "draining",
+			Self::Removed => "removed",
+			Self::Migrating => "migrating",
+		};
+		f.write_str(status)
+	}
+}
@@ -0,0 +1,13 @@
+use scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+
+/// Onboarding status of a validator candidate.
+#[derive(Encode, Decode, TypeInfo, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CandidateStatus {
+	/// Waiting for governance approval.
+	Pending,
+	/// Approved, activated at the next session.
+	Approved,
+	/// Part of the validator set.
+	Active,
+}
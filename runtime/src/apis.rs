@@ -27,10 +27,13 @@ use sp_version::RuntimeVersion;
 
 pub use time_primitives::{MembersInterface, NetworksInterface};
 
+#[cfg(feature = "testnet")]
+use time_primitives::CandidateStatus;
 #[cfg(feature = "testnet")]
 use time_primitives::{
-	BatchId, BlockNumber, ChainName, ChainNetwork, Commitment, ErrorMsg, Gateway, GatewayMessage,
-	MemberStatus, NetworkId, PeerId, PublicKey, ShardId, ShardStatus, Task, TaskId,
+	BatchId, BlockNumber, ChainName, ChainNetwork, Commitment, DmailInboxMessage, ErrorMsg,
+	Gateway, GatewayMessage, MemberStatus, NetworkId, PeerId, PublicKey, Route, ShardId,
+	ShardStatus, Task, TaskId,
 };
 // Local module imports
 use super::{
@@ -41,7 +44,7 @@ use super::{
 #[cfg(feature = "genesis-builder")]
 use crate::RuntimeGenesisConfig;
 #[cfg(feature = "testnet")]
-use crate::{Members, Networks, Shards, Staking, Tasks};
+use crate::{Dmail, Members, Networks, Oracle, Shards, Staking, Tasks, Validators};
 
 // HASHI Bridge
 use crate::configs::bridge::NetworkId as BridgeNetworkId;
@@ -290,6 +293,17 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[cfg(feature = "testnet")]
+	impl time_primitives::DmailApi<Block> for Runtime {
+		fn get_inbox(account: AccountId, offset: u32, limit: u32) -> Vec<DmailInboxMessage> {
+			Dmail::get_inbox(account, offset, limit)
+		}
+
+		fn get_inbox_len(account: AccountId) -> u32 {
+			Dmail::get_inbox_len(account)
+		}
+	}
+
 	#[cfg(feature = "testnet")]
 	impl time_primitives::MembersApi<Block> for Runtime {
 		fn get_member_peer_id(account: &AccountId) -> Option<PeerId> {
@@ -306,6 +320,7 @@ impl_runtime_apis! {
 	}
 
 	#[cfg(feature = "testnet")]
+	#[api_version(2)]
 	impl time_primitives::NetworksApi<Block> for Runtime {
 		fn get_network(network_id: NetworkId) -> Option<(ChainName, ChainNetwork)> {
 			Networks::get_network(network_id)
@@ -314,6 +329,26 @@ impl_runtime_apis! {
 		fn get_gateway(network: NetworkId) -> Option<Gateway> {
 			Networks::gateway(network)
 		}
+
+		fn get_routes(network: NetworkId) -> Vec<Route> {
+			Networks::get_routes(network)
+		}
+
+		fn estimate_message_cost(
+			src: NetworkId,
+			dest: NetworkId,
+			gas_limit: u128,
+			payload_len: u32,
+		) -> Option<u128> {
+			Networks::estimate_message_cost(src, dest, gas_limit, payload_len)
+		}
+	}
+
+	#[cfg(feature = "testnet")]
+	impl time_primitives::OracleApi<Block> for Runtime {
+		fn relative_gas_price(src: NetworkId, dest: NetworkId) -> Option<(u128, u128)> {
+			Oracle::relative_gas_price(src, dest)
+		}
 	}
 
 	#[cfg(feature = "testnet")]
@@ -370,6 +405,17 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[cfg(feature = "testnet")]
+	impl time_primitives::ValidatorsApi<Block> for Runtime {
+		fn get_candidate_status(account: AccountId) -> Option<CandidateStatus> {
+			Validators::candidate_status(account)
+		}
+
+		fn get_candidates() -> Vec<(AccountId, CandidateStatus)> {
+			Validators::candidates()
+		}
+	}
+
 	#[cfg(feature = "testnet")]
 	impl time_primitives::SubmitTransactionApi<Block> for Runtime {
 		fn submit_transaction(encoded_transaction: Vec<u8>) -> Result<(), ()> {
@@ -13,6 +13,7 @@ polkadot_sdk::frame_benchmarking::define_benchmarks!(
 	[pallet_collective, TechnicalCommittee]
 	[pallet_election_provider_multi_phase, ElectionProviderMultiPhase]
 	[pallet_election_provider_support_benchmarking, EPSBench::<Runtime>]
+	[pallet_governance, Governance]
 	[pallet_grandpa, Grandpa]
 	[pallet_im_online, ImOnline]
 	[pallet_launch, Launch]
@@ -43,6 +44,7 @@ polkadot_sdk::frame_benchmarking::define_benchmarks!(
 	[pallet_election_provider_multi_phase, ElectionProviderMultiPhase]
 	[pallet_election_provider_support_benchmarking, EPSBench::<Runtime>]
 	[pallet_elections, Elections]
+	[pallet_governance, Governance]
 	[pallet_grandpa, Grandpa]
 	[pallet_identity, Identity]
 	[pallet_im_online, ImOnline]
@@ -52,6 +54,7 @@ polkadot_sdk::frame_benchmarking::define_benchmarks!(
 	[pallet_networks, Networks]
 	[pallet_nomination_pools, NominationPoolsBench::<Runtime>]
 	[pallet_offences, OffencesBench::<Runtime>]
+	[pallet_oracle, Oracle]
 	[pallet_preimage, Preimage]
 	[pallet_proxy, Proxy]
 	[pallet_scheduler, Scheduler]
@@ -63,6 +66,7 @@ polkadot_sdk::frame_benchmarking::define_benchmarks!(
 	[pallet_timestamp, Timestamp]
 	[pallet_treasury, Treasury]
 	[pallet_utility, Utility]
+	[pallet_validators, Validators]
 	[pallet_vesting, Vesting]
 	[eth_bridge, EthBridge]
 	[bridge_multisig, BridgeMultisig]
@@ -4,18 +4,26 @@ use frame_support::{
 	parameter_types,
 	traits::{ConstU128, ConstU32},
 };
+use frame_system::EnsureRoot;
+use scale_codec::DecodeAll;
+use sp_runtime::{BoundToRuntimeAppPublic, RuntimeAppPublic};
 
 // Can't use `FungibleAdapter` here until Treasury pallet migrates to fungibles
 // <https://github.com/paritytech/polkadot-sdk/issues/226>
 #[allow(deprecated)]
 pub use pallet_transaction_payment::{CurrencyAdapter, Multiplier, TargetedFeeAdjustment};
 
-use time_primitives::ANLOG;
+use time_primitives::{ANLOG, MILLIANLOG};
 // Local module imports
+#[cfg(feature = "runtime-benchmarks")]
+use crate::RuntimeOrigin;
 use crate::{
-	weights, AccountId, Balance, Balances, Elections, Members, Networks, Runtime, RuntimeEvent,
-	Shards, Tasks,
+	weights, AccountId, AuthorityDiscovery, Babe, Balance, Balances, Elections, Grandpa, ImOnline,
+	Members, Networks, Runtime, RuntimeEvent, SessionKeys, Shards, Staking, Tasks, DAYS, HOURS,
+	MINUTES,
 };
+#[cfg(feature = "runtime-benchmarks")]
+use time_primitives::NetworkId;
 
 #[cfg(not(feature = "testnet"))]
 use super::governance::EnsureRootOrHalfTechnical;
@@ -35,6 +43,10 @@ type ChronicleAdmin = EnsureRootOrHalfTechnical;
 /// Development admin origin for all chronicle related pallets
 type ChronicleAdmin = EnsureRootOrTechnicalMember;
 
+/// Admin origin for the chronicle calls time-locked by the governance
+/// pallet, they can only be dispatched by its schedule.
+type ChronicleTimelocked = EnsureRoot<AccountId>;
+
 impl pallet_members::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = weights::pallet_members::WeightInfo<Runtime>;
@@ -57,7 +69,7 @@ impl pallet_elections::Config for Runtime {
 
 impl pallet_shards::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type AdminOrigin = ChronicleAdmin;
+	type AdminOrigin = ChronicleTimelocked;
 	type WeightInfo = weights::pallet_shards::WeightInfo<Runtime>;
 	type Members = Members;
 	type Elections = Elections;
@@ -67,7 +79,7 @@ impl pallet_shards::Config for Runtime {
 
 impl pallet_tasks::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type AdminOrigin = ChronicleAdmin;
+	type AdminOrigin = ChronicleTimelocked;
 	type WeightInfo = weights::pallet_tasks::WeightInfo<Runtime>;
 	type Networks = Networks;
 	type Shards = Shards;
@@ -88,19 +100,174 @@ impl pallet_timegraph::Config for Runtime {
 	type InitialRewardPoolAccount = InitialRewardPoolAccount;
 	type InitialTimegraphAccount = InitialTimegraphAccount;
 	type InitialThreshold = InitialThreshold;
+	type InitialWithdrawalDelay = ConstU32<HOURS>;
 	type AdminOrigin = EnsureRootOrTechnicalMember;
+	type MaxChargesPerBlock = ConstU32<50>;
+	type MaxUsageCharges = ConstU32<100>;
 }
 
 impl pallet_networks::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type AdminOrigin = ChronicleAdmin;
+	type RouteOrigin = ChronicleAdmin;
 	type WeightInfo = weights::pallet_networks::WeightInfo<Runtime>;
 	type Tasks = Tasks;
+	type Shards = Shards;
+	type Elections = Elections;
+	type Members = Members;
+	type MaxCleanupPerBlock = ConstU32<25>;
+	type MaxGatewayMigrationsPerBlock = ConstU32<10>;
+	type MaxNetworks = ConstU32<64>;
+}
+
+parameter_types! {
+	pub const DmailMessageDeposit: Balance = 100 * MILLIANLOG;
 }
 
 impl pallet_dmail::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = weights::pallet_dmail::WeightInfo<Runtime>;
+	type Currency = Balances;
+	type MessageDeposit = DmailMessageDeposit;
+	type MaxInboxSize = ConstU32<250>;
+	type MaxMessagesPerSender = ConstU32<10>;
+	type MessageLifetime = ConstU32<{ 30 * DAYS }>;
+}
+
+impl pallet_oracle::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AdminOrigin = ChronicleAdmin;
+	type WeightInfo = weights::pallet_oracle::WeightInfo<Runtime>;
+	type Networks = Networks;
+	type MaxPrices = ConstU32<50>;
+	type MaxPriceAge = ConstU32<{ 10 * MINUTES }>;
+	type MinFeeders = ConstU32<1>;
+	type MaxNetworks = <Runtime as pallet_networks::Config>::MaxNetworks;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = OracleBenchmarkHelper;
+}
+
+/// Registers the networks and routes updated in the oracle benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct OracleBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_oracle::BenchmarkHelper for OracleBenchmarkHelper {
+	fn create_route(src: NetworkId, dest: NetworkId) {
+		use scale_codec::Encode;
+		use sp_runtime::BoundedVec;
+		use time_primitives::{ChainName, ChainNetwork, Network, NetworkConfig, Route};
+
+		for id in [src, dest] {
+			if pallet_networks::Networks::<Runtime>::contains_key(id) {
+				continue;
+			}
+			let network = Network {
+				id,
+				chain_name: ChainName(BoundedVec::truncate_from("Ethereum".encode())),
+				chain_network: ChainNetwork(BoundedVec::truncate_from(id.encode())),
+				gateway: [0; 32],
+				gateway_block: 0,
+				config: NetworkConfig {
+					batch_size: 32,
+					batch_offset: 0,
+					batch_gas_limit: 10_000,
+					shard_task_limit: 10,
+					shard_size: 3,
+					shard_threshold: 2,
+				},
+			};
+			Networks::register_network(RuntimeOrigin::root(), network).unwrap();
+		}
+		let route = Route {
+			network_id: dest,
+			gateway: [0; 32],
+			relative_gas_price: (1, 1),
+			gas_limit: 100_000,
+			base_fee: 0,
+		};
+		Networks::set_route(RuntimeOrigin::root(), src, route).unwrap();
+	}
+}
+
+parameter_types! {
+	pub const ValidatorCandidateDeposit: Balance = 10_000 * ANLOG;
+}
+
+impl pallet_validators::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = weights::pallet_validators::WeightInfo<Runtime>;
+	type PrivilegedOrigin = EnsureRootOrTechnicalMember;
+	type InnerSessionManager = Staking;
+	type KeysProof = SessionKeysProof;
+	type Currency = Balances;
+	type CandidateDeposit = ValidatorCandidateDeposit;
+	type MaxCandidates = ConstU32<32>;
+	type MaxValidators = ConstU32<32>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = SessionKeysProof;
+}
+
+type SignatureOf<T> = <<T as BoundToRuntimeAppPublic>::Public as RuntimeAppPublic>::Signature;
+
+/// Proof of possession of the [`SessionKeys`], the signatures of each key
+/// over the statement of the candidate.
+type SessionKeysSignatures = (
+	SignatureOf<Grandpa>,
+	SignatureOf<Babe>,
+	SignatureOf<ImOnline>,
+	SignatureOf<AuthorityDiscovery>,
+);
+
+/// Verifies the proofs of possession of validator candidates.
+pub struct SessionKeysProof;
+
+impl pallet_validators::KeysProof<AccountId, SessionKeys> for SessionKeysProof {
+	fn verify(who: &AccountId, keys: &SessionKeys, proof: &[u8]) -> bool {
+		let Ok((grandpa, babe, im_online, authority_discovery)) =
+			SessionKeysSignatures::decode_all(&mut &proof[..])
+		else {
+			return false;
+		};
+		let statement = pallet_validators::keys_proof_statement(who);
+		keys.grandpa.verify(&statement, &grandpa)
+			&& keys.babe.verify(&statement, &babe)
+			&& keys.im_online.verify(&statement, &im_online)
+			&& keys.authority_discovery.verify(&statement, &authority_discovery)
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_validators::BenchmarkHelper<AccountId, SessionKeys> for SessionKeysProof {
+	fn candidate(who: &AccountId) -> (SessionKeys, sp_std::vec::Vec<u8>) {
+		use frame_support::traits::Currency;
+		use scale_codec::Encode;
+
+		// session keys can only be set by stash accounts
+		let balance = 1_000_000 * ANLOG;
+		Balances::make_free_balance_be(who, balance);
+		Staking::bond(
+			RuntimeOrigin::signed(who.clone()),
+			balance / 10,
+			pallet_staking::RewardDestination::Staked,
+		)
+		.unwrap();
+
+		let keys = SessionKeys {
+			grandpa: RuntimeAppPublic::generate_pair(None),
+			babe: RuntimeAppPublic::generate_pair(None),
+			im_online: RuntimeAppPublic::generate_pair(None),
+			authority_discovery: RuntimeAppPublic::generate_pair(None),
+		};
+		let statement = pallet_validators::keys_proof_statement(who);
+		let proof: SessionKeysSignatures = (
+			keys.grandpa.sign(&statement).unwrap(),
+			keys.babe.sign(&statement).unwrap(),
+			keys.im_online.sign(&statement).unwrap(),
+			keys.authority_discovery.sign(&statement).unwrap(),
+		);
+		(keys, proof.encode())
+	}
 }
 
 #[cfg(test)]
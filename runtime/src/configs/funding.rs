@@ -8,10 +8,12 @@ use frame_system::{EnsureRoot, EnsureWithSuccess};
 #[cfg(feature = "testnet")]
 use frame_support::traits::{
 	tokens::{PayFromAccount, UnityAssetBalanceConversion},
-	EitherOfDiverse,
+	ConstU32, EitherOfDiverse,
 };
 use frame_support::{parameter_types, PalletId};
 
+#[cfg(not(feature = "testnet"))]
+use sp_runtime::traits::AccountIdConversion;
 #[cfg(feature = "testnet")]
 use sp_runtime::{traits::IdentityLookup, Percent, Permill};
 
@@ -25,8 +27,10 @@ use time_primitives::BlockNumber;
 #[cfg(not(feature = "testnet"))]
 use crate::EnsureRootOrHalfTechnical;
 #[cfg(feature = "testnet")]
-use crate::{deposit, AccountId, Balance, Balances, TechnicalCollective, Treasury, ANLOG, DAYS};
-use crate::{main_or_test, weights, ExistentialDeposit, Runtime, RuntimeEvent, Vesting};
+use crate::{deposit, Balance, TechnicalCollective, Treasury, ANLOG, DAYS};
+use crate::{
+	main_or_test, weights, AccountId, Balances, ExistentialDeposit, Runtime, RuntimeEvent, Vesting,
+};
 
 #[cfg(feature = "testnet")]
 parameter_types! {
@@ -44,6 +48,8 @@ parameter_types! {
 	pub const MaxBalance: Balance = Balance::MAX;
 	pub const SpendPayoutPeriod: BlockNumber = 30 * DAYS;
 	pub TreasuryAccount: AccountId = Treasury::account_id();
+	/// The treasury funds the airdrop rounds.
+	pub AirdropRoundPot: AccountId = TreasuryAccount::get();
 }
 
 #[cfg(feature = "testnet")]
@@ -77,11 +83,21 @@ parameter_types! {
 	pub LaunchId: PalletId = PalletId(*b"timelnch");
 }
 
+#[cfg(not(feature = "testnet"))]
+parameter_types! {
+	/// Virtual wallet of the airdrop allocation of the launch pallet, funds the
+	/// airdrop rounds.
+	pub AirdropRoundPot: AccountId = LaunchId::get().into_sub_account_truncating(b"airdrop");
+}
+
 impl pallet_airdrop::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type VestingSchedule = Vesting;
 	type RawPrefix = RawPrefix;
 	type MinimumBalance = ExistentialDeposit;
+	type Currency = Balances;
+	type RoundPot = AirdropRoundPot;
+	type MaxRoundClaimsRemoved = ConstU32<500>;
 	type WeightInfo = weights::pallet_airdrop::WeightInfo<Runtime>;
 }
 
@@ -2,27 +2,33 @@
 
 use polkadot_sdk::*;
 
-use frame_support::{parameter_types, traits::EitherOfDiverse, weights::Weight};
+use frame_support::{
+	parameter_types,
+	traits::{ConstU32, EitherOfDiverse, Get},
+	weights::Weight,
+};
 use frame_system::EnsureRoot;
 
 use sp_runtime::Perbill;
 
 use time_primitives::{AccountId, Balance, BlockNumber, ANLOG};
 
+#[cfg(feature = "testnet")]
+use frame_support::traits::ConstBool;
 #[cfg(not(feature = "testnet"))]
 use frame_support::traits::Contains;
 #[cfg(not(feature = "testnet"))]
 use frame_system::EnsureRootWithSuccess;
-#[cfg(not(feature = "testnet"))]
-use sp_runtime::traits::ConstU32;
 
 // Local module imports
-#[cfg(not(feature = "testnet"))]
-use crate::{Balances, RuntimeHoldReason};
+#[cfg(feature = "testnet")]
+use crate::MINUTES;
 use crate::{
-	Runtime, RuntimeBlockWeights, RuntimeCall, RuntimeEvent, RuntimeOrigin, TechnicalCollective,
-	TechnicalCommittee, DAYS, HOURS,
+	weights, Runtime, RuntimeBlockWeights, RuntimeCall, RuntimeEvent, RuntimeOrigin,
+	TechnicalCollective, TechnicalCommittee, DAYS, HOURS,
 };
+#[cfg(not(feature = "testnet"))]
+use crate::{Balances, RuntimeHoldReason};
 
 parameter_types! {
 	pub const TechnicalMotionDuration: BlockNumber = DAYS;
@@ -48,6 +54,9 @@ impl pallet_collective::Config<TechnicalCollective> for Runtime {
 // Limit membership check to development mode
 pub type TechnicalMember = pallet_collective::EnsureMember<AccountId, TechnicalCollective>;
 
+pub type TechnicalMinority =
+	pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 1, 3>;
+
 pub type TechnicalHalf =
 	pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 1, 2>;
 pub type TechnicalMajority =
@@ -61,6 +70,7 @@ pub type TechnicalUnanimity =
 
 pub type EnsureRootOrTechnicalMember = EitherOfDiverse<EnsureRoot<AccountId>, TechnicalMember>;
 pub type EnsureRootOrHalfTechnical = EitherOfDiverse<EnsureRoot<AccountId>, TechnicalHalf>;
+pub type EnsureRootOrMinorityTechnical = EitherOfDiverse<EnsureRoot<AccountId>, TechnicalMinority>;
 
 impl pallet_membership::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
@@ -127,18 +137,101 @@ impl pallet_safe_mode::Config for Runtime {
 	type WeightInfo = pallet_safe_mode::weights::SubstrateWeight<Runtime>;
 }
 
+#[cfg(not(feature = "testnet"))]
+parameter_types! {
+	/// Delay before a scheduled runtime upgrade authorization is executed.
+	pub const UpgradeDelay: BlockNumber = 2 * DAYS;
+	/// Delay before a scheduled staking configuration change is executed.
+	pub const StakingDelay: BlockNumber = DAYS;
+	/// Delay before a scheduled chronicle admin call is executed.
+	pub const ChronicleDelay: BlockNumber = DAYS;
+}
+
+#[cfg(feature = "testnet")]
+parameter_types! {
+	/// Delay before a scheduled runtime upgrade authorization is executed.
+	pub const UpgradeDelay: BlockNumber = HOURS;
+	/// Delay before a scheduled staking configuration change is executed.
+	pub const StakingDelay: BlockNumber = HOURS;
+	/// Delay before a scheduled chronicle admin call is executed.
+	pub const ChronicleDelay: BlockNumber = 10 * MINUTES;
+}
+
+parameter_types! {
+	/// Maximum weight of the scheduled calls executed per block.
+	pub MaxGovernanceCallWeight: Weight = Perbill::from_percent(50) * RuntimeBlockWeights::get().max_block;
+}
+
+/// Calls time-locked by the governance pallet and their delays.
+pub struct GovernanceTimelock;
+impl pallet_governance::Timelock<RuntimeCall, BlockNumber> for GovernanceTimelock {
+	fn delay(call: &RuntimeCall) -> Option<BlockNumber> {
+		match call {
+			RuntimeCall::System(frame_system::Call::authorize_upgrade { .. }) => {
+				Some(UpgradeDelay::get())
+			},
+			RuntimeCall::Staking(
+				pallet_staking::Call::force_new_era { .. }
+				| pallet_staking::Call::set_validator_count { .. }
+				| pallet_staking::Call::set_staking_configs { .. },
+			) => Some(StakingDelay::get()),
+			// The chronicle pallets are only part of the testnet runtime.
+			#[cfg(feature = "testnet")]
+			RuntimeCall::Tasks(
+				pallet_tasks::Call::submit_gmp_events { .. }
+				| pallet_tasks::Call::sync_network { .. }
+				| pallet_tasks::Call::stop_network { .. }
+				| pallet_tasks::Call::remove_task { .. }
+				| pallet_tasks::Call::restart_batch { .. },
+			)
+			| RuntimeCall::Shards(pallet_shards::Call::force_shard_offline { .. }) => {
+				Some(ChronicleDelay::get())
+			},
+			_ => None,
+		}
+	}
+}
+
+/// Freezes scheduled governance calls while safe mode is entered.
+#[cfg(not(feature = "testnet"))]
+pub struct SafeModeEntered;
+#[cfg(not(feature = "testnet"))]
+impl Get<bool> for SafeModeEntered {
+	fn get() -> bool {
+		pallet_safe_mode::EnteredUntil::<Runtime>::exists()
+	}
+}
+
 #[cfg(not(feature = "testnet"))]
 impl pallet_governance::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type WeightInfo = weights::pallet_governance::WeightInfo<Runtime>;
 	/// Default admin origin for system related governance
 	type SystemAdmin = EnsureRootOrHalfTechnical;
 	// Default admin origin for staking related governance
 	type StakingAdmin = EnsureRootOrHalfTechnical;
+	/// A technical committee minority can veto scheduled calls
+	type VetoOrigin = EnsureRootOrMinorityTechnical;
+	type Timelock = GovernanceTimelock;
+	type ExecutionFrozen = SafeModeEntered;
+	type MaxScheduled = ConstU32<32>;
+	type MaxCallWeight = MaxGovernanceCallWeight;
 }
 
 #[cfg(feature = "testnet")]
 impl pallet_governance::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type WeightInfo = weights::pallet_governance::WeightInfo<Runtime>;
 	/// Development admin origin for all system calls
 	type SystemAdmin = EnsureRootOrTechnicalMember;
 	// Development admin origin for all staking calls
 	type StakingAdmin = EnsureRootOrTechnicalMember;
+	/// Development origin to veto scheduled calls
+	type VetoOrigin = EnsureRootOrTechnicalMember;
+	type Timelock = GovernanceTimelock;
+	type ExecutionFrozen = ConstBool<false>;
+	type MaxScheduled = ConstU32<32>;
+	type MaxCallWeight = MaxGovernanceCallWeight;
 }
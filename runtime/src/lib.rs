@@ -608,6 +608,12 @@ mod runtime {
 	#[runtime::pallet_index(39)]
 	pub type Dmail = pallet_dmail;
 
+	#[runtime::pallet_index(40)]
+	pub type Oracle = pallet_oracle;
+
+	#[runtime::pallet_index(41)]
+	pub type Validators = pallet_validators;
+
 	// Custom funding pallets
 
 	#[runtime::pallet_index(42)]
@@ -623,7 +629,10 @@ mod runtime {
 }
 
 // All migrations executed on runtime upgrade implementing `OnRuntimeUpgrade`.
-type Migrations = ();
+type Migrations = (
+	pallet_tasks::migrations::MigrateV0ToV1<Runtime>,
+	pallet_timegraph::migrations::MigrateV0ToV1<Runtime>,
+);
 
 #[cfg(test)]
 mod core_tests {
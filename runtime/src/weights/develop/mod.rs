@@ -12,18 +12,21 @@ pub mod pallet_bags_list;
 pub mod pallet_balances;
 pub mod pallet_dmail;
 pub mod pallet_elections;
+pub mod pallet_governance;
 pub mod pallet_im_online;
 #[cfg(not(feature = "testnet"))]
 pub mod pallet_launch;
 pub mod pallet_members;
 pub mod pallet_multisig;
 pub mod pallet_networks;
+pub mod pallet_oracle;
 pub mod pallet_proxy;
 pub mod pallet_shards;
 pub mod pallet_tasks;
 pub mod pallet_timegraph;
 pub mod pallet_timestamp;
 pub mod pallet_utility;
+pub mod pallet_validators;
 
 pub use block_weights::BlockExecutionWeight;
 pub use extrinsic_weights::ExtrinsicBaseWeight;
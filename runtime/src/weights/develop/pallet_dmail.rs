@@ -41,4 +41,59 @@ impl<T: frame_system::Config> pallet_dmail::WeightInfo for WeightInfo<T> {
 		Weight::from_parts(8_826_000, 0)
 			.saturating_add(Weight::from_parts(0, 0))
 	}
+	/// FIXME Measure me!
+	fn send_message() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(1_408_604_000, 0)
+			.saturating_add(Weight::from_parts(0, 925_619))
+			.saturating_add(T::DbWeight::get().reads(258))
+			.saturating_add(T::DbWeight::get().writes(10))
+	}
+	/// FIXME Measure me!
+	fn mark_read(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(15_618_000, 0)
+			.saturating_add(Weight::from_parts(0, 3593))
+			.saturating_add(Weight::from_parts(52_206_441, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 6375).saturating_mul(n.into()))
+	}
+	/// FIXME Measure me!
+	fn delete_messages(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(18_403_000, 0)
+			.saturating_add(Weight::from_parts(0, 3748))
+			.saturating_add(Weight::from_parts(59_019_322, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().writes((6_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 6383).saturating_mul(n.into()))
+	}
+	/// Storage: `Dmail::AllowedSenders` (r:0 w:1)
+	/// Proof: `Dmail::AllowedSenders` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn set_allowed_sender() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_315_000 picoseconds.
+		Weight::from_parts(9_104_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// FIXME Measure me!
+	fn prune_inbox(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(19_247_000, 0)
+			.saturating_add(Weight::from_parts(0, 3748))
+			.saturating_add(Weight::from_parts(56_388_065, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 6383).saturating_mul(n.into()))
+	}
 }
@@ -136,4 +136,16 @@ impl<T: frame_system::Config> pallet_members::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(b.into())))
 			.saturating_add(Weight::from_parts(0, 2648).saturating_mul(b.into()))
 	}
+	/// FIXME Measure me!
+	fn network_removed(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(39_963_000, 0)
+			.saturating_add(Weight::from_parts(0, 3829))
+			.saturating_add(Weight::from_parts(66_845_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((7_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 3983).saturating_mul(n.into()))
+	}
 }
@@ -0,0 +1,66 @@
+//! Weights for `pallet_oracle`
+//!
+//! FIXME Measure me! The weights are estimated until the pallet is benchmarked
+//! by the `pr-optional-benchmarks` workflow.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+
+use polkadot_sdk::*;
+
+use frame_support::{traits::Get, weights::Weight};
+use core::marker::PhantomData;
+
+/// Weight functions for `pallet_oracle`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_oracle::WeightInfo for WeightInfo<T> {
+	/// FIXME Measure me!
+	fn add_feeder() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(9_578_000, 0)
+			.saturating_add(Weight::from_parts(0, 3471))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// FIXME Measure me!
+	fn remove_feeder() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(14_027_000, 0)
+			.saturating_add(Weight::from_parts(0, 3543))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// FIXME Measure me!
+	fn set_token_config(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(12_694_000, 0)
+			.saturating_add(Weight::from_parts(0, 3507))
+			.saturating_add(Weight::from_parts(9_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2571).saturating_mul(n.into()))
+	}
+	/// FIXME Measure me!
+	fn set_tolerance() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(5_711_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// FIXME Measure me!
+	fn submit_prices(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(10_864_000, 0)
+			.saturating_add(Weight::from_parts(0, 3608))
+			.saturating_add(Weight::from_parts(14_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2571).saturating_mul(n.into()))
+	}
+}
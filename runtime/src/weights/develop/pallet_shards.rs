@@ -166,4 +166,16 @@ impl<T: frame_system::Config> pallet_shards::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().writes((9_u64).saturating_mul(b.into())))
 			.saturating_add(Weight::from_parts(0, 10178).saturating_mul(b.into()))
 	}
+	/// FIXME Measure me!
+	fn network_removed(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(138_609_000, 0)
+			.saturating_add(Weight::from_parts(0, 8802))
+			.saturating_add(Weight::from_parts(124_424_788, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((9_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((9_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 10178).saturating_mul(n.into()))
+	}
 }
@@ -35,6 +35,8 @@ pub struct WeightInfo<T>(PhantomData<T>);
 impl<T: frame_system::Config> pallet_timegraph::WeightInfo for WeightInfo<T> {
 	/// Storage: `Timegraph::NextDepositSequence` (r:1 w:1)
 	/// Proof: `Timegraph::NextDepositSequence` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Reserves` (r:1 w:1)
+	/// Proof: `Timegraph::Reserves` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	fn deposit() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `3`
@@ -42,13 +44,23 @@ impl<T: frame_system::Config> pallet_timegraph::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 33_253_000 picoseconds.
 		Weight::from_parts(35_948_000, 0)
 			.saturating_add(Weight::from_parts(0, 3468))
-			.saturating_add(T::DbWeight::get().reads(1))
-			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
 	}
 	/// Storage: `Timegraph::Threshold` (r:1 w:0)
 	/// Proof: `Timegraph::Threshold` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::PendingWithdrawals` (r:1 w:1)
+	/// Proof: `Timegraph::PendingWithdrawals` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	/// Storage: `Timegraph::NextWithdrawalSequence` (r:1 w:1)
 	/// Proof: `Timegraph::NextWithdrawalSequence` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::WithdrawalDelay` (r:1 w:0)
+	/// Proof: `Timegraph::WithdrawalDelay` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::NextDepositSequence` (r:1 w:0)
+	/// Proof: `Timegraph::NextDepositSequence` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::WithdrawalRequests` (r:0 w:1)
+	/// Proof: `Timegraph::WithdrawalRequests` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Reserves` (r:1 w:0)
+	/// Proof: `Timegraph::Reserves` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	fn withdraw() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `3`
@@ -56,24 +68,16 @@ impl<T: frame_system::Config> pallet_timegraph::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 34_345_000 picoseconds.
 		Weight::from_parts(36_840_000, 0)
 			.saturating_add(Weight::from_parts(0, 3468))
-			.saturating_add(T::DbWeight::get().reads(2))
-			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(3))
 	}
-	/// Storage: `Timegraph::TimegraphAccount` (r:1 w:0)
-	/// Proof: `Timegraph::TimegraphAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
-	/// Storage: `System::Account` (r:2 w:2)
-	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
-	/// Storage: `Timegraph::RewardPoolAccount` (r:1 w:0)
-	/// Proof: `Timegraph::RewardPoolAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// FIXME Measure me!
 	fn transfer_to_pool() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `272`
-		//  Estimated: `6196`
-		// Minimum execution time: 85_811_000 picoseconds.
-		Weight::from_parts(90_349_000, 0)
+		// FIXME Measure me!
+		Weight::from_parts(117_453_000, 0)
 			.saturating_add(Weight::from_parts(0, 6196))
-			.saturating_add(T::DbWeight::get().reads(4))
-			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().writes(5))
 	}
 	/// Storage: `Timegraph::TimegraphAccount` (r:1 w:0)
 	/// Proof: `Timegraph::TimegraphAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
@@ -81,6 +85,8 @@ impl<T: frame_system::Config> pallet_timegraph::WeightInfo for WeightInfo<T> {
 	/// Proof: `Timegraph::RewardPoolAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
 	/// Storage: `System::Account` (r:1 w:1)
 	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Timegraph::Reserves` (r:1 w:1)
+	/// Proof: `Timegraph::Reserves` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	fn transfer_award_to_user() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `285`
@@ -88,8 +94,8 @@ impl<T: frame_system::Config> pallet_timegraph::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 83_446_000 picoseconds.
 		Weight::from_parts(88_506_000, 0)
 			.saturating_add(Weight::from_parts(0, 3593))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(2))
 	}
 	/// Storage: `Timegraph::TimegraphAccount` (r:1 w:1)
 	/// Proof: `Timegraph::TimegraphAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
@@ -127,4 +133,177 @@ impl<T: frame_system::Config> pallet_timegraph::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// Storage: `Timegraph::NextPlanId` (r:1 w:1)
+	/// Proof: `Timegraph::NextPlanId` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Plans` (r:0 w:1)
+	/// Proof: `Timegraph::Plans` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn create_plan() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `3`
+		//  Estimated: `1488`
+		// Minimum execution time: 7_344_000 picoseconds.
+		Weight::from_parts(7_695_000, 0)
+			.saturating_add(Weight::from_parts(0, 1488))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// Storage: `Timegraph::Plans` (r:1 w:1)
+	/// Proof: `Timegraph::Plans` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn remove_plan() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `96`
+		//  Estimated: `3561`
+		// Minimum execution time: 9_107_000 picoseconds.
+		Weight::from_parts(9_528_000, 0)
+			.saturating_add(Weight::from_parts(0, 3561))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `Timegraph::Subscriptions` (r:1 w:1)
+	/// Proof: `Timegraph::Subscriptions` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Plans` (r:1 w:0)
+	/// Proof: `Timegraph::Plans` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::SpendingLimits` (r:1 w:0)
+	/// Proof: `Timegraph::SpendingLimits` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::RewardPoolAccount` (r:1 w:0)
+	/// Proof: `Timegraph::RewardPoolAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Timegraph::NextInvoiceId` (r:1 w:1)
+	/// Proof: `Timegraph::NextInvoiceId` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::ChargeQueue` (r:0 w:1)
+	/// Proof: `Timegraph::ChargeQueue` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Reserves` (r:1 w:1)
+	/// Proof: `Timegraph::Reserves` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn subscribe() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `412`
+		//  Estimated: `6196`
+		// Minimum execution time: 61_725_000 picoseconds.
+		Weight::from_parts(63_649_000, 0)
+			.saturating_add(Weight::from_parts(0, 6196))
+			.saturating_add(T::DbWeight::get().reads(8))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	/// Storage: `Timegraph::Subscriptions` (r:1 w:1)
+	/// Proof: `Timegraph::Subscriptions` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::ChargeQueue` (r:0 w:1)
+	/// Proof: `Timegraph::ChargeQueue` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn unsubscribe() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `194`
+		//  Estimated: `3659`
+		// Minimum execution time: 13_274_000 picoseconds.
+		Weight::from_parts(13_846_000, 0)
+			.saturating_add(Weight::from_parts(0, 3659))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// Storage: `Timegraph::TimegraphAccount` (r:1 w:0)
+	/// Proof: `Timegraph::TimegraphAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Subscriptions` (r:100 w:100)
+	/// Proof: `Timegraph::Subscriptions` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Plans` (r:1 w:0)
+	/// Proof: `Timegraph::Plans` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::SpendingLimits` (r:100 w:0)
+	/// Proof: `Timegraph::SpendingLimits` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::RewardPoolAccount` (r:1 w:0)
+	/// Proof: `Timegraph::RewardPoolAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `System::Account` (r:101 w:101)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Timegraph::NextInvoiceId` (r:1 w:1)
+	/// Proof: `Timegraph::NextInvoiceId` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Reserves` (r:100 w:100)
+	/// Proof: `Timegraph::Reserves` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// The range of component `n` is `[1, 100]`.
+	fn charge_usage(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `455 + n * (231 ±0)`
+		//  Estimated: `3920 + n * (2700 ±0)`
+		// Minimum execution time: 54_322_000 picoseconds.
+		Weight::from_parts(21_457_000, 0)
+			.saturating_add(Weight::from_parts(0, 3920))
+			// Standard Error: 18_904
+			.saturating_add(Weight::from_parts(36_228_341, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2700).saturating_mul(n.into()))
+	}
+	/// Storage: `Timegraph::SpendingLimits` (r:0 w:1)
+	/// Proof: `Timegraph::SpendingLimits` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn set_spending_limit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 6_051_000 picoseconds.
+		Weight::from_parts(6_372_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `Timegraph::NextChargeBlock` (r:1 w:1)
+	/// Proof: `Timegraph::NextChargeBlock` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::ChargeQueue` (r:51 w:100)
+	/// Proof: `Timegraph::ChargeQueue` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Subscriptions` (r:50 w:50)
+	/// Proof: `Timegraph::Subscriptions` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Plans` (r:1 w:0)
+	/// Proof: `Timegraph::Plans` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::SpendingLimits` (r:50 w:0)
+	/// Proof: `Timegraph::SpendingLimits` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::RewardPoolAccount` (r:1 w:0)
+	/// Proof: `Timegraph::RewardPoolAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `System::Account` (r:51 w:51)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Timegraph::NextInvoiceId` (r:1 w:1)
+	/// Proof: `Timegraph::NextInvoiceId` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Reserves` (r:50 w:50)
+	/// Proof: `Timegraph::Reserves` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// The range of component `n` is `[1, 50]`.
+	fn charge_subscriptions(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `520 + n * (264 ±0)`
+		//  Estimated: `3985 + n * (2739 ±0)`
+		// Minimum execution time: 58_911_000 picoseconds.
+		Weight::from_parts(24_806_000, 0)
+			.saturating_add(Weight::from_parts(0, 3985))
+			// Standard Error: 21_377
+			.saturating_add(Weight::from_parts(39_512_804, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2739).saturating_mul(n.into()))
+	}
+	/// Storage: `Timegraph::WithdrawalRequests` (r:1 w:1)
+	/// Proof: `Timegraph::WithdrawalRequests` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Threshold` (r:1 w:0)
+	/// Proof: `Timegraph::Threshold` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::PendingWithdrawals` (r:1 w:1)
+	/// Proof: `Timegraph::PendingWithdrawals` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Reserves` (r:1 w:1)
+	/// Proof: `Timegraph::Reserves` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn claim_withdrawal() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `234`
+		//  Estimated: `3699`
+		// Minimum execution time: 24_536_000 picoseconds.
+		Weight::from_parts(25_268_000, 0)
+			.saturating_add(Weight::from_parts(0, 3699))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Storage: `Timegraph::WithdrawalDelay` (r:1 w:1)
+	/// Proof: `Timegraph::WithdrawalDelay` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	fn set_withdrawal_delay() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `3`
+		//  Estimated: `1488`
+		// Minimum execution time: 5_170_000 picoseconds.
+		Weight::from_parts(5_460_000, 0)
+			.saturating_add(Weight::from_parts(0, 1488))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
 }
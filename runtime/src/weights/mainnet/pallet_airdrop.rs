@@ -83,4 +83,33 @@ impl<T: frame_system::Config> pallet_airdrop::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(2))
 			.saturating_add(T::DbWeight::get().writes(3))
 	}
+	/// FIXME Measure me!
+	fn create_round() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(15_196_000, 0)
+			.saturating_add(Weight::from_parts(0, 3593))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	/// FIXME Measure me!
+	fn claim_merkle(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(72_194_000, 0)
+			.saturating_add(Weight::from_parts(0, 4764))
+			.saturating_add(Weight::from_parts(1_384_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	/// FIXME Measure me!
+	fn expire_round(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(17_223_000, 0)
+			.saturating_add(Weight::from_parts(0, 3593))
+			.saturating_add(Weight::from_parts(1_505_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2511).saturating_mul(n.into()))
+	}
 }
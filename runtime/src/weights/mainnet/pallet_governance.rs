@@ -0,0 +1,48 @@
+//! Weights for `pallet_governance`
+//!
+//! FIXME Measure me! The weights are estimated until the pallet is benchmarked
+//! by the `pr-optional-benchmarks` workflow.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+
+use polkadot_sdk::*;
+
+use frame_support::{traits::Get, weights::Weight};
+use core::marker::PhantomData;
+
+/// Weight functions for `pallet_governance`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_governance::WeightInfo for WeightInfo<T> {
+	/// FIXME Measure me!
+	fn schedule() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(9_782_000, 0)
+			.saturating_add(Weight::from_parts(0, 2579))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// FIXME Measure me!
+	fn veto() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(10_578_000, 0)
+			.saturating_add(Weight::from_parts(0, 4746))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// FIXME Measure me!
+	fn on_initialize(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(2_746_000, 0)
+			.saturating_add(Weight::from_parts(0, 1527))
+			.saturating_add(Weight::from_parts(4_926_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2539).saturating_mul(n.into()))
+	}
+}
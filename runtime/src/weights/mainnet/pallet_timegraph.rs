@@ -37,6 +37,8 @@ pub struct WeightInfo<T>(PhantomData<T>);
 impl<T: frame_system::Config> pallet_timegraph::WeightInfo for WeightInfo<T> {
 	/// Storage: `Timegraph::NextDepositSequence` (r:1 w:1)
 	/// Proof: `Timegraph::NextDepositSequence` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Reserves` (r:1 w:1)
+	/// Proof: `Timegraph::Reserves` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	fn deposit() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `3`
@@ -44,13 +46,15 @@ impl<T: frame_system::Config> pallet_timegraph::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 12_985_000 picoseconds.
 		Weight::from_parts(13_515_000, 0)
 			.saturating_add(Weight::from_parts(0, 3468))
-			.saturating_add(T::DbWeight::get().reads(1))
-			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
 	}
 	/// Storage: `Timegraph::Threshold` (r:1 w:0)
 	/// Proof: `Timegraph::Threshold` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
 	/// Storage: `Timegraph::NextWithdrawalSequence` (r:1 w:1)
 	/// Proof: `Timegraph::NextWithdrawalSequence` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Timegraph::Reserves` (r:1 w:0)
+	/// Proof: `Timegraph::Reserves` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	fn withdraw() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `3`
@@ -58,24 +62,16 @@ impl<T: frame_system::Config> pallet_timegraph::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 13_125_000 picoseconds.
 		Weight::from_parts(13_606_000, 0)
 			.saturating_add(Weight::from_parts(0, 3468))
-			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads(3))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
-	/// Storage: `Timegraph::TimegraphAccount` (r:1 w:0)
-	/// Proof: `Timegraph::TimegraphAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
-	/// Storage: `System::Account` (r:2 w:2)
-	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
-	/// Storage: `Timegraph::RewardPoolAccount` (r:1 w:0)
-	/// Proof: `Timegraph::RewardPoolAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// FIXME Measure me!
 	fn transfer_to_pool() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `272`
-		//  Estimated: `6196`
-		// Minimum execution time: 34_945_000 picoseconds.
-		Weight::from_parts(36_268_000, 0)
+		// FIXME Measure me!
+		Weight::from_parts(47_148_000, 0)
 			.saturating_add(Weight::from_parts(0, 6196))
-			.saturating_add(T::DbWeight::get().reads(4))
-			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().writes(5))
 	}
 	/// Storage: `Timegraph::TimegraphAccount` (r:1 w:0)
 	/// Proof: `Timegraph::TimegraphAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
@@ -83,6 +79,8 @@ impl<T: frame_system::Config> pallet_timegraph::WeightInfo for WeightInfo<T> {
 	/// Proof: `Timegraph::RewardPoolAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
 	/// Storage: `System::Account` (r:1 w:1)
 	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Timegraph::Reserves` (r:1 w:1)
+	/// Proof: `Timegraph::Reserves` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	fn transfer_award_to_user() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `285`
@@ -90,8 +88,8 @@ impl<T: frame_system::Config> pallet_timegraph::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 33_643_000 picoseconds.
 		Weight::from_parts(34_635_000, 0)
 			.saturating_add(Weight::from_parts(0, 3593))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(2))
 	}
 	/// Storage: `Timegraph::TimegraphAccount` (r:1 w:1)
 	/// Proof: `Timegraph::TimegraphAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
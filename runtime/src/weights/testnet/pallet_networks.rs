@@ -101,27 +101,87 @@ impl<T: frame_system::Config> pallet_networks::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(4))
 	}
-	/// Storage: `Networks::Networks` (r:0 w:1)
-	/// Proof: `Networks::Networks` (`max_values`: None, `max_size`: None, mode: `Measured`)
-	/// Storage: `Networks::NetworkBatchSize` (r:0 w:1)
-	/// Proof: `Networks::NetworkBatchSize` (`max_values`: None, `max_size`: None, mode: `Measured`)
-	/// Storage: `Networks::NetworkName` (r:0 w:1)
-	/// Proof: `Networks::NetworkName` (`max_values`: None, `max_size`: None, mode: `Measured`)
-	/// Storage: `Networks::NetworkShardTaskLimit` (r:0 w:1)
-	/// Proof: `Networks::NetworkShardTaskLimit` (`max_values`: None, `max_size`: None, mode: `Measured`)
-	/// Storage: `Networks::NetworkGatewayAddress` (r:0 w:1)
-	/// Proof: `Networks::NetworkGatewayAddress` (`max_values`: None, `max_size`: None, mode: `Measured`)
-	/// Storage: `Networks::NetworkGatewayBlock` (r:0 w:1)
-	/// Proof: `Networks::NetworkGatewayBlock` (`max_values`: None, `max_size`: None, mode: `Measured`)
-	/// Storage: `Networks::NetworkBatchGasLimit` (r:0 w:1)
-	/// Proof: `Networks::NetworkBatchGasLimit` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// FIXME Measure me!
 	fn remove_network() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 10_289_000 picoseconds.
+		// FIXME Measure me!
 		Weight::from_parts(10_580_000, 0)
-			.saturating_add(Weight::from_parts(0, 0))
-			.saturating_add(T::DbWeight::get().writes(7))
+			.saturating_add(Weight::from_parts(0, 3742))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// FIXME Measure me!
+	fn pause_network() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(13_835_000, 0)
+			.saturating_add(Weight::from_parts(0, 3742))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// FIXME Measure me!
+	fn resume_network() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(13_835_000, 0)
+			.saturating_add(Weight::from_parts(0, 3742))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// FIXME Measure me!
+	fn drain_network() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(13_835_000, 0)
+			.saturating_add(Weight::from_parts(0, 3742))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// FIXME Measure me!
+	fn migrate_gateway() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(13_835_000, 0)
+			.saturating_add(Weight::from_parts(0, 3742))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// FIXME Measure me!
+	fn complete_gateway_migrations(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(6_917_500, 0)
+			.saturating_add(Weight::from_parts(0, 1527))
+			.saturating_add(Weight::from_parts(13_370_879, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((8_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2571).saturating_mul(n.into()))
+	}
+	/// FIXME Measure me!
+	fn migrate_routes(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(4_820_000, 0)
+			.saturating_add(Weight::from_parts(0, 1527))
+			.saturating_add(Weight::from_parts(12_855_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2571).saturating_mul(n.into()))
+	}
+	/// FIXME Measure me!
+	fn set_route() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(17_293_750, 0)
+			.saturating_add(Weight::from_parts(0, 3794))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// FIXME Measure me!
+	fn cleanup_network(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(21_160_000, 0)
+			.saturating_add(Weight::from_parts(0, 3583))
+			.saturating_add(Weight::from_parts(32_170_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(11))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2571).saturating_mul(n.into()))
 	}
 }
@@ -166,4 +166,16 @@ impl<T: frame_system::Config> pallet_shards::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().writes((8_u64).saturating_mul(b.into())))
 			.saturating_add(Weight::from_parts(0, 10184).saturating_mul(b.into()))
 	}
+	/// FIXME Measure me!
+	fn network_removed(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(15_619_565, 0)
+			.saturating_add(Weight::from_parts(0, 8802))
+			.saturating_add(Weight::from_parts(91_696_938, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((9_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((9_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 10178).saturating_mul(n.into()))
+	}
 }
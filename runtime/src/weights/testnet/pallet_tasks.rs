@@ -228,4 +228,28 @@ impl<T: frame_system::Config> pallet_tasks::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(2))
 			.saturating_add(T::DbWeight::get().writes(4))
 	}
+	/// FIXME Measure me!
+	fn refund_queued_ops(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(14_199_066, 0)
+			.saturating_add(Weight::from_parts(0, 3894))
+			.saturating_add(Weight::from_parts(39_028_903, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 5137).saturating_mul(n.into()))
+	}
+	/// FIXME Measure me!
+	fn network_removed(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(14_199_066, 0)
+			.saturating_add(Weight::from_parts(0, 3894))
+			.saturating_add(Weight::from_parts(12_894_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(6))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 3751).saturating_mul(n.into()))
+	}
 }
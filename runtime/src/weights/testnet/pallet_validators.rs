@@ -0,0 +1,80 @@
+//! Weights for `pallet_validators`
+//!
+//! FIXME Measure me! The weights are estimated until the pallet is benchmarked
+//! by the `pr-optional-benchmarks` workflow.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+
+use polkadot_sdk::*;
+
+use frame_support::{traits::Get, weights::Weight};
+use core::marker::PhantomData;
+
+/// Weight functions for `pallet_validators`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_validators::WeightInfo for WeightInfo<T> {
+	/// FIXME Measure me!
+	fn register_validators(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(7_499_000, 0)
+			.saturating_add(Weight::from_parts(0, 1613))
+			.saturating_add(Weight::from_parts(642_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(Weight::from_parts(0, 32).saturating_mul(n.into()))
+	}
+	/// FIXME Measure me!
+	fn deregister_validators(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(5_892_000, 0)
+			.saturating_add(Weight::from_parts(0, 1613))
+			.saturating_add(Weight::from_parts(589_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(Weight::from_parts(0, 32).saturating_mul(n.into()))
+	}
+	/// FIXME Measure me!
+	fn apply() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(140_342_000, 0)
+			.saturating_add(Weight::from_parts(0, 4290))
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().writes(9))
+	}
+	/// FIXME Measure me!
+	fn withdraw_application() -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(25_711_000, 0)
+			.saturating_add(Weight::from_parts(0, 3593))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// FIXME Measure me!
+	fn approve_candidates(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(9_641_000, 0)
+			.saturating_add(Weight::from_parts(0, 1613))
+			.saturating_add(Weight::from_parts(5_088_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(n.into()))
+	}
+	/// FIXME Measure me!
+	fn reject_candidates(n: u32, ) -> Weight {
+		// FIXME Measure me!
+		Weight::from_parts(8_570_000, 0)
+			.saturating_add(Weight::from_parts(0, 1613))
+			.saturating_add(Weight::from_parts(21_962_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(n.into()))
+	}
+}
@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+
+set -e
+set -x
+
+SCRIPT_DIR=$( cd -- "$( dirname -- "${BASH_SOURCE[0]}" )" &> /dev/null && pwd )
+WORKSPACE_ROOT=$SCRIPT_DIR/../
+cd $WORKSPACE_ROOT
+
+# Check for 'cargo-build-sbf' and abort if it is not available.
+cargo build-sbf --version > /dev/null 2>&1 || { echo >&2 "ERROR - requires the solana cli tools v1.18.26 for 'cargo build-sbf'"; exit 1; }
+
+PROGRAMS=gmp/svm/programs
+OUT=config/envs/local/svm
+
+cargo build-sbf --manifest-path $PROGRAMS/Cargo.toml --sbf-out-dir $PROGRAMS/target/deploy
+
+# the program ids are pinned by the keypairs in $OUT, the generated ones are ignored
+cp $PROGRAMS/target/deploy/gmp_svm_gateway.so $OUT/gateway.so
+cp $PROGRAMS/target/deploy/gmp_svm_tester.so $OUT/tester.so
@@ -0,0 +1,101 @@
+use anyhow::{Context, Result};
+use csv::Reader;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use time_primitives::balance::BalanceFormatter;
+use time_primitives::traits::Ss58Codec;
+use time_primitives::{merkle_leaf, AccountId, Balance, BlockNumber, MerkleTree};
+
+/// Row of the airdrop csv, the vesting columns are either all set or all empty.
+#[derive(Deserialize)]
+struct AirdropRecord {
+	address: String,
+	amount: String,
+	vesting_locked: Option<String>,
+	vesting_per_block: Option<String>,
+	vesting_starting_block: Option<BlockNumber>,
+}
+
+/// Vesting schedule of a claim as `(locked, per_block, starting_block)`.
+type Vesting = (Balance, Balance, BlockNumber);
+
+#[derive(Serialize)]
+pub struct AirdropClaim {
+	pub address: String,
+	pub amount: Balance,
+	pub vesting: Option<Vesting>,
+	/// Hex encoded siblings from the leaf to the root.
+	pub proof: Vec<String>,
+}
+
+/// Merkle root of an airdrop round and the proofs of its claims.
+#[derive(Serialize)]
+pub struct AirdropTree {
+	pub merkle_root: String,
+	pub total: Balance,
+	pub claims: Vec<AirdropClaim>,
+}
+
+fn parse_balance(balance: &str) -> Result<Balance> {
+	BalanceFormatter::new(12, "ANLG").parse(balance)
+}
+
+fn parse_record(record: &AirdropRecord) -> Result<(AccountId, Balance, Option<Vesting>)> {
+	let account = AccountId::from_ss58check(record.address.trim())
+		.map_err(|err| anyhow::anyhow!("invalid address {}: {err:?}", record.address))?;
+	let amount = parse_balance(&record.amount)?;
+	let vesting =
+		match (&record.vesting_locked, &record.vesting_per_block, record.vesting_starting_block) {
+			(Some(locked), Some(per_block), Some(starting_block)) => {
+				Some((parse_balance(locked)?, parse_balance(per_block)?, starting_block))
+			},
+			(None, None, None) => None,
+			_ => anyhow::bail!("incomplete vesting schedule for {}", record.address),
+		};
+	Ok((account, amount, vesting))
+}
+
+/// Builds the merkle tree of the claims in `csv` and writes the root and the
+/// proofs of all claims to `out` as json.
+///
+/// The csv has the columns `address,amount,vesting_locked,vesting_per_block,vesting_starting_block`
+/// where the vesting columns may be empty.
+pub fn build_airdrop_tree(csv: &Path, out: &Path) -> Result<AirdropTree> {
+	let mut rdr =
+		Reader::from_path(csv).with_context(|| format!("failed to open {}", csv.display()))?;
+	let mut claims = vec![];
+	let mut leaves = vec![];
+	let mut accounts = HashSet::new();
+	for (row, result) in rdr.deserialize().enumerate() {
+		let record: AirdropRecord = result?;
+		let (account, amount, vesting) =
+			parse_record(&record).with_context(|| format!("invalid row {}", row + 1))?;
+		anyhow::ensure!(accounts.insert(account.clone()), "duplicate claim for {}", record.address);
+		leaves.push(merkle_leaf(&(&account, amount, vesting)));
+		claims.push((record.address, amount, vesting));
+	}
+	let tree = MerkleTree::new(leaves);
+	let claims = claims
+		.into_iter()
+		.enumerate()
+		.map(|(i, (address, amount, vesting))| AirdropClaim {
+			address,
+			amount,
+			vesting,
+			proof: tree
+				.proof(i)
+				.unwrap()
+				.iter()
+				.map(|node| format!("0x{}", hex::encode(node)))
+				.collect(),
+		})
+		.collect::<Vec<_>>();
+	let tree = AirdropTree {
+		merkle_root: format!("0x{}", hex::encode(tree.root())),
+		total: claims.iter().map(|claim| claim.amount).sum(),
+		claims,
+	};
+	std::fs::write(out, serde_json::to_string_pretty(&tree)?)?;
+	Ok(tree)
+}
@@ -136,6 +136,22 @@ pub struct NetworkConfig {
 	pub route_base_fee: u128,
 	pub shard_size: u16,
 	pub shard_threshold: u16,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub treasurer: Option<TreasurerConfig>,
+}
+
+/// Balances maintained by `tc-cli treasurer`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TreasurerConfig {
+	/// Chronicle wallets below this balance are topped up.
+	pub chronicle_min_funds: String,
+	/// Balance chronicle wallets are topped up to.
+	pub chronicle_target_funds: String,
+	/// Gateway balance above which the surplus over `gateway_funds` is withdrawn.
+	pub gateway_max_funds: Option<String>,
+	/// Address receiving the gateway surplus, defaults to the funding account.
+	pub sweep_address: Option<String>,
 }
 
 #[cfg(test)]
@@ -1,19 +1,18 @@
 use crate::env::CoinMarketCap;
 use crate::Tc;
 use anyhow::{Context, Result};
-use csv::{Reader, Writer};
-use num_bigint::{BigInt, BigUint};
-use num_rational::Ratio;
-use num_traits::Signed;
-use num_traits::ToPrimitive;
-use num_traits::{identities::Zero, pow};
+use csv::Reader;
 use reqwest::header::{HeaderMap, HeaderValue};
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use time_primitives::NetworkId;
 
+/// Accuracy of the `FixedU128` prices submitted to the oracle pallet.
+const PRICE_ACCURACY: f64 = 1_000_000_000_000_000_000.0;
+/// Accuracy of the `Perbill` margin of a token config.
+const MARGIN_ACCURACY: f64 = 1_000_000_000.0;
+
 #[derive(Clone, Deserialize)]
 pub struct TokenPriceData {
 	pub data: Vec<CryptoData>,
@@ -43,94 +42,6 @@ pub struct NetworkPrice {
 	pub usd_price: f64,
 }
 
-fn bigint_log10(n: &BigUint) -> f64 {
-	let n_str = n.to_string();
-	let num_digits = n_str.len();
-	let most_significant_digit = &n_str[0..1].parse::<f64>().unwrap();
-	(num_digits as f64 - 1.0) + most_significant_digit.log10()
-}
-
-fn to_fixed(n: Ratio<BigUint>, precision: Option<usize>) -> String {
-	let value = n.to_integer();
-	let mut fract = n.fract();
-
-	let precision = match precision {
-		Some(p) => p,
-		None => {
-			if fract.is_zero() {
-				0
-			} else {
-				let denominator = n.denom();
-				let log_value = bigint_log10(denominator);
-				log_value.ceil() as usize + 1
-			}
-		},
-	};
-
-	if precision == 0 {
-		return format!("{}", value);
-	}
-
-	let mut result = format!("{}", value);
-	result.push('.');
-
-	for _ in 0..precision {
-		fract *= Ratio::from_integer(10u32.into());
-		let int_part = fract.to_integer();
-		result.push_str(&format!("{}", int_part));
-		fract -= Ratio::from_integer(int_part);
-	}
-
-	result
-}
-
-fn compute_src_wei_per_dst_gas_rate(
-	src_usd_price: Ratio<BigUint>,
-	src_decimals: u32,
-	dst_usd_price: Ratio<BigUint>,
-	dst_decimals: u32,
-	dst_gas_fee: u128,
-) -> Ratio<BigUint> {
-	let src_usd_per_wei =
-		src_usd_price / Ratio::from_integer(pow(BigUint::from(10u32), src_decimals as usize));
-	let dst_usd_per_wei =
-		dst_usd_price / Ratio::from_integer(pow(BigUint::from(10u32), dst_decimals as usize));
-	let dst_usd_per_gas = dst_usd_per_wei * Ratio::from_integer(BigUint::from(dst_gas_fee));
-	dst_usd_per_gas / src_usd_per_wei
-}
-
-fn convert_bigint_ratio_to_biguint(ratio: Ratio<BigInt>) -> Result<Ratio<BigUint>> {
-	let (numerator, denominator) = ratio.into();
-
-	if numerator.is_negative() || denominator.is_negative() {
-		anyhow::bail!("Cannot convert negative ratio to Uint ratio");
-	}
-
-	let numerator_biguint =
-		numerator.to_biguint().ok_or(anyhow::anyhow!("Unable to convert numberator"))?;
-	let denominator_biguint = denominator
-		.to_biguint()
-		.ok_or(anyhow::anyhow!("Unable to convert denominator"))?;
-
-	Ok(Ratio::new(numerator_biguint, denominator_biguint))
-}
-
-pub fn get_network_price(
-	network_prices: &HashMap<NetworkId, (String, f64)>,
-	network_id: &NetworkId,
-) -> Result<f64> {
-	network_prices
-		.get(network_id)
-		.map(|(_, price)| *price)
-		.ok_or_else(|| anyhow::anyhow!("Unable to get network {} from csv", network_id))
-}
-
-pub fn convert_bigint_to_u128(value: &BigUint) -> Result<u128> {
-	value
-		.to_u128()
-		.ok_or_else(|| anyhow::anyhow!("Could not convert bigint to u128"))
-}
-
 pub fn read_csv_token_prices(price_path: &Path) -> Result<HashMap<NetworkId, (String, f64)>> {
 	let mut rdr = Reader::from_path(price_path)
 		.with_context(|| format!("failed to open {}", price_path.display()))?;
@@ -143,90 +54,120 @@ pub fn read_csv_token_prices(price_path: &Path) -> Result<HashMap<NetworkId, (St
 	Ok(network_map)
 }
 
-impl Tc {
-	pub async fn fetch_token_prices(&self) -> Result<()> {
-		let env = CoinMarketCap::from_env()?;
+/// Source of the USD prices fed to the oracle pallet.
+#[async_trait::async_trait]
+pub trait PriceSource: Send + Sync {
+	/// Returns the USD price of the currency `symbol` of `network`.
+	async fn price(&self, network: NetworkId, symbol: &str) -> Result<f64>;
+}
+
+/// Fetches prices from the CoinMarketCap api.
+pub struct CoinMarketCapSource {
+	env: CoinMarketCap,
+	client: reqwest::Client,
+}
+
+impl CoinMarketCapSource {
+	pub fn from_env() -> Result<Self> {
+		Ok(Self {
+			env: CoinMarketCap::from_env()?,
+			client: reqwest::Client::new(),
+		})
+	}
+}
+
+#[async_trait::async_trait]
+impl PriceSource for CoinMarketCapSource {
+	async fn price(&self, _network: NetworkId, symbol: &str) -> Result<f64> {
 		let mut header_map = HeaderMap::new();
 		header_map.insert(
 			"X-CMC_PRO_API_KEY",
-			HeaderValue::from_str(&env.token_api_key).expect("Failed to create header value"),
+			HeaderValue::from_str(&self.env.token_api_key).context("invalid api key")?,
 		);
-		let price_path = self.config.prices();
-		let file = File::create(&price_path)
-			.with_context(|| format!("failed to create {}", price_path.display()))?;
-		let mut wtr = Writer::from_writer(file);
-		wtr.write_record(["network_id", "symbol", "usd_price"])?;
-		for network_id in self.config.networks().keys() {
-			let symbol = self.currency(Some(*network_id))?.1;
-			let token_url = format!("{}{}", env.token_price_url, symbol);
-			let client = reqwest::Client::new();
-			let request = client.get(token_url).headers(header_map.clone()).build()?;
-			log::info!("GET {}", request.url());
-			let response = client.execute(request).await?;
-			if response.status() != 200 {
-				anyhow::bail!("{}", response.status());
-			}
-			let response = response.json::<TokenPriceData>().await?;
-			let data = response.data[0].clone();
-			let usd_price = data
-				.quote
-				.usd
-				.price
-				.ok_or_else(|| anyhow::anyhow!("Couldnt fetch token price for {}", symbol))?;
-			let symbol = data.symbol;
-
-			wtr.write_record(&[network_id.to_string(), symbol, usd_price.to_string()])?;
+		let token_url = format!("{}{}", self.env.token_price_url, symbol);
+		let request = self.client.get(token_url).headers(header_map).build()?;
+		log::info!("GET {}", request.url());
+		let response = self.client.execute(request).await?;
+		if response.status() != 200 {
+			anyhow::bail!("{}", response.status());
 		}
-		wtr.flush()?;
-		log::info!("Saved in prices.csv");
-		Ok(())
+		let response = response.json::<TokenPriceData>().await?;
+		let data = response.data.first().context("no price data")?;
+		data.quote
+			.usd
+			.price
+			.ok_or_else(|| anyhow::anyhow!("Couldnt fetch token price for {}", symbol))
 	}
+}
+
+/// Reads prices from a csv file with a `network_id,symbol,usd_price` header.
+pub struct FileSource {
+	path: PathBuf,
+}
 
-	pub fn read_csv_token_prices(&self) -> Result<HashMap<NetworkId, (String, f64)>> {
-		read_csv_token_prices(&self.config.prices())
+impl FileSource {
+	pub fn new(path: PathBuf) -> Self {
+		Self { path }
 	}
+}
 
-	pub fn calculate_relative_price(
-		&self,
-		src_network: NetworkId,
-		dest_network: NetworkId,
-		src_usd_price: f64,
-		dest_usd_price: f64,
-	) -> Result<Ratio<BigUint>> {
-		let src_config = self.config.network(src_network)?;
-		let src_margin: f64 = src_config.gmp_margin;
-		let src_decimals = self.currency(Some(src_network))?.0;
-
-		let dest_config = self.config.network(dest_network)?;
-		let dest_decimals = self.currency(Some(dest_network))?.0;
-		let dest_gas_fee = dest_config.route_base_fee;
-
-		let src_usd_price =
-			Ratio::from_float(src_usd_price).context("Cannot convert float to ratio")?;
-		let src_usd_price = convert_bigint_ratio_to_biguint(src_usd_price)?;
-		let dest_usd_price =
-			Ratio::from_float(dest_usd_price).context("Cannot convert float to ratio")?;
-		let dest_usd_price = convert_bigint_ratio_to_biguint(dest_usd_price)?;
-
-		// Parse the price strings into `Ratio<BigUint>` for arbitrary precision
-		let src_margin = Ratio::from_float(src_margin).context("Cannot convert float to ratio")?;
-
-		// src to dest relative gas price
-		let mut src_to_dest = compute_src_wei_per_dst_gas_rate(
-			src_usd_price.clone(),
-			src_decimals,
-			dest_usd_price.clone(),
-			dest_decimals,
-			dest_gas_fee,
-		);
+#[async_trait::async_trait]
+impl PriceSource for FileSource {
+	async fn price(&self, network: NetworkId, _symbol: &str) -> Result<f64> {
+		read_csv_token_prices(&self.path)?
+			.get(&network)
+			.map(|(_, price)| *price)
+			.ok_or_else(|| anyhow::anyhow!("Unable to get network {} from csv", network))
+	}
+}
 
-		// Add margin
-		src_to_dest += src_to_dest.clone() * convert_bigint_ratio_to_biguint(src_margin.clone())?;
+/// Available price sources of `tc-cli fetch-prices`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum PriceSourceKind {
+	/// CoinMarketCap api configured with `TOKEN_PRICE_URL` and `TOKEN_API_KEY`.
+	#[default]
+	CoinMarketCap,
+	/// The prices csv of the environment.
+	File,
+}
 
-		log::info!(
-			"relative gas price {src_network} -> {dest_network}: {}",
-			to_fixed(src_to_dest.clone(), None),
-		);
-		Ok(src_to_dest)
+impl Tc {
+	pub fn price_source(&self, kind: PriceSourceKind) -> Result<Box<dyn PriceSource>> {
+		Ok(match kind {
+			PriceSourceKind::CoinMarketCap => Box::new(CoinMarketCapSource::from_env()?),
+			PriceSourceKind::File => Box::new(FileSource::new(self.config.prices())),
+		})
+	}
+
+	/// Sets the token configs of the oracle pallet from the network configs.
+	pub async fn set_token_configs(&self) -> Result<()> {
+		for (network, config) in self.config.networks() {
+			let decimals = self.currency(Some(*network))?.0;
+			let margin = (config.gmp_margin * MARGIN_ACCURACY) as u32;
+			self.println(None, format!("set_token_config {network}")).await?;
+			self.runtime
+				.set_token_config(*network, decimals, config.route_base_fee, margin)
+				.await?;
+		}
+		Ok(())
+	}
+
+	/// Submits the prices of all networks to the oracle pallet, registering the
+	/// timechain account as a feeder if needed.
+	pub async fn feed_prices(&self, source: &dyn PriceSource) -> Result<()> {
+		let account = self.runtime.account_id();
+		if !self.runtime.is_feeder(account).await? {
+			self.println(None, "add_feeder").await?;
+			self.runtime.add_feeder(account.clone()).await?;
+		}
+		let mut prices = Vec::with_capacity(self.config.networks().len());
+		for network in self.config.networks().keys().copied() {
+			let symbol = self.currency(Some(network))?.1;
+			let price = source.price(network, symbol).await?;
+			log::info!("price of {symbol} on {network}: {price} USD");
+			prices.push((network, (price * PRICE_ACCURACY) as u128));
+		}
+		self.println(None, format!("submit_prices {}", prices.len())).await?;
+		self.runtime.submit_prices(prices).await
 	}
 }
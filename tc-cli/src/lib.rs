@@ -1,6 +1,5 @@
 use crate::config::Config;
 use crate::env::Mnemonics;
-use crate::gas_price::{convert_bigint_to_u128, get_network_price};
 use crate::table::IntoRow;
 use anyhow::{Context, Result};
 use futures::stream::{BoxStream, FuturesUnordered, StreamExt};
@@ -12,7 +11,9 @@ use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
-use tc_subxt::SubxtClient;
+use tc_subxt::offline::{Authorization, OfflineTx};
+use tc_subxt::worker::Tx;
+use tc_subxt::{EventFilter, SubxtClient, TimechainEvent};
 use time_primitives::{
 	balance::BalanceFormatter, traits::IdentifyAccount, AccountId, Address, BatchId, BlockHash,
 	BlockNumber, ChainName, ChainNetwork, ConnectorParams, Gateway, GatewayMessage, GmpEvent,
@@ -21,15 +22,28 @@ use time_primitives::{
 };
 use tokio::time::sleep;
 
+mod airdrop;
 mod config;
 mod env;
 mod gas_price;
+mod load_test;
 mod loki;
+mod monitor;
+mod plan;
 mod slack;
 mod table;
+mod treasurer;
 
+pub use crate::airdrop::{build_airdrop_tree, AirdropClaim, AirdropTree};
+pub use crate::gas_price::PriceSourceKind;
+pub use crate::load_test::{
+	LoadTestConfig, LoadTestReport, MessageSample, RouteReport, TrafficProfile,
+};
 pub use crate::loki::{Log, Query};
-pub use crate::slack::{Sender, TableRef, TextRef};
+pub use crate::monitor::MonitorConfig;
+pub use crate::plan::{Change, Plan};
+pub use crate::slack::{Output, Sender, TableRef, TextRef};
+pub use crate::treasurer::Ledger;
 
 async fn sleep_or_abort(duration: Duration) -> Result<()> {
 	tokio::select! {
@@ -41,6 +55,18 @@ async fn sleep_or_abort(duration: Duration) -> Result<()> {
 	}
 }
 
+/// Signs an admin tx prepared with [`Tc::prepare_offline_tx`] with the `TIMECHAIN_MNEMONIC`,
+/// without connecting to the timechain.
+pub fn sign_offline_tx(env: &Path, path: &Path) -> Result<()> {
+	dotenv::from_path(env.join(".env")).ok();
+	let mnemonics = Mnemonics::from_env()?;
+	let keypair = tc_subxt::keypair_from_mnemonic(&mnemonics.timechain_mnemonic)?;
+	let mut tx: OfflineTx = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+	tx.sign(&keypair)?;
+	std::fs::write(path, serde_json::to_string_pretty(&tx)?)?;
+	Ok(())
+}
+
 pub struct Tc {
 	config: Config,
 	runtime: SubxtClient,
@@ -130,6 +156,29 @@ impl Tc {
 		self.runtime.set_code(bytecode).await
 	}
 
+	/// Writes an admin tx to `path` to be signed offline by `signer`.
+	pub async fn prepare_offline_tx(
+		&self,
+		signer: &str,
+		tx: Tx,
+		authorization: Authorization,
+		path: &Path,
+	) -> Result<()> {
+		let signer: AccountId = self.parse_address(None, signer)?.into();
+		let tx = self.runtime.prepare_offline_tx(&signer, tx, authorization).await?;
+		std::fs::write(path, serde_json::to_string_pretty(&tx)?)?;
+		self.println(None, format!("tx with nonce {} written to {}", tx.nonce, path.display()))
+			.await?;
+		Ok(())
+	}
+
+	pub async fn submit_offline_tx(&self, path: &Path) -> Result<()> {
+		let tx: OfflineTx = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+		let hash = self.runtime.submit_offline_tx(&tx).await?;
+		self.println(None, format!("tx {} finalized", hex::encode(hash))).await?;
+		Ok(())
+	}
+
 	pub async fn find_online_shard_keys(&self, network: NetworkId) -> Result<Vec<TssPublicKey>> {
 		let shard_id_counter = self.runtime.shard_id_counter().await?;
 		let mut shards = vec![];
@@ -412,6 +461,14 @@ pub struct MessageTrace {
 	pub exec: Option<Task>,
 }
 
+/// Routes are considered the same if the relative gas prices are within the tolerance.
+fn same_route(a: &Route, b: &Route) -> bool {
+	a.network_id == b.network_id
+		&& a.gas_limit == b.gas_limit
+		&& a.base_fee == b.base_fee
+		&& (a.relative_gas_price() - b.relative_gas_price()).abs() < 100_000.0
+}
+
 fn same<T: PartialEq>(a: &[T], b: &[T]) -> bool {
 	if a.len() != b.len() {
 		return false;
@@ -598,6 +655,22 @@ impl Tc {
 		connector.read_events(gateway, blocks).await
 	}
 
+	/// Prints the events of newly finalized blocks starting at `start` until aborted.
+	pub async fn follow_events(&self, network: NetworkId, mut start: u64) -> Result<()> {
+		loop {
+			sleep_or_abort(Duration::from_secs(5)).await?;
+			let end = self.connector(network)?.finalized_block().await? + 1;
+			if end <= start {
+				continue;
+			}
+			let events = self.events(network, start..end).await?;
+			if !events.is_empty() {
+				self.print_table(None, "events", events).await?;
+			}
+			start = end;
+		}
+	}
+
 	pub async fn messages(
 		&self,
 		network: NetworkId,
@@ -693,7 +766,6 @@ impl Tc {
 impl Tc {
 	async fn register_network(&self, network: NetworkId) -> Result<Gateway> {
 		let connector = self.connector(network)?;
-		let config = self.config.network(network)?;
 		let contracts = self.config.contracts(network)?;
 		let gateway = if let Some(gateway) = self.runtime.network_gateway(network).await? {
 			self.set_network_config(network).await?;
@@ -705,51 +777,56 @@ impl Tc {
 				.await?;
 			self.println(None, format!("register_network {network}")).await?;
 			self.runtime
-				.register_network(time_primitives::Network {
-					id: network,
-					chain_name: ChainName(BoundedVec::truncate_from(config.blockchain.encode())),
-					chain_network: ChainNetwork(BoundedVec::truncate_from(config.network.encode())),
-					gateway,
-					gateway_block: block,
-					config: NetworkConfig {
-						batch_size: config.batch_size,
-						batch_offset: config.batch_offset,
-						batch_gas_limit: config.batch_gas_limit,
-						shard_task_limit: config.shard_task_limit,
-						shard_size: config.shard_size,
-						shard_threshold: config.shard_threshold,
-					},
-				})
+				.register_network(self.network_registration(network, gateway, block)?)
 				.await?;
 			gateway
 		};
 		Ok(gateway)
 	}
 
-	async fn set_network_config(&self, network: NetworkId) -> Result<()> {
+	pub fn network_config(&self, network: NetworkId) -> Result<NetworkConfig> {
 		let config = self.config.network(network)?;
-		let config = NetworkConfig {
+		Ok(NetworkConfig {
 			batch_size: config.batch_size,
 			batch_offset: config.batch_offset,
 			batch_gas_limit: config.batch_gas_limit,
 			shard_task_limit: config.shard_task_limit,
 			shard_size: config.shard_size,
 			shard_threshold: config.shard_threshold,
-		};
+		})
+	}
 
-		let batch_size = self.runtime.network_batch_size(network).await?;
-		let batch_offset = self.runtime.network_batch_offset(network).await?;
-		let batch_gas_limit = self.runtime.network_batch_gas_limit(network).await?;
-		let shard_task_limit = self.runtime.network_shard_task_limit(network).await?;
-		let shard_size = self.runtime.network_shard_size(network).await?;
-		let shard_threshold = self.runtime.network_shard_threshold(network).await?;
-		if batch_size == config.batch_size
-			&& batch_offset == config.batch_offset
-			&& batch_gas_limit == config.batch_gas_limit
-			&& shard_task_limit == config.shard_task_limit
-			&& shard_size == config.shard_size
-			&& shard_threshold == config.shard_threshold
-		{
+	pub fn network_registration(
+		&self,
+		network: NetworkId,
+		gateway: Gateway,
+		gateway_block: u64,
+	) -> Result<time_primitives::Network> {
+		let config = self.config.network(network)?;
+		Ok(time_primitives::Network {
+			id: network,
+			chain_name: ChainName(BoundedVec::truncate_from(config.blockchain.encode())),
+			chain_network: ChainNetwork(BoundedVec::truncate_from(config.network.encode())),
+			gateway,
+			gateway_block,
+			config: self.network_config(network)?,
+		})
+	}
+
+	async fn onchain_network_config(&self, network: NetworkId) -> Result<NetworkConfig> {
+		Ok(NetworkConfig {
+			batch_size: self.runtime.network_batch_size(network).await?,
+			batch_offset: self.runtime.network_batch_offset(network).await?,
+			batch_gas_limit: self.runtime.network_batch_gas_limit(network).await?,
+			shard_task_limit: self.runtime.network_shard_task_limit(network).await?,
+			shard_size: self.runtime.network_shard_size(network).await?,
+			shard_threshold: self.runtime.network_shard_threshold(network).await?,
+		})
+	}
+
+	async fn set_network_config(&self, network: NetworkId) -> Result<()> {
+		let config = self.network_config(network)?;
+		if self.onchain_network_config(network).await? == config {
 			return Ok(());
 		}
 		self.println(None, format!("set_network_config {network}")).await?;
@@ -757,35 +834,45 @@ impl Tc {
 		Ok(())
 	}
 
+	/// Route from `src` to `dest` as configured, priced by the oracle pallet. Returns
+	/// `None` if the oracle has no prices for `src` or `dest` yet.
+	async fn try_route(
+		&self,
+		src: NetworkId,
+		dest: NetworkId,
+		dest_gateway: Gateway,
+	) -> Result<Option<Route>> {
+		let config = self.config.network(dest)?;
+		let Some(relative_gas_price) = self.runtime.relative_gas_price(src, dest).await? else {
+			return Ok(None);
+		};
+		Ok(Some(Route {
+			network_id: dest,
+			gateway: dest_gateway,
+			relative_gas_price,
+			gas_limit: config.route_gas_limit,
+			base_fee: config.route_base_fee,
+		}))
+	}
+
+	/// Route from `src` to `dest` as configured, priced by the oracle pallet.
+	async fn route(&self, src: NetworkId, dest: NetworkId, dest_gateway: Gateway) -> Result<Route> {
+		self.try_route(src, dest, dest_gateway)
+			.await?
+			.with_context(|| format!("no oracle prices for route {src} -> {dest}"))
+	}
+
+	/// Sets the on-chain routes, which are sent to the gateways by the timechain.
 	async fn register_routes(&self, gateways: HashMap<NetworkId, Gateway>) -> Result<()> {
-		for (src, src_gateway) in gateways.iter().map(|(src, gateway)| (*src, *gateway)) {
-			let connector = self.connector(src)?;
-			let routes = connector.routes(src_gateway).await?;
+		for src in gateways.keys().copied() {
+			let routes = self.runtime.routes(src).await?;
 			for (dest, dest_gateway) in gateways.iter().map(|(dest, gateway)| (*dest, *gateway)) {
-				let config = self.config.network(dest)?;
-				let network_prices = self.read_csv_token_prices()?;
-				let src_price = gas_price::get_network_price(&network_prices, &src)?;
-				let dest_price = get_network_price(&network_prices, &dest)?;
-				let ratio = self.calculate_relative_price(src, dest, src_price, dest_price)?;
-				let numerator = convert_bigint_to_u128(ratio.numer())?;
-				let denominator = convert_bigint_to_u128(ratio.denom())?;
-				let route = Route {
-					network_id: dest,
-					gateway: dest_gateway,
-					relative_gas_price: (numerator, denominator),
-					gas_limit: config.route_gas_limit,
-					base_fee: config.route_base_fee,
-				};
-				if let Some(r) = routes.iter().find(|r| r.network_id == route.network_id) {
-					if r.gas_limit == route.gas_limit
-						&& r.base_fee == route.base_fee
-						&& r.relative_gas_price() - route.relative_gas_price() < 100_000.0
-					{
-						continue;
-					}
+				let route = self.route(src, dest, dest_gateway).await?;
+				if routes.iter().any(|r| same_route(r, &route)) {
+					continue;
 				}
 				self.println(None, format!("register_route {src} {dest}")).await?;
-				connector.set_route(src_gateway, route).await?;
+				self.runtime.set_route(src, route).await?;
 			}
 		}
 		Ok(())
@@ -918,6 +1005,8 @@ impl Tc {
 			let gateway = self.deploy_network(network).await?;
 			gateways.insert(network, gateway);
 		}
+		self.set_token_configs().await?;
+		self.feed_prices(&gas_price::FileSource::new(self.config.prices())).await?;
 		self.register_routes(gateways).await?;
 		for chronicle in self.config.chronicles() {
 			self.deploy_chronicle(chronicle).await?;
@@ -1104,9 +1193,12 @@ impl Tc {
 		self.register_shards(dest).await?;
 		while blocks.next().await.is_some() {
 			let shards = self.shards().await?;
-			let is_registered =
-				shards.iter().any(|shard| shard.registered && shard.network == dest);
-			tracing::info!("waiting for shard to be registered");
+			// routes are queued before the shards are registered, so they are set once
+			// the shards of both networks are registered
+			let is_registered = [src, dest].iter().all(|network| {
+				shards.iter().any(|shard| shard.registered && shard.network == *network)
+			});
+			tracing::info!("waiting for shards to be registered");
 			id = Some(self.print_table(id, "shards", shards).await?);
 			if is_registered {
 				break;
@@ -1116,8 +1208,9 @@ impl Tc {
 	}
 
 	pub async fn wait_for_sync(&self, network: NetworkId) -> Result<()> {
-		let mut blocks = self.finality_notification_stream();
-		while blocks.next().await.is_some() {
+		// the read events task is replaced with a new one every time it completes
+		let mut events = self.runtime.event_stream(EventFilter::network(network), None);
+		loop {
 			let status = self.sync_status(network).await?;
 			tracing::info!(
 				"waiting for network {network} to sync {} / {}",
@@ -1127,6 +1220,18 @@ impl Tc {
 			if status.next_sync > status.block {
 				break;
 			}
+			while let Some((_, event)) = events.next().await {
+				match event {
+					TimechainEvent::NetworkRemovalStarted(_)
+					| TimechainEvent::NetworkRemoved(_) => {
+						anyhow::bail!("network {network} was removed while waiting for sync");
+					},
+					TimechainEvent::TaskCreated(_)
+					| TimechainEvent::NetworkResumed(_)
+					| TimechainEvent::GatewayMigrated(_) => break,
+					_ => {},
+				}
+			}
 		}
 		Ok(())
 	}
@@ -1137,6 +1242,11 @@ impl Tc {
 		title: &str,
 		table: Vec<R>,
 	) -> Result<TableRef> {
+		if self.msg.output() == Output::Json {
+			let records =
+				table.into_iter().map(|row| row.into_record(self)).collect::<Result<_>>()?;
+			return self.msg.json(records).await;
+		}
 		let mut out = Vec::new();
 		{
 			let mut wtr = csv::Writer::from_writer(&mut out);
@@ -1148,13 +1258,38 @@ impl Tc {
 		self.msg.csv(id, title, out).await
 	}
 
+	pub fn output(&self) -> Output {
+		self.msg.output()
+	}
+
+	/// Prints a single json record, used for nested structures that don't fit a table.
+	pub async fn print_record(&self, record: impl serde::Serialize) -> Result<TableRef> {
+		self.msg.json(vec![serde_json::to_value(record)?]).await
+	}
+
 	pub async fn println(&self, id: Option<TextRef>, line: impl Into<String>) -> Result<TextRef> {
 		self.msg.text(id, line.into()).await
 	}
 
-	pub async fn log(&self, query: Query, since: String) -> Result<TableRef> {
-		let logs = loki::logs(query, since).await?;
-		self.print_table(None, "logs", logs).await
+	/// Prints the logs matching `query`, followed by new logs until aborted if `follow`
+	/// is set.
+	pub async fn log(&self, query: Query, since: String, follow: bool) -> Result<()> {
+		let query = query.to_string();
+		let logs = loki::logs(&query, since, None).await?;
+		let mut last = logs.iter().map(|(timestamp, _)| *timestamp).max();
+		self.print_table(None, "logs", logs.into_iter().map(|(_, log)| log).collect())
+			.await?;
+		while follow {
+			sleep_or_abort(Duration::from_secs(5)).await?;
+			let logs = loki::logs(&query, "5m".into(), last.map(|last| last + 1)).await?;
+			if logs.is_empty() {
+				continue;
+			}
+			last = logs.iter().map(|(timestamp, _)| *timestamp).max().or(last);
+			self.print_table(None, "logs", logs.into_iter().map(|(_, log)| log).collect())
+				.await?;
+		}
+		Ok(())
 	}
 
 	pub async fn debug_transaction(&self, network: NetworkId, hash: Hash) -> Result<String> {
@@ -1171,4 +1306,36 @@ impl Tc {
 		let connector = self.connector(network)?;
 		connector.load_state(state).await
 	}
+
+	pub async fn advance_blocks(&self, network: NetworkId, blocks: u64) -> Result<u64> {
+		let connector = self.connector(network)?;
+		connector.advance_blocks(blocks).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn route(relative_gas_price: (u128, u128)) -> Route {
+		Route {
+			network_id: 2,
+			gateway: [0; 32],
+			relative_gas_price,
+			gas_limit: 100_000,
+			base_fee: 0,
+		}
+	}
+
+	#[test]
+	fn same_route_tolerates_price_changes_both_ways() {
+		let onchain = route((1_000_000, 1));
+		assert!(same_route(&onchain, &route((1_050_000, 1))));
+		assert!(same_route(&onchain, &route((950_000, 1))));
+		// the on chain price is lower than the new one
+		assert!(!same_route(&onchain, &route((1_200_000, 1))));
+		// the on chain price is higher than the new one
+		assert!(!same_route(&onchain, &route((800_000, 1))));
+		assert!(!same_route(&onchain, &Route { base_fee: 1, ..onchain.clone() }));
+	}
 }
@@ -0,0 +1,273 @@
+use crate::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+/// How messages are sent on every route.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(tag = "profile", rename_all = "snake_case")]
+pub enum TrafficProfile {
+	/// Sends `rate` messages per route every block.
+	Constant { rate: u32 },
+	/// Sends `size` messages per route every `interval` blocks.
+	Burst { size: u32, interval: u64 },
+}
+
+impl TrafficProfile {
+	fn messages(&self, block: u64) -> u32 {
+		match *self {
+			Self::Constant { rate } => rate,
+			Self::Burst { size, interval } => {
+				if block % interval.max(1) == 0 {
+					size
+				} else {
+					0
+				}
+			},
+		}
+	}
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LoadTestConfig {
+	pub profile: TrafficProfile,
+	/// Number of blocks messages are sent for.
+	pub blocks: u64,
+	/// Number of tester contracts sending and receiving messages on every network.
+	pub senders: usize,
+	/// Payload sizes used in turn, repeat a size to weight it.
+	pub payload_sizes: Vec<usize>,
+	/// Number of blocks to wait for outstanding messages after sending stopped.
+	pub timeout: u64,
+}
+
+/// Lifecycle of a message, stages are the timechain block they were first observed at.
+#[derive(Clone, Debug, Serialize)]
+pub struct MessageSample {
+	pub message: String,
+	pub src: NetworkId,
+	pub dest: NetworkId,
+	pub payload_size: usize,
+	pub gas_cost: u128,
+	pub sent: BlockNumber,
+	pub received: Option<BlockNumber>,
+	pub batched: Option<BlockNumber>,
+	pub submitted: Option<BlockNumber>,
+	pub executed: Option<BlockNumber>,
+	/// Seconds between sending the message and observing its execution.
+	pub latency_secs: Option<f64>,
+	pub batch: Option<BatchId>,
+	#[serde(skip)]
+	id: MessageId,
+	#[serde(skip)]
+	sent_at: Instant,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RouteReport {
+	pub src: NetworkId,
+	pub dest: NetworkId,
+	pub sent: usize,
+	pub executed: usize,
+	/// End-to-end latency percentiles in blocks.
+	pub p50_blocks: Option<u32>,
+	pub p95_blocks: Option<u32>,
+	pub p99_blocks: Option<u32>,
+	/// End-to-end latency percentiles in seconds.
+	pub p50_secs: Option<f64>,
+	pub p95_secs: Option<f64>,
+	pub p99_secs: Option<f64>,
+	/// Average number of operations per batch relative to the batch size.
+	pub batch_fill_ratio: Option<f64>,
+	/// Average cost per message in the currency of the source network.
+	pub cost_per_message: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LoadTestReport {
+	pub spec_version: u32,
+	pub config: LoadTestConfig,
+	pub routes: Vec<RouteReport>,
+	pub messages: Vec<MessageSample>,
+}
+
+/// Nearest rank percentile of sorted values.
+fn percentile<T: Copy>(sorted: &[T], percentile: usize) -> Option<T> {
+	if sorted.is_empty() {
+		return None;
+	}
+	let rank = (percentile * sorted.len()).div_ceil(100).max(1);
+	Some(sorted[rank - 1])
+}
+
+impl Tc {
+	/// Sends messages on all routes between the configured networks according to the
+	/// traffic profile and traces them until they are executed or timed out.
+	pub async fn load_test(&self, config: LoadTestConfig) -> Result<LoadTestReport> {
+		anyhow::ensure!(!config.payload_sizes.is_empty(), "no payload sizes");
+		anyhow::ensure!(config.senders > 0, "no senders");
+		let mut networks: Vec<_> = self.connectors.keys().copied().collect();
+		networks.sort();
+		self.deploy().await?;
+		let mut testers = HashMap::new();
+		for network in networks.iter().copied() {
+			let mut addresses = vec![];
+			for _ in 0..config.senders {
+				let (address, _) = self.deploy_tester(network).await?;
+				addresses.push(address);
+			}
+			testers.insert(network, addresses);
+		}
+		let mut blocks = self.finality_notification_stream();
+		for network in networks.iter().copied() {
+			while self.find_online_shard_keys(network).await?.is_empty() {
+				tracing::info!("waiting for shards of {network} to come online");
+				blocks.next().await.context("expected block")?;
+			}
+			self.register_shards(network).await?;
+		}
+		let routes: Vec<_> = networks
+			.iter()
+			.flat_map(|src| networks.iter().map(move |dest| (*src, *dest)))
+			.filter(|(src, dest)| src != dest)
+			.collect();
+		let mut costs = HashMap::new();
+		let mut samples: Vec<MessageSample> = vec![];
+		let mut batches: HashMap<BatchId, f64> = HashMap::new();
+		let mut counter = 0;
+		let (_, start) = blocks.next().await.context("expected block")?;
+		let mut id = None;
+		while let Some((_, block)) = blocks.next().await {
+			let elapsed = (block - start) as u64;
+			if elapsed < config.blocks {
+				for (src, dest) in routes.iter().copied() {
+					for _ in 0..config.profile.messages(elapsed) {
+						let src_addr = testers[&src][counter % config.senders];
+						let dest_addr = testers[&dest][counter % config.senders];
+						let payload_size =
+							config.payload_sizes[counter % config.payload_sizes.len()];
+						counter += 1;
+						let payload = vec![0xff; payload_size];
+						let (gas_limit, gas_cost) = match costs.get(&(src, dest, payload_size)) {
+							Some(cost) => *cost,
+							None => {
+								let gas_limit = self
+									.estimate_message_gas_limit(
+										dest,
+										dest_addr,
+										src,
+										src_addr,
+										payload.clone(),
+									)
+									.await?;
+								let gas_cost = self
+									.estimate_message_cost(src, dest, gas_limit, payload.clone())
+									.await?;
+								costs.insert((src, dest, payload_size), (gas_limit, gas_cost));
+								(gas_limit, gas_cost)
+							},
+						};
+						let message = self
+							.connector(src)?
+							.send_message(src_addr, dest, dest_addr, gas_limit, gas_cost, payload)
+							.await?;
+						samples.push(MessageSample {
+							message: hex::encode(message),
+							src,
+							dest,
+							payload_size,
+							gas_cost,
+							sent: block,
+							received: None,
+							batched: None,
+							submitted: None,
+							executed: None,
+							latency_secs: None,
+							batch: None,
+							id: message,
+							sent_at: Instant::now(),
+						});
+					}
+				}
+			}
+			for sample in samples.iter_mut().filter(|sample| sample.executed.is_none()) {
+				let message = self.message(sample.id).await?;
+				if message.recv.is_some() {
+					sample.received.get_or_insert(block);
+				}
+				if let Some(batch) = message.batch {
+					sample.batched.get_or_insert(block);
+					sample.batch = Some(batch);
+					if sample.submitted.is_none() {
+						let info = self.batch(batch).await?;
+						if info.tx.is_some() {
+							sample.submitted = Some(block);
+						}
+						if let Entry::Vacant(e) = batches.entry(batch) {
+							let batch_size = self.config.network(sample.dest)?.batch_size;
+							e.insert(info.msg.ops.len() as f64 / batch_size as f64);
+						}
+					}
+				}
+				if message.exec.is_some() {
+					sample.executed = Some(block);
+					sample.latency_secs = Some(sample.sent_at.elapsed().as_secs_f64());
+				}
+			}
+			let pending = samples.iter().filter(|sample| sample.executed.is_none()).count();
+			id = Some(
+				self.println(
+					id,
+					format!("block {elapsed}: sent {} messages, {pending} pending", samples.len()),
+				)
+				.await?,
+			);
+			if elapsed >= config.blocks
+				&& (pending == 0 || elapsed >= config.blocks + config.timeout)
+			{
+				break;
+			}
+		}
+		let mut reports = vec![];
+		for (src, dest) in routes {
+			let route: Vec<_> = samples
+				.iter()
+				.filter(|sample| sample.src == src && sample.dest == dest)
+				.collect();
+			let mut latencies: Vec<_> =
+				route.iter().filter_map(|sample| Some(sample.executed? - sample.sent)).collect();
+			latencies.sort();
+			let mut secs: Vec<_> = route.iter().filter_map(|sample| sample.latency_secs).collect();
+			secs.sort_by(f64::total_cmp);
+			let route_batches: BTreeMap<_, _> = route
+				.iter()
+				.filter_map(|sample| sample.batch)
+				.filter_map(|batch| Some((batch, *batches.get(&batch)?)))
+				.collect();
+			let batch_fill_ratio = (!route_batches.is_empty())
+				.then(|| route_batches.values().sum::<f64>() / route_batches.len() as f64);
+			let cost = route.iter().map(|sample| sample.gas_cost).sum::<u128>()
+				/ route.len().max(1) as u128;
+			reports.push(RouteReport {
+				src,
+				dest,
+				sent: route.len(),
+				executed: latencies.len(),
+				p50_blocks: percentile(&latencies, 50),
+				p95_blocks: percentile(&latencies, 95),
+				p99_blocks: percentile(&latencies, 99),
+				p50_secs: percentile(&secs, 50),
+				p95_secs: percentile(&secs, 95),
+				p99_secs: percentile(&secs, 99),
+				batch_fill_ratio,
+				cost_per_message: self.format_balance(Some(src), cost)?,
+			});
+		}
+		Ok(LoadTestReport {
+			spec_version: self.runtime.spec_version(),
+			config,
+			routes: reports,
+			messages: samples,
+		})
+	}
+}
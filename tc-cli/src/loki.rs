@@ -4,15 +4,19 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use time_primitives::{BlockNumber, ShardId, TaskId};
 
-//const DIRECTION_FORWARD: &'static str = "FORWARD";
+const DIRECTION_FORWARD: &str = "FORWARD";
 //const DIRECTION_BACKWARD: &'static str = "BACKWARD";
 
 #[derive(Serialize)]
 struct Request {
 	pub query: String,
 	pub since: String,
+	/// Start timestamp in nanoseconds, takes precedence over `since`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub start: Option<u128>,
 	//pub limit: Option<u32>,
-	//pub direction: Option<&'static str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub direction: Option<&'static str>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -176,16 +180,23 @@ impl std::str::FromStr for Log {
 	}
 }
 
-pub async fn logs(query: Query, since: String) -> Result<Vec<Log>> {
-	let query = query.to_string();
+/// Queries the logs matching `query` together with their timestamp in nanoseconds. When
+/// `start` is set the oldest logs after `start` are returned in ascending order.
+pub async fn logs(query: &str, since: String, start: Option<u128>) -> Result<Vec<(u128, Log)>> {
 	log::info!("{query}");
 	let env = Loki::from_env()?;
 	let client = reqwest::Client::new();
 	let url: reqwest::Url = format!("{}/loki/api/v1/query_range", &env.loki_url).parse()?;
+	let direction = start.map(|_| DIRECTION_FORWARD);
 	let req = client
 		.get(url)
 		.basic_auth(env.loki_username, Some(env.loki_password))
-		.query(&Request { query, since })
+		.query(&Request {
+			query: query.into(),
+			since,
+			start,
+			direction,
+		})
 		.build()
 		.context("invalid request")?;
 	log::debug!("GET {}", req.url());
@@ -199,12 +210,17 @@ pub async fn logs(query: Query, since: String) -> Result<Vec<Log>> {
 	anyhow::ensure!(resp.status == "success", "unexpected status");
 	anyhow::ensure!(resp.data.result_type == "streams", "unexpected result type");
 
-	let logs = resp
+	let mut logs = resp
 		.data
 		.result
 		.into_iter()
 		.flat_map(|v| v.values)
-		.map(|(_, log)| log.parse().unwrap())
-		.collect::<Vec<Log>>();
+		.map(|(timestamp, log)| -> Result<(u128, Log)> {
+			Ok((timestamp.parse()?, log.parse().unwrap()))
+		})
+		.collect::<Result<Vec<_>>>()?;
+	if start.is_some() {
+		logs.sort_by_key(|(timestamp, _)| *timestamp);
+	}
 	Ok(logs)
 }
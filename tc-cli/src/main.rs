@@ -5,7 +5,13 @@ use std::collections::HashSet;
 use std::io::Write;
 use std::path::PathBuf;
 use std::str::FromStr;
-use tc_cli::{Query, Sender, Tc};
+use std::time::Duration;
+use tc_cli::{
+	Ledger, LoadTestConfig, MonitorConfig, Output, Plan, PriceSourceKind, Query, Sender, Tc,
+	TrafficProfile,
+};
+use tc_subxt::offline::{Authorization, Multisig};
+use tc_subxt::worker::Tx;
 use time_primitives::{BatchId, Hash, NetworkId, ShardId, TaskId};
 use tracing_subscriber::filter::EnvFilter;
 
@@ -29,12 +35,39 @@ impl FromStr for RelGasPrice {
 	}
 }
 
+async fn print_plan(tc: &Tc, plan: Plan) -> Result<()> {
+	if tc.output() == Output::Json {
+		tc.print_record(&plan).await?;
+		return Ok(());
+	}
+	for warning in &plan.warnings {
+		tc.println(None, format!("warning: {warning}")).await?;
+	}
+	if plan.is_empty() {
+		tc.println(None, "no changes").await?;
+	} else {
+		tc.print_table(None, "plan", plan.changes).await?;
+	}
+	Ok(())
+}
+
+fn confirm(question: &str) -> Result<bool> {
+	print!("{question} [y/N] ");
+	std::io::stdout().flush()?;
+	let mut answer = String::new();
+	std::io::stdin().read_line(&mut answer)?;
+	Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
 #[derive(Parser, Debug)]
 struct Args {
 	#[arg(long, default_value = "/etc/envs/local")]
 	env: PathBuf,
 	#[arg(long, default_value = "config.yaml")]
 	config: String,
+	/// Format of the printed tables, logs are written to stderr unless it is `table`.
+	#[arg(long, value_enum, default_value_t = Output::Table)]
+	output: Output,
 	#[clap(subcommand)]
 	cmd: Command,
 }
@@ -69,7 +102,10 @@ enum Command {
 		amount: String,
 	},
 	// read data
-	FetchPrices,
+	FetchPrices {
+		#[arg(long, value_enum, default_value_t = PriceSourceKind::CoinMarketCap)]
+		source: PriceSourceKind,
+	},
 	Networks,
 	Chronicles,
 	Shards,
@@ -83,6 +119,9 @@ enum Command {
 		network: NetworkId,
 		start: u64,
 		end: u64,
+		/// Keeps printing the events of new blocks.
+		#[arg(long)]
+		follow: bool,
 	},
 	Messages {
 		network: NetworkId,
@@ -116,11 +155,46 @@ enum Command {
 		network: NetworkId,
 		message: String,
 	},
+	/// Evaluates alert rules on every finalized block.
+	Monitor {
+		/// Maximum number of blocks the gateway events may lag behind a network.
+		#[arg(long, default_value_t = 100)]
+		max_sync_lag: u64,
+		/// Number of blocks the unassigned tasks may grow before alerting.
+		#[arg(long, default_value_t = 20)]
+		backlog_blocks: usize,
+		/// Number of blocks between evaluations of the failed batch and chronicle balance rules.
+		#[arg(long, default_value_t = 10)]
+		poll_blocks: u64,
+		/// Port to export prometheus metrics on.
+		#[arg(long)]
+		prometheus_port: Option<u16>,
+	},
+	/// Tops up chronicle wallets and sweeps gateway surplus periodically.
+	Treasurer {
+		/// Append-only csv ledger of all movements.
+		#[arg(long, default_value = "treasurer.csv")]
+		ledger: PathBuf,
+		/// Seconds between two rounds.
+		#[arg(long, default_value_t = 60)]
+		interval: u64,
+		/// Runs a single round and exits.
+		#[arg(long)]
+		once: bool,
+	},
 	// management
 	RuntimeUpgrade {
 		path: PathBuf,
 	},
 	Deploy,
+	/// Shows the changes required to reconcile the on-chain and gateway state with the config.
+	Plan,
+	/// Applies the changes shown by `plan`.
+	Apply {
+		/// Doesn't ask for confirmation.
+		#[arg(long)]
+		yes: bool,
+	},
 	DeployChronicle {
 		url: String,
 	},
@@ -186,11 +260,38 @@ enum Command {
 		dest: NetworkId,
 		num_messages: u16,
 	},
+	/// Sends messages on all routes and reports latency, batch fill ratio and cost.
+	LoadTest {
+		/// Messages per route and block, or per burst when `burst_interval` is set.
+		#[arg(long, default_value_t = 1)]
+		rate: u32,
+		/// Sends bursts of `rate` messages every `burst_interval` blocks.
+		#[arg(long)]
+		burst_interval: Option<u64>,
+		/// Number of blocks to send messages for.
+		#[arg(long, default_value_t = 10)]
+		blocks: u64,
+		/// Number of sender contracts per network.
+		#[arg(long, default_value_t = 1)]
+		senders: usize,
+		/// Payload sizes used in turn, repeat a size to weight it.
+		#[arg(long, value_delimiter = ',', default_value = "0")]
+		payload_sizes: Vec<usize>,
+		/// Number of blocks to wait for outstanding messages.
+		#[arg(long, default_value_t = 100)]
+		timeout: u64,
+		/// File the json report is written to.
+		#[arg(long, default_value = "load-test.json")]
+		out: PathBuf,
+	},
 	Log {
 		#[clap(subcommand)]
 		query: Query,
 		#[arg(long, default_value = "7d")]
 		since: String,
+		/// Keeps printing new logs.
+		#[arg(long)]
+		follow: bool,
 	},
 	ForceShardOffline {
 		shard_id: ShardId,
@@ -207,6 +308,95 @@ enum Command {
 		network: NetworkId,
 		path: Option<PathBuf>,
 	},
+	AdvanceBlocks {
+		network: NetworkId,
+		#[arg(default_value_t = 1)]
+		blocks: u64,
+	},
+	Offline {
+		#[clap(subcommand)]
+		cmd: OfflineCommand,
+	},
+}
+
+#[derive(Parser, Debug)]
+enum OfflineCommand {
+	/// Writes an admin tx to `out` to be signed by `signer` on another machine.
+	Prepare {
+		#[arg(long)]
+		signer: String,
+		/// Proposes the call to the technical committee instead of executing it.
+		#[arg(long)]
+		proposal_threshold: Option<u32>,
+		/// Submits the call through a multisig of the signer and the `signatory`s.
+		#[arg(long, requires = "signatory")]
+		multisig_threshold: Option<u16>,
+		#[arg(long)]
+		signatory: Vec<String>,
+		/// Timepoint `height:index` of the first multisig approval.
+		#[arg(long)]
+		timepoint: Option<String>,
+		#[arg(long)]
+		out: PathBuf,
+		#[clap(subcommand)]
+		call: AdminCall,
+	},
+	/// Signs a prepared tx without connecting to the timechain.
+	Sign { path: PathBuf },
+	/// Submits a signed tx.
+	Submit { path: PathBuf },
+	/// Builds the merkle tree of an airdrop round from a csv of claims and
+	/// writes the proofs of all claims to `out`.
+	AirdropTree {
+		csv: PathBuf,
+		#[arg(long)]
+		out: PathBuf,
+	},
+}
+
+#[derive(Parser, Debug)]
+enum AdminCall {
+	RuntimeUpgrade { path: PathBuf },
+	RegisterNetwork { network: NetworkId, gateway: String, gateway_block: u64 },
+	SetNetworkConfig { network: NetworkId },
+	MigrateGateway { network: NetworkId, gateway: String, gateway_block: u64 },
+	ForceShardOffline { shard_id: ShardId },
+	RemoveTask { task_id: TaskId },
+	RestartBatch { batch_id: BatchId },
+}
+
+impl AdminCall {
+	fn into_tx(self, tc: &Tc) -> Result<Tx> {
+		Ok(match self {
+			Self::RuntimeUpgrade { path } => Tx::SetCode { code: std::fs::read(path)? },
+			Self::RegisterNetwork {
+				network,
+				gateway,
+				gateway_block,
+			} => {
+				let gateway = tc.parse_address(Some(network), &gateway)?;
+				Tx::RegisterNetwork {
+					network: tc.network_registration(network, gateway, gateway_block)?,
+				}
+			},
+			Self::SetNetworkConfig { network } => Tx::SetNetworkConfig {
+				network,
+				config: tc.network_config(network)?,
+			},
+			Self::MigrateGateway {
+				network,
+				gateway,
+				gateway_block,
+			} => Tx::MigrateGateway {
+				network,
+				gateway: tc.parse_address(Some(network), &gateway)?,
+				block: gateway_block,
+			},
+			Self::ForceShardOffline { shard_id } => Tx::ForceShardOffline { shard_id },
+			Self::RemoveTask { task_id } => Tx::RemoveTask { task_id },
+			Self::RestartBatch { batch_id } => Tx::RestartBatch { batch_id },
+		})
+	}
 }
 
 #[tokio::main]
@@ -228,10 +418,31 @@ async fn real_main() -> Result<()> {
 	let filter = EnvFilter::from_default_env()
 		.add_directive("tc_cli=info".parse().unwrap())
 		.add_directive("gmp_evm=info".parse().unwrap());
-	tracing_subscriber::fmt().with_env_filter(filter).init();
-	let sender = Sender::new();
 	let args = Args::parse();
+	let subscriber = tracing_subscriber::fmt().with_env_filter(filter);
+	if args.output == Output::Table {
+		subscriber.init();
+	} else {
+		subscriber.with_writer(std::io::stderr).init();
+	}
+	let sender = Sender::new().with_output(args.output);
 	tracing::info!("main");
+	if let Command::Offline {
+		cmd: OfflineCommand::Sign { path },
+	} = &args.cmd
+	{
+		return tc_cli::sign_offline_tx(&args.env, path);
+	}
+	if let Command::Offline {
+		cmd: OfflineCommand::AirdropTree { csv, out },
+	} = &args.cmd
+	{
+		let tree = tc_cli::build_airdrop_tree(csv, out)?;
+		println!("merkle root: {}", tree.merkle_root);
+		println!("total: {}", tree.total);
+		println!("claims: {}", tree.claims.len());
+		return Ok(());
+	}
 	let now = std::time::SystemTime::now();
 	let tc = args.tc(sender).await?;
 	tracing::info!("tc ready in {}s", now.elapsed().unwrap().as_secs());
@@ -262,8 +473,9 @@ async fn real_main() -> Result<()> {
 			tc.transfer(network, address, amount).await?;
 		},
 		// read data
-		Command::FetchPrices => {
-			tc.fetch_token_prices().await?;
+		Command::FetchPrices { source } => {
+			let source = tc.price_source(source)?;
+			tc.feed_prices(source.as_ref()).await?;
 		},
 		Command::Networks => {
 			let networks = tc.networks().await?;
@@ -285,9 +497,12 @@ async fn real_main() -> Result<()> {
 			let routes = tc.routes(network).await?;
 			tc.print_table(None, "routes", routes).await?;
 		},
-		Command::Events { network, start, end } => {
+		Command::Events { network, start, end, follow } => {
 			let events = tc.events(network, start..end).await?;
 			tc.print_table(None, "events", events).await?;
+			if follow {
+				tc.follow_events(network, end).await?;
+			}
 		},
 		Command::Messages { network, tester, start, end } => {
 			let tester = tc.parse_address(Some(network), &tester)?;
@@ -344,6 +559,31 @@ async fn real_main() -> Result<()> {
 			let trace = tc.message_trace(network, message).await?;
 			tc.print_table(None, "message", vec![trace]).await?;
 		},
+		Command::Monitor {
+			max_sync_lag,
+			backlog_blocks,
+			poll_blocks,
+			prometheus_port,
+		} => {
+			if let Some(port) = prometheus_port {
+				let binding = format!("0.0.0.0:{port}").parse()?;
+				prometheus_exporter::start(binding)?;
+			}
+			let config = MonitorConfig {
+				max_sync_lag,
+				backlog_blocks,
+				poll_blocks,
+			};
+			tc.monitor(config).await?;
+		},
+		Command::Treasurer { ledger, interval, once } => {
+			let ledger = Ledger::new(ledger);
+			if once {
+				tc.treasurer_round(&ledger).await?;
+			} else {
+				tc.treasurer(&ledger, Duration::from_secs(interval)).await?;
+			}
+		},
 		// management
 		Command::RuntimeUpgrade { path } => {
 			tc.runtime_upgrade(&path).await?;
@@ -351,6 +591,19 @@ async fn real_main() -> Result<()> {
 		Command::Deploy => {
 			tc.deploy().await?;
 		},
+		Command::Plan => {
+			let plan = tc.plan().await?;
+			print_plan(&tc, plan).await?;
+		},
+		Command::Apply { yes } => {
+			let plan = tc.plan().await?;
+			print_plan(&tc, plan.clone()).await?;
+			if !plan.is_empty()
+				&& (yes || confirm(&format!("apply {} changes?", plan.changes.len()))?)
+			{
+				tc.apply(plan).await?;
+			}
+		},
 		Command::DeployChronicle { url } => {
 			tc.deploy_chronicle(&url).await?;
 		},
@@ -519,12 +772,76 @@ async fn real_main() -> Result<()> {
 				}
 			}
 		},
-		Command::Log { query, since } => {
-			tc.log(query, since).await?;
+		Command::LoadTest {
+			rate,
+			burst_interval,
+			blocks,
+			senders,
+			payload_sizes,
+			timeout,
+			out,
+		} => {
+			let profile = match burst_interval {
+				Some(interval) => TrafficProfile::Burst { size: rate, interval },
+				None => TrafficProfile::Constant { rate },
+			};
+			let config = LoadTestConfig {
+				profile,
+				blocks,
+				senders,
+				payload_sizes,
+				timeout,
+			};
+			let report = tc.load_test(config).await?;
+			std::fs::write(&out, serde_json::to_string_pretty(&report)?)?;
+			tc.print_table(None, "load-test", report.routes).await?;
+			tracing::info!("load test report stored to: {:?}", &out);
+		},
+		Command::Log { query, since, follow } => {
+			tc.log(query, since, follow).await?;
 		},
 		Command::ForceShardOffline { shard_id } => {
 			tc.force_shard_offline(shard_id).await?;
 		},
+		Command::Offline { cmd } => match cmd {
+			OfflineCommand::Prepare {
+				signer,
+				proposal_threshold,
+				multisig_threshold,
+				signatory,
+				timepoint,
+				out,
+				call,
+			} => {
+				let multisig = if let Some(threshold) = multisig_threshold {
+					let timepoint = if let Some(timepoint) = timepoint {
+						let (height, index) = timepoint
+							.split_once(':')
+							.context("invalid timepoint, expected `height:index`")?;
+						Some((height.parse()?, index.parse()?))
+					} else {
+						None
+					};
+					let other_signatories = signatory
+						.iter()
+						.map(|signatory| Ok(tc.parse_address(None, signatory)?.into()))
+						.collect::<Result<_>>()?;
+					Some(Multisig {
+						threshold,
+						other_signatories,
+						timepoint,
+					})
+				} else {
+					None
+				};
+				let authorization = Authorization { proposal_threshold, multisig };
+				let tx = call.into_tx(&tc)?;
+				tc.prepare_offline_tx(&signer, tx, authorization, &out).await?;
+			},
+			OfflineCommand::Sign { .. } => unreachable!("signed without connecting"),
+			OfflineCommand::AirdropTree { .. } => unreachable!("built without connecting"),
+			OfflineCommand::Submit { path } => tc.submit_offline_tx(&path).await?,
+		},
 		Command::WithdrawFunds { network, amount, address } => {
 			let address = tc.parse_address(Some(network), &address)?;
 			tc.withdraw_funds(network, amount, address).await?;
@@ -551,6 +868,10 @@ async fn real_main() -> Result<()> {
 			tc.load_state(network, state).await?;
 			tracing::info!("Anvil state loaded from: {:?}", &path);
 		},
+		Command::AdvanceBlocks { network, blocks } => {
+			let block = tc.advance_blocks(network, blocks).await?;
+			tc.println(None, format!("network {network} advanced to block {block}")).await?;
+		},
 	}
 	tracing::info!("executed query in {}s", now.elapsed().unwrap().as_secs());
 	Ok(())
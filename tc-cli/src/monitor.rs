@@ -0,0 +1,321 @@
+use crate::*;
+use prometheus_exporter::prometheus::{IntCounterVec, IntGaugeVec};
+use std::collections::{HashSet, VecDeque};
+use std::sync::LazyLock;
+
+static ALERTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+	prometheus_exporter::prometheus::register_int_counter_vec!(
+		"tc_monitor_alerts_total",
+		"Number of alerts raised by rule",
+		&["rule"]
+	)
+	.unwrap()
+});
+
+static ACTIVE_ALERTS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+	prometheus_exporter::prometheus::register_int_gauge_vec!(
+		"tc_monitor_active_alerts",
+		"Number of alerts currently active by rule",
+		&["rule"]
+	)
+	.unwrap()
+});
+
+static SYNC_LAG: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+	prometheus_exporter::prometheus::register_int_gauge_vec!(
+		"tc_monitor_sync_lag",
+		"Number of target blocks the gateway events are lagging behind",
+		&["network"]
+	)
+	.unwrap()
+});
+
+static UNASSIGNED_TASKS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+	prometheus_exporter::prometheus::register_int_gauge_vec!(
+		"tc_monitor_unassigned_tasks",
+		"Number of unassigned tasks",
+		&["network"]
+	)
+	.unwrap()
+});
+
+/// Thresholds of the monitor rules.
+#[derive(Clone, Debug)]
+pub struct MonitorConfig {
+	/// Alerts when a network's gateway events are more than `max_sync_lag` blocks behind.
+	pub max_sync_lag: u64,
+	/// Alerts when a network's unassigned tasks grew over `backlog_blocks` finalized blocks.
+	pub backlog_blocks: usize,
+	/// Evaluates the failed batch and chronicle balance rules every `poll_blocks` finalized
+	/// blocks, they query all failed batches and every chronicle's balances.
+	pub poll_blocks: u64,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+enum Rule {
+	SyncLag,
+	FailedBatch,
+	ShardOffline,
+	ChronicleBalance,
+	TaskBacklog,
+}
+
+impl Rule {
+	const ALL: [Rule; 5] = [
+		Self::SyncLag,
+		Self::FailedBatch,
+		Self::ShardOffline,
+		Self::ChronicleBalance,
+		Self::TaskBacklog,
+	];
+
+	fn as_str(&self) -> &'static str {
+		match self {
+			Self::SyncLag => "sync_lag",
+			Self::FailedBatch => "failed_batch",
+			Self::ShardOffline => "shard_offline",
+			Self::ChronicleBalance => "chronicle_balance",
+			Self::TaskBacklog => "task_backlog",
+		}
+	}
+}
+
+impl std::fmt::Display for Rule {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// Condition raised by a rule. An alert stays active until a later block no longer
+/// raises it, except shards going offline which is only reported once.
+#[derive(Clone, Debug)]
+struct Alert {
+	rule: Rule,
+	subject: String,
+	message: String,
+}
+
+struct MonitoredChronicle {
+	address: String,
+	network: NetworkId,
+	account: AccountId,
+	target_address: Address,
+}
+
+#[derive(Default)]
+struct MonitorState {
+	active: HashMap<(Rule, String), String>,
+	/// Status of the shards which are not offline, offline shards never come back.
+	shards: HashMap<ShardId, ShardStatus>,
+	/// Shards below this id are tracked already.
+	next_shard: ShardId,
+	backlog: HashMap<NetworkId, VecDeque<usize>>,
+	evaluations: u64,
+}
+
+impl MonitorState {
+	/// Raises the active alerts of a rule again, for rules not evaluated at a block.
+	fn keep_active(&self, rule: Rule) -> impl Iterator<Item = Alert> + '_ {
+		self.active.iter().filter(move |((r, _), _)| *r == rule).map(
+			|((rule, subject), message)| Alert {
+				rule: *rule,
+				subject: subject.clone(),
+				message: message.clone(),
+			},
+		)
+	}
+}
+
+impl Tc {
+	/// Evaluates the monitor rules on every finalized block, or every `poll_blocks` blocks for
+	/// the expensive ones, reporting raised and resolved alerts through the message sender.
+	/// Metrics are registered with the default prometheus registry.
+	pub async fn monitor(&self, config: MonitorConfig) -> Result<()> {
+		let mut chronicles = vec![];
+		for chronicle in self.config.chronicles() {
+			match self.chronicle_config(chronicle).await {
+				Ok(config) => chronicles.push(MonitoredChronicle {
+					address: chronicle.clone(),
+					network: config.network,
+					account: config.account,
+					target_address: config.address,
+				}),
+				Err(err) => {
+					self.println(None, format!("not monitoring chronicle {chronicle}: {err}"))
+						.await?;
+				},
+			}
+		}
+		let mut state = MonitorState::default();
+		let mut blocks = self.finality_notification_stream();
+		while let Some((_, block)) = blocks.next().await {
+			let alerts = match self.evaluate(&config, &chronicles, &mut state).await {
+				Ok(alerts) => alerts,
+				Err(err) => {
+					tracing::error!("failed to evaluate monitor rules at block {block}: {err:?}");
+					continue;
+				},
+			};
+			self.report(block, &mut state, alerts).await?;
+		}
+		Ok(())
+	}
+
+	async fn evaluate(
+		&self,
+		config: &MonitorConfig,
+		chronicles: &[MonitoredChronicle],
+		state: &mut MonitorState,
+	) -> Result<Vec<Alert>> {
+		let mut alerts = vec![];
+		let poll = state.evaluations % config.poll_blocks.max(1) == 0;
+		state.evaluations += 1;
+		let mut networks: Vec<_> = self.connectors.keys().copied().collect();
+		networks.sort();
+		for network in networks {
+			if self.runtime.network_gateway(network).await?.is_none() {
+				continue;
+			}
+			let status = self.sync_status(network).await?;
+			let lag = status.block.saturating_sub(status.sync);
+			SYNC_LAG.with_label_values(&[&network.to_string()]).set(lag as _);
+			if lag > config.max_sync_lag {
+				alerts.push(Alert {
+					rule: Rule::SyncLag,
+					subject: network.to_string(),
+					message: format!(
+						"network {network} is {lag} blocks behind (synced {}, target {})",
+						status.sync, status.block
+					),
+				});
+			}
+			let unassigned = self.runtime.unassigned_tasks(network).await?.len();
+			UNASSIGNED_TASKS.with_label_values(&[&network.to_string()]).set(unassigned as _);
+			let backlog = state.backlog.entry(network).or_default();
+			backlog.push_back(unassigned);
+			if backlog.len() > config.backlog_blocks + 1 {
+				backlog.pop_front();
+			}
+			let growing = backlog.len() > config.backlog_blocks
+				&& backlog.iter().zip(backlog.iter().skip(1)).all(|(a, b)| a <= b)
+				&& backlog.front() < backlog.back();
+			if growing {
+				alerts.push(Alert {
+					rule: Rule::TaskBacklog,
+					subject: network.to_string(),
+					message: format!(
+						"unassigned tasks of network {network} grew from {} to {unassigned} over {} blocks",
+						backlog.front().unwrap(),
+						config.backlog_blocks
+					),
+				});
+			}
+		}
+		// only the shards which are not offline are queried, new shards start out created
+		let shard_id_counter = self.runtime.shard_id_counter().await?;
+		for shard in state.next_shard..shard_id_counter {
+			state.shards.insert(shard, ShardStatus::Created);
+		}
+		state.next_shard = state.next_shard.max(shard_id_counter);
+		let shards: Vec<_> = state.shards.keys().copied().collect();
+		for shard in shards {
+			let status = self.runtime.shard_status(shard).await?;
+			let previous = state.shards.insert(shard, status);
+			if !matches!(status, ShardStatus::Offline) {
+				continue;
+			}
+			state.shards.remove(&shard);
+			if matches!(previous, Some(ShardStatus::Online)) {
+				let network = self.runtime.shard_network(shard).await?;
+				alerts.push(Alert {
+					rule: Rule::ShardOffline,
+					subject: shard.to_string(),
+					message: format!(
+						"shard {shard} of network {} went offline",
+						network.map(|network| network.to_string()).unwrap_or_default()
+					),
+				});
+			}
+		}
+		if !poll {
+			alerts.extend(state.keep_active(Rule::FailedBatch));
+			alerts.extend(state.keep_active(Rule::ChronicleBalance));
+			return Ok(alerts);
+		}
+		for batch in self.runtime.get_failed_tasks().await? {
+			alerts.push(Alert {
+				rule: Rule::FailedBatch,
+				subject: batch.to_string(),
+				message: format!("batch {batch} failed"),
+			});
+		}
+		let funds = self.parse_balance(None, &self.config.global().chronicle_funds)?;
+		for chronicle in chronicles {
+			let balance = self.balance(None, chronicle.account.clone().into()).await?;
+			if balance < funds {
+				alerts.push(Alert {
+					rule: Rule::ChronicleBalance,
+					subject: format!("{} timechain", chronicle.address),
+					message: format!(
+						"chronicle {} timechain balance is {}, below {}",
+						chronicle.address,
+						self.format_balance(None, balance)?,
+						self.format_balance(None, funds)?
+					),
+				});
+			}
+			let network = Some(chronicle.network);
+			let funds = self
+				.parse_balance(network, &self.config.network(chronicle.network)?.chronicle_funds)?;
+			let balance = self.balance(network, chronicle.target_address).await?;
+			if balance < funds {
+				alerts.push(Alert {
+					rule: Rule::ChronicleBalance,
+					subject: format!("{} {}", chronicle.address, chronicle.network),
+					message: format!(
+						"chronicle {} balance on network {} is {}, below {}",
+						chronicle.address,
+						chronicle.network,
+						self.format_balance(network, balance)?,
+						self.format_balance(network, funds)?
+					),
+				});
+			}
+		}
+		Ok(alerts)
+	}
+
+	async fn report(
+		&self,
+		block: BlockNumber,
+		state: &mut MonitorState,
+		alerts: Vec<Alert>,
+	) -> Result<()> {
+		let mut raised = HashSet::new();
+		for alert in alerts {
+			let key = (alert.rule, alert.subject);
+			raised.insert(key.clone());
+			if state.active.contains_key(&key) {
+				continue;
+			}
+			ALERTS.with_label_values(&[alert.rule.as_str()]).inc();
+			self.println(None, format!("[{block}] ALERT {}: {}", alert.rule, alert.message))
+				.await?;
+			// shards going offline is an event rather than a condition
+			if alert.rule != Rule::ShardOffline {
+				state.active.insert(key, alert.message);
+			}
+		}
+		let resolved: Vec<_> =
+			state.active.keys().filter(|key| !raised.contains(*key)).cloned().collect();
+		for key in resolved {
+			let message = state.active.remove(&key).unwrap();
+			self.println(None, format!("[{block}] RESOLVED {}: {message}", key.0)).await?;
+		}
+		for rule in Rule::ALL {
+			let active = state.active.keys().filter(|(r, _)| *r == rule).count();
+			ACTIVE_ALERTS.with_label_values(&[rule.as_str()]).set(active as _);
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,202 @@
+use crate::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Change required to reconcile the on-chain and gateway state with the config.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Change {
+	/// Deploys the gateway, registers the network and funds the gateway.
+	RegisterNetwork {
+		network: NetworkId,
+	},
+	SetNetworkConfig {
+		network: NetworkId,
+		config: NetworkConfig,
+	},
+	/// The route is `None` while a gateway is yet to be deployed.
+	SetRoute {
+		src: NetworkId,
+		dest: NetworkId,
+		route: Option<Route>,
+	},
+	RegisterShards {
+		network: NetworkId,
+		shards: usize,
+	},
+	Fund {
+		network: Option<NetworkId>,
+		label: String,
+		address: Address,
+		balance: u128,
+		min_balance: u128,
+	},
+	RegisterMember {
+		chronicle: String,
+		network: NetworkId,
+	},
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Plan {
+	pub changes: Vec<Change>,
+	/// Differences which can't be reconciled by tc-cli.
+	pub warnings: Vec<String>,
+}
+
+impl Plan {
+	pub fn is_empty(&self) -> bool {
+		self.changes.is_empty()
+	}
+}
+
+impl Tc {
+	/// Diffs the config against the on-chain and gateway state.
+	pub async fn plan(&self) -> Result<Plan> {
+		let mut plan = Plan::default();
+		let mut gateways = BTreeMap::new();
+		let mut networks: Vec<_> = self.connectors.keys().copied().collect();
+		networks.sort();
+		for network in networks {
+			let config = self.config.network(network)?;
+			let Some(gateway) = self.runtime.network_gateway(network).await? else {
+				plan.changes.push(Change::RegisterNetwork { network });
+				gateways.insert(network, None);
+				continue;
+			};
+			gateways.insert(network, Some(gateway));
+			let network_config = self.network_config(network)?;
+			if self.onchain_network_config(network).await? != network_config {
+				plan.changes.push(Change::SetNetworkConfig {
+					network,
+					config: network_config,
+				});
+			}
+			let connector = self.connector(network)?;
+			let keys = self.find_online_shard_keys(network).await?;
+			if !same(&keys, &connector.shards(gateway).await?) {
+				plan.changes.push(Change::RegisterShards { network, shards: keys.len() });
+			}
+			let admin = connector.admin(gateway).await?;
+			if admin != self.address(Some(network))? {
+				plan.warnings.push(format!(
+					"gateway admin of {network} is {}, tc-cli can't manage the gateway",
+					self.format_address(Some(network), admin)?
+				));
+			}
+			let gateway_funds = self.parse_balance(Some(network), &config.gateway_funds)?;
+			self.plan_funds(&mut plan, Some(network), gateway, gateway_funds, "gateway")
+				.await?;
+		}
+		for (src, src_gateway) in gateways.iter().map(|(src, gateway)| (*src, *gateway)) {
+			let routes =
+				if src_gateway.is_some() { self.runtime.routes(src).await? } else { vec![] };
+			for (dest, dest_gateway) in gateways.iter().map(|(dest, gateway)| (*dest, *gateway)) {
+				let route = match dest_gateway {
+					Some(gateway) => self.try_route(src, dest, gateway).await?,
+					None => None,
+				};
+				if let Some(route) = route.as_ref() {
+					if routes.iter().any(|r| same_route(r, route)) {
+						continue;
+					}
+				}
+				plan.changes.push(Change::SetRoute { src, dest, route });
+			}
+		}
+		let funds = self.parse_balance(None, &self.config.global().chronicle_funds)?;
+		for chronicle in self.config.chronicles() {
+			let config = match self.chronicle_config(chronicle).await {
+				Ok(config) => config,
+				Err(err) => {
+					plan.warnings.push(format!("chronicle {chronicle} is unreachable: {err}"));
+					continue;
+				},
+			};
+			let account = config.account.clone().into();
+			self.plan_funds(&mut plan, None, account, funds, "chronicle timechain account")
+				.await?;
+			let network_config = self.config.network(config.network)?;
+			let chronicle_funds =
+				self.parse_balance(Some(config.network), &network_config.chronicle_funds)?;
+			self.plan_funds(
+				&mut plan,
+				Some(config.network),
+				config.address,
+				chronicle_funds,
+				"chronicle target account",
+			)
+			.await?;
+			if self.runtime.member_stake(&config.account).await? == 0 {
+				plan.changes.push(Change::RegisterMember {
+					chronicle: chronicle.clone(),
+					network: config.network,
+				});
+			}
+		}
+		Ok(plan)
+	}
+
+	async fn plan_funds(
+		&self,
+		plan: &mut Plan,
+		network: Option<NetworkId>,
+		address: Address,
+		min_balance: u128,
+		label: &str,
+	) -> Result<()> {
+		let balance = self.balance(network, address).await?;
+		if balance < min_balance {
+			plan.changes.push(Change::Fund {
+				network,
+				label: label.into(),
+				address,
+				balance,
+				min_balance,
+			});
+		}
+		Ok(())
+	}
+
+	/// Executes the changes of a plan in order.
+	pub async fn apply(&self, plan: Plan) -> Result<()> {
+		for change in plan.changes {
+			match change {
+				Change::RegisterNetwork { network } => {
+					self.deploy_network(network).await?;
+				},
+				Change::SetNetworkConfig { network, config } => {
+					self.println(None, format!("set_network_config {network}")).await?;
+					self.runtime.set_network_config(network, config).await?;
+				},
+				Change::SetRoute { src, dest, route } => {
+					let route = if let Some(route) = route {
+						route
+					} else {
+						let (_, gateway) = self.gateway(dest).await?;
+						self.route(src, dest, gateway).await?
+					};
+					self.println(None, format!("register_route {src} {dest}")).await?;
+					self.runtime.set_route(src, route).await?;
+				},
+				Change::RegisterShards { network, .. } => {
+					self.register_shards(network).await?;
+				},
+				Change::Fund {
+					network,
+					label,
+					address,
+					min_balance,
+					..
+				} => {
+					self.fund(network, address, min_balance, &label).await?;
+				},
+				Change::RegisterMember { chronicle, .. } => {
+					let config = self.chronicle_config(&chronicle).await?;
+					self.register_member(config.network, config.public_key, config.peer_id).await?;
+				},
+			}
+		}
+		Ok(())
+	}
+}
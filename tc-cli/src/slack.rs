@@ -1,5 +1,20 @@
 use anyhow::Result;
 use slack_morphism::prelude::*;
+use std::collections::HashSet;
+use std::io::Write;
+use std::sync::Mutex;
+
+/// Format tables are printed to stdout in.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum Output {
+	/// Pretty printed tables.
+	#[default]
+	Table,
+	/// One json object per row and line.
+	Json,
+	/// Csv with a header the first time a table is printed.
+	Csv,
+}
 
 #[derive(Default)]
 pub struct TextRef {
@@ -26,6 +41,8 @@ impl From<SlackFileId> for TableRef {
 #[derive(Default)]
 pub struct Sender {
 	slack: Option<Slack>,
+	output: Output,
+	csv_headers: Mutex<HashSet<String>>,
 }
 
 impl Sender {
@@ -37,7 +54,16 @@ impl Sender {
 				None
 			},
 		};
-		Self { slack }
+		Self { slack, ..Default::default() }
+	}
+
+	pub fn with_output(mut self, output: Output) -> Self {
+		self.output = output;
+		self
+	}
+
+	pub fn output(&self) -> Output {
+		self.output
 	}
 
 	fn println(&self, _restore: bool, text: &str) {
@@ -55,8 +81,25 @@ impl Sender {
 	}
 
 	pub async fn csv(&self, id: Option<TableRef>, title: &str, csv: Vec<u8>) -> Result<TableRef> {
-		let table = csv_to_table::from_reader(&mut &csv[..])?;
-		self.println(id.is_some(), &format!("\n{table}"));
+		match self.output {
+			Output::Csv => {
+				let first = self.csv_headers.lock().unwrap().insert(title.to_string());
+				let mut content = &csv[..];
+				if !first {
+					// strip the header of subsequent tables with the same title
+					let header =
+						content.iter().position(|b| *b == b'\n').map_or(content.len(), |i| i + 1);
+					content = &content[header..];
+				}
+				let mut stdout = std::io::stdout().lock();
+				stdout.write_all(content)?;
+				stdout.flush()?;
+			},
+			_ => {
+				let table = csv_to_table::from_reader(&mut &csv[..])?;
+				self.println(id.is_some(), &format!("\n{table}"));
+			},
+		}
 		if let Some(slack) = self.slack.as_ref() {
 			let id = id.map(|id| id.slack).unwrap_or_default();
 			let file = slack.post_table(id, title.into(), csv).await?;
@@ -64,6 +107,16 @@ impl Sender {
 		}
 		Ok(Default::default())
 	}
+
+	pub async fn json(&self, records: Vec<serde_json::Value>) -> Result<TableRef> {
+		let mut stdout = std::io::stdout().lock();
+		for record in records {
+			serde_json::to_writer(&mut stdout, &record)?;
+			writeln!(stdout)?;
+		}
+		stdout.flush()?;
+		Ok(Default::default())
+	}
 }
 
 struct Slack {
@@ -7,6 +7,14 @@ pub trait IntoRow {
 	type Row: Serialize;
 
 	fn into_row(self, tc: &Tc) -> Result<Self::Row>;
+
+	/// Json representation of the row, which may keep nested structures intact.
+	fn into_record(self, tc: &Tc) -> Result<serde_json::Value>
+	where
+		Self: Sized,
+	{
+		Ok(serde_json::to_value(self.into_row(tc)?)?)
+	}
 }
 
 #[derive(Serialize)]
@@ -328,6 +336,50 @@ impl IntoRow for MessageTrace {
 			exec: self.exec.map(task_to_string).unwrap_or_default(),
 		})
 	}
+
+	fn into_record(self, tc: &Tc) -> Result<serde_json::Value> {
+		let record = MessageTraceRecord {
+			message: hex::encode(self.message),
+			src: self.src.into(),
+			dest: self.dest.map(Into::into),
+			recv: self.recv.map(|task| task.into_row(tc)).transpose()?,
+			submit: self.submit.map(|task| task.into_row(tc)).transpose()?,
+			exec: self.exec.map(|task| task.into_row(tc)).transpose()?,
+		};
+		Ok(serde_json::to_value(record)?)
+	}
+}
+
+#[derive(Serialize)]
+pub struct SyncStatusEntry {
+	network: NetworkId,
+	task: TaskId,
+	block: u64,
+	sync: u64,
+	next_sync: u64,
+}
+
+impl From<SyncStatus> for SyncStatusEntry {
+	fn from(status: SyncStatus) -> Self {
+		Self {
+			network: status.network,
+			task: status.task,
+			block: status.block,
+			sync: status.sync,
+			next_sync: status.next_sync,
+		}
+	}
+}
+
+/// Json representation of a `MessageTrace`.
+#[derive(Serialize)]
+pub struct MessageTraceRecord {
+	message: String,
+	src: SyncStatusEntry,
+	dest: Option<SyncStatusEntry>,
+	recv: Option<TaskEntry>,
+	submit: Option<TaskEntry>,
+	exec: Option<TaskEntry>,
 }
 
 #[derive(Serialize)]
@@ -372,3 +424,77 @@ impl IntoRow for Log {
 		})
 	}
 }
+
+#[derive(Serialize)]
+pub struct ChangeEntry {
+	action: &'static str,
+	network: String,
+	details: String,
+}
+
+impl IntoRow for Change {
+	type Row = ChangeEntry;
+
+	fn into_row(self, tc: &Tc) -> Result<Self::Row> {
+		Ok(match self {
+			Change::RegisterNetwork { network } => ChangeEntry {
+				action: "register_network",
+				network: network.to_string(),
+				details: "deploy and fund gateway".into(),
+			},
+			Change::SetNetworkConfig { network, config } => ChangeEntry {
+				action: "set_network_config",
+				network: network.to_string(),
+				details: format!("{config:?}"),
+			},
+			Change::SetRoute { src, dest, route } => ChangeEntry {
+				action: "set_route",
+				network: src.to_string(),
+				details: if let Some(route) = route {
+					format!(
+						"to {dest}: relative_gas_price {}, gas_limit {}, base_fee {}",
+						route.relative_gas_price(),
+						route.gas_limit,
+						route.base_fee
+					)
+				} else {
+					format!("to {dest}")
+				},
+			},
+			Change::RegisterShards { network, shards } => ChangeEntry {
+				action: "register_shards",
+				network: network.to_string(),
+				details: format!("{shards} online shards"),
+			},
+			Change::Fund {
+				network,
+				label,
+				address,
+				balance,
+				min_balance,
+			} => ChangeEntry {
+				action: "fund",
+				network: network.map(|network| network.to_string()).unwrap_or_default(),
+				details: format!(
+					"{label} {} from {} to {}",
+					tc.format_address(network, address)?,
+					tc.format_balance(network, balance)?,
+					tc.format_balance(network, min_balance)?
+				),
+			},
+			Change::RegisterMember { chronicle, network } => ChangeEntry {
+				action: "register_member",
+				network: network.to_string(),
+				details: chronicle,
+			},
+		})
+	}
+}
+
+impl IntoRow for RouteReport {
+	type Row = RouteReport;
+
+	fn into_row(self, _tc: &Tc) -> Result<Self::Row> {
+		Ok(self)
+	}
+}
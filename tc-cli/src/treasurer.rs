@@ -0,0 +1,148 @@
+use crate::*;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::time::SystemTime;
+
+/// Movement of funds recorded in the treasurer ledger.
+#[derive(Clone, Debug, Serialize)]
+struct LedgerEntry {
+	timestamp: u64,
+	network: NetworkId,
+	kind: &'static str,
+	from: String,
+	to: String,
+	amount: String,
+	balance: String,
+}
+
+/// Append-only csv file of all movements made by the treasurer.
+pub struct Ledger {
+	path: PathBuf,
+}
+
+impl Ledger {
+	pub fn new(path: PathBuf) -> Self {
+		Self { path }
+	}
+
+	fn append(&self, entry: LedgerEntry) -> Result<()> {
+		let exists = self.path.exists();
+		let file = OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(&self.path)
+			.with_context(|| format!("failed to open ledger {}", self.path.display()))?;
+		let mut wtr = csv::WriterBuilder::new().has_headers(!exists).from_writer(file);
+		wtr.serialize(entry)?;
+		wtr.flush()?;
+		Ok(())
+	}
+}
+
+impl Tc {
+	/// Tops up chronicle wallets and sweeps gateway surplus every `interval` until aborted.
+	pub async fn treasurer(&self, ledger: &Ledger, interval: Duration) -> Result<()> {
+		loop {
+			self.treasurer_round(ledger).await?;
+			sleep_or_abort(interval).await?;
+		}
+	}
+
+	/// Runs a single round of top-ups and sweeps. Failures of individual wallets or
+	/// gateways are reported and don't prevent the others from being funded.
+	pub async fn treasurer_round(&self, ledger: &Ledger) -> Result<()> {
+		for chronicle in self.config.chronicles() {
+			let config = match self.chronicle_config(chronicle).await {
+				Ok(config) => config,
+				Err(err) => {
+					tracing::warn!("skipping chronicle {chronicle}: {err}");
+					continue;
+				},
+			};
+			if let Err(err) = self.top_up(ledger, config.network, config.address).await {
+				self.println(None, format!("failed to top up chronicle {chronicle}: {err:#}"))
+					.await?;
+			}
+		}
+		let mut networks: Vec<_> = self.connectors.keys().copied().collect();
+		networks.sort();
+		for network in networks {
+			if let Err(err) = self.sweep(ledger, network).await {
+				self.println(None, format!("failed to sweep gateway of {network}: {err:#}"))
+					.await?;
+			}
+		}
+		Ok(())
+	}
+
+	async fn top_up(&self, ledger: &Ledger, network: NetworkId, address: Address) -> Result<()> {
+		let config = self.config.network(network)?;
+		let (min_funds, target_funds) = match config.treasurer.as_ref() {
+			Some(treasurer) => (
+				self.parse_balance(Some(network), &treasurer.chronicle_min_funds)?,
+				self.parse_balance(Some(network), &treasurer.chronicle_target_funds)?,
+			),
+			None => {
+				let funds = self.parse_balance(Some(network), &config.chronicle_funds)?;
+				(funds, funds)
+			},
+		};
+		let balance = self.balance(Some(network), address).await?;
+		if balance >= min_funds {
+			return Ok(());
+		}
+		let amount = target_funds.saturating_sub(balance);
+		let funder = self.address(Some(network))?;
+		self.transfer(Some(network), address, amount).await?;
+		ledger.append(LedgerEntry {
+			timestamp: now(),
+			network,
+			kind: "top_up",
+			from: self.format_address(Some(network), funder)?,
+			to: self.format_address(Some(network), address)?,
+			amount: self.format_balance(Some(network), amount)?,
+			balance: self.format_balance(Some(network), balance)?,
+		})
+	}
+
+	async fn sweep(&self, ledger: &Ledger, network: NetworkId) -> Result<()> {
+		let config = self.config.network(network)?;
+		let Some(treasurer) = config.treasurer.as_ref() else {
+			return Ok(());
+		};
+		let Some(max_funds) = treasurer.gateway_max_funds.as_ref() else {
+			return Ok(());
+		};
+		let max_funds = self.parse_balance(Some(network), max_funds)?;
+		let funds = self.parse_balance(Some(network), &config.gateway_funds)?;
+		let Some(gateway) = self.runtime.network_gateway(network).await? else {
+			return Ok(());
+		};
+		let balance = self.balance(Some(network), gateway).await?;
+		if balance <= max_funds {
+			return Ok(());
+		}
+		let address = match treasurer.sweep_address.as_ref() {
+			Some(address) => self.parse_address(Some(network), address)?,
+			None => self.address(Some(network))?,
+		};
+		let amount = balance.saturating_sub(funds);
+		self.withdraw_funds(network, amount, address).await?;
+		ledger.append(LedgerEntry {
+			timestamp: now(),
+			network,
+			kind: "sweep",
+			from: self.format_address(Some(network), gateway)?,
+			to: self.format_address(Some(network), address)?,
+			amount: self.format_balance(Some(network), amount)?,
+			balance: self.format_balance(Some(network), balance)?,
+		})
+	}
+}
+
+fn now() -> u64 {
+	SystemTime::now()
+		.duration_since(SystemTime::UNIX_EPOCH)
+		.unwrap_or_default()
+		.as_secs()
+}
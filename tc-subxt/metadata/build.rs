@@ -8,10 +8,13 @@ fn derive(path: &Path, module: &str) -> String {
 	let simple_types = [
 		"time_primitives::dmail::DmailTo",
 		"time_primitives::dmail::DmailPath",
+		"time_primitives::dmail::DmailEnvelope",
+		"time_primitives::dmail::DmailInboxMessage",
 		"time_primitives::gmp::GmpMessage",
 		"time_primitives::gmp::GatewayOp",
 		"time_primitives::gmp::GatewayMessage",
 		"time_primitives::gmp::GmpEvent",
+		"time_primitives::gmp::Route",
 		"time_primitives::network::ChainName",
 		"time_primitives::network::ChainNetwork",
 		"time_primitives::network::Network",
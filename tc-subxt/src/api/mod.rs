@@ -1,4 +1,5 @@
 mod members;
 mod networks;
+mod oracle;
 mod shards;
 mod tasks;
@@ -2,7 +2,10 @@ use crate::worker::Tx;
 use crate::{metadata, SubxtClient};
 use anyhow::Result;
 use futures::channel::oneshot;
-use time_primitives::{ChainName, ChainNetwork, Gateway, Network, NetworkConfig, NetworkId};
+use time_primitives::{
+	supports_networks_api_routes, ChainName, ChainNetwork, Gateway, Network, NetworkConfig,
+	NetworkId, Route, NETWORKS_API_ROUTES_VERSION,
+};
 
 impl SubxtClient {
 	pub async fn register_network(&self, network: Network) -> Result<()> {
@@ -25,6 +28,27 @@ impl SubxtClient {
 		Ok(())
 	}
 
+	pub async fn migrate_gateway(
+		&self,
+		network: NetworkId,
+		gateway: Gateway,
+		block: u64,
+	) -> Result<()> {
+		let (tx, rx) = oneshot::channel();
+		self.tx.unbounded_send((Tx::MigrateGateway { network, gateway, block }, tx))?;
+		let tx = rx.await?;
+		self.is_success(&tx).await?;
+		Ok(())
+	}
+
+	pub async fn set_route(&self, network: NetworkId, route: Route) -> Result<()> {
+		let (tx, rx) = oneshot::channel();
+		self.tx.unbounded_send((Tx::SetRoute { network, route }, tx))?;
+		let tx = rx.await?;
+		self.is_success(&tx).await?;
+		Ok(())
+	}
+
 	pub async fn networks(&self) -> Result<Vec<NetworkId>> {
 		let mut networks = vec![];
 		let storage = metadata::storage().networks().networks_iter();
@@ -57,6 +81,44 @@ impl SubxtClient {
 		Ok(data)
 	}
 
+	/// Fails if the runtime's `NetworksApi` predates `get_routes` and
+	/// `estimate_message_cost`.
+	async fn ensure_networks_api_routes(&self) -> Result<()> {
+		let runtime_call = metadata::apis().core().version();
+		let version = self.client.runtime_api().at_latest().await?.call(runtime_call).await?;
+		anyhow::ensure!(
+			supports_networks_api_routes(&version.apis),
+			"runtime doesn't support routes, NetworksApi version {} required",
+			NETWORKS_API_ROUTES_VERSION
+		);
+		Ok(())
+	}
+
+	pub async fn routes(&self, network: NetworkId) -> Result<Vec<Route>> {
+		self.ensure_networks_api_routes().await?;
+		let runtime_call = metadata::apis().networks_api().get_routes(network);
+		let data = self.client.runtime_api().at_latest().await?.call(runtime_call).await?;
+		Ok(data.into_iter().map(|route| route.0).collect())
+	}
+
+	pub async fn estimate_message_cost(
+		&self,
+		src: NetworkId,
+		dest: NetworkId,
+		gas_limit: u128,
+		payload_len: u32,
+	) -> Result<Option<u128>> {
+		self.ensure_networks_api_routes().await?;
+		let runtime_call = metadata::apis().networks_api().estimate_message_cost(
+			src,
+			dest,
+			gas_limit,
+			payload_len,
+		);
+		let data = self.client.runtime_api().at_latest().await?.call(runtime_call).await?;
+		Ok(data)
+	}
+
 	pub async fn network_batch_size(&self, network: NetworkId) -> Result<u32> {
 		let storage_query = metadata::storage().networks().network_batch_size(network);
 		let data = self
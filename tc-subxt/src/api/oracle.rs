@@ -0,0 +1,64 @@
+use crate::worker::Tx;
+use crate::{metadata, SubxtClient};
+use anyhow::Result;
+use futures::channel::oneshot;
+use time_primitives::{AccountId, NetworkId};
+
+impl SubxtClient {
+	pub async fn add_feeder(&self, feeder: AccountId) -> Result<()> {
+		let (tx, rx) = oneshot::channel();
+		self.tx.unbounded_send((Tx::AddFeeder { feeder }, tx))?;
+		let tx = rx.await?;
+		self.is_success(&tx).await?;
+		Ok(())
+	}
+
+	pub async fn is_feeder(&self, account: &AccountId) -> Result<bool> {
+		let account = subxt::utils::Static(account.clone());
+		let storage_query = metadata::storage().oracle().feeders(&account);
+		let data = self.client.storage().at_latest().await?.fetch(&storage_query).await?;
+		Ok(data.is_some())
+	}
+
+	/// Sets the token config of a network, `margin` is in parts per billion.
+	pub async fn set_token_config(
+		&self,
+		network: NetworkId,
+		decimals: u32,
+		gas_price: u128,
+		margin: u32,
+	) -> Result<()> {
+		let (tx, rx) = oneshot::channel();
+		self.tx.unbounded_send((
+			Tx::SetTokenConfig {
+				network,
+				decimals,
+				gas_price,
+				margin,
+			},
+			tx,
+		))?;
+		let tx = rx.await?;
+		self.is_success(&tx).await?;
+		Ok(())
+	}
+
+	/// Submits prices as the inner value of a `FixedU128`.
+	pub async fn submit_prices(&self, prices: Vec<(NetworkId, u128)>) -> Result<()> {
+		let (tx, rx) = oneshot::channel();
+		self.tx.unbounded_send((Tx::SubmitPrices { prices }, tx))?;
+		let tx = rx.await?;
+		self.is_success(&tx).await?;
+		Ok(())
+	}
+
+	pub async fn relative_gas_price(
+		&self,
+		src: NetworkId,
+		dest: NetworkId,
+	) -> Result<Option<(u128, u128)>> {
+		let runtime_call = metadata::apis().oracle_api().relative_gas_price(src, dest);
+		let data = self.client.runtime_api().at_latest().await?.call(runtime_call).await?;
+		Ok(data)
+	}
+}
@@ -4,10 +4,36 @@ use scale_codec::{Decode, Encode};
 use std::collections::VecDeque;
 use subxt::utils::H256;
 
-use crate::{timechain_client::ITransactionDbOps, worker::TxData};
+use crate::{
+	timechain_client::ITransactionDbOps,
+	worker::{Tx, TxData},
+};
 
 const TX_TABLE: TableDefinition<[u8; 64], &[u8]> = TableDefinition::new("pending_txs");
 
+/// Pending txs stored before tips were recorded.
+#[derive(Decode)]
+struct LegacyTxData {
+	hash: H256,
+	era: u64,
+	nonce: u64,
+	transaction: Tx,
+}
+
+fn decode_tx_data(value: &[u8]) -> Result<TxData> {
+	if let Ok(tx_data) = TxData::decode(&mut &*value) {
+		return Ok(tx_data);
+	}
+	let tx_data = LegacyTxData::decode(&mut &*value)?;
+	Ok(TxData {
+		hash: tx_data.hash,
+		era: tx_data.era,
+		nonce: tx_data.nonce,
+		transaction: tx_data.transaction,
+		tips: vec![],
+	})
+}
+
 pub struct TransactionsDB {
 	db: Database,
 	public_key: [u8; 32],
@@ -72,7 +98,7 @@ impl ITransactionDbOps for TransactionsDB {
 
 			for entry in table.range(lower_bound..=upper_bound)? {
 				let (key, value) = entry?;
-				let tx_data = TxData::decode(&mut value.value())?;
+				let tx_data = decode_tx_data(value.value())?;
 				if tx_data.nonce < nonce {
 					let key_bytes: [u8; 64] = key.value();
 					delete_keys.push(key_bytes);
@@ -0,0 +1,319 @@
+//! Typed stream of the timechain events relevant to chronicles and tc-cli.
+use crate::{metadata, LegacyRpcMethods, OnlineClient, SubxtClient};
+use anyhow::{Context, Result};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use std::time::Duration;
+use subxt::PolkadotConfig;
+use time_primitives::{
+	AccountId, BlockNumber, ErrorMsg, MessageId, NetworkId, ShardId, TaskId, TssPublicKey,
+};
+
+type Storage = subxt::storage::Storage<PolkadotConfig, OnlineClient>;
+
+/// Decoded events of the tasks, shards, members and networks pallets.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimechainEvent {
+	TaskCreated(TaskId),
+	TaskResult(TaskId, Result<(), ErrorMsg>),
+	/// Batch restarted (old_task_id, new_task_id)
+	BatchRestarted(TaskId, TaskId),
+	MessageReceived(MessageId),
+	MessageExecuted(MessageId),
+	ShardCreated(ShardId, NetworkId),
+	ShardCommitted(ShardId),
+	ShardKeyGenTimedOut(ShardId),
+	ShardOnline(ShardId, TssPublicKey),
+	ShardOffline(ShardId),
+	RegisteredMember(AccountId, NetworkId),
+	UnRegisteredMember(AccountId, NetworkId),
+	MemberOnline(AccountId),
+	MemberOffline(AccountId),
+	NetworkRegistered(NetworkId),
+	NetworkConfigChanged(NetworkId),
+	NetworkPaused(NetworkId),
+	NetworkResumed(NetworkId),
+	NetworkDraining(NetworkId),
+	NetworkRemovalStarted(NetworkId),
+	NetworkRemoved(NetworkId),
+	GatewayMigrationStarted(NetworkId),
+	GatewayMigrated(NetworkId),
+	/// Route changed (source, destination)
+	RouteChanged(NetworkId, NetworkId),
+}
+
+impl TimechainEvent {
+	fn from_runtime_event(event: metadata::Event) -> Option<Self> {
+		use metadata::{members, networks, shards, tasks};
+		Some(match event {
+			metadata::Event::Tasks(event) => match event {
+				tasks::Event::TaskCreated(task) => Self::TaskCreated(task),
+				tasks::Event::TaskResult(task, result) => {
+					Self::TaskResult(task, result.map_err(|err| err.0))
+				},
+				tasks::Event::BatchRestarted(old, new) => Self::BatchRestarted(old, new),
+				tasks::Event::MessageReceived(message) => Self::MessageReceived(message),
+				tasks::Event::MessageExecuted(message) => Self::MessageExecuted(message),
+				_ => return None,
+			},
+			metadata::Event::Shards(event) => match event {
+				shards::Event::ShardCreated(shard, network) => Self::ShardCreated(shard, network),
+				shards::Event::ShardCommitted(shard, _) => Self::ShardCommitted(shard),
+				shards::Event::ShardKeyGenTimedOut(shard) => Self::ShardKeyGenTimedOut(shard),
+				shards::Event::ShardOnline(shard, key) => Self::ShardOnline(shard, key),
+				shards::Event::ShardOffline(shard) => Self::ShardOffline(shard),
+			},
+			metadata::Event::Members(event) => match event {
+				members::Event::RegisteredMember(account, network, _) => {
+					Self::RegisteredMember(account.0, network)
+				},
+				members::Event::UnRegisteredMember(account, network) => {
+					Self::UnRegisteredMember(account.0, network)
+				},
+				members::Event::MemberOnline(account) => Self::MemberOnline(account.0),
+				members::Event::MemberOffline(account) => Self::MemberOffline(account.0),
+				_ => return None,
+			},
+			metadata::Event::Networks(event) => match event {
+				networks::Event::NetworkRegistered(network, ..) => Self::NetworkRegistered(network),
+				networks::Event::NetworkConfigChanged(network, _) => {
+					Self::NetworkConfigChanged(network)
+				},
+				networks::Event::NetworkPaused(network) => Self::NetworkPaused(network),
+				networks::Event::NetworkResumed(network) => Self::NetworkResumed(network),
+				networks::Event::NetworkDraining(network) => Self::NetworkDraining(network),
+				networks::Event::NetworkRemovalStarted(network) => {
+					Self::NetworkRemovalStarted(network)
+				},
+				networks::Event::NetworkRemoved(network) => Self::NetworkRemoved(network),
+				networks::Event::GatewayMigrationStarted(network, ..) => {
+					Self::GatewayMigrationStarted(network)
+				},
+				networks::Event::GatewayMigrated(network, ..) => Self::GatewayMigrated(network),
+				networks::Event::RouteChanged(network, route) => {
+					Self::RouteChanged(network, route.0.network_id)
+				},
+				// new events of the regenerated metadata are skipped until mapped
+				#[allow(unreachable_patterns)]
+				_ => return None,
+			},
+			_ => return None,
+		})
+	}
+
+	/// Network carried by the event itself.
+	fn network(&self) -> Option<NetworkId> {
+		match self {
+			Self::ShardCreated(_, network)
+			| Self::RegisteredMember(_, network)
+			| Self::UnRegisteredMember(_, network)
+			| Self::NetworkRegistered(network)
+			| Self::NetworkConfigChanged(network)
+			| Self::NetworkPaused(network)
+			| Self::NetworkResumed(network)
+			| Self::NetworkDraining(network)
+			| Self::NetworkRemovalStarted(network)
+			| Self::NetworkRemoved(network)
+			| Self::GatewayMigrationStarted(network)
+			| Self::GatewayMigrated(network)
+			| Self::RouteChanged(network, _) => Some(*network),
+			_ => None,
+		}
+	}
+
+	/// Shard carried by the event itself.
+	fn shard(&self) -> Option<ShardId> {
+		match self {
+			Self::ShardCreated(shard, _)
+			| Self::ShardCommitted(shard)
+			| Self::ShardKeyGenTimedOut(shard)
+			| Self::ShardOnline(shard, _)
+			| Self::ShardOffline(shard) => Some(*shard),
+			_ => None,
+		}
+	}
+
+	/// Tasks the event refers to, messages are resolved to the task that received or
+	/// executed them.
+	async fn tasks(&self, storage: &Storage) -> Result<Vec<TaskId>> {
+		Ok(match self {
+			Self::TaskCreated(task) | Self::TaskResult(task, _) => vec![*task],
+			Self::BatchRestarted(old, new) => vec![*old, *new],
+			Self::MessageReceived(message) => {
+				let query = metadata::storage().tasks().message_received_task_id(*message);
+				storage.fetch(&query).await?.into_iter().collect()
+			},
+			Self::MessageExecuted(message) => {
+				let query = metadata::storage().tasks().message_executed_task_id(*message);
+				storage.fetch(&query).await?.into_iter().collect()
+			},
+			_ => vec![],
+		})
+	}
+
+	fn member(&self) -> Option<&AccountId> {
+		match self {
+			Self::MemberOnline(account) | Self::MemberOffline(account) => Some(account),
+			_ => None,
+		}
+	}
+}
+
+/// Restricts an event stream to the events concerning a network, shard or task.
+///
+/// Events which don't carry the filtered field are matched against the storage of the
+/// block they were emitted in, i.e. a `TaskResult` matches a network filter if the task
+/// belongs to that network.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EventFilter {
+	pub network: Option<NetworkId>,
+	pub shard: Option<ShardId>,
+	pub task: Option<TaskId>,
+}
+
+impl EventFilter {
+	pub fn network(network: NetworkId) -> Self {
+		Self {
+			network: Some(network),
+			..Default::default()
+		}
+	}
+
+	pub fn shard(shard: ShardId) -> Self {
+		Self {
+			shard: Some(shard),
+			..Default::default()
+		}
+	}
+
+	pub fn task(task: TaskId) -> Self {
+		Self {
+			task: Some(task),
+			..Default::default()
+		}
+	}
+
+	async fn matches(&self, storage: &Storage, event: &TimechainEvent) -> Result<bool> {
+		if self.network.is_none() && self.shard.is_none() && self.task.is_none() {
+			return Ok(true);
+		}
+		let tasks = event.tasks(storage).await?;
+		if let Some(task) = self.task {
+			if !tasks.contains(&task) {
+				return Ok(false);
+			}
+		}
+		if let Some(shard) = self.shard {
+			let mut shards = event.shard().into_iter().collect::<Vec<_>>();
+			for task in &tasks {
+				let query = metadata::storage().tasks().task_shard(*task);
+				shards.extend(storage.fetch(&query).await?);
+			}
+			if !shards.contains(&shard) {
+				return Ok(false);
+			}
+		}
+		if let Some(network) = self.network {
+			let event_network = if let Some(network) = event.network() {
+				Some(network)
+			} else if let Some(shard) = event.shard() {
+				storage.fetch(&metadata::storage().shards().shard_network(shard)).await?
+			} else if let Some(task) = tasks.first() {
+				storage.fetch(&metadata::storage().tasks().task_network(*task)).await?
+			} else if let Some(account) = event.member() {
+				let account = subxt::utils::Static(account.clone());
+				storage.fetch(&metadata::storage().members().member_network(&account)).await?
+			} else {
+				None
+			};
+			if event_network != Some(network) {
+				return Ok(false);
+			}
+		}
+		Ok(true)
+	}
+}
+
+async fn block_events(
+	client: &OnlineClient,
+	rpc: &LegacyRpcMethods,
+	number: BlockNumber,
+	filter: &EventFilter,
+) -> Result<Vec<TimechainEvent>> {
+	let hash = rpc
+		.chain_get_block_hash(Some(u64::from(number).into()))
+		.await?
+		.with_context(|| format!("block {number} not found"))?;
+	let block = client.blocks().at(hash).await?;
+	let storage = client.storage().at(block.reference());
+	let mut events = vec![];
+	for event in block.events().await?.iter() {
+		let Some(event) = TimechainEvent::from_runtime_event(event?.as_root_event()?) else {
+			continue;
+		};
+		if filter.matches(&storage, &event).await? {
+			events.push(event);
+		}
+	}
+	Ok(events)
+}
+
+impl SubxtClient {
+	/// Streams the finalized events matching `filter` together with the block they were
+	/// emitted in.
+	///
+	/// When `from` is set, the events of the blocks between `from` and the current
+	/// finalized block are replayed first, which requires the node to keep the state
+	/// of those blocks. Blocks which fail to load are retried on the next finality
+	/// notification, so no events are skipped.
+	pub fn event_stream(
+		&self,
+		filter: EventFilter,
+		from: Option<BlockNumber>,
+	) -> BoxStream<'static, (BlockNumber, TimechainEvent)> {
+		let client = self.client.clone();
+		let rpc = self.rpc.clone();
+		let stream = async_stream::stream! {
+			let mut next = from;
+			loop {
+				let mut finalized = match client.blocks().subscribe_finalized().await {
+					Ok(stream) => stream,
+					Err(e) => {
+						tracing::error!("Error subscribing to block stream {:?}", e);
+						tokio::time::sleep(Duration::from_secs(1)).await;
+						continue;
+					},
+				};
+				while let Some(block) = finalized.next().await {
+					let head = match block {
+						Ok(block) => block.number(),
+						Err(e) => {
+							if e.is_disconnected_will_reconnect() {
+								continue;
+							}
+							tracing::error!("Subxt error: {:?}", e);
+							tokio::time::sleep(Duration::from_secs(1)).await;
+							break;
+						},
+					};
+					for number in next.unwrap_or(head)..=head {
+						match block_events(&client, &rpc, number, &filter).await {
+							Ok(events) => {
+								for event in events {
+									yield (number, event);
+								}
+							},
+							Err(e) => {
+								tracing::error!("failed to read events of block {number}: {:?}", e);
+								next = Some(number);
+								break;
+							},
+						}
+						next = Some(number + 1);
+					}
+				}
+			}
+		};
+		Box::pin(stream)
+	}
+}
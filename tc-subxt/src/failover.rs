@@ -0,0 +1,225 @@
+//! Rpc client failing over between several timechain endpoints.
+use anyhow::Result;
+use futures::{FutureExt, StreamExt};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use subxt::backend::rpc::reconnecting_rpc_client::{ExponentialBackoff, RpcClient as Client};
+use subxt::backend::rpc::{RawRpcFuture, RawRpcSubscription, RpcClientT};
+use subxt::error::RpcError;
+use tokio::sync::watch;
+
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Health {
+	is_syncing: bool,
+}
+
+struct Endpoint {
+	url: String,
+	client: Mutex<Option<Arc<Client>>>,
+}
+
+struct Endpoints {
+	endpoints: Vec<Endpoint>,
+	active: watch::Sender<usize>,
+}
+
+impl Endpoints {
+	async fn connect(&self, index: usize) -> Result<Arc<Client>, RpcError> {
+		let endpoint = &self.endpoints[index];
+		if let Some(client) = endpoint.client.lock().unwrap().clone() {
+			return Ok(client);
+		}
+		let client = Client::builder()
+			.retry_policy(
+				ExponentialBackoff::from_millis(100).max_delay(Duration::from_secs(10)).take(3),
+			)
+			.build(endpoint.url.clone())
+			.await
+			.map_err(|e| RpcError::ClientError(Box::new(e)))?;
+		let client = Arc::new(client);
+		*endpoint.client.lock().unwrap() = Some(client.clone());
+		Ok(client)
+	}
+
+	/// An endpoint is healthy if it responds and isn't syncing. Unhealthy endpoints are
+	/// reconnected to from scratch the next time they are used.
+	async fn is_healthy(&self, index: usize) -> bool {
+		let health = async {
+			let client = self.connect(index).await?;
+			let health = client.request_raw("system_health", None).await?;
+			serde_json::from_str::<Health>(health.get())
+				.map_err(|e| RpcError::ClientError(Box::new(e)))
+		};
+		match health.await {
+			Ok(health) => !health.is_syncing,
+			Err(e) => {
+				tracing::warn!(
+					"timechain endpoint {} is unhealthy: {e}",
+					self.endpoints[index].url
+				);
+				self.endpoints[index].client.lock().unwrap().take();
+				false
+			},
+		}
+	}
+
+	/// Switches from the `failed` endpoint to the next healthy one. Returns false if no
+	/// endpoint is healthy.
+	async fn failover(&self, failed: usize) -> bool {
+		if *self.active.borrow() != failed {
+			return true;
+		}
+		let len = self.endpoints.len();
+		for offset in 1..len {
+			let index = (failed + offset) % len;
+			if self.is_healthy(index).await {
+				tracing::warn!(
+					"failing over from timechain endpoint {} to {}",
+					self.endpoints[failed].url,
+					self.endpoints[index].url
+				);
+				self.active.send_replace(index);
+				return true;
+			}
+		}
+		false
+	}
+
+	/// Runs `f` against the active endpoint, failing over to the other endpoints while it
+	/// fails and the active endpoint is unhealthy.
+	async fn with_active<'a, T, F>(&'a self, f: F) -> Result<(usize, T), RpcError>
+	where
+		F: Fn(Arc<Client>) -> RawRpcFuture<'a, T>,
+	{
+		let mut attempts = self.endpoints.len();
+		loop {
+			let index = *self.active.borrow();
+			let result = match self.connect(index).await {
+				Ok(client) => f(client).await,
+				Err(e) => Err(e),
+			};
+			match result {
+				Ok(result) => return Ok((index, result)),
+				Err(e) => {
+					attempts -= 1;
+					if attempts == 0 || self.is_healthy(index).await || !self.failover(index).await
+					{
+						return Err(e);
+					}
+				},
+			}
+		}
+	}
+}
+
+/// Rpc client connected to one of several timechain endpoints at a time.
+///
+/// Requests failing on an unhealthy endpoint are retried on the next healthy one and the
+/// active endpoint is health checked in the background. Subscriptions end when the client
+/// fails over, so that they are resubscribed on the new endpoint.
+#[derive(Clone)]
+pub struct FailoverRpcClient {
+	endpoints: Arc<Endpoints>,
+}
+
+impl FailoverRpcClient {
+	pub async fn new(urls: Vec<String>) -> Result<Self> {
+		anyhow::ensure!(!urls.is_empty(), "no timechain endpoint configured");
+		let endpoints = Endpoints {
+			endpoints: urls
+				.into_iter()
+				.map(|url| Endpoint { url, client: Mutex::new(None) })
+				.collect(),
+			active: watch::channel(0).0,
+		};
+		// prefer the first healthy endpoint, fall back to the first reachable one
+		let mut active = None;
+		for index in 0..endpoints.endpoints.len() {
+			if endpoints.is_healthy(index).await {
+				active = Some(index);
+				break;
+			}
+		}
+		if active.is_none() {
+			for index in 0..endpoints.endpoints.len() {
+				if endpoints.connect(index).await.is_ok() {
+					active = Some(index);
+					break;
+				}
+			}
+		}
+		let Some(active) = active else {
+			anyhow::bail!("failed to connect to any timechain endpoint");
+		};
+		endpoints.active.send_replace(active);
+		let endpoints = Arc::new(endpoints);
+		if endpoints.endpoints.len() > 1 {
+			tokio::task::spawn(health_check(Arc::downgrade(&endpoints)));
+		}
+		Ok(Self { endpoints })
+	}
+}
+
+async fn health_check(endpoints: Weak<Endpoints>) {
+	loop {
+		tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+		let Some(endpoints) = endpoints.upgrade() else {
+			return;
+		};
+		let active = *endpoints.active.borrow();
+		if !endpoints.is_healthy(active).await && !endpoints.failover(active).await {
+			tracing::error!("no healthy timechain endpoint");
+		}
+	}
+}
+
+impl RpcClientT for FailoverRpcClient {
+	fn request_raw<'a>(
+		&'a self,
+		method: &'a str,
+		params: Option<Box<RawValue>>,
+	) -> RawRpcFuture<'a, Box<RawValue>> {
+		async move {
+			let (_, result) = self
+				.endpoints
+				.with_active(|client| {
+					let params = params.clone();
+					async move { client.request_raw(method, params).await }.boxed()
+				})
+				.await?;
+			Ok(result)
+		}
+		.boxed()
+	}
+
+	fn subscribe_raw<'a>(
+		&'a self,
+		sub: &'a str,
+		params: Option<Box<RawValue>>,
+		unsub: &'a str,
+	) -> RawRpcFuture<'a, RawRpcSubscription> {
+		async move {
+			let (index, subscription) = self
+				.endpoints
+				.with_active(|client| {
+					let params = params.clone();
+					async move { client.subscribe_raw(sub, params, unsub).await }.boxed()
+				})
+				.await?;
+			let mut active = self.endpoints.active.subscribe();
+			let failed_over = async move {
+				active.wait_for(|active| *active != index).await.ok();
+			};
+			Ok(RawRpcSubscription {
+				stream: subscription.stream.take_until(failed_over).boxed(),
+				id: subscription.id,
+			})
+		}
+		.boxed()
+	}
+}
@@ -1,13 +1,13 @@
 #![allow(clippy::missing_transmute_annotations)]
-use crate::worker::{SubxtWorker, Tx};
+use crate::worker::{SubxtWorker, Tx, MAX_BATCH_SIZE};
 use anyhow::{Context, Result};
 use db::TransactionsDB;
+use failover::FailoverRpcClient;
 use futures::channel::{mpsc, oneshot};
 use futures::stream::BoxStream;
 use std::future::Future;
 use std::str::FromStr;
 use std::time::Duration;
-use subxt::backend::rpc::reconnecting_rpc_client::{ExponentialBackoff, RpcClient as Client};
 use subxt::backend::rpc::RpcClient;
 use subxt::config::DefaultExtrinsicParams;
 use subxt::PolkadotConfig;
@@ -18,12 +18,17 @@ use time_primitives::{AccountId, BlockHash, BlockNumber, PublicKey, H256};
 
 mod api;
 pub mod db;
+pub mod events;
+mod failover;
 pub mod metadata;
+pub mod offline;
 pub mod timechain_client;
 pub mod worker;
 
 use metadata::technical_committee::events as CommitteeEvent;
+use metadata::utility::events as UtilityEvent;
 
+pub use events::{EventFilter, TimechainEvent};
 pub use subxt_signer::sr25519::Keypair;
 
 pub type OnlineClient = subxt::OnlineClient<PolkadotConfig>;
@@ -36,6 +41,7 @@ pub type ExtrinsicParams =
 
 pub struct SubxtClient {
 	client: OnlineClient,
+	rpc: LegacyRpcMethods,
 	tx: mpsc::UnboundedSender<(Tx, oneshot::Sender<TimechainExtrinsic>)>,
 	public_key: PublicKey,
 	account_id: AccountId,
@@ -52,13 +58,16 @@ impl SubxtClient {
 		let nonce = legacy_rpc.system_account_next_index(&account_id).await?;
 		let timechain_client = TimechainOnlineClient::new(client.clone(), keypair.clone());
 		let db = TransactionsDB::new(tx_db, keypair.public_key().0)?;
-		let worker = SubxtWorker::new(nonce, timechain_client, db, keypair).await?;
+		let worker = SubxtWorker::new(nonce, timechain_client, db, keypair)
+			.await?
+			.with_batch_size(MAX_BATCH_SIZE);
 		let public_key = worker.public_key();
 		let account_id = worker.account_id();
 		tracing::info!("account id {}", account_id);
 		let tx = worker.into_sender();
 		Ok(Self {
 			client,
+			rpc: legacy_rpc,
 			tx,
 			public_key,
 			account_id,
@@ -66,20 +75,15 @@ impl SubxtClient {
 	}
 
 	pub async fn with_key(url: &str, mnemonic: &str, tx_db: &str) -> Result<Self> {
-		let secret =
-			SecretUri::from_str(mnemonic.trim()).context("failed to parse substrate keyfile")?;
-		let keypair = Keypair::from_uri(&secret).context("substrate keyfile contains uri")?;
-		Self::new(url, keypair, tx_db).await
+		Self::new(url, keypair_from_mnemonic(mnemonic)?, tx_db).await
 	}
 
+	/// Connects to `url`, which may be a comma separated list of endpoints to fail over
+	/// between.
 	pub async fn get_client(url: &str) -> Result<RpcClient> {
-		let client = Client::builder()
-			.retry_policy(
-				ExponentialBackoff::from_millis(100).max_delay(Duration::from_secs(10)).take(3),
-			)
-			.build(url.to_string())
-			.await?;
-		Ok(client.into())
+		let urls = url.split(',').map(str::trim).filter(|url| !url.is_empty());
+		let client = FailoverRpcClient::new(urls.map(String::from).collect()).await?;
+		Ok(RpcClient::new(client))
 	}
 
 	pub fn public_key(&self) -> &PublicKey {
@@ -90,6 +94,11 @@ impl SubxtClient {
 		&self.account_id
 	}
 
+	/// Spec version of the runtime the client is connected to.
+	pub fn spec_version(&self) -> u32 {
+		self.client.runtime_version().spec_version
+	}
+
 	pub async fn latest_block(&self) -> Result<u64> {
 		Ok(self.client.blocks().at_latest().await?.number().into())
 	}
@@ -135,6 +144,12 @@ impl SubxtClient {
 	}
 }
 
+pub fn keypair_from_mnemonic(mnemonic: &str) -> Result<Keypair> {
+	let secret =
+		SecretUri::from_str(mnemonic.trim()).context("failed to parse substrate keyfile")?;
+	Keypair::from_uri(&secret).context("substrate keyfile contains uri")
+}
+
 type Block = subxt::blocks::Block<subxt::PolkadotConfig, OnlineClient>;
 type BlockStreamOutput = Result<subxt::backend::StreamOfResults<Block>, subxt::error::Error>;
 
@@ -0,0 +1,185 @@
+//! Admin calls signed on an air-gapped machine.
+//!
+//! A call is prepared online into an [`OfflineTx`] holding the payload to sign, signed
+//! offline with [`OfflineTx::sign`] and submitted with [`SubxtClient::submit_offline_tx`].
+//! The tx is immortal, so it can be submitted any time until the signer's nonce is used.
+use crate::metadata::{self, runtime_types, RuntimeCall};
+use crate::worker::Tx;
+use crate::{CommitteeEvent, ExtrinsicParams, Keypair, SubxtClient};
+use anyhow::{Context, Result};
+use scale_codec::Encode;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use subxt::config::DefaultExtrinsicParamsBuilder;
+use subxt::utils::{AccountId32, MultiAddress, MultiSignature, H256};
+use time_primitives::{AccountId, BlockNumber};
+
+/// Submits the call through a multisig account made of the signer and
+/// `other_signatories`.
+#[derive(Clone, Debug)]
+pub struct Multisig {
+	pub threshold: u16,
+	pub other_signatories: Vec<AccountId>,
+	/// Block and extrinsic index of the first approval, required by all other approvals.
+	pub timepoint: Option<(BlockNumber, u32)>,
+}
+
+/// How an admin call is authorized.
+#[derive(Clone, Debug, Default)]
+pub struct Authorization {
+	/// Proposes the call to the technical committee instead of executing it. It is
+	/// executed once `threshold` members voted for it.
+	pub proposal_threshold: Option<u32>,
+	pub multisig: Option<Multisig>,
+}
+
+/// Unsigned or signed tx exchanged with the air-gapped machine.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OfflineTx {
+	/// Ss58 address of the signer.
+	pub signer: String,
+	pub nonce: u64,
+	/// Hex encoded call.
+	pub call: String,
+	/// Hex encoded payload to sign.
+	pub payload: String,
+	/// Hex encoded sr25519 signature of the payload.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub signature: Option<String>,
+}
+
+impl OfflineTx {
+	fn signer(&self) -> Result<AccountId> {
+		AccountId::from_str(&self.signer)
+			.map_err(|e| anyhow::anyhow!("invalid signer {}: {e}", self.signer))
+	}
+
+	/// Signs the payload, doesn't require a connection to the timechain.
+	pub fn sign(&mut self, keypair: &Keypair) -> Result<()> {
+		let account = AccountId::from(keypair.public_key().0);
+		anyhow::ensure!(
+			account == self.signer()?,
+			"tx must be signed by {}, not {account}",
+			self.signer
+		);
+		let payload = hex::decode(&self.payload).context("invalid payload")?;
+		self.signature = Some(hex::encode(keypair.sign(&payload).0));
+		Ok(())
+	}
+}
+
+/// Call data which is already encoded.
+struct EncodedCall(Vec<u8>);
+
+impl subxt::tx::Payload for EncodedCall {
+	fn encode_call_data_to(
+		&self,
+		_metadata: &subxt::Metadata,
+		out: &mut Vec<u8>,
+	) -> Result<(), subxt::Error> {
+		out.extend(&self.0);
+		Ok(())
+	}
+}
+
+fn committee_call(call: RuntimeCall, proposal_threshold: Option<u32>) -> RuntimeCall {
+	use runtime_types::pallet_collective::pallet::Call;
+	let length_bound = call.encoded_size() as u32;
+	let proposal = Box::new(call);
+	RuntimeCall::TechnicalCommittee(match proposal_threshold {
+		Some(threshold) => Call::propose {
+			threshold,
+			proposal,
+			length_bound,
+		},
+		None => Call::execute { proposal, length_bound },
+	})
+}
+
+fn params(nonce: u64) -> ExtrinsicParams {
+	DefaultExtrinsicParamsBuilder::new().nonce(nonce).build()
+}
+
+impl SubxtClient {
+	/// Prepares an admin tx to be signed offline by `signer`.
+	pub async fn prepare_offline_tx(
+		&self,
+		signer: &AccountId,
+		tx: Tx,
+		authorization: Authorization,
+	) -> Result<OfflineTx> {
+		let call = tx.admin_call().context("not an admin tx")?;
+		let mut call = committee_call(call, authorization.proposal_threshold);
+		if let Some(multisig) = authorization.multisig {
+			use runtime_types::pallet_multisig::{pallet::Call, Timepoint};
+			let length = call.encoded_size() as u32;
+			let info = metadata::apis()
+				.transaction_payment_call_api()
+				.query_call_info(call.clone(), length);
+			let info = self.client.runtime_api().at_latest().await?.call(info).await?;
+			let mut other_signatories = multisig.other_signatories;
+			other_signatories.sort();
+			call = RuntimeCall::Multisig(Call::as_multi {
+				threshold: multisig.threshold,
+				other_signatories: other_signatories
+					.into_iter()
+					.map(subxt::utils::Static)
+					.collect(),
+				maybe_timepoint: multisig
+					.timepoint
+					.map(|(height, index)| Timepoint { height, index }),
+				call: Box::new(call),
+				max_weight: info.weight,
+			});
+		}
+		let account = AccountId32(signer.clone().into());
+		let nonce = self.rpc.system_account_next_index(&account).await?;
+		let call = call.encode();
+		let partial = self
+			.client
+			.tx()
+			.create_partial_signed_offline(&EncodedCall(call.clone()), params(nonce))?;
+		Ok(OfflineTx {
+			signer: signer.to_string(),
+			nonce,
+			call: hex::encode(call),
+			payload: hex::encode(partial.signer_payload()),
+			signature: None,
+		})
+	}
+
+	/// Submits a tx signed offline and waits for it to be finalized.
+	pub async fn submit_offline_tx(&self, tx: &OfflineTx) -> Result<H256> {
+		let signature = tx.signature.as_ref().context("tx is not signed")?;
+		let signature: [u8; 64] = hex::decode(signature)?
+			.try_into()
+			.map_err(|_| anyhow::anyhow!("invalid signature"))?;
+		let account = AccountId32(tx.signer()?.into());
+		let call = hex::decode(&tx.call).context("invalid call")?;
+		let partial = self
+			.client
+			.tx()
+			.create_partial_signed_offline(&EncodedCall(call), params(tx.nonce))?;
+		anyhow::ensure!(
+			hex::encode(partial.signer_payload()) == tx.payload,
+			"signing payload changed since the tx was prepared, it needs to be prepared again"
+		);
+		let extrinsic = partial.sign_with_address_and_signature(
+			&MultiAddress::Id(account),
+			&MultiSignature::Sr25519(signature),
+		);
+		let events = extrinsic.submit_and_watch().await?.wait_for_finalized_success().await?;
+		if let Some(event) = events.find_first::<CommitteeEvent::MemberExecuted>()? {
+			if let Err(err) = event.result {
+				anyhow::bail!("Tx failed with error: {:?}", err);
+			}
+		}
+		if let Some(event) = events.find_first::<CommitteeEvent::Proposed>()? {
+			tracing::info!(
+				"proposal {} submitted to the technical committee",
+				event.proposal_index
+			);
+		}
+		Ok(events.extrinsic_hash())
+	}
+}
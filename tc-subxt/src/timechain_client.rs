@@ -1,4 +1,5 @@
 use std::collections::VecDeque;
+use std::sync::Arc;
 
 use anyhow::Result;
 use futures::{stream::BoxStream, StreamExt, TryStreamExt};
@@ -7,7 +8,9 @@ use subxt::{client::Update, tx::Payload};
 pub use subxt_signer::sr25519::Keypair;
 
 use crate::worker::TxData;
-use crate::{metadata, CommitteeEvent, ExtrinsicParams, OnlineClient, SubmittableExtrinsic};
+use crate::{
+	metadata, CommitteeEvent, ExtrinsicParams, OnlineClient, SubmittableExtrinsic, UtilityEvent,
+};
 
 #[derive(Clone, Copy, Debug, Default)]
 pub struct BlockId {
@@ -55,6 +58,10 @@ pub trait IExtrinsic: Send + Sync {
 	async fn events(&self) -> Result<Self::Events>;
 	fn hash(&self) -> H256;
 	async fn is_success(&self) -> Result<()>;
+	/// Returns the extrinsic with the outcome of the call at `index` of a batch.
+	fn batch_item(&self, index: usize) -> Self
+	where
+		Self: Sized;
 }
 
 pub trait ITransactionDbOps: Send + Sync {
@@ -83,7 +90,8 @@ pub struct TimechainBlock {
 }
 
 pub struct TimechainExtrinsic {
-	pub extrinsic: crate::ExtrinsicDetails,
+	pub extrinsic: Arc<crate::ExtrinsicDetails>,
+	pub batch_item: Option<usize>,
 }
 
 pub struct TimechainEvents {
@@ -190,7 +198,13 @@ impl IBlock for TimechainBlock {
 	type Extrinsic = TimechainExtrinsic;
 	async fn extrinsics(&self) -> Result<Vec<Self::Extrinsic>> {
 		let extrinsics = self.block.extrinsics().await?;
-		Ok(extrinsics.iter().map(|extrinsic| TimechainExtrinsic { extrinsic }).collect())
+		Ok(extrinsics
+			.iter()
+			.map(|extrinsic| TimechainExtrinsic {
+				extrinsic: Arc::new(extrinsic),
+				batch_item: None,
+			})
+			.collect())
 	}
 	fn number(&self) -> u64 {
 		self.block.number().into()
@@ -213,6 +227,9 @@ impl IExtrinsic for TimechainExtrinsic {
 	}
 	async fn is_success(&self) -> Result<()> {
 		type SpRuntimeDispatchError = metadata::runtime_types::sp_runtime::DispatchError;
+		if let Some(index) = self.batch_item {
+			return self.is_batch_item_success(index).await;
+		}
 		let events = self.extrinsic.events().await?;
 		for ev in events.iter() {
 			let ev = ev?;
@@ -247,4 +264,42 @@ impl IExtrinsic for TimechainExtrinsic {
 		}
 		Ok(())
 	}
+
+	fn batch_item(&self, index: usize) -> Self {
+		Self {
+			extrinsic: self.extrinsic.clone(),
+			batch_item: Some(index),
+		}
+	}
+}
+
+impl TimechainExtrinsic {
+	/// Decodes the outcome of a batched call from the `Utility` events. Every call emits
+	/// either `ItemCompleted` or `ItemFailed`, an interrupted batch emits `BatchInterrupted`
+	/// for the failing call instead.
+	async fn is_batch_item_success(&self, index: usize) -> Result<()> {
+		let events = self.extrinsic.events().await?;
+		let mut item = 0;
+		for ev in events.iter() {
+			let ev = ev?;
+			if ev.as_event::<UtilityEvent::ItemCompleted>()?.is_some() {
+				if item == index {
+					return Ok(());
+				}
+				item += 1;
+			} else if let Some(event) = ev.as_event::<UtilityEvent::ItemFailed>()? {
+				if item == index {
+					anyhow::bail!("Tx failed with error: {:?}", event.error);
+				}
+				item += 1;
+			} else if let Some(event) = ev.as_event::<UtilityEvent::BatchInterrupted>()? {
+				anyhow::bail!(
+					"batch interrupted at call {} with error: {:?}",
+					event.index,
+					event.error
+				);
+			}
+		}
+		anyhow::bail!("no outcome for call {index} of batch {:?}", self.hash())
+	}
 }
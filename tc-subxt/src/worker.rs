@@ -8,19 +8,37 @@ use futures::channel::{mpsc, oneshot};
 use futures::future::BoxFuture;
 use futures::stream::{BoxStream, Fuse, FuturesUnordered};
 use futures::{Future, FutureExt, StreamExt};
+use prometheus::IntCounterVec;
 use scale_codec::{Decode, Encode};
 use std::collections::{HashSet, VecDeque};
 use std::pin::Pin;
+use std::sync::LazyLock;
 use subxt::config::DefaultExtrinsicParamsBuilder;
 use subxt::utils::H256;
 use subxt_signer::sr25519::Keypair;
 use time_primitives::BatchId;
 use time_primitives::{
-	traits::IdentifyAccount, AccountId, Commitment, GmpEvents, Network, NetworkConfig, NetworkId,
-	PeerId, ProofOfKnowledge, PublicKey, ShardId, TaskId, TaskResult,
+	traits::IdentifyAccount, AccountId, Balance, Commitment, Gateway, GmpEvents, Network,
+	NetworkConfig, NetworkId, PeerId, ProofOfKnowledge, PublicKey, Route, ShardId, TaskId,
+	TaskResult, MICROANLOG, MILLIANLOG,
 };
 
 pub const MORTALITY: u8 = 32;
+/// Maximum number of calls submitted in a single `Utility::force_batch`.
+pub const MAX_BATCH_SIZE: usize = 32;
+/// Default cap on the tip of a single tx.
+pub const MAX_TIP: Balance = MILLIANLOG;
+/// Minimum tip of a tx resubmitted after its era expired.
+const TIP_STEP: Balance = MICROANLOG;
+
+static TIPS_PAID: LazyLock<IntCounterVec> = LazyLock::new(|| {
+	prometheus::register_int_counter_vec!(
+		"timechain_tx_tips_paid",
+		"Tips paid for included transactions in planck",
+		&["priority"]
+	)
+	.unwrap()
+});
 type TransactionFuture = Pin<Box<dyn Future<Output = Result<H256>> + Send>>;
 type TransactionsUnordered = FuturesUnordered<TransactionFuture>;
 
@@ -46,6 +64,30 @@ pub enum Tx {
 		network: NetworkId,
 		config: NetworkConfig,
 	},
+	MigrateGateway {
+		network: NetworkId,
+		gateway: Gateway,
+		block: u64,
+	},
+	SetRoute {
+		network: NetworkId,
+		route: Route,
+	},
+	// oracle
+	AddFeeder {
+		feeder: AccountId,
+	},
+	SetTokenConfig {
+		network: NetworkId,
+		decimals: u32,
+		gas_price: u128,
+		/// Margin in parts per billion.
+		margin: u32,
+	},
+	/// Prices as the inner value of a `FixedU128`.
+	SubmitPrices {
+		prices: Vec<(NetworkId, u128)>,
+	},
 	// members
 	RegisterMember {
 		network: NetworkId,
@@ -82,6 +124,202 @@ pub enum Tx {
 	RestartBatch {
 		batch_id: BatchId,
 	},
+	// utility
+	Batch {
+		txs: Vec<Tx>,
+	},
+}
+
+/// Priority class of a tx. Higher classes start with a higher tip, so that they are included
+/// before bulk admin traffic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+	Bulk,
+	Normal,
+	High,
+}
+
+impl Priority {
+	pub fn base_tip(self) -> Balance {
+		match self {
+			Self::Bulk => 0,
+			Self::Normal => MICROANLOG,
+			Self::High => 10 * MICROANLOG,
+		}
+	}
+
+	fn label(self) -> &'static str {
+		match self {
+			Self::Bulk => "bulk",
+			Self::Normal => "normal",
+			Self::High => "high",
+		}
+	}
+}
+
+impl Tx {
+	pub fn priority(&self) -> Priority {
+		match self {
+			Self::Heartbeat
+			| Self::Commitment { .. }
+			| Self::Ready { .. }
+			| Self::SubmitTaskResult { .. } => Priority::High,
+			Self::Transfer { .. } | Self::RegisterMember { .. } | Self::UnregisterMember { .. } => {
+				Priority::Normal
+			},
+			Self::Batch { txs } => txs.iter().map(Tx::priority).max().unwrap_or(Priority::Bulk),
+			_ => Priority::Bulk,
+		}
+	}
+
+	/// Signed calls can be batched, sudo calls are always submitted on their own.
+	///
+	/// Heartbeats are operational calls, batching them with normal calls would make them
+	/// compete for normal block space.
+	pub fn is_batchable(&self) -> bool {
+		matches!(
+			self,
+			Self::Transfer { .. }
+				| Self::RegisterMember { .. }
+				| Self::UnregisterMember { .. }
+				| Self::Commitment { .. }
+				| Self::Ready { .. }
+				| Self::SubmitTaskResult { .. }
+		)
+	}
+
+	fn batch_call(self) -> Option<RuntimeCall> {
+		use runtime_types::{pallet_balances, pallet_members, pallet_shards, pallet_tasks};
+		Some(match self {
+			Self::Transfer { account, balance } => {
+				RuntimeCall::Balances(pallet_balances::pallet::Call::transfer_allow_death {
+					dest: subxt::utils::Static(account).into(),
+					value: balance,
+				})
+			},
+			Self::RegisterMember {
+				network,
+				public_key,
+				peer_id,
+				stake_amount,
+			} => RuntimeCall::Members(pallet_members::pallet::Call::register_member {
+				network,
+				public_key: subxt::utils::Static(public_key),
+				peer_id,
+				bond: stake_amount,
+			}),
+			Self::UnregisterMember { member } => {
+				RuntimeCall::Members(pallet_members::pallet::Call::unregister_member {
+					member: subxt::utils::Static(member),
+				})
+			},
+			Self::Commitment {
+				shard_id,
+				commitment,
+				proof_of_knowledge,
+			} => RuntimeCall::Shards(pallet_shards::pallet::Call::commit {
+				shard_id,
+				commitment: subxt::utils::Static(commitment),
+				proof_of_knowledge,
+			}),
+			Self::Ready { shard_id } => {
+				RuntimeCall::Shards(pallet_shards::pallet::Call::ready { shard_id })
+			},
+			Self::SubmitTaskResult { task_id, result } => {
+				RuntimeCall::Tasks(pallet_tasks::pallet::Call::submit_task_result {
+					task_id,
+					result: subxt::utils::Static(result),
+				})
+			},
+			_ => return None,
+		})
+	}
+
+	/// Calls requiring the technical committee, submitted with `metadata::sudo` by the
+	/// worker or prepared for offline signing.
+	///
+	/// Privileged calls of the chronicle pallets are time-locked and scheduled
+	/// through the governance pallet.
+	pub fn admin_call(self) -> Option<RuntimeCall> {
+		use runtime_types::{
+			frame_system, pallet_governance, pallet_networks, pallet_oracle, pallet_shards,
+			pallet_tasks, sp_arithmetic::per_things::Perbill,
+		};
+		let timelocked = |call| {
+			RuntimeCall::Governance(pallet_governance::pallet::Call::schedule {
+				call: Box::new(call),
+			})
+		};
+		Some(match self {
+			Self::SetCode { code } => {
+				RuntimeCall::System(frame_system::pallet::Call::set_code { code })
+			},
+			Self::RegisterNetwork { network } => {
+				RuntimeCall::Networks(pallet_networks::pallet::Call::register_network {
+					network: subxt::utils::Static(network),
+				})
+			},
+			Self::ForceShardOffline { shard_id } => {
+				timelocked(RuntimeCall::Shards(pallet_shards::pallet::Call::force_shard_offline {
+					shard_id,
+				}))
+			},
+			Self::SetNetworkConfig { network, config } => {
+				RuntimeCall::Networks(pallet_networks::pallet::Call::set_network_config {
+					network,
+					config: subxt::utils::Static(config),
+				})
+			},
+			Self::MigrateGateway { network, gateway, block } => {
+				RuntimeCall::Networks(pallet_networks::pallet::Call::migrate_gateway {
+					network,
+					gateway,
+					gateway_block: block,
+				})
+			},
+			Self::SetRoute { network, route } => {
+				RuntimeCall::Networks(pallet_networks::pallet::Call::set_route {
+					network,
+					route: subxt::utils::Static(route),
+				})
+			},
+			Self::AddFeeder { feeder } => {
+				RuntimeCall::Oracle(pallet_oracle::pallet::Call::add_feeder {
+					feeder: subxt::utils::Static(feeder),
+				})
+			},
+			Self::SetTokenConfig {
+				network,
+				decimals,
+				gas_price,
+				margin,
+			} => RuntimeCall::Oracle(pallet_oracle::pallet::Call::set_token_config {
+				network,
+				config: pallet_oracle::pallet::TokenConfig {
+					decimals,
+					gas_price,
+					margin: Perbill(margin),
+				},
+			}),
+			Self::SubmitGmpEvents { network, gmp_events } => {
+				timelocked(RuntimeCall::Tasks(pallet_tasks::pallet::Call::submit_gmp_events {
+					network,
+					events: subxt::utils::Static(gmp_events),
+				}))
+			},
+			Self::RemoveTask { task_id } => {
+				timelocked(RuntimeCall::Tasks(pallet_tasks::pallet::Call::remove_task {
+					task: task_id,
+				}))
+			},
+			Self::RestartBatch { batch_id } => {
+				timelocked(RuntimeCall::Tasks(pallet_tasks::pallet::Call::restart_batch {
+					batch_id,
+				}))
+			},
+			_ => return None,
+		})
+	}
 }
 
 #[derive(Clone, Encode, Decode)]
@@ -90,11 +328,16 @@ pub struct TxData {
 	pub era: u64,
 	pub nonce: u64,
 	pub transaction: Tx,
+	/// Tips of every submission of the tx, the last one is the current tip.
+	pub tips: Vec<Balance>,
 }
 
 pub struct TxStatus<C: ITimechainClient> {
 	data: TxData,
-	event_sender: Option<oneshot::Sender<<C::Block as IBlock>::Extrinsic>>,
+	// one sender per call of a batch, empty for txs loaded from the db
+	event_senders: Vec<oneshot::Sender<<C::Block as IBlock>::Extrinsic>>,
+	// signed extrinsic, empty for txs loaded from the db
+	extrinsic: Vec<u8>,
 	best_block: Option<u64>,
 }
 
@@ -110,6 +353,9 @@ where
 	pending_tx: VecDeque<TxStatus<C>>,
 	transaction_pool: TransactionsUnordered,
 	db: D,
+	batch_size: usize,
+	batch: Vec<(Tx, oneshot::Sender<<C::Block as IBlock>::Extrinsic>)>,
+	max_tip: Balance,
 }
 
 impl<C, D> SubxtWorker<C, D>
@@ -129,7 +375,8 @@ where
 			.into_iter()
 			.map(|tx_data| TxStatus {
 				data: tx_data,
-				event_sender: None,
+				event_senders: vec![],
+				extrinsic: vec![],
 				best_block: None,
 			})
 			.collect();
@@ -141,9 +388,26 @@ where
 			pending_tx,
 			transaction_pool,
 			db,
+			batch_size: 1,
+			batch: vec![],
+			max_tip: MAX_TIP,
 		})
 	}
 
+	/// Caps the tip of a single tx. Txs whose era expired are resubmitted with twice the tip
+	/// until the cap is reached.
+	pub fn with_max_tip(mut self, max_tip: Balance) -> Self {
+		self.max_tip = max_tip;
+		self
+	}
+
+	/// Batches up to `batch_size` batchable txs queued within a block into a single
+	/// `Utility::force_batch` call. A batch size of one disables batching.
+	pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+		self.batch_size = batch_size.max(1);
+		self
+	}
+
 	pub fn public_key(&self) -> PublicKey {
 		PublicKey::Sr25519(self.keypair.public_key().as_ref().try_into().unwrap())
 	}
@@ -154,12 +418,19 @@ where
 
 	fn build_tx(&mut self, tx: Tx, params: ExtrinsicParams) -> Vec<u8> {
 		match tx.clone() {
-			// system
-			Tx::SetCode { code } => {
-				let runtime_call =
-					RuntimeCall::System(runtime_types::frame_system::pallet::Call::set_code {
-						code,
-					});
+			// admin
+			Tx::SetCode { .. }
+			| Tx::RegisterNetwork { .. }
+			| Tx::ForceShardOffline { .. }
+			| Tx::SetNetworkConfig { .. }
+			| Tx::MigrateGateway { .. }
+			| Tx::SetRoute { .. }
+			| Tx::AddFeeder { .. }
+			| Tx::SetTokenConfig { .. }
+			| Tx::SubmitGmpEvents { .. }
+			| Tx::RemoveTask { .. }
+			| Tx::RestartBatch { .. } => {
+				let runtime_call = tx.admin_call().expect("admin tx");
 				let payload = metadata::sudo(runtime_call);
 				self.client.sign_payload(&payload, params)
 			},
@@ -170,33 +441,17 @@ where
 					metadata::tx().balances().transfer_allow_death(account.into(), balance);
 				self.client.sign_payload(&payload, params)
 			},
-			// networks
-			Tx::RegisterNetwork { network } => {
-				let network = subxt::utils::Static(network);
-				let runtime_call = RuntimeCall::Networks(
-					runtime_types::pallet_networks::pallet::Call::register_network { network },
-				);
-				let payload = metadata::sudo(runtime_call);
-				self.client.sign_payload(&payload, params)
-			},
-			Tx::ForceShardOffline { shard_id } => {
-				let runtime_call = RuntimeCall::Shards(
-					metadata::runtime_types::pallet_shards::pallet::Call::force_shard_offline {
-						shard_id,
-					},
-				);
-				let payload = metadata::sudo(runtime_call);
-				self.client.sign_payload(&payload, params)
-			},
-			Tx::SetNetworkConfig { network, config } => {
-				let config = subxt::utils::Static(config);
-				let runtime_call = RuntimeCall::Networks(
-					metadata::runtime_types::pallet_networks::pallet::Call::set_network_config {
-						network,
-						config,
-					},
-				);
-				let payload = metadata::sudo(runtime_call);
+			// oracle
+			Tx::SubmitPrices { prices } => {
+				use metadata::runtime_types::{
+					bounded_collections::bounded_vec::BoundedVec,
+					sp_arithmetic::fixed_point::FixedU128,
+				};
+				let prices = prices
+					.into_iter()
+					.map(|(network, price)| (network, FixedU128(price)))
+					.collect();
+				let payload = metadata::tx().oracle().submit_prices(BoundedVec(prices));
 				self.client.sign_payload(&payload, params)
 			},
 			// members
@@ -245,30 +500,10 @@ where
 				let payload = metadata::tx().tasks().submit_task_result(task_id, result);
 				self.client.sign_payload(&payload, params)
 			},
-			Tx::SubmitGmpEvents { network, gmp_events } => {
-				let runtime_call = RuntimeCall::Tasks(
-					metadata::runtime_types::pallet_tasks::pallet::Call::submit_gmp_events {
-						network,
-						events: subxt::utils::Static(gmp_events),
-					},
-				);
-				let payload = metadata::sudo(runtime_call);
-				self.client.sign_payload(&payload, params)
-			},
-			Tx::RemoveTask { task_id } => {
-				let runtime_call = RuntimeCall::Tasks(
-					metadata::runtime_types::pallet_tasks::pallet::Call::remove_task {
-						task: task_id,
-					},
-				);
-				let payload = metadata::sudo(runtime_call);
-				self.client.sign_payload(&payload, params)
-			},
-			Tx::RestartBatch { batch_id } => {
-				let runtime_call = RuntimeCall::Tasks(
-					metadata::runtime_types::pallet_tasks::pallet::Call::restart_batch { batch_id },
-				);
-				let payload = metadata::sudo(runtime_call);
+			// utility
+			Tx::Batch { txs } => {
+				let calls = txs.into_iter().filter_map(Tx::batch_call).collect();
+				let payload = metadata::tx().utility().force_batch(calls);
 				self.client.sign_payload(&payload, params)
 			},
 		}
@@ -277,24 +512,32 @@ where
 	fn add_tx_to_pool(
 		&mut self,
 		transaction: Tx,
-		event_sender: Option<oneshot::Sender<<C::Block as IBlock>::Extrinsic>>,
-		nonce: Option<u64>,
+		event_senders: Vec<oneshot::Sender<<C::Block as IBlock>::Extrinsic>>,
+		previous: Option<TxData>,
 	) {
-		let mut is_new_tx = true;
+		let is_new_tx = previous.is_none();
 		let block = &self.latest_block;
-		let nonce = match nonce {
-			Some(nonce) => {
-				is_new_tx = false;
-				nonce
+		let (nonce, mut tips) = match previous {
+			Some(previous) => {
+				if let Err(e) = self.db.remove_tx(previous.hash) {
+					tracing::error!("Unable to remove tx from db {e}");
+				}
+				(previous.nonce, previous.tips)
 			},
-			None => self.nonce,
+			None => (self.nonce, vec![]),
+		};
+		let tip = match tips.last() {
+			Some(tip) => (tip.saturating_mul(2)).max(TIP_STEP).min(self.max_tip),
+			None => transaction.priority().base_tip().min(self.max_tip),
 		};
+		tips.push(tip);
 		let params: ExtrinsicParams = DefaultExtrinsicParamsBuilder::new()
 			.nonce(nonce)
+			.tip(tip)
 			.mortal_unchecked(block.number, block.hash, MORTALITY.into())
 			.build();
-		let tx = self.build_tx(transaction.clone(), params);
-		let tx = self.client.submittable_transaction(tx.clone());
+		let extrinsic = self.build_tx(transaction.clone(), params);
+		let tx = self.client.submittable_transaction(extrinsic.clone());
 		let hash = tx.hash();
 		let tx_status = TxStatus {
 			data: TxData {
@@ -302,8 +545,10 @@ where
 				era: self.latest_block.number + MORTALITY as u64,
 				hash,
 				nonce,
+				tips,
 			},
-			event_sender,
+			event_senders,
+			extrinsic,
 			best_block: None,
 		};
 
@@ -321,6 +566,63 @@ where
 		self.transaction_pool.push(fut);
 	}
 
+	/// Resubmits the pending txs which aren't in a block yet. The node may have changed
+	/// after a reconnect, so its pool doesn't necessarily contain them. The same signed
+	/// extrinsics are submitted, keeping the nonces in use.
+	fn resubmit_pending(&mut self) {
+		for tx in &self.pending_tx {
+			if tx.best_block.is_some() || tx.extrinsic.is_empty() {
+				continue;
+			}
+			tracing::info!("resubmitting tx with nonce {}", tx.data.nonce);
+			let tx = self.client.submittable_transaction(tx.extrinsic.clone());
+			self.transaction_pool.push(async move { tx.submit().await }.boxed());
+		}
+	}
+
+	/// Submits the txs queued within the current block, as a batch if there are several.
+	fn flush_batch(&mut self) {
+		let mut batch = std::mem::take(&mut self.batch);
+		match batch.len() {
+			0 => {},
+			1 => {
+				let (tx, sender) = batch.pop().unwrap();
+				self.add_tx_to_pool(tx, vec![sender], None);
+			},
+			len => {
+				tracing::info!("submitting batch of {len} txs");
+				let (txs, senders) = batch.into_iter().unzip();
+				self.add_tx_to_pool(Tx::Batch { txs }, senders, None);
+			},
+		}
+	}
+
+	/// Notifies the senders of a finalized tx. Calls of a batch that failed as a whole are
+	/// resubmitted individually.
+	async fn complete_tx(&mut self, tx: TxStatus<C>, extrinsic: <C::Block as IBlock>::Extrinsic) {
+		let tip = tx.data.tips.last().copied().unwrap_or_default();
+		TIPS_PAID
+			.with_label_values(&[tx.data.transaction.priority().label()])
+			.inc_by(tip.try_into().unwrap_or(u64::MAX));
+		let Tx::Batch { txs } = tx.data.transaction else {
+			if let Some(sender) = tx.event_senders.into_iter().next() {
+				sender.send(extrinsic).ok();
+			}
+			return;
+		};
+		if let Err(e) = extrinsic.is_success().await {
+			tracing::warn!("batch of {} txs failed, resubmitting individually: {e}", txs.len());
+			let mut senders = tx.event_senders.into_iter();
+			for tx in txs {
+				self.add_tx_to_pool(tx, senders.next().into_iter().collect(), None);
+			}
+			return;
+		}
+		for (index, sender) in tx.event_senders.into_iter().enumerate() {
+			sender.send(extrinsic.batch_item(index)).ok();
+		}
+	}
+
 	pub fn into_sender(
 		mut self,
 	) -> mpsc::UnboundedSender<(Tx, oneshot::Sender<<C::Block as IBlock>::Extrinsic>)> {
@@ -340,8 +642,16 @@ where
 				futures::select! {
 					tx = rx.next().fuse() => {
 						let Some((command, channel)) = tx else { break; };
+						if self.batch_size > 1 && command.is_batchable() {
+							tracing::info!("tx added to batch");
+							self.batch.push((command, channel));
+							if self.batch.len() >= self.batch_size {
+								self.flush_batch();
+							}
+							continue;
+						}
 						tracing::info!("tx added to pool");
-						self.add_tx_to_pool(command, Some(channel), None);
+						self.add_tx_to_pool(command, vec![channel], None);
 					}
 					block_data = finalized_blocks.next() => {
 						match block_data {
@@ -360,7 +670,7 @@ where
 										if let Err(e) = self.db.remove_tx(tx.data.hash){
 											tracing::error!("Unable to remove tx from db {e}");
 										};
-										tx.event_sender.unwrap().send(extrinsic).ok();
+										self.complete_tx(tx, extrinsic).await;
 									}
 								}
 							},
@@ -392,6 +702,7 @@ where
 									hash: block.hash()
 								};
 								tracing::info!("best block stream hit: {}", self.latest_block.number);
+								self.flush_batch();
 
 								if self.pending_tx.is_empty() {
 									continue;
@@ -412,9 +723,9 @@ where
 									if tx.best_block.is_none() && self.latest_block.number > tx.data.era {
 										tracing::warn!("outdated tx found retrying with nonce {}", tx.data.nonce);
 										self.add_tx_to_pool(
-											tx.data.transaction,
-											tx.event_sender,
-											Some(tx.data.nonce),
+											tx.data.transaction.clone(),
+											tx.event_senders,
+											Some(tx.data),
 										);
 									} else {
 										new_pending.push_back(tx);
@@ -439,6 +750,7 @@ where
 										}.boxed()
 									)
 									.await;
+								self.resubmit_pending();
 							}
 						}
 					}
@@ -30,12 +30,19 @@ pub struct TestingEnv {
 
 impl TestingEnv {
 	pub async fn new() -> Self {
+		Self::with_batch_size(1).await
+	}
+
+	pub async fn with_batch_size(batch_size: usize) -> Self {
 		env_logger::try_init().ok();
 		let client = MockClient::new();
 		let uri = SecretUri::from_str("//Alice").unwrap();
 		let keypair = Keypair::from_uri(&uri).unwrap();
 		let db = MockDb::default();
-		let worker = SubxtWorker::new(0, client.clone(), db.clone(), keypair).await.unwrap();
+		let worker = SubxtWorker::new(0, client.clone(), db.clone(), keypair)
+			.await
+			.unwrap()
+			.with_batch_size(batch_size);
 		let tx_sender = worker.into_sender();
 		Self { client, tx_sender, db }
 	}
@@ -65,6 +72,7 @@ pub struct MockClient {
 	best_block: Arc<Mutex<BlockId>>,
 	subscription_counter: Arc<Mutex<u8>>,
 	force_stream_error: Arc<Mutex<bool>>,
+	force_best_stream_error: Arc<Mutex<bool>>,
 }
 
 impl Default for MockClient {
@@ -87,6 +95,7 @@ impl MockClient {
 			best_block: Default::default(),
 			subscription_counter: Default::default(),
 			force_stream_error: Default::default(),
+			force_best_stream_error: Default::default(),
 		}
 	}
 
@@ -95,7 +104,11 @@ impl MockClient {
 	}
 
 	pub async fn execute_tx(&self, nonce: u64, success: bool) {
-		self.executed.lock().await.push(MockExtrinsic { nonce, success });
+		self.executed.lock().await.push(MockExtrinsic { nonce, success, items: vec![] });
+	}
+
+	pub async fn execute_batch(&self, nonce: u64, items: Vec<bool>) {
+		self.executed.lock().await.push(MockExtrinsic { nonce, success: true, items });
 	}
 
 	pub async fn make_block(&self) -> MockBlock {
@@ -115,6 +128,10 @@ impl MockClient {
 	pub async fn set_force_stream_error(&self, flag: bool) {
 		*self.force_stream_error.lock().await = flag;
 	}
+
+	pub async fn set_force_best_stream_error(&self, flag: bool) {
+		*self.force_best_stream_error.lock().await = flag;
+	}
 }
 
 #[async_trait::async_trait]
@@ -173,6 +190,9 @@ impl ITimechainClient for MockClient {
 			.map(|res| res.map_err(|e| anyhow::anyhow!(e)))
 			.map(|block_result| block_result.map(|block| (block.clone(), block.extrinsics.clone())))
 			.boxed();
+
+		let stream =
+			ErrorInjectingStream::new(stream, self.force_best_stream_error.clone()).boxed();
 		Ok(stream)
 	}
 
@@ -231,6 +251,7 @@ impl IBlock for MockBlock {
 pub struct MockExtrinsic {
 	pub nonce: u64,
 	pub success: bool,
+	pub items: Vec<bool>,
 }
 
 #[async_trait::async_trait]
@@ -254,6 +275,14 @@ impl IExtrinsic for MockExtrinsic {
 			anyhow::bail!("tx is failed")
 		}
 	}
+
+	fn batch_item(&self, index: usize) -> Self {
+		Self {
+			nonce: self.nonce,
+			success: self.items[index],
+			items: vec![],
+		}
+	}
 }
 
 #[derive(Default, Clone)]
@@ -2,7 +2,7 @@ use crate::mock::TestingEnv;
 use anyhow::Result;
 use std::time::Duration;
 use tc_subxt::timechain_client::ITransactionDbOps;
-use tc_subxt::worker::MORTALITY;
+use tc_subxt::worker::{Priority, MORTALITY};
 use tokio::time::sleep;
 
 mod mock;
@@ -132,3 +132,112 @@ async fn test_finalized_stream_error_and_recovery() -> Result<()> {
 	assert!(tx.success);
 	Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_pending_tx_resubmitted_after_reconnect() -> Result<()> {
+	let env = TestingEnv::new().await;
+	let rx = env.submit_tx().await;
+	let nonce = env.submission().await;
+	assert_eq!(nonce, 0);
+
+	// best block stream restart
+	env.set_force_best_stream_error(true).await;
+	env.make_block().await;
+	sleep(Duration::from_millis(100)).await;
+	env.set_force_best_stream_error(false).await;
+
+	// the same tx is submitted to the new node
+	let nonce = env.submission().await;
+	assert_eq!(nonce, 0);
+	env.execute_tx(nonce, true).await;
+	env.make_block().await;
+	let tx = rx.await?;
+	assert_eq!(tx.nonce, 0);
+	assert!(tx.success);
+	Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_batch_flow() -> Result<()> {
+	let env = TestingEnv::with_batch_size(10).await;
+	let mut rxs = vec![];
+	for _ in 0..3 {
+		rxs.push(env.submit_tx().await);
+	}
+	// the queued txs are submitted as a single batch on the next block
+	env.make_block().await;
+	let nonce = env.submission().await;
+	assert_eq!(nonce, 0);
+	env.execute_batch(nonce, vec![true, false, true]).await;
+	env.make_block().await;
+	let mut results = vec![];
+	for rx in rxs {
+		let tx = rx.await?;
+		assert_eq!(tx.nonce, 0);
+		results.push(tx.success);
+	}
+	assert_eq!(results, vec![true, false, true]);
+	Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_failed_batch_falls_back_to_individual_txs() -> Result<()> {
+	let env = TestingEnv::with_batch_size(10).await;
+	let mut rxs = vec![];
+	for _ in 0..3 {
+		rxs.push(env.submit_tx().await);
+	}
+	env.make_block().await;
+	let nonce = env.submission().await;
+	assert_eq!(nonce, 0);
+	env.execute_tx(nonce, false).await;
+	env.make_block().await;
+	for expected in 1..4 {
+		let nonce = env.submission().await;
+		assert_eq!(nonce, expected);
+		env.execute_tx(nonce, true).await;
+	}
+	env.make_block().await;
+	for (rx, nonce) in rxs.into_iter().zip(1..) {
+		let tx = rx.await?;
+		assert_eq!(tx.nonce, nonce);
+		assert!(tx.success);
+	}
+	Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_full_batch_is_submitted_immediately() -> Result<()> {
+	let env = TestingEnv::with_batch_size(2).await;
+	let rx1 = env.submit_tx().await;
+	let rx2 = env.submit_tx().await;
+	let nonce = env.submission().await;
+	assert_eq!(nonce, 0);
+	env.execute_batch(nonce, vec![true, true]).await;
+	env.make_block().await;
+	assert!(rx1.await?.success);
+	assert!(rx2.await?.success);
+	Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_tip_escalation() -> Result<()> {
+	let env = TestingEnv::new().await;
+	let rx = env.submit_tx().await;
+	let nonce = env.submission().await;
+	let base_tip = Priority::High.base_tip();
+	let txs = env.db.load_pending_txs(0)?;
+	assert_eq!(txs[0].tips, vec![base_tip]);
+	for _ in 0..(MORTALITY + 1) {
+		env.make_block().await;
+	}
+	assert_eq!(env.submission().await, nonce);
+	// the tip history survives in the db and replaces the outdated entry
+	let txs = env.db.load_pending_txs(0)?;
+	assert_eq!(txs.len(), 1);
+	assert_eq!(txs[0].tips, vec![base_tip, 2 * base_tip]);
+	env.execute_tx(nonce, true).await;
+	env.make_block().await;
+	assert!(rx.await?.success);
+	Ok(())
+}